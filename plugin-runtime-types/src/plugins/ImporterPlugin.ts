@@ -12,5 +12,9 @@ export type ImportPluginResponse = null | {
 export type ImporterPlugin = {
   name: string;
   description?: string;
+  /** File extensions this importer reads, without the leading dot. Omit to be tried on any file. */
+  extensions?: string[];
+  /** MIME types this importer reads, matched against the type guessed from the file name */
+  mimeTypes?: string[];
   onImport(ctx: Context, args: { text: string }): Promise<ImportPluginResponse>;
 };
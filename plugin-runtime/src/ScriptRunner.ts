@@ -0,0 +1,69 @@
+import { InternalEvent, InternalEventPayload } from '@yaakapp-internal/plugin';
+import { randomUUID } from 'node:crypto';
+import path from 'node:path';
+import { Worker } from 'node:worker_threads';
+import { EventChannel } from './EventChannel';
+
+/** Ref id the app sends scripts to. It's not a plugin, so it's never booted. */
+export const SCRIPT_RUNNER_REF_ID = '__script_runner__';
+
+/** How long a script may run, including anything it awaits, before it's stopped */
+const SCRIPT_TIMEOUT_MS = 5000;
+
+/**
+ * Run the script from a `run_script_request` in a fresh worker, then reply to the app. Scripts
+ * can come from imported requests, so the worker gets no environment variables and nothing from
+ * this process or the plugins, and it's terminated as soon as the script finishes or times out.
+ */
+export function runScript(event: InternalEvent, events: EventChannel) {
+  if (event.payload.type !== 'run_script_request') {
+    return;
+  }
+
+  const { type: _, ...request } = event.payload;
+  const workerPath =
+    process.env.YAAK_SCRIPT_WORKER_PATH ?? path.join(__dirname, 'index.script.cjs');
+  const worker = new Worker(workerPath, {
+    workerData: request,
+    env: {},
+    stdout: true,
+    stderr: true,
+    resourceLimits: { maxOldGenerationSizeMb: 128 },
+  });
+
+  let replied = false;
+  const reply = (payload: InternalEventPayload) => {
+    if (replied) return;
+    replied = true;
+    clearTimeout(timeout);
+    worker.terminate().catch((err) => console.warn('Failed to terminate script worker', err));
+    events.emit({
+      pluginRefId: event.pluginRefId,
+      id: randomUUID(),
+      replyId: event.id,
+      payload,
+      windowContext: event.windowContext,
+    });
+  };
+
+  const timeout = setTimeout(
+    () => reply({ type: 'error_response', error: `Script took longer than ${SCRIPT_TIMEOUT_MS}ms` }),
+    SCRIPT_TIMEOUT_MS,
+  );
+
+  worker.on('message', (message: string) => {
+    const result = JSON.parse(message);
+    if (result.error != null) {
+      reply({ type: 'error_response', error: result.error });
+    } else {
+      reply({
+        type: 'run_script_response',
+        httpRequest: result.httpRequest,
+        variables: result.variables ?? {},
+        logs: result.logs ?? [],
+      });
+    }
+  });
+  worker.on('error', (err) => reply({ type: 'error_response', error: String(err) }));
+  worker.on('exit', () => reply({ type: 'error_response', error: 'Script never finished' }));
+}
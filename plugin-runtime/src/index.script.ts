@@ -0,0 +1,78 @@
+import { RunScriptRequest } from '@yaakapp-internal/plugin';
+import vm from 'node:vm';
+import { parentPort, workerData } from 'node:worker_threads';
+
+/**
+ * Sets up the `yaak` and `console` globals inside the script's context, from the request as a
+ * JSON string. Nothing from this realm is handed to the script, so it can't reach `process` or
+ * `require` through the prototype chain of an object it was given. Its results come back as a
+ * JSON string too.
+ */
+const PRELUDE = `(async () => {
+  const input = JSON.parse(__input);
+  const logs = [];
+  const format = (args) =>
+    args.map((a) => (typeof a === 'string' ? a : JSON.stringify(a))).join(' ');
+  const console = {
+    log: (...args) => logs.push(format(args)),
+    info: (...args) => logs.push(format(args)),
+    warn: (...args) => logs.push(format(args)),
+    error: (...args) => logs.push(format(args)),
+  };
+  const changed = {};
+  const current = { ...input.variables };
+  const yaak = {
+    phase: input.phase,
+    request: input.httpRequest,
+    response:
+      input.httpResponse == null
+        ? null
+        : {
+            ...input.httpResponse,
+            body: input.body ?? null,
+            json() {
+              return JSON.parse(input.body ?? '');
+            },
+          },
+    variables: {
+      get(name) {
+        return current[name] ?? null;
+      },
+      set(name, value) {
+        const v = typeof value === 'string' ? value : JSON.stringify(value);
+        current[name] = v;
+        changed[name] = v;
+      },
+    },
+  };
+  try {
+    await (async () => {
+`;
+
+const POSTLUDE = `
+    })();
+    return JSON.stringify({ httpRequest: yaak.request, variables: changed, logs });
+  } catch (err) {
+    return JSON.stringify({ error: String(err && err.stack ? err.stack : err), logs });
+  }
+})()`;
+
+/**
+ * Run a request's pre-request or post-response script. This worker is started for a single
+ * script and terminated once it replies, or when it takes too long. The script sees a `yaak`
+ * global with the request (which it may change before sending), the response (after sending),
+ * and the variables. Scripts are wrapped in an async function, so they may use `await`.
+ */
+async function run({ phase, script, httpRequest, httpResponse, body, variables }: RunScriptRequest) {
+  const input = JSON.stringify({ phase, httpRequest, httpResponse, body, variables });
+  const context = vm.createContext(Object.assign(Object.create(null), { __input: input }), {
+    codeGeneration: { strings: false, wasm: false },
+  });
+  const result = await vm.runInContext(PRELUDE + script + POSTLUDE, context);
+  return typeof result === 'string' ? result : JSON.stringify({ error: 'Script returned nothing' });
+}
+
+run(workerData).then(
+  (result) => parentPort!.postMessage(result),
+  (err) => parentPort!.postMessage(JSON.stringify({ error: String(err) })),
+);
@@ -3,6 +3,7 @@ import { createChannel, createClient, Status } from 'nice-grpc';
 import { EventChannel } from './EventChannel';
 import { PluginRuntimeClient, PluginRuntimeDefinition } from './gen/plugins/runtime';
 import { PluginHandle } from './PluginHandle';
+import { runScript, SCRIPT_RUNNER_REF_ID } from './ScriptRunner';
 
 const port = process.env.PORT || '50051';
 
@@ -22,6 +23,12 @@ const plugins: Record<string, PluginHandle> = {};
         plugins[pluginEvent.pluginRefId] = plugin;
       }
 
+      // Scripts don't belong to any plugin, so each one runs in its own isolated worker
+      if (pluginEvent.pluginRefId === SCRIPT_RUNNER_REF_ID) {
+        runScript(pluginEvent, events);
+        continue;
+      }
+
       // Once booted, forward all events to the plugin worker
       const plugin = plugins[pluginEvent.pluginRefId];
       if (!plugin) {
@@ -43,16 +43,24 @@ async function initialize() {
     return require(id);
   }
 
-  const pkg = JSON.parse(readFileSync(pathPkg, 'utf8'));
+  function readPackage() {
+    return JSON.parse(readFileSync(pathPkg, 'utf8'));
+  }
+
+  function moduleCapabilities(mod: any): string[] {
+    const capabilities: string[] = [];
+    if (typeof mod.pluginHookExport === 'function') capabilities.push('export');
+    if (typeof mod.pluginHookImport === 'function') capabilities.push('import');
+    if (typeof mod.pluginHookResponseFilter === 'function') capabilities.push('filter');
+    return capabilities;
+  }
+
+  let pkg = readPackage();
 
   prefixStdout(`[plugin][${pkg.name}] %s`);
 
   let mod = await importModule();
-
-  const capabilities: string[] = [];
-  if (typeof mod.pluginHookExport === 'function') capabilities.push('export');
-  if (typeof mod.pluginHookImport === 'function') capabilities.push('import');
-  if (typeof mod.pluginHookResponseFilter === 'function') capabilities.push('filter');
+  let capabilities = moduleCapabilities(mod);
 
   console.log('Plugin initialized', pkg.name, { capabilities, enableWatch });
 
@@ -111,7 +119,17 @@ async function initialize() {
   }
 
   async function reloadModule() {
-    mod = await importModule();
+    const newPkg = readPackage();
+    const newMod = await importModule();
+
+    // Only swap once everything loaded, so a broken build leaves the previous version running
+    pkg = newPkg;
+    mod = newMod;
+    capabilities = moduleCapabilities(newMod);
+  }
+
+  function bootResponse(): InternalEventPayload {
+    return { type: 'boot_response', name: pkg.name, version: pkg.version, capabilities };
   }
 
   // Reload plugin if JS or package.json changes
@@ -204,13 +222,7 @@ async function initialize() {
     const ctx = newCtx(event);
     try {
       if (payload.type === 'boot_request') {
-        const payload: InternalEventPayload = {
-          type: 'boot_response',
-          name: pkg.name,
-          version: pkg.version,
-          capabilities,
-        };
-        sendPayload(windowContext, payload, replyId);
+        sendPayload(windowContext, bootResponse(), replyId);
         return;
       }
 
@@ -236,6 +248,25 @@ async function initialize() {
         }
       }
 
+      if (payload.type === 'get_importer_request' && typeof mod.pluginHookImport === 'function') {
+        const importer: {
+          name?: string;
+          description?: string;
+          extensions?: string[];
+          mimeTypes?: string[];
+        } = mod.plugin?.importer ?? {};
+        const replyPayload: InternalEventPayload = {
+          type: 'get_importer_response',
+          pluginRefId,
+          name: importer.name ?? pkg.name,
+          description: importer.description ?? null,
+          extensions: importer.extensions ?? [],
+          mimeTypes: importer.mimeTypes ?? [],
+        };
+        sendPayload(windowContext, replyPayload, replyId);
+        return;
+      }
+
       if (
         payload.type === 'export_http_request_request' &&
         typeof mod.pluginHookExport === 'function'
@@ -339,10 +370,14 @@ async function initialize() {
 
       if (payload.type === 'reload_request') {
         await reloadModule();
+        sendPayload(windowContext, bootResponse(), replyId);
+        return;
       }
     } catch (err) {
       console.log('Plugin call threw exception', payload.type, err);
-      // TODO: Return errors to server
+      const error = err instanceof Error ? err.message : String(err);
+      sendPayload(windowContext, { type: 'error_response', error }, replyId);
+      return;
     }
 
     // No matches, so send back an empty response so the caller doesn't block forever
@@ -352,6 +387,21 @@ async function initialize() {
 
 initialize().catch((err) => {
   console.log('failed to boot plugin', err);
+  const { pluginRefId }: PluginWorkerData = workerData;
+  const error = err instanceof Error ? err.message : String(err);
+
+  // Answer the boot request with the error, so the app can show why the plugin didn't load
+  parentPort!.on('message', (event: InternalEvent) => {
+    if (event.payload.type !== 'boot_request') return;
+    const reply: InternalEvent = {
+      pluginRefId,
+      id: genId(),
+      replyId: event.id,
+      payload: { type: 'error_response', error },
+      windowContext: event.windowContext,
+    };
+    parentPort!.postMessage(reply);
+  });
 });
 
 function genId(len = 5): string {
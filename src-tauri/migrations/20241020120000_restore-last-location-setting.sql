@@ -0,0 +1 @@
+ALTER TABLE settings ADD COLUMN restore_last_location BOOLEAN DEFAULT TRUE;
@@ -0,0 +1,2 @@
+ALTER TABLE workspaces ADD COLUMN setting_grpc_max_send_message_size INTEGER DEFAULT '0' NOT NULL;
+ALTER TABLE workspaces ADD COLUMN setting_grpc_max_receive_message_size INTEGER DEFAULT '0' NOT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE workspaces ADD COLUMN grpc_metadata TEXT DEFAULT '[]' NOT NULL;
+ALTER TABLE grpc_requests ADD COLUMN inherit_metadata BOOLEAN DEFAULT TRUE NOT NULL;
@@ -0,0 +1 @@
+ALTER TABLE http_requests ADD COLUMN setting_server_name TEXT DEFAULT '' NOT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE workspaces ADD COLUMN setting_user_agent TEXT DEFAULT '' NOT NULL;
+ALTER TABLE workspaces ADD COLUMN setting_send_default_accept BOOLEAN DEFAULT TRUE NOT NULL;
@@ -0,0 +1 @@
+ALTER TABLE http_requests ADD COLUMN setting_ignore_cookies BOOLEAN DEFAULT FALSE NOT NULL;
@@ -0,0 +1 @@
+ALTER TABLE environments ADD COLUMN color TEXT NULL;
@@ -0,0 +1 @@
+ALTER TABLE http_responses ADD COLUMN source TEXT DEFAULT 'network' NOT NULL;
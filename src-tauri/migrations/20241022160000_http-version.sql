@@ -0,0 +1,2 @@
+ALTER TABLE workspaces ADD COLUMN setting_http_version TEXT DEFAULT 'auto' NOT NULL;
+ALTER TABLE http_requests ADD COLUMN setting_http_version TEXT NULL;
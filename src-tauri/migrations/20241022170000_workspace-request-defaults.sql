@@ -0,0 +1,2 @@
+ALTER TABLE workspaces ADD COLUMN setting_default_body_type TEXT NULL;
+ALTER TABLE workspaces ADD COLUMN setting_default_method TEXT DEFAULT '' NOT NULL;
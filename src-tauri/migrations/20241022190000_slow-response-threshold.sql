@@ -0,0 +1,2 @@
+ALTER TABLE workspaces ADD COLUMN setting_slow_response_threshold INTEGER DEFAULT 0 NOT NULL;
+ALTER TABLE http_responses ADD COLUMN slow BOOLEAN DEFAULT FALSE NOT NULL;
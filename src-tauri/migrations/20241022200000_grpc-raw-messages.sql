@@ -0,0 +1,2 @@
+ALTER TABLE workspaces ADD COLUMN setting_grpc_capture_raw_messages BOOLEAN DEFAULT FALSE NOT NULL;
+ALTER TABLE grpc_events ADD COLUMN content_raw TEXT NULL;
@@ -0,0 +1 @@
+ALTER TABLE settings ADD COLUMN response_body_dir TEXT NULL;
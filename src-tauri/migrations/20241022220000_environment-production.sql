@@ -0,0 +1 @@
+ALTER TABLE environments ADD COLUMN is_production BOOLEAN DEFAULT FALSE NOT NULL;
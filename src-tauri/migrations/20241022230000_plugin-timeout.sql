@@ -0,0 +1 @@
+ALTER TABLE settings ADD COLUMN plugin_timeout INTEGER DEFAULT 30000 NOT NULL;
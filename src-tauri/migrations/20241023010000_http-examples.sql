@@ -0,0 +1,19 @@
+CREATE TABLE http_examples
+(
+    id             TEXT                                                    NOT NULL
+        PRIMARY KEY,
+    model          TEXT     DEFAULT 'http_example'                         NOT NULL,
+    workspace_id   TEXT                                                    NOT NULL
+        REFERENCES workspaces
+            ON DELETE CASCADE,
+    request_id     TEXT                                                    NOT NULL
+        REFERENCES http_requests
+            ON DELETE CASCADE,
+    created_at     DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    updated_at     DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    name           TEXT                                                    NOT NULL,
+    status         INTEGER                                                 NOT NULL,
+    headers        TEXT     DEFAULT '[]'                                   NOT NULL,
+    body_path      TEXT                                                    NULL,
+    content_length INTEGER                                                 NULL
+);
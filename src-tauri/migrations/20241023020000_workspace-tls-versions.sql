@@ -0,0 +1,2 @@
+ALTER TABLE workspaces ADD COLUMN setting_tls_min_version TEXT NULL;
+ALTER TABLE workspaces ADD COLUMN setting_tls_max_version TEXT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE workspaces ADD COLUMN last_active_at DATETIME NULL;
+UPDATE workspaces SET last_active_at = updated_at;
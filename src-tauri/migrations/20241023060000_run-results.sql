@@ -0,0 +1,23 @@
+CREATE TABLE run_results
+(
+    id            TEXT                                                    NOT NULL
+        PRIMARY KEY,
+    model         TEXT     DEFAULT 'run_result'                           NOT NULL,
+    workspace_id  TEXT                                                    NOT NULL
+        REFERENCES workspaces
+            ON DELETE CASCADE,
+    folder_id     TEXT                                                    NOT NULL
+        REFERENCES folders
+            ON DELETE CASCADE,
+    run_id        TEXT                                                    NOT NULL,
+    request_id    TEXT                                                    NOT NULL,
+    request_model TEXT                                                    NOT NULL,
+    request_name  TEXT                                                    NOT NULL,
+    created_at    DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    updated_at    DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    status        TEXT                                                    NOT NULL,
+    response_id   TEXT                                                    NULL,
+    status_code   INTEGER                                                 NULL,
+    elapsed       INTEGER  DEFAULT 0                                      NOT NULL,
+    error         TEXT                                                    NULL
+);
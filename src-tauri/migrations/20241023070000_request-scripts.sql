@@ -0,0 +1,3 @@
+ALTER TABLE http_requests ADD COLUMN pre_request_script TEXT DEFAULT '' NOT NULL;
+ALTER TABLE http_requests ADD COLUMN post_response_script TEXT DEFAULT '' NOT NULL;
+ALTER TABLE http_requests ADD COLUMN scripts_trusted BOOLEAN DEFAULT FALSE NOT NULL;
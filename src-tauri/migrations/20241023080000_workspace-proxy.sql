@@ -0,0 +1 @@
+ALTER TABLE workspaces ADD COLUMN setting_proxy TEXT NULL;
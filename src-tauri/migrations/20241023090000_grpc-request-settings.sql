@@ -0,0 +1,6 @@
+ALTER TABLE grpc_requests ADD COLUMN setting_validate_certificates BOOLEAN NULL;
+ALTER TABLE grpc_requests ADD COLUMN setting_ca_certificate TEXT NULL;
+ALTER TABLE grpc_requests ADD COLUMN setting_client_certificate TEXT NULL;
+ALTER TABLE grpc_requests ADD COLUMN setting_client_key TEXT NULL;
+ALTER TABLE grpc_requests ADD COLUMN setting_keepalive_interval INTEGER DEFAULT 0 NOT NULL;
+ALTER TABLE grpc_requests ADD COLUMN setting_timeout INTEGER DEFAULT 0 NOT NULL;
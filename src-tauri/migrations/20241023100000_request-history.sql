@@ -0,0 +1,21 @@
+CREATE TABLE request_history
+(
+    id               TEXT                                                    NOT NULL
+        PRIMARY KEY,
+    model            TEXT     DEFAULT 'request_history'                      NOT NULL,
+    workspace_id     TEXT                                                    NOT NULL
+        REFERENCES workspaces
+            ON DELETE CASCADE,
+    request_id       TEXT                                                    NOT NULL,
+    response_id      TEXT                                                    NULL,
+    environment_id   TEXT                                                    NULL,
+    sent_by          TEXT     DEFAULT ''                                     NOT NULL,
+    created_at       DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    updated_at       DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    method           TEXT                                                    NOT NULL,
+    url              TEXT                                                    NOT NULL,
+    request_snapshot TEXT                                                    NULL,
+    status           INTEGER  DEFAULT 0                                      NOT NULL,
+    elapsed          INTEGER  DEFAULT 0                                      NOT NULL,
+    error            TEXT                                                    NULL
+);
@@ -0,0 +1,160 @@
+-- Full-text indexes for workspace search. Headers and bodies are stored as JSON, so each index
+-- keeps its own copy of just their values, to keep keys like "name" or "enabled" from matching
+-- every request. The request indexes are kept in step with their tables by the triggers below.
+CREATE VIRTUAL TABLE http_requests_fts USING fts5
+(
+    name,
+    url,
+    headers,
+    body
+);
+
+CREATE TRIGGER http_requests_fts_insert
+    AFTER INSERT
+    ON http_requests
+BEGIN
+    INSERT INTO http_requests_fts (rowid, name, url, headers, body)
+    VALUES (new.rowid, new.name, new.url,
+            (SELECT group_concat(value, ' ')
+             FROM json_tree(new.headers)
+             WHERE type = 'text' AND key IN ('name', 'value')),
+            (SELECT group_concat(value, ' ')
+             FROM json_tree(new.body)
+             WHERE type = 'text' AND key != 'id'));
+END;
+
+CREATE TRIGGER http_requests_fts_delete
+    AFTER DELETE
+    ON http_requests
+BEGIN
+    DELETE FROM http_requests_fts WHERE rowid = old.rowid;
+END;
+
+CREATE TRIGGER http_requests_fts_update
+    AFTER UPDATE OF name, url, headers, body
+    ON http_requests
+BEGIN
+    UPDATE http_requests_fts
+    SET name    = new.name,
+        url     = new.url,
+        headers = (SELECT group_concat(value, ' ')
+                   FROM json_tree(new.headers)
+                   WHERE type = 'text' AND key IN ('name', 'value')),
+        body    = (SELECT group_concat(value, ' ')
+                   FROM json_tree(new.body)
+                   WHERE type = 'text' AND key != 'id')
+    WHERE rowid = new.rowid;
+END;
+
+INSERT INTO http_requests_fts (rowid, name, url, headers, body)
+SELECT r.rowid,
+       r.name,
+       r.url,
+       (SELECT group_concat(value, ' ')
+        FROM json_tree(r.headers)
+        WHERE type = 'text' AND key IN ('name', 'value')),
+       (SELECT group_concat(value, ' ')
+        FROM json_tree(r.body)
+        WHERE type = 'text' AND key != 'id')
+FROM http_requests r;
+
+-- The message is JSON the user wrote, so unlike metadata it's indexed as it is
+CREATE VIRTUAL TABLE grpc_requests_fts USING fts5
+(
+    name,
+    url,
+    metadata,
+    message
+);
+
+CREATE TRIGGER grpc_requests_fts_insert
+    AFTER INSERT
+    ON grpc_requests
+BEGIN
+    INSERT INTO grpc_requests_fts (rowid, name, url, metadata, message)
+    VALUES (new.rowid, new.name, new.url,
+            (SELECT group_concat(value, ' ')
+             FROM json_tree(new.metadata)
+             WHERE type = 'text' AND key IN ('name', 'value')),
+            new.message);
+END;
+
+CREATE TRIGGER grpc_requests_fts_delete
+    AFTER DELETE
+    ON grpc_requests
+BEGIN
+    DELETE FROM grpc_requests_fts WHERE rowid = old.rowid;
+END;
+
+CREATE TRIGGER grpc_requests_fts_update
+    AFTER UPDATE OF name, url, metadata, message
+    ON grpc_requests
+BEGIN
+    UPDATE grpc_requests_fts
+    SET name     = new.name,
+        url      = new.url,
+        metadata = (SELECT group_concat(value, ' ')
+                    FROM json_tree(new.metadata)
+                    WHERE type = 'text' AND key IN ('name', 'value')),
+        message  = new.message
+    WHERE rowid = new.rowid;
+END;
+
+INSERT INTO grpc_requests_fts (rowid, name, url, metadata, message)
+SELECT r.rowid,
+       r.name,
+       r.url,
+       (SELECT group_concat(value, ' ')
+        FROM json_tree(r.metadata)
+        WHERE type = 'text' AND key IN ('name', 'value')),
+       r.message
+FROM grpc_requests r;
+
+-- Response bodies live in files, so the body is filled in by the app once the response has been
+-- received
+CREATE VIRTUAL TABLE http_responses_fts USING fts5
+(
+    url,
+    headers,
+    body
+);
+
+CREATE TRIGGER http_responses_fts_insert
+    AFTER INSERT
+    ON http_responses
+BEGIN
+    INSERT INTO http_responses_fts (rowid, url, headers, body)
+    VALUES (new.rowid, new.url,
+            (SELECT group_concat(value, ' ')
+             FROM json_tree(new.headers)
+             WHERE type = 'text' AND key IN ('name', 'value')),
+            '');
+END;
+
+CREATE TRIGGER http_responses_fts_delete
+    AFTER DELETE
+    ON http_responses
+BEGIN
+    DELETE FROM http_responses_fts WHERE rowid = old.rowid;
+END;
+
+CREATE TRIGGER http_responses_fts_update
+    AFTER UPDATE OF url, headers
+    ON http_responses
+BEGIN
+    UPDATE http_responses_fts
+    SET url     = new.url,
+        headers = (SELECT group_concat(value, ' ')
+                   FROM json_tree(new.headers)
+                   WHERE type = 'text' AND key IN ('name', 'value'))
+    WHERE rowid = new.rowid;
+END;
+
+INSERT INTO http_responses_fts (rowid, url, headers, body)
+SELECT s.rowid,
+       s.url,
+       (SELECT group_concat(value, ' ')
+        FROM json_tree(s.headers)
+        WHERE type = 'text' AND key IN ('name', 'value')),
+       ''
+FROM http_responses s;
@@ -0,0 +1 @@
+ALTER TABLE workspaces ADD COLUMN setting_http_cache BOOLEAN DEFAULT FALSE NOT NULL;
@@ -0,0 +1,19 @@
+CREATE TABLE http_timeline_events
+(
+    id           TEXT                                                    NOT NULL
+        PRIMARY KEY,
+    model        TEXT     DEFAULT 'http_timeline_event'                  NOT NULL,
+    workspace_id TEXT                                                    NOT NULL
+        REFERENCES workspaces
+            ON DELETE CASCADE,
+    response_id  TEXT                                                    NOT NULL
+        REFERENCES http_responses
+            ON DELETE CASCADE,
+    created_at   DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    updated_at   DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    event_type   TEXT                                                    NOT NULL,
+    content      TEXT                                                    NOT NULL,
+    headers      TEXT     DEFAULT '[]'                                   NOT NULL,
+    elapsed      INTEGER                                                 NOT NULL,
+    duration     INTEGER                                                 NULL
+);
@@ -0,0 +1,9 @@
+ALTER TABLE workspaces ADD COLUMN setting_retry_count INTEGER DEFAULT 0 NOT NULL;
+ALTER TABLE workspaces ADD COLUMN setting_retry_backoff INTEGER DEFAULT 0 NOT NULL;
+
+ALTER TABLE http_requests ADD COLUMN setting_request_timeout INTEGER NULL;
+ALTER TABLE http_requests ADD COLUMN setting_retry_count INTEGER NULL;
+ALTER TABLE http_requests ADD COLUMN setting_retry_backoff INTEGER NULL;
+ALTER TABLE http_requests ADD COLUMN setting_retry_non_idempotent BOOLEAN DEFAULT FALSE NOT NULL;
+
+ALTER TABLE http_responses ADD COLUMN attempts INTEGER DEFAULT 1 NOT NULL;
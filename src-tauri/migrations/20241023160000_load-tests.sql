@@ -0,0 +1,28 @@
+CREATE TABLE load_tests
+(
+    id            TEXT                                                    NOT NULL
+        PRIMARY KEY,
+    model         TEXT     DEFAULT 'load_test'                            NOT NULL,
+    workspace_id  TEXT                                                    NOT NULL
+        REFERENCES workspaces
+            ON DELETE CASCADE,
+    request_id    TEXT                                                    NOT NULL
+        REFERENCES http_requests
+            ON DELETE CASCADE,
+    created_at    DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    updated_at    DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    duration      INTEGER                                                 NOT NULL,
+    concurrency   INTEGER                                                 NOT NULL,
+    state         TEXT                                                    NOT NULL,
+    elapsed       INTEGER  DEFAULT 0                                      NOT NULL,
+    requests      INTEGER  DEFAULT 0                                      NOT NULL,
+    errors        INTEGER  DEFAULT 0                                      NOT NULL,
+    status_counts TEXT     DEFAULT '{}'                                   NOT NULL,
+    latency_min   INTEGER  DEFAULT 0                                      NOT NULL,
+    latency_mean  INTEGER  DEFAULT 0                                      NOT NULL,
+    latency_p50   INTEGER  DEFAULT 0                                      NOT NULL,
+    latency_p90   INTEGER  DEFAULT 0                                      NOT NULL,
+    latency_p95   INTEGER  DEFAULT 0                                      NOT NULL,
+    latency_p99   INTEGER  DEFAULT 0                                      NOT NULL,
+    latency_max   INTEGER  DEFAULT 0                                      NOT NULL
+);
@@ -1,10 +1,12 @@
 use std::fmt::Display;
+use std::future::Future;
 
 use log::{debug, info};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tauri::{Manager, Runtime, WebviewWindow};
 
+use yaak_models::models::Settings;
 use yaak_models::queries::{generate_id, get_key_value_int, get_key_value_string, get_or_create_settings, set_key_value_int, set_key_value_string};
 
 use crate::is_dev;
@@ -156,7 +158,34 @@ pub async fn track_event<R: Runtime>(
     action: AnalyticsAction,
     attributes: Option<Value>,
 ) {
-    
+    let settings = get_or_create_settings(w).await;
+    track_if_enabled(&settings, &resource, &action, || {
+        send_event(w, &resource, &action, attributes)
+    })
+    .await;
+}
+
+/// Check the opt-out before doing anything else, so when telemetry is off the event is never
+/// built, let alone sent
+async fn track_if_enabled<F: Future<Output = ()>>(
+    settings: &Settings,
+    resource: &AnalyticsResource,
+    action: &AnalyticsAction,
+    send: impl FnOnce() -> F,
+) {
+    if !settings.telemetry {
+        debug!("Track event (disabled): {}.{}", resource, action);
+        return;
+    }
+    send().await;
+}
+
+async fn send_event<R: Runtime>(
+    w: &WebviewWindow<R>,
+    resource: &AnalyticsResource,
+    action: &AnalyticsAction,
+    attributes: Option<Value>,
+) {
     let id = get_id(w).await;
     let event = format!("{}.{}", resource, action);
     let attributes_json = attributes.unwrap_or("{}".to_string().into()).to_string();
@@ -186,12 +215,6 @@ pub async fn track_event<R: Runtime>(
         .get(format!("{base_url}/t/e"))
         .query(&params);
 
-    let settings = get_or_create_settings(w).await;
-    if !settings.telemetry {
-        info!("Track event (disabled): {}", event);
-        return
-    }
-
     // Disable analytics actual sending in dev
     if is_dev() {
         debug!("Track event: {} {}", event, attributes_json);
@@ -247,3 +270,32 @@ async fn get_id<R: Runtime>(w: &WebviewWindow<R>) -> String {
 pub async fn get_num_launches<R: Runtime>(w: &WebviewWindow<R>) -> i32 {
     get_key_value_int(w, NAMESPACE, NUM_LAUNCHES_KEY, 0).await
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::analytics::{track_if_enabled, AnalyticsAction, AnalyticsResource};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use yaak_models::models::Settings;
+
+    #[tokio::test]
+    async fn nothing_is_sent_with_telemetry_off() {
+        let track = |telemetry: bool| async move {
+            let settings = Settings {
+                telemetry,
+                ..Default::default()
+            };
+            let built = AtomicBool::new(false);
+            let send = || async { built.store(true, Ordering::SeqCst) };
+            track_if_enabled(
+                &settings,
+                &AnalyticsResource::App,
+                &AnalyticsAction::Launch,
+                send,
+            )
+            .await;
+            built.load(Ordering::SeqCst)
+        };
+        assert!(!track(false).await);
+        assert!(track(true).await);
+    }
+}
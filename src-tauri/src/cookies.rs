@@ -0,0 +1,238 @@
+use std::collections::{HashMap, HashSet};
+
+use reqwest::Url;
+use reqwest_cookie_store::CookieStore;
+use yaak_models::models::{Cookie, CookieJar};
+
+/// Which of a jar's cookies to list or clear. A domain also matches its subdomains, and a path
+/// also matches the paths beneath it, the same way they'd match a request.
+#[derive(Debug, Default)]
+pub struct CookieFilter<'a> {
+    pub domain: Option<&'a str>,
+    pub path: Option<&'a str>,
+}
+
+impl CookieFilter<'_> {
+    fn matches(&self, domain: &str, path: &str) -> bool {
+        let domain_matches = match self.domain.map(|d| d.trim_start_matches('.')) {
+            None | Some("") => true,
+            Some(d) => {
+                domain.eq_ignore_ascii_case(d)
+                    || domain.to_lowercase().ends_with(&format!(".{}", d.to_lowercase()))
+            }
+        };
+        let path_matches = match self.path {
+            None | Some("") => true,
+            Some(p) => {
+                path == p
+                    || (path.starts_with(p)
+                        && (p.ends_with('/') || path[p.len()..].starts_with('/')))
+            }
+        };
+        domain_matches && path_matches
+    }
+}
+
+/// The jar's cookies, loaded into a store so they can be looked up the way a client would.
+/// Expired cookies are kept, so they aren't dropped just by editing the jar.
+pub fn cookie_store(cookie_jar: &CookieJar) -> CookieStore {
+    // HACK: Can't construct Cookie without serde, so we have to do this
+    let existing = cookie_jar.cookies.iter().filter_map(|c| {
+        serde_json::to_value(c).ok().and_then(|v| serde_json::from_value(v).ok()).map(Ok::<_, ()>)
+    });
+    CookieStore::from_cookies(existing, true).unwrap_or_default()
+}
+
+/// Replace the jar's cookies with everything in the store
+pub fn set_cookies(cookie_jar: &mut CookieJar, store: &CookieStore) {
+    cookie_jar.cookies = store
+        .iter_any()
+        .filter_map(|c| serde_json::to_value(c).ok())
+        .filter_map(|v| serde_json::from_value::<Cookie>(v).ok())
+        .collect();
+}
+
+/// Apply what a request changed in its copy of the jar, from the cookies it started with to the
+/// ones it ended with, to the jar as it is now. Requests sent at the same time each start with
+/// the same cookies, so saving a whole copy would drop the cookies set by the others.
+pub fn merge_cookies(cookie_jar: &mut CookieJar, before: &[Cookie], after: &[Cookie]) {
+    let before: HashMap<_, _> = cookie_entries(before).into_iter().collect();
+    let after = cookie_entries(after);
+    let kept: HashSet<_> = after.iter().map(|(k, _)| k.clone()).collect();
+    let changed: Vec<_> = after.into_iter().filter(|(k, v)| before.get(k) != Some(v)).collect();
+    let replaced: HashSet<_> = before
+        .keys()
+        .filter(|k| !kept.contains(*k))
+        .chain(changed.iter().map(|(k, _)| k))
+        .cloned()
+        .collect();
+
+    let mut merged: Vec<_> = cookie_entries(&cookie_jar.cookies)
+        .into_iter()
+        .filter(|(k, _)| !replaced.contains(k))
+        .map(|(_, v)| v)
+        .collect();
+    merged.extend(changed.into_iter().map(|(_, v)| v));
+    cookie_jar.cookies =
+        merged.into_iter().filter_map(|v| serde_json::from_value(v).ok()).collect();
+}
+
+/// Each cookie keyed by its domain, path, and name, which is what makes two cookies the same
+fn cookie_entries(cookies: &[Cookie]) -> Vec<((String, String, String), serde_json::Value)> {
+    cookies
+        .iter()
+        .filter_map(|c| serde_json::to_value(c).ok())
+        .map(|v| {
+            let raw = v["raw_cookie"].as_str().unwrap_or_default();
+            let name = raw.split(['=', ';']).next().unwrap_or_default().trim().to_string();
+            ((v["domain"].to_string(), v["path"][0].to_string(), name), v)
+        })
+        .collect()
+}
+
+pub fn list_cookies(cookie_jar: &CookieJar, filter: &CookieFilter) -> Vec<Cookie> {
+    let mut store = cookie_store(cookie_jar);
+    retain_cookies(&mut store, |domain, path| filter.matches(domain, path));
+    let mut filtered = cookie_jar.clone();
+    set_cookies(&mut filtered, &store);
+    filtered.cookies
+}
+
+/// Add a cookie, written like a `Set-Cookie` header, as if it was set by a response from the URL.
+/// It replaces any cookie with the same name, domain, and path.
+pub fn upsert_cookie(
+    cookie_jar: &mut CookieJar,
+    url: &str,
+    raw_cookie: &str,
+) -> Result<(), String> {
+    let url = Url::parse(url).map_err(|e| format!("Invalid URL {url}: {e}"))?;
+    let mut store = cookie_store(cookie_jar);
+    store.parse(raw_cookie, &url).map_err(|e| format!("Invalid cookie {raw_cookie}: {e}"))?;
+    set_cookies(cookie_jar, &store);
+    Ok(())
+}
+
+/// Delete the cookie with the given name, domain, and path, returning whether there was one
+pub fn delete_cookie(cookie_jar: &mut CookieJar, domain: &str, path: &str, name: &str) -> bool {
+    let mut store = cookie_store(cookie_jar);
+    let deleted = store.remove(domain, path, name).is_some();
+    set_cookies(cookie_jar, &store);
+    deleted
+}
+
+/// Delete the cookies matching the filter, returning how many were deleted
+pub fn clear_cookies(cookie_jar: &mut CookieJar, filter: &CookieFilter) -> usize {
+    let mut store = cookie_store(cookie_jar);
+    let before = store.iter_any().count();
+    retain_cookies(&mut store, |domain, path| !filter.matches(domain, path));
+    let deleted = before - store.iter_any().count();
+    set_cookies(cookie_jar, &store);
+    deleted
+}
+
+fn retain_cookies(store: &mut CookieStore, keep: impl Fn(&str, &str) -> bool) {
+    let removed: Vec<(String, String, String)> = store
+        .iter_any()
+        .filter_map(|c| {
+            let domain = c.domain.as_cow().unwrap_or_default().to_string();
+            let path = c.path.to_string();
+            match keep(&domain, &path) {
+                true => None,
+                false => Some((domain, path, c.name().to_string())),
+            }
+        })
+        .collect();
+    for (domain, path, name) in removed {
+        store.remove(&domain, &path, &name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cookies::{
+        clear_cookies, cookie_store, delete_cookie, list_cookies, merge_cookies, upsert_cookie,
+        CookieFilter,
+    };
+    use yaak_models::models::CookieJar;
+
+    fn names(cookie_jar: &CookieJar) -> Vec<String> {
+        let mut names: Vec<String> =
+            cookie_store(cookie_jar).iter_any().map(|c| c.name().to_string()).collect();
+        names.sort();
+        names
+    }
+
+    fn jar() -> CookieJar {
+        let mut jar = CookieJar::default();
+        upsert_cookie(&mut jar, "https://yaak.test", "a=1").unwrap();
+        upsert_cookie(&mut jar, "https://api.yaak.test/v1/users", "b=2; Path=/v1").unwrap();
+        upsert_cookie(&mut jar, "https://other.test", "c=3; Path=/v10").unwrap();
+        jar
+    }
+
+    #[test]
+    fn upsert_replaces_same_cookie() {
+        let mut jar = jar();
+        upsert_cookie(&mut jar, "https://yaak.test", "a=4").unwrap();
+        assert_eq!(names(&jar), vec!["a", "b", "c"]);
+
+        let raw: Vec<String> = jar
+            .cookies
+            .iter()
+            .map(|c| serde_json::to_value(c).unwrap()["raw_cookie"].as_str().unwrap().to_string())
+            .collect();
+        assert!(raw.contains(&"a=4".to_string()), "{raw:?}");
+        assert!(upsert_cookie(&mut jar, "not a url", "a=1").is_err());
+    }
+
+    #[test]
+    fn merge_keeps_cookies_set_at_the_same_time() {
+        let before = jar();
+
+        // Two requests start from the same jar, and the first one finishes first
+        let mut first = before.clone();
+        upsert_cookie(&mut first, "https://yaak.test", "d=4").unwrap();
+        upsert_cookie(&mut first, "https://yaak.test", "a=5").unwrap();
+        let mut second = before.clone();
+        upsert_cookie(&mut second, "https://yaak.test", "e=6").unwrap();
+        delete_cookie(&mut second, "other.test", "/v10", "c");
+
+        let mut saved = before.clone();
+        merge_cookies(&mut saved, &before.cookies, &first.cookies);
+        merge_cookies(&mut saved, &before.cookies, &second.cookies);
+        assert_eq!(names(&saved), vec!["a", "b", "d", "e"]);
+
+        let raw = serde_json::to_string(&saved.cookies).unwrap();
+        assert!(raw.contains(r#""raw_cookie":"a=5""#), "{raw}");
+    }
+
+    #[test]
+    fn delete_by_name_domain_and_path() {
+        let mut jar = jar();
+        assert!(!delete_cookie(&mut jar, "api.yaak.test", "/", "b"));
+        assert!(delete_cookie(&mut jar, "api.yaak.test", "/v1", "b"));
+        assert_eq!(names(&jar), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn filter_by_domain_and_path() {
+        let jar = jar();
+        let count = |domain, path| list_cookies(&jar, &CookieFilter { domain, path }).len();
+        assert_eq!(count(None, None), 3);
+        assert_eq!(count(Some("yaak.test"), None), 2);
+        assert_eq!(count(Some(".YAAK.test"), None), 2);
+        assert_eq!(count(Some("ak.test"), None), 0);
+        assert_eq!(count(None, Some("/v1")), 1);
+        assert_eq!(count(None, Some("/v1/")), 0);
+
+        let mut jar = jar;
+        let filter = CookieFilter {
+            domain: Some("yaak.test"),
+            path: Some("/v1"),
+        };
+        assert_eq!(clear_cookies(&mut jar, &filter), 1);
+        assert_eq!(names(&jar), vec!["a", "c"]);
+        assert_eq!(clear_cookies(&mut jar, &CookieFilter::default()), 2);
+        assert!(jar.cookies.is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+use crate::cookies::{cookie_store, set_cookies};
+use reqwest::Url;
+use yaak_models::models::CookieJar;
+
+/// Cookies sent by a curl command, along with the URL they were sent to
+#[derive(Debug, PartialEq)]
+pub struct CurlCookies {
+    pub url: Url,
+    pub cookies: Vec<String>,
+}
+
+/// Flags that consume the following argument, so it isn't mistaken for the URL
+const FLAGS_WITH_VALUES: &[&str] = &[
+    "-A",
+    "--user-agent",
+    "-b",
+    "--cookie",
+    "-c",
+    "--cookie-jar",
+    "--connect-timeout",
+    "-d",
+    "--data",
+    "--data-ascii",
+    "--data-binary",
+    "--data-raw",
+    "--data-urlencode",
+    "-e",
+    "--referer",
+    "-F",
+    "--form",
+    "-H",
+    "--header",
+    "-m",
+    "--max-time",
+    "-o",
+    "--output",
+    "-u",
+    "--user",
+    "--url",
+    "-X",
+    "--request",
+    "-x",
+    "--proxy",
+];
+
+/// Pull the cookies out of the `-b`/`--cookie` flags and `Cookie:` headers of a curl command.
+/// A `-b` value without `=` names a cookie file, which is ignored.
+pub fn parse_curl_cookies(command: &str) -> Result<CurlCookies, String> {
+    let args = split_args(command)?;
+    let mut args = args.iter().map(|a| a.as_str()).skip_while(|a| *a == "curl");
+
+    let mut url = None;
+    let mut cookies = Vec::new();
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((f, v)) if f.starts_with("--") => (f, Some(v)),
+            _ if arg.starts_with("-b") && arg.len() > 2 => ("-b", Some(&arg[2..])),
+            _ if arg.starts_with("-H") && arg.len() > 2 => ("-H", Some(&arg[2..])),
+            _ => (arg, None),
+        };
+
+        if !flag.starts_with('-') {
+            url = url.or(Some(arg));
+            continue;
+        }
+        if !FLAGS_WITH_VALUES.contains(&flag) {
+            continue;
+        }
+
+        let value = match inline_value.or_else(|| args.next()) {
+            Some(v) => v,
+            None => return Err(format!("Missing value for {flag}")),
+        };
+        match flag {
+            "-b" | "--cookie" if value.contains('=') => cookies.extend(split_cookies(value)),
+            "-H" | "--header" => {
+                if let Some((name, value)) = value.split_once(':') {
+                    if name.trim().eq_ignore_ascii_case("cookie") {
+                        cookies.extend(split_cookies(value));
+                    }
+                }
+            }
+            "--url" => url = Some(value),
+            _ => {}
+        }
+    }
+
+    let url = match url {
+        Some(u) if u.contains("://") => u.to_string(),
+        Some(u) => format!("http://{u}"),
+        None => return Err("No URL found in curl command".to_string()),
+    };
+    let url = Url::parse(&url).map_err(|e| format!("Invalid URL {url}: {e}"))?;
+    if cookies.is_empty() {
+        return Err("No cookies found in curl command".to_string());
+    }
+
+    Ok(CurlCookies { url, cookies })
+}
+
+/// Add the cookies to the jar as host-only cookies for the command's URL, replacing any
+/// existing cookies with the same name, domain, and path
+pub fn add_curl_cookies(cookie_jar: &mut CookieJar, curl_cookies: &CurlCookies) {
+    let mut store = cookie_store(cookie_jar);
+
+    for cookie in curl_cookies.cookies.iter() {
+        if let Err(e) = store.parse(cookie, &curl_cookies.url) {
+            log::warn!("Failed to add cookie {cookie} {e:?}");
+        }
+    }
+
+    set_cookies(cookie_jar, &store);
+}
+
+fn split_cookies(value: &str) -> Vec<String> {
+    value.split(';').map(|c| c.trim()).filter(|c| c.contains('=')).map(|c| c.to_string()).collect()
+}
+
+/// Split a command into arguments the way a POSIX shell would, handling quotes, escapes, and
+/// line continuations
+fn split_args(command: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                let arg = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => arg.push(c),
+                        None => return Err("Unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                let arg = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => arg.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                arg.push('\\');
+                                arg.push(c);
+                            }
+                            None => return Err("Unterminated double quote".to_string()),
+                        },
+                        Some(c) => arg.push(c),
+                        None => return Err("Unterminated double quote".to_string()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') | None => {}
+                Some(c) => current.get_or_insert_with(String::new).push(c),
+            },
+            c if c.is_whitespace() => {
+                if let Some(arg) = current.take() {
+                    args.push(arg);
+                }
+            }
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if let Some(arg) = current {
+        args.push(arg);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curl_cookies::{add_curl_cookies, parse_curl_cookies, split_args};
+    use reqwest::Url;
+    use yaak_models::models::CookieJar;
+
+    #[test]
+    fn split_quoted_args() {
+        assert_eq!(
+            split_args("curl 'https://a.test' -H \"X-Name: \\\"yaak\\\"\" \\\n  --compressed")
+                .unwrap(),
+            vec![
+                "curl",
+                "https://a.test",
+                "-H",
+                "X-Name: \"yaak\"",
+                "--compressed"
+            ]
+        );
+        assert!(split_args("curl 'https://a.test").is_err());
+    }
+
+    #[test]
+    fn parse_cookie_flags_and_headers() {
+        let parsed = parse_curl_cookies(
+            "curl -X POST https://yaak.test/api -b 'a=1; b=2' --cookie=c=3 \
+             -H 'Cookie: d=4' -H 'Accept: */*' -b cookies.txt",
+        )
+        .unwrap();
+        assert_eq!(parsed.url, Url::parse("https://yaak.test/api").unwrap());
+        assert_eq!(parsed.cookies, vec!["a=1", "b=2", "c=3", "d=4"]);
+    }
+
+    #[test]
+    fn parse_requires_url_and_cookies() {
+        assert!(parse_curl_cookies("curl -b a=1").is_err());
+        assert!(parse_curl_cookies("curl https://yaak.test").is_err());
+    }
+
+    #[test]
+    fn add_cookies_to_jar() {
+        let mut jar = CookieJar::default();
+        add_curl_cookies(&mut jar, &parse_curl_cookies("curl yaak.test -b 'a=1; b=2'").unwrap());
+        add_curl_cookies(&mut jar, &parse_curl_cookies("curl yaak.test -b a=3").unwrap());
+
+        let mut cookies: Vec<String> = jar
+            .cookies
+            .iter()
+            .map(|c| serde_json::to_value(c).unwrap()["raw_cookie"].as_str().unwrap().to_string())
+            .collect();
+        cookies.sort();
+        assert_eq!(cookies, vec!["a=3", "b=2"]);
+    }
+}
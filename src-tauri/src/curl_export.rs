@@ -0,0 +1,430 @@
+use std::time::Duration;
+
+use reqwest::Url;
+use yaak_models::models::{
+    HttpRequest, HttpVersionSetting, ProxySetting, ProxySettingAuth, Workspace,
+};
+
+use crate::http_request::{
+    chunk_source, decode_bytes_body, ensure_proto, get_bool, get_str, get_str_h, graphql_body,
+    http_version, request_proxy, request_timeout, text_body_file, user_agent, ChunkSource,
+};
+use crate::proxy::socks_proxy_url;
+
+/// Largest body of repeated chunks that's written out in the command
+const MAX_REPEATED_BODY_BYTES: usize = 1024 * 1024;
+
+/// A curl command that sends the rendered request the way Yaak would, with the same default
+/// headers, auth, proxy, HTTP and TLS versions, and redirect, certificate, and timeout settings.
+/// Cookies from the jar aren't included. Files in the body are referenced by path, so curl reads
+/// them when the command is run, and a file's contents aren't rendered as templates like they
+/// are when Yaak sends it.
+pub fn request_to_curl(
+    workspace: &Workspace,
+    request: &HttpRequest,
+    global_proxy: Option<ProxySetting>,
+) -> Result<String, String> {
+    // Each flag goes on its own line, along with its value
+    let mut args = vec!["curl".to_string()];
+    let body = body_args(request)?;
+    let url = request_url(request);
+
+    match request.method.to_uppercase().as_str() {
+        "HEAD" => args.push("--head".to_string()),
+        // curl would send a body with POST unless told otherwise
+        "GET" if body.is_empty() => {}
+        method => args.push(format!("--request {method}")),
+    }
+    args.push(format!("--url {}", quote(&url)));
+
+    match http_version(workspace, request) {
+        HttpVersionSetting::Auto => {}
+        HttpVersionSetting::Http1 => args.push("--http1.1".to_string()),
+        HttpVersionSetting::Http2 => args.push("--http2".to_string()),
+        HttpVersionSetting::Http2PriorKnowledge => args.push("--http2-prior-knowledge".to_string()),
+    }
+    if request.setting_follow_redirects.unwrap_or(workspace.setting_follow_redirects) {
+        args.push("--location".to_string());
+    }
+    if !workspace.setting_validate_certificates {
+        args.push("--insecure".to_string());
+    }
+    if let Some(min) = &workspace.setting_tls_min_version {
+        args.push(format!("--tlsv{}", min.as_str()));
+    }
+    if let Some(max) = &workspace.setting_tls_max_version {
+        args.push(format!("--tls-max {}", max.as_str()));
+    }
+    if let Some(timeout) = request_timeout(workspace, request) {
+        args.push(format!("--max-time {}", format_seconds(timeout)));
+    }
+    args.extend(proxy_args(request_proxy(workspace, request, global_proxy), &url));
+
+    // Yaak's defaults, unless the request sets them itself
+    let has_header =
+        |name: &str| request.headers.iter().any(|h| h.enabled && h.name.eq_ignore_ascii_case(name));
+    if !has_header("user-agent") {
+        args.push(format!("--user-agent {}", quote(user_agent(workspace))));
+    }
+    if !workspace.setting_send_default_accept && !has_header("accept") {
+        // curl sends Accept: */* unless the header is removed
+        args.push(format!("--header {}", quote("Accept:")));
+    }
+
+    for h in &request.headers {
+        if !h.enabled || h.name.is_empty() {
+            continue;
+        }
+        args.push(format!("--header {}", quote(&format!("{}: {}", h.name, h.value))));
+    }
+
+    let a = &request.authentication;
+    let user = format!("{}:{}", get_str_h(a, "username"), get_str_h(a, "password"));
+    match request.authentication_type.as_deref() {
+        Some("basic") => args.push(format!("--user {}", quote(&user))),
+        Some("digest") => args.push(format!("--digest --user {}", quote(&user))),
+        Some("ntlm") => args.push(format!("--ntlm --user {}", quote(&user))),
+        Some("bearer") => {
+            let header = format!("Authorization: Bearer {}", get_str_h(a, "token"));
+            args.push(format!("--header {}", quote(&header)));
+        }
+        _ => {}
+    }
+
+    args.extend(body);
+    Ok(args.join(" \\\n  "))
+}
+
+/// The URL with the request's enabled query parameters added
+fn request_url(request: &HttpRequest) -> String {
+    let url = ensure_proto(&request.url);
+    let params: Vec<_> =
+        request.url_parameters.iter().filter(|p| p.enabled && !p.name.is_empty()).collect();
+    if params.is_empty() {
+        return url;
+    }
+
+    match Url::parse(&url) {
+        Ok(mut u) => {
+            u.query_pairs_mut().extend_pairs(params.iter().map(|p| (&p.name, &p.value)));
+            u.to_string()
+        }
+        Err(_) => url,
+    }
+}
+
+/// The curl flags that send the request's body, matching how each body type is sent
+fn body_args(request: &HttpRequest) -> Result<Vec<String>, String> {
+    let body = &request.body;
+    let body_type = match &request.body_type {
+        Some(t) => t.as_str(),
+        None => return Ok(Vec::new()),
+    };
+    let data = |flag: &str, value: String| vec![format!("{flag} {value}")];
+
+    let args = if body_type == "chunked" {
+        let mut args = data("--header", quote("Transfer-Encoding: chunked"));
+        args.extend(match chunk_source(body)? {
+            ChunkSource::FileLines(path) => {
+                data("--data-binary", quote(&format!("@{}", path.display())))
+            }
+            ChunkSource::Repeat { chunk, count } => {
+                let size = (count as usize).checked_mul(chunk.len()).unwrap_or(usize::MAX);
+                if size > MAX_REPEATED_BODY_BYTES {
+                    return Err(format!(
+                        "The body repeats to {size} bytes, which is too large to copy as curl"
+                    ));
+                }
+                data("--data-raw", quote(&chunk.repeat(count as usize)))
+            }
+        });
+        args
+    } else if body_type == "bytes" {
+        let bytes = decode_bytes_body(get_str_h(body, "text"), get_str_h(body, "encoding"))?;
+        let mut args = Vec::new();
+        if !request.headers.iter().any(|h| h.enabled && h.name.eq_ignore_ascii_case("content-type"))
+        {
+            args.extend(data("--header", quote("Content-Type: application/octet-stream")));
+        }
+        args.extend(data("--data-binary", quote_bytes(&bytes)));
+        args
+    } else if body.contains_key("query") && body.contains_key("variables") {
+        data("--data-raw", quote(&graphql_body(body)))
+    } else if let Some(file_path) = text_body_file(body_type, body) {
+        data("--data-binary", quote(&format!("@{file_path}")))
+    } else if body.contains_key("text") {
+        data("--data-raw", quote(get_str_h(body, "text")))
+    } else if body_type == "application/x-www-form-urlencoded" {
+        form_fields(body)
+            .flat_map(|f| data("--data-urlencode", quote(&format!("{}={}", f.0, f.1))))
+            .collect()
+    } else if body_type == "binary" {
+        data("--data-binary", quote(&format!("@{}", get_str_h(body, "filePath"))))
+    } else if body_type == "multipart/form-data" {
+        let mut args = Vec::new();
+        for p in body.get("form").and_then(|f| f.as_array()).into_iter().flatten() {
+            let name = get_str(p, "name");
+            if !get_bool(p, "enabled") || name.is_empty() {
+                continue;
+            }
+            let file = get_str(p, "file");
+            let content_type = get_str(p, "contentType");
+            let (flag, mut value) = match (file.is_empty(), content_type.is_empty()) {
+                (false, _) => ("--form", format!("{name}=@{file}")),
+                // --form-string doesn't treat a leading @ or < as a file to read
+                (true, true) => ("--form-string", format!("{name}={}", get_str(p, "value"))),
+                (true, false) => ("--form", format!("{name}={}", get_str(p, "value"))),
+            };
+            if !content_type.is_empty() {
+                value.push_str(&format!(";type={content_type}"));
+            }
+            args.extend(data(flag, quote(&value)));
+        }
+        args
+    } else {
+        Vec::new()
+    };
+    Ok(args)
+}
+
+/// The enabled, named fields of a form body
+fn form_fields(
+    body: &std::collections::BTreeMap<String, serde_json::Value>,
+) -> impl Iterator<Item = (&str, &str)> {
+    body.get("form")
+        .and_then(|f| f.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|p| get_bool(p, "enabled") && !get_str(p, "name").is_empty())
+        .map(|p| (get_str(p, "name"), get_str(p, "value")))
+}
+
+/// The proxy flags for sending to the URL. A system proxy is left to curl, which reads the same
+/// `HTTP(S)_PROXY` environment variables.
+fn proxy_args(proxy: Option<ProxySetting>, url: &str) -> Vec<String> {
+    let (http, https, socks, auth) = match proxy {
+        Some(ProxySetting::Disabled) => return vec![format!("--noproxy {}", quote("*"))],
+        Some(ProxySetting::System) | None => return Vec::new(),
+        Some(ProxySetting::Enabled {
+            http,
+            https,
+            socks,
+            auth,
+        }) => (http, https, socks, auth),
+    };
+
+    let proxy = match url.split_once("://").map(|(scheme, _)| scheme) {
+        Some("http") if !http.is_empty() => http,
+        Some("https") if !https.is_empty() => https,
+        // The SOCKS proxy's URL carries the credentials itself
+        _ => {
+            return socks_proxy_url(&socks, auth.as_ref())
+                .map(|u| vec![format!("--proxy {}", quote(&u))])
+                .unwrap_or_default()
+        }
+    };
+    let mut args = vec![format!("--proxy {}", quote(&proxy))];
+    if let Some(ProxySettingAuth { user, password }) = auth {
+        args.push(format!("--proxy-user {}", quote(&format!("{user}:{password}"))));
+    }
+    args
+}
+
+fn format_seconds(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    match secs.fract() == 0.0 {
+        true => format!("{secs:.0}"),
+        false => format!("{secs}"),
+    }
+}
+
+/// Quote an argument for a POSIX shell
+fn quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// Quote raw bytes with ANSI-C quoting, so bytes that aren't printable survive the shell
+fn quote_bytes(bytes: &[u8]) -> String {
+    let mut quoted = String::from("$'");
+    for b in bytes {
+        match b {
+            b'\'' | b'\\' => quoted.push_str(&format!("\\{}", *b as char)),
+            0x20..=0x7e => quoted.push(*b as char),
+            _ => quoted.push_str(&format!("\\x{b:02x}")),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curl_export::request_to_curl;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+    use yaak_models::models::{
+        HttpRequest, HttpRequestHeader, HttpUrlParameter, HttpVersionSetting, ProxySetting,
+        ProxySettingAuth, TlsVersionSetting, Workspace,
+    };
+
+    fn workspace() -> Workspace {
+        Workspace {
+            setting_validate_certificates: true,
+            setting_follow_redirects: false,
+            setting_send_default_accept: true,
+            ..Default::default()
+        }
+    }
+
+    fn body(value: serde_json::Value) -> BTreeMap<String, serde_json::Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn get_with_params_headers_and_auth() {
+        let request = HttpRequest {
+            method: "GET".to_string(),
+            url: "yaak.test/users".to_string(),
+            url_parameters: vec![
+                HttpUrlParameter {
+                    enabled: true,
+                    name: "q".to_string(),
+                    value: "it's me".to_string(),
+                },
+                HttpUrlParameter {
+                    enabled: false,
+                    name: "page".to_string(),
+                    value: "2".to_string(),
+                },
+            ],
+            headers: vec![HttpRequestHeader {
+                enabled: true,
+                name: "Accept".to_string(),
+                value: "application/json".to_string(),
+            }],
+            authentication_type: Some("basic".to_string()),
+            authentication: body(json!({"username": "user", "password": "pass"})),
+            ..Default::default()
+        };
+        assert_eq!(
+            request_to_curl(&workspace(), &request, None).unwrap(),
+            "curl \\\n  --url 'http://yaak.test/users?q=it%27s+me' \\\n  --user-agent 'yaak' \\\n  \
+             --header 'Accept: application/json' \\\n  --user 'user:pass'"
+        );
+    }
+
+    #[test]
+    fn client_settings() {
+        let workspace = Workspace {
+            setting_user_agent: "my-agent".to_string(),
+            setting_send_default_accept: false,
+            setting_http_version: HttpVersionSetting::Http1,
+            setting_tls_min_version: Some(TlsVersionSetting::Tls1_2),
+            setting_proxy: Some(ProxySetting::Enabled {
+                http: "http://proxy.test:8080".to_string(),
+                https: "".to_string(),
+                socks: "socks.test".to_string(),
+                auth: Some(ProxySettingAuth {
+                    user: "me".to_string(),
+                    password: "pw".to_string(),
+                }),
+            }),
+            ..workspace()
+        };
+        let request = |url: &str| HttpRequest {
+            method: "GET".to_string(),
+            url: url.to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            request_to_curl(&workspace, &request("http://yaak.test"), None).unwrap(),
+            "curl \\\n  --url 'http://yaak.test' \\\n  --http1.1 \\\n  --tlsv1.2 \\\n  --proxy \
+             'http://proxy.test:8080' \\\n  --proxy-user 'me:pw' \\\n  --user-agent 'my-agent' \
+             \\\n  --header 'Accept:'"
+        );
+
+        let https = request_to_curl(&workspace, &request("https://yaak.test"), None).unwrap();
+        assert!(https.contains("--proxy 'socks5h://me:pw@socks.test'"), "{https}");
+        assert!(!https.contains("--proxy-user"), "{https}");
+
+        // The global proxy only applies when the workspace doesn't set one
+        let global = Some(ProxySetting::Disabled);
+        let unproxied = Workspace {
+            setting_proxy: None,
+            ..workspace
+        };
+        let curl = request_to_curl(&unproxied, &request("https://yaak.test"), global).unwrap();
+        assert!(curl.contains("--noproxy '*'"), "{curl}");
+    }
+
+    #[test]
+    fn refuse_huge_repeated_body() {
+        let request = |count: u64| HttpRequest {
+            method: "POST".to_string(),
+            url: "https://yaak.test".to_string(),
+            body_type: Some("chunked".to_string()),
+            body: body(json!({"chunk": "abcd", "count": count})),
+            ..Default::default()
+        };
+        assert!(request_to_curl(&workspace(), &request(3), None)
+            .unwrap()
+            .ends_with("'abcdabcdabcd'"));
+        assert!(request_to_curl(&workspace(), &request(u64::MAX), None).is_err());
+    }
+
+    #[test]
+    fn body_types() {
+        let curl = |body_type: &str, b: serde_json::Value| {
+            let request = HttpRequest {
+                method: "POST".to_string(),
+                url: "https://yaak.test".to_string(),
+                body_type: Some(body_type.to_string()),
+                body: body(b),
+                ..Default::default()
+            };
+            let curl = request_to_curl(&workspace(), &request, None).unwrap();
+            let prefix =
+                "curl \\\n  --request POST \\\n  --url 'https://yaak.test' \\\n  --user-agent \
+                 'yaak' \\\n  ";
+            curl.strip_prefix(prefix).unwrap().to_string()
+        };
+
+        assert_eq!(curl("application/json", json!({"text": "{\"a\":'b'}"})), {
+            r#"--data-raw '{"a":'\''b'\''}'"#
+        });
+        assert_eq!(
+            curl("graphql", json!({"query": "{ me }", "variables": ""})),
+            r#"--data-raw '{"query":"{ me }"}'"#
+        );
+        assert_eq!(
+            curl(
+                "application/x-www-form-urlencoded",
+                json!({"form": [
+                    {"enabled": true, "name": "a", "value": "1 2"},
+                    {"enabled": false, "name": "b", "value": "3"},
+                ]})
+            ),
+            "--data-urlencode 'a=1 2'"
+        );
+        assert_eq!(
+            curl("binary", json!({"filePath": "/tmp/body.bin"})),
+            "--data-binary '@/tmp/body.bin'"
+        );
+        assert_eq!(
+            curl("bytes", json!({"text": "00ff27", "encoding": "hex"})),
+            "--header 'Content-Type: application/octet-stream' \\\n  --data-binary $'\\x00\\xff\\''"
+        );
+        assert_eq!(
+            curl(
+                "multipart/form-data",
+                json!({"form": [
+                    {"enabled": true, "name": "name", "value": "@me"},
+                    {"enabled": true, "name": "meta", "value": "{}", "contentType": "application/json"},
+                    {"enabled": true, "name": "avatar", "file": "/tmp/a.png"},
+                ]})
+            ),
+            "--form-string 'name=@me' \\\n  --form 'meta={};type=application/json' \\\n  --form \
+             'avatar=@/tmp/a.png'"
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use md5::Md5;
+use rand::RngCore;
+use sha2::{Digest, Sha256, Sha512_256};
+
+/// Username and password for a request using digest authentication
+#[derive(Debug, Clone)]
+pub struct DigestCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Algorithm {
+    Md5,
+    Sha256,
+    Sha512_256,
+}
+
+impl Algorithm {
+    fn parse(name: &str) -> Option<(Algorithm, bool)> {
+        let name = name.to_ascii_uppercase();
+        let (name, sess) = match name.strip_suffix("-SESS") {
+            Some(n) => (n.to_string(), true),
+            None => (name, false),
+        };
+        let algorithm = match name.as_str() {
+            "MD5" => Algorithm::Md5,
+            "SHA-256" => Algorithm::Sha256,
+            "SHA-512-256" => Algorithm::Sha512_256,
+            _ => return None,
+        };
+        Some((algorithm, sess))
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Md5 => "MD5",
+            Algorithm::Sha256 => "SHA-256",
+            Algorithm::Sha512_256 => "SHA-512-256",
+        }
+    }
+
+    fn hash(&self, data: &[u8]) -> String {
+        match self {
+            Algorithm::Md5 => format!("{:x}", Md5::digest(data)),
+            Algorithm::Sha256 => format!("{:x}", Sha256::digest(data)),
+            Algorithm::Sha512_256 => format!("{:x}", Sha512_256::digest(data)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Qop {
+    Auth,
+    AuthInt,
+}
+
+/// A `WWW-Authenticate: Digest` challenge from the server (RFC 7616)
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestChallenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    algorithm: Algorithm,
+    sess: bool,
+    qop: Option<Qop>,
+    userhash: bool,
+}
+
+/// Parse the strongest digest challenge from a response's `WWW-Authenticate` header values.
+/// Challenges using an algorithm we don't support are skipped.
+pub fn parse_digest_challenge<'a>(
+    header_values: impl IntoIterator<Item = &'a str>,
+) -> Option<DigestChallenge> {
+    header_values
+        .into_iter()
+        .flat_map(split_challenges)
+        .filter_map(|c| parse_challenge(&c))
+        .max_by_key(|c| match c.algorithm {
+            Algorithm::Md5 => 0,
+            Algorithm::Sha512_256 => 1,
+            Algorithm::Sha256 => 2,
+        })
+}
+
+/// The `Authorization` header value answering a challenge. The nonce count is always 1,
+/// because every request gets a fresh challenge instead of reusing the nonce.
+pub fn digest_authorization(
+    challenge: &DigestChallenge,
+    credentials: &DigestCredentials,
+    method: &str,
+    uri: &str,
+    body: Option<&[u8]>,
+) -> String {
+    let mut cnonce = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut cnonce);
+    let cnonce: String = cnonce.iter().map(|b| format!("{b:02x}")).collect();
+    authorization_with_cnonce(challenge, credentials, method, uri, body, &cnonce)
+}
+
+fn authorization_with_cnonce(
+    challenge: &DigestChallenge,
+    credentials: &DigestCredentials,
+    method: &str,
+    uri: &str,
+    body: Option<&[u8]>,
+    cnonce: &str,
+) -> String {
+    let c = challenge;
+    let h = |s: &str| c.algorithm.hash(s.as_bytes());
+    let nc = "00000001";
+
+    let mut ha1 = h(&format!("{}:{}:{}", credentials.username, c.realm, credentials.password));
+    if c.sess {
+        ha1 = h(&format!("{ha1}:{}:{cnonce}", c.nonce));
+    }
+
+    let ha2 = match c.qop {
+        // The body is hashed as sent, since it may not be text
+        Some(Qop::AuthInt) => {
+            h(&format!("{method}:{uri}:{}", c.algorithm.hash(body.unwrap_or_default())))
+        }
+        _ => h(&format!("{method}:{uri}")),
+    };
+
+    let response = match c.qop {
+        None => h(&format!("{ha1}:{}:{ha2}", c.nonce)),
+        Some(qop) => h(&format!("{ha1}:{}:{nc}:{cnonce}:{}:{ha2}", c.nonce, qop_name(qop))),
+    };
+
+    let username = match c.userhash {
+        true => h(&format!("{}:{}", credentials.username, c.realm)),
+        false => credentials.username.clone(),
+    };
+    let algorithm = match c.sess {
+        true => format!("{}-sess", c.algorithm.name()),
+        false => c.algorithm.name().to_string(),
+    };
+
+    let mut params = vec![
+        format!("username=\"{}\"", quote(&username)),
+        format!("realm=\"{}\"", quote(&c.realm)),
+        format!("nonce=\"{}\"", quote(&c.nonce)),
+        format!("uri=\"{}\"", quote(uri)),
+        format!("algorithm={algorithm}"),
+        format!("response=\"{response}\""),
+    ];
+    if let Some(qop) = c.qop {
+        params.push(format!("qop={}", qop_name(qop)));
+        params.push(format!("nc={nc}"));
+        params.push(format!("cnonce=\"{cnonce}\""));
+    }
+    if let Some(opaque) = &c.opaque {
+        params.push(format!("opaque=\"{}\"", quote(opaque)));
+    }
+    if c.userhash {
+        params.push("userhash=true".to_string());
+    }
+
+    format!("Digest {}", params.join(", "))
+}
+
+fn qop_name(qop: Qop) -> &'static str {
+    match qop {
+        Qop::Auth => "auth",
+        Qop::AuthInt => "auth-int",
+    }
+}
+
+fn quote(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn parse_challenge(challenge: &str) -> Option<DigestChallenge> {
+    let (scheme, params) = challenge.trim().split_once(char::is_whitespace)?;
+    if !scheme.eq_ignore_ascii_case("digest") {
+        return None;
+    }
+
+    let params = parse_params(params);
+    let param = |name: &str| {
+        params.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.clone())
+    };
+
+    let (algorithm, sess) = match param("algorithm") {
+        None => (Algorithm::Md5, false),
+        Some(a) => Algorithm::parse(&a)?,
+    };
+
+    // Prefer plain auth when the server offers both, since auth-int needs the whole body
+    let qop = param("qop").map(|q| {
+        let options: Vec<String> = q.split(',').map(|o| o.trim().to_ascii_lowercase()).collect();
+        match options.iter().any(|o| o == "auth") {
+            true => Some(Qop::Auth),
+            false if options.iter().any(|o| o == "auth-int") => Some(Qop::AuthInt),
+            false => None,
+        }
+    });
+
+    Some(DigestChallenge {
+        realm: param("realm").unwrap_or_default(),
+        nonce: param("nonce")?,
+        opaque: param("opaque"),
+        algorithm,
+        sess,
+        qop: qop.unwrap_or_default(),
+        userhash: param("userhash").is_some_and(|u| u.eq_ignore_ascii_case("true")),
+    })
+}
+
+/// Split a header value holding several challenges, like `Digest a=1, Digest b=2`, on the
+/// commas that start a new scheme rather than a new parameter
+fn split_challenges(value: &str) -> Vec<String> {
+    let mut challenges: Vec<String> = Vec::new();
+    for part in split_unquoted(value, ',') {
+        let starts_scheme = part
+            .trim()
+            .split_once(char::is_whitespace)
+            .is_some_and(|(first, _)| !first.contains('='));
+        match challenges.last_mut() {
+            Some(last) if !starts_scheme => {
+                last.push(',');
+                last.push_str(&part);
+            }
+            _ => challenges.push(part),
+        }
+    }
+    challenges
+}
+
+fn parse_params(params: &str) -> Vec<(String, String)> {
+    split_unquoted(params, ',')
+        .iter()
+        .filter_map(|p| {
+            let (k, v) = p.split_once('=')?;
+            Some((k.trim().to_string(), unquote(v.trim())))
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> String {
+    let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+        return value.to_string();
+    };
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next()),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn split_unquoted(value: &str, sep: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for c in value.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            c if c == sep && !in_quotes => {
+                parts.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::digest_auth::{
+        authorization_with_cnonce, parse_digest_challenge, Algorithm, DigestChallenge,
+        DigestCredentials, Qop,
+    };
+
+    const CHALLENGE: &str = r#"Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=SHA-256, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS", Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=MD5, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#;
+    const CNONCE: &str = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+
+    fn credentials() -> DigestCredentials {
+        DigestCredentials {
+            username: "Mufasa".to_string(),
+            password: "Circle of Life".to_string(),
+        }
+    }
+
+    #[test]
+    fn strongest_challenge_wins() {
+        let c = parse_digest_challenge([CHALLENGE]).unwrap();
+        assert_eq!(c.algorithm, Algorithm::Sha256);
+        assert_eq!(c.realm, "http-auth@example.org");
+        assert_eq!(c.nonce, "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v");
+        assert_eq!(c.qop, Some(Qop::Auth));
+
+        let c = parse_digest_challenge(["Basic realm=\"x\"", "Digest nonce=\"n\", realm=\"r\""]);
+        assert_eq!(c.map(|c| (c.algorithm, c.qop)), Some((Algorithm::Md5, None)));
+        assert_eq!(parse_digest_challenge(["Digest algorithm=SHA-1, nonce=\"n\""]), None);
+    }
+
+    #[test]
+    fn rfc7616_responses() {
+        // The example from RFC 7616 section 3.9.1
+        let sha256 = parse_digest_challenge([CHALLENGE]).unwrap();
+        let header = authorization_with_cnonce(
+            &sha256,
+            &credentials(),
+            "GET",
+            "/dir/index.html",
+            None,
+            CNONCE,
+        );
+        assert!(header.starts_with("Digest username=\"Mufasa\", "));
+        assert!(header.contains(
+            "response=\"753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1\""
+        ));
+        assert!(header.contains("qop=auth, nc=00000001, cnonce=\"f2/wE4q74E6zIJEtWaHKaf5wv"));
+        assert!(header.contains("opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\""));
+
+        let md5 = DigestChallenge {
+            algorithm: Algorithm::Md5,
+            ..sha256
+        };
+        let header =
+            authorization_with_cnonce(&md5, &credentials(), "GET", "/dir/index.html", None, CNONCE);
+        assert!(header.contains("algorithm=MD5, response=\"8ca523f5e9506fed4657c9700eebdbec\""));
+    }
+
+    #[test]
+    fn auth_int_hashes_raw_body() {
+        let challenge = DigestChallenge {
+            qop: Some(Qop::AuthInt),
+            ..parse_digest_challenge([CHALLENGE]).unwrap()
+        };
+        let header = |body: &[u8]| {
+            authorization_with_cnonce(&challenge, &credentials(), "POST", "/", Some(body), CNONCE)
+        };
+        assert!(header(b"").contains("qop=auth-int"));
+        // Both bodies are the same once decoded lossily as text
+        assert_ne!(header(&[0xff]), header(&[0xfe]));
+    }
+}
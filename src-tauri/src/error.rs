@@ -0,0 +1,150 @@
+use std::fmt::{Display, Formatter};
+
+use serde::Serialize;
+
+/// Broad category of a command error, so the frontend can branch on it without parsing the
+/// message
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    NotFound,
+    Validation,
+    Network,
+    Database,
+    Io,
+    Plugin,
+    /// The action needs the user to confirm it first, like sending to a production environment
+    ConfirmationRequired,
+    Unknown,
+}
+
+/// Error returned from commands. It serializes to `{ kind, message, details }` and displays as
+/// just the message, which is the same string commands used to return.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppError {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub details: Option<String>,
+}
+
+impl AppError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        AppError {
+            kind,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+}
+
+impl Display for AppError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::new(ErrorKind::Unknown, message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::new(ErrorKind::Unknown, message)
+    }
+}
+
+impl From<yaak_models::error::Error> for AppError {
+    fn from(e: yaak_models::error::Error) -> Self {
+        use yaak_models::error::Error::*;
+        let kind = match &e {
+            ModelNotFound(_) => ErrorKind::NotFound,
+            SqlError(_) if e.is_not_found() => ErrorKind::NotFound,
+            AmbiguousModel(_) | InvalidBackup(_) | InvalidColor(_) | JsonError(_) => {
+                ErrorKind::Validation
+            }
+            SqlError(_) => ErrorKind::Database,
+            IoError(_) => ErrorKind::Io,
+            Unknown => ErrorKind::Unknown,
+        };
+        AppError::new(kind, e.to_string())
+    }
+}
+
+impl From<yaak_plugin_runtime::error::Error> for AppError {
+    fn from(e: yaak_plugin_runtime::error::Error) -> Self {
+        AppError::new(ErrorKind::Plugin, e.to_string())
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(e: reqwest::Error) -> Self {
+        let err = AppError::new(ErrorKind::Network, e.to_string());
+        match e.url() {
+            Some(url) => err.with_details(url.to_string()),
+            None => err,
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        let kind = match e.kind() {
+            std::io::ErrorKind::NotFound => ErrorKind::NotFound,
+            _ => ErrorKind::Io,
+        };
+        AppError::new(kind, e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::new(ErrorKind::Validation, e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{AppError, ErrorKind};
+    use serde_json::json;
+
+    #[test]
+    fn display_is_the_message() {
+        let err = AppError::from(yaak_models::error::Error::ModelNotFound("rq_1".to_string()));
+        assert_eq!(err.to_string(), "Model not found rq_1");
+        assert_eq!(err.kind, ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn maps_error_kinds() {
+        use yaak_models::error::Error;
+        let kind = |e: Error| AppError::from(e).kind;
+        assert_eq!(kind(Error::AmbiguousModel("env".to_string())), ErrorKind::Validation);
+        assert_eq!(kind(Error::InvalidColor("#zz".to_string())), ErrorKind::Validation);
+        assert_eq!(kind(Error::Unknown), ErrorKind::Unknown);
+
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        assert_eq!(AppError::from(io).kind, ErrorKind::NotFound);
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        assert_eq!(AppError::from(io).kind, ErrorKind::Io);
+        assert_eq!(AppError::from("oops").kind, ErrorKind::Unknown);
+    }
+
+    #[test]
+    fn serializes_kind_and_message() {
+        let err = AppError::new(ErrorKind::NotFound, "gone").with_details("rq_1");
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({ "kind": "not_found", "message": "gone", "details": "rq_1" })
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// Version of the encrypted export format, to bump if the cipher or key derivation changes
+const ENCRYPTED_EXPORT_VERSION: i64 = 1;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// An export sealed with a passphrase. The key is derived from the passphrase with Argon2id,
+/// using a random salt, and the export is encrypted with AES-256-GCM, so a wrong passphrase or
+/// a tampered file fails to decrypt rather than producing garbage.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptedExport {
+    yaak_encrypted: i64,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Encrypt the contents of an export with a passphrase, returning the file to write
+pub fn encrypt_export(contents: &[u8], passphrase: &str) -> Result<String, String> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let cipher = cipher(passphrase, &salt)?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), contents)
+        .map_err(|e| format!("Failed to encrypt export: {e}"))?;
+
+    let export = EncryptedExport {
+        yaak_encrypted: ENCRYPTED_EXPORT_VERSION,
+        salt: BASE64_STANDARD.encode(salt),
+        nonce: BASE64_STANDARD.encode(nonce),
+        ciphertext: BASE64_STANDARD.encode(ciphertext),
+    };
+    serde_json::to_string_pretty(&export).map_err(|e| e.to_string())
+}
+
+/// Whether a file is an export encrypted with `encrypt_export`
+pub fn is_encrypted_export(contents: &str) -> bool {
+    serde_json::from_str::<EncryptedExport>(contents).is_ok()
+}
+
+/// Decrypt a file written by `encrypt_export`, returning the original export
+pub fn decrypt_export(contents: &str, passphrase: &str) -> Result<String, String> {
+    let export: EncryptedExport =
+        serde_json::from_str(contents).map_err(|e| format!("Invalid encrypted export: {e}"))?;
+    if export.yaak_encrypted != ENCRYPTED_EXPORT_VERSION {
+        return Err(format!(
+            "Unsupported encrypted export version {}. Update Yaak to import it.",
+            export.yaak_encrypted
+        ));
+    }
+
+    let decode =
+        |v: &str| BASE64_STANDARD.decode(v).map_err(|e| format!("Invalid encrypted export: {e}"));
+    let salt = decode(&export.salt)?;
+    let nonce = decode(&export.nonce)?;
+    let ciphertext = decode(&export.ciphertext)?;
+    if nonce.len() != NONCE_LEN {
+        return Err("Invalid encrypted export: bad nonce".to_string());
+    }
+
+    let plaintext = cipher(passphrase, &salt)?
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| "Incorrect passphrase, or the file is damaged".to_string())?;
+    String::from_utf8(plaintext).map_err(|e| format!("Invalid encrypted export: {e}"))
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<Aes256Gcm, String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive key from passphrase: {e}"))?;
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export_encryption::{decrypt_export, encrypt_export, is_encrypted_export};
+
+    #[test]
+    fn round_trip() {
+        let export = r#"{"yaakSchema":2,"resources":{}}"#;
+        let encrypted = encrypt_export(export.as_bytes(), "hunter2").unwrap();
+        assert!(is_encrypted_export(&encrypted));
+        assert!(!encrypted.contains("yaakSchema"));
+        assert_eq!(decrypt_export(&encrypted, "hunter2").unwrap(), export);
+    }
+
+    #[test]
+    fn wrong_passphrase_fails() {
+        let encrypted = encrypt_export(b"{}", "hunter2").unwrap();
+        assert_eq!(
+            decrypt_export(&encrypted, "hunter3"),
+            Err("Incorrect passphrase, or the file is damaged".to_string())
+        );
+    }
+
+    #[test]
+    fn plain_exports_are_not_encrypted() {
+        assert!(!is_encrypted_export(r#"{"yaakSchema":2,"resources":{}}"#));
+        assert!(!is_encrypted_export("curl https://yaak.app"));
+    }
+}
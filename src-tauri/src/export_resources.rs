@@ -1,7 +1,12 @@
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use tauri::{Manager, WebviewWindow};
-use yaak_models::models::{Environment, Folder, GrpcRequest, HttpRequest, Workspace};
+use yaak_models::models::{
+    Environment, EnvironmentVariable, Folder, GrpcRequest, HttpRequest, ProxySetting, Workspace,
+};
 
 #[derive(Default, Debug, Deserialize, Serialize)]
 #[serde(default, rename_all = "camelCase")]
@@ -27,6 +32,88 @@ pub struct ImportResult {
     pub resources: WorkspaceExportResources,
 }
 
+#[derive(Default, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedResources {
+    #[serde(flatten)]
+    pub resources: WorkspaceExportResources,
+    pub created: usize,
+    pub updated: usize,
+}
+
+impl ImportedResources {
+    pub fn count(&mut self, updated: bool) {
+        if updated {
+            self.updated += 1;
+        } else {
+            self.created += 1;
+        }
+    }
+}
+
+/// Hash a model's content, ignoring its id and timestamps, so that the same model
+/// coming from two separate imports can be matched up. The digest is taken over JSON
+/// with sorted keys, so it stays the same across builds and field reordering.
+pub fn model_hash<T: Serialize>(model: &T) -> String {
+    let mut value = serde_json::to_value(model).unwrap_or_default();
+    if let Some(obj) = value.as_object_mut() {
+        for key in ["id", "createdAt", "updatedAt"] {
+            obj.remove(key);
+        }
+    }
+
+    format!("{:x}", Sha256::digest(canonical_json(value).to_string()))
+}
+
+fn canonical_json(value: Value) -> Value {
+    match value {
+        Value::Object(obj) => {
+            let mut entries: Vec<(String, Value)> = obj.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(entries.into_iter().map(|(k, v)| (k, canonical_json(v))).collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(canonical_json).collect()),
+        v => v,
+    }
+}
+
+/// Existing models indexed by content hash, so merging an import hashes each of
+/// them once rather than once per imported model.
+#[derive(Default)]
+pub struct ModelHashIndex {
+    unclaimed: HashSet<String>,
+    by_hash: HashMap<String, Vec<String>>,
+}
+
+impl ModelHashIndex {
+    pub fn new<T: Serialize>(models: &[T], id: impl Fn(&T) -> &str) -> Self {
+        let mut index = Self::default();
+        for m in models {
+            let id = id(m).to_string();
+            index.by_hash.entry(model_hash(m)).or_default().push(id.clone());
+            index.unclaimed.insert(id);
+        }
+        index
+    }
+
+    /// Find an unclaimed model with the same id, or failing that the same content
+    pub fn find<T: Serialize>(&self, id: &str, model: &T) -> Option<&str> {
+        if let Some(id) = self.unclaimed.get(id) {
+            return Some(id);
+        }
+        if self.by_hash.is_empty() {
+            return None;
+        }
+        let ids = self.by_hash.get(&model_hash(model))?;
+        ids.iter().find(|id| self.unclaimed.contains(id.as_str())).map(|id| id.as_str())
+    }
+
+    /// Mark a model as imported into, so no other imported model merges with it
+    pub fn claim(&mut self, id: &str) {
+        self.unclaimed.remove(id);
+    }
+}
+
 pub async fn get_workspace_export_resources(
     window: &WebviewWindow,
     workspace_ids: Vec<&str>,
@@ -46,16 +133,20 @@ pub async fn get_workspace_export_resources(
     };
 
     for workspace_id in workspace_ids {
-        data.resources.workspaces.push(
-            yaak_models::queries::get_workspace(window, workspace_id)
-                .await
-                .expect("Failed to get workspace"),
-        );
-        data.resources.environments.append(
-            &mut yaak_models::queries::list_environments(window, workspace_id)
-                .await
-                .expect("Failed to get environments"),
-        );
+        let mut workspace = yaak_models::queries::get_workspace(window, workspace_id)
+            .await
+            .expect("Failed to get workspace");
+        mask_secrets(&mut workspace.variables);
+        mask_proxy_password(&mut workspace.setting_proxy);
+        data.resources.workspaces.push(workspace);
+
+        let mut environments = yaak_models::queries::list_environments(window, workspace_id)
+            .await
+            .expect("Failed to get environments");
+        for e in environments.iter_mut() {
+            mask_secrets(&mut e.variables);
+        }
+        data.resources.environments.append(&mut environments);
         data.resources.folders.append(
             &mut yaak_models::queries::list_folders(window, workspace_id)
                 .await
@@ -75,3 +166,198 @@ pub async fn get_workspace_export_resources(
 
     return data;
 }
+
+/// Blank out the values of secret variables, so they never end up in an export. The variables
+/// themselves are kept, so whoever imports it knows which values to fill in.
+fn mask_secrets(variables: &mut [EnvironmentVariable]) {
+    for v in variables.iter_mut().filter(|v| v.secret) {
+        v.value = String::new();
+    }
+}
+
+/// Blank out the password of a proxy, keeping the user so whoever imports it knows what to fill in
+fn mask_proxy_password(proxy: &mut Option<ProxySetting>) {
+    if let Some(ProxySetting::Enabled {
+        auth: Some(auth), ..
+    }) = proxy
+    {
+        auth.password = String::new();
+    }
+}
+
+/// Keep the saved proxy password that an import left blank, if it's for the same user
+pub fn restore_masked_proxy_password(
+    imported: &mut Option<ProxySetting>,
+    saved: &Option<ProxySetting>,
+) {
+    let (
+        Some(ProxySetting::Enabled {
+            auth: Some(imported),
+            ..
+        }),
+        Some(ProxySetting::Enabled {
+            auth: Some(saved), ..
+        }),
+    ) = (imported, saved)
+    else {
+        return;
+    };
+    if imported.password.is_empty() && imported.user == saved.user {
+        imported.password = saved.password.clone();
+    }
+}
+
+/// Keep the saved values of secrets that an import left blank, like those of a masked export
+/// imported over the models it came from. Secrets are matched by name, in order.
+pub fn restore_masked_secrets(imported: &mut [EnvironmentVariable], saved: &[EnvironmentVariable]) {
+    let mut saved_secrets: Vec<&EnvironmentVariable> = saved.iter().filter(|v| v.secret).collect();
+    for v in imported.iter_mut().filter(|v| v.secret && v.value.is_empty()) {
+        if let Some(i) = saved_secrets.iter().position(|s| s.name == v.name) {
+            v.value = saved_secrets.remove(i).value.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export_resources::{
+        mask_proxy_password, mask_secrets, model_hash, restore_masked_proxy_password,
+        restore_masked_secrets, ModelHashIndex,
+    };
+    use serde_json::json;
+    use yaak_models::models::{EnvironmentVariable, HttpRequest, ProxySetting, ProxySettingAuth};
+
+    #[test]
+    fn hash_ignores_id_and_timestamps() {
+        let a = HttpRequest {
+            id: "rq_a".to_string(),
+            url: "https://yaak.app".to_string(),
+            ..Default::default()
+        };
+        let b = HttpRequest {
+            id: "rq_b".to_string(),
+            updated_at: chrono::Utc::now().naive_utc(),
+            ..a.clone()
+        };
+        assert_eq!(model_hash(&a), model_hash(&b));
+    }
+
+    #[test]
+    fn hash_changes_with_content() {
+        let a = HttpRequest {
+            url: "https://yaak.app".to_string(),
+            ..Default::default()
+        };
+        let b = HttpRequest {
+            url: "https://yaak.app/docs".to_string(),
+            ..a.clone()
+        };
+        assert_ne!(model_hash(&a), model_hash(&b));
+    }
+
+    #[test]
+    fn hash_ignores_key_order() {
+        let a = json!({"name": "a", "headers": [{"name": "x", "value": "1"}]});
+        let b = json!({"headers": [{"value": "1", "name": "x"}], "name": "a"});
+        assert_eq!(model_hash(&a), model_hash(&b));
+    }
+
+    #[test]
+    fn hash_index_matches_unclaimed_models() {
+        let a = HttpRequest {
+            id: "rq_a".to_string(),
+            url: "https://yaak.app".to_string(),
+            ..Default::default()
+        };
+        let b = HttpRequest {
+            id: "rq_b".to_string(),
+            ..a.clone()
+        };
+        let mut index = ModelHashIndex::new(&[a.clone(), b], |m| &m.id);
+        let other = HttpRequest::default();
+
+        assert_eq!(index.find("rq_b", &other), Some("rq_b"));
+        assert_eq!(index.find("rq_new", &a), Some("rq_a"));
+        index.claim("rq_a");
+        assert_eq!(index.find("rq_new", &a), Some("rq_b"));
+        index.claim("rq_b");
+        assert_eq!(index.find("rq_new", &a), None);
+    }
+
+    #[test]
+    fn secrets_are_masked() {
+        let mut variables = vec![
+            EnvironmentVariable {
+                name: "host".to_string(),
+                value: "yaak.app".to_string(),
+                ..Default::default()
+            },
+            EnvironmentVariable {
+                name: "token".to_string(),
+                value: "abc123".to_string(),
+                secret: true,
+                ..Default::default()
+            },
+        ];
+        mask_secrets(&mut variables);
+        let values: Vec<&str> = variables.iter().map(|v| v.value.as_str()).collect();
+        assert_eq!(values, vec!["yaak.app", ""]);
+        assert!(variables[1].secret);
+    }
+
+    #[test]
+    fn masked_secrets_keep_saved_values() {
+        let secret = |name: &str, value: &str| EnvironmentVariable {
+            enabled: true,
+            name: name.to_string(),
+            value: value.to_string(),
+            secret: true,
+        };
+        let saved = [
+            secret("token", "abc"),
+            secret("token", "def"),
+            secret("key", "xyz"),
+        ];
+        let mut imported = vec![
+            secret("token", ""),
+            secret("token", "new"),
+            secret("token", ""),
+            secret("pw", ""),
+        ];
+        restore_masked_secrets(&mut imported, &saved);
+        let values: Vec<&str> = imported.iter().map(|v| v.value.as_str()).collect();
+        assert_eq!(values, vec!["abc", "new", "def", ""]);
+    }
+
+    #[test]
+    fn proxy_password_is_masked() {
+        let proxy = |user: &str, password: &str| {
+            Some(ProxySetting::Enabled {
+                http: "proxy.test:8080".to_string(),
+                https: String::new(),
+                socks: String::new(),
+                auth: Some(ProxySettingAuth {
+                    user: user.to_string(),
+                    password: password.to_string(),
+                }),
+            })
+        };
+        let password = |p: &Option<ProxySetting>| match p {
+            Some(ProxySetting::Enabled {
+                auth: Some(auth), ..
+            }) => auth.password.clone(),
+            _ => panic!("Expected proxy auth"),
+        };
+
+        let mut exported = proxy("yaak", "hunter2");
+        mask_proxy_password(&mut exported);
+        assert_eq!(password(&exported), "");
+
+        restore_masked_proxy_password(&mut exported, &proxy("yaak", "hunter2"));
+        assert_eq!(password(&exported), "hunter2");
+
+        let mut other_user = proxy("other", "");
+        restore_masked_proxy_password(&mut other_user, &proxy("yaak", "hunter2"));
+        assert_eq!(password(&other_user), "");
+    }
+}
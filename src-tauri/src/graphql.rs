@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+use yaak_models::models::HttpRequest;
+
+/// Key value (in the global namespace) that a request's introspected schema is cached under
+pub fn introspection_key(request_id: &str) -> String {
+    format!("graphql_introspection::{request_id}")
+}
+
+/// Copy of the request that asks the server for its schema instead of running the request's query.
+/// The request's scripts are left out, so fetching the schema doesn't change any variables.
+pub fn introspection_request(request: &HttpRequest) -> HttpRequest {
+    HttpRequest {
+        id: "".to_string(),
+        body_type: Some("graphql".to_string()),
+        body: BTreeMap::from([
+            ("query".to_string(), Value::String(INTROSPECTION_QUERY.to_string())),
+            ("variables".to_string(), Value::String("".to_string())),
+        ]),
+        pre_request_script: "".to_string(),
+        post_response_script: "".to_string(),
+        ..request.clone()
+    }
+}
+
+/// The `data` of an introspection response, which is what the frontend builds the schema from
+pub fn parse_introspection(status: i32, body: &str) -> Result<Value, String> {
+    if !(200..300).contains(&status) {
+        return Err(format!("Request failed with status {status}.\n\n{body}"));
+    }
+    if body.trim().is_empty() {
+        return Err("Empty body returned in response".to_string());
+    }
+
+    let mut response: Value =
+        serde_json::from_str(body).map_err(|e| format!("Invalid introspection response: {e}"))?;
+    match response.get_mut("data").map(Value::take) {
+        Some(data) if data.get("__schema").is_some() => Ok(data),
+        _ => {
+            let errors = response["errors"].as_array().cloned().unwrap_or_default();
+            let messages: Vec<&str> = errors.iter().filter_map(|e| e["message"].as_str()).collect();
+            if messages.is_empty() {
+                Err("Introspection response has no schema".to_string())
+            } else {
+                Err(format!("Introspection failed: {}", messages.join("\n")))
+            }
+        }
+    }
+}
+
+/// Same as `getIntrospectionQuery()` from graphql-js, which the editor builds the schema with
+const INTROSPECTION_QUERY: &str = r#"
+    query IntrospectionQuery {
+      __schema {
+        queryType { name }
+        mutationType { name }
+        subscriptionType { name }
+        types {
+          ...FullType
+        }
+        directives {
+          name
+          description
+          locations
+          args {
+            ...InputValue
+          }
+        }
+      }
+    }
+
+    fragment FullType on __Type {
+      kind
+      name
+      description
+      fields(includeDeprecated: true) {
+        name
+        description
+        args {
+          ...InputValue
+        }
+        type {
+          ...TypeRef
+        }
+        isDeprecated
+        deprecationReason
+      }
+      inputFields {
+        ...InputValue
+      }
+      interfaces {
+        ...TypeRef
+      }
+      enumValues(includeDeprecated: true) {
+        name
+        description
+        isDeprecated
+        deprecationReason
+      }
+      possibleTypes {
+        ...TypeRef
+      }
+    }
+
+    fragment InputValue on __InputValue {
+      name
+      description
+      type { ...TypeRef }
+      defaultValue
+    }
+
+    fragment TypeRef on __Type {
+      kind
+      name
+      ofType {
+        kind
+        name
+        ofType {
+          kind
+          name
+          ofType {
+            kind
+            name
+            ofType {
+              kind
+              name
+              ofType {
+                kind
+                name
+                ofType {
+                  kind
+                  name
+                  ofType {
+                    kind
+                    name
+                  }
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+  "#;
+
+#[cfg(test)]
+mod tests {
+    use crate::graphql::{introspection_request, parse_introspection};
+    use serde_json::json;
+    use yaak_models::models::HttpRequest;
+
+    #[test]
+    fn introspection_request_skips_scripts() {
+        let request = HttpRequest {
+            id: "rq_1".to_string(),
+            url: "https://yaak.app/graphql".to_string(),
+            pre_request_script: "yaak.variables.set('a', 1);".to_string(),
+            post_response_script: "yaak.variables.set('b', 2);".to_string(),
+            scripts_trusted: true,
+            ..Default::default()
+        };
+        let introspection = introspection_request(&request);
+        assert_eq!(introspection.id, "");
+        assert_eq!(introspection.url, request.url);
+        assert_eq!(introspection.body_type.as_deref(), Some("graphql"));
+        assert_eq!(introspection.pre_request_script, "");
+        assert_eq!(introspection.post_response_script, "");
+    }
+
+    #[test]
+    fn parse_introspection_data() {
+        let body = r#"{"data":{"__schema":{"queryType":{"name":"Query"}}}}"#;
+        assert_eq!(
+            parse_introspection(200, body),
+            Ok(json!({"__schema": {"queryType": {"name": "Query"}}}))
+        );
+    }
+
+    #[test]
+    fn parse_introspection_errors() {
+        assert_eq!(
+            parse_introspection(401, "Unauthorized"),
+            Err("Request failed with status 401.\n\nUnauthorized".to_string())
+        );
+        assert_eq!(
+            parse_introspection(200, r#"{"errors":[{"message":"Introspection is disabled"}]}"#),
+            Err("Introspection failed: Introspection is disabled".to_string())
+        );
+        assert_eq!(
+            parse_introspection(200, r#"{"data":null}"#),
+            Err("Introspection response has no schema".to_string())
+        );
+    }
+}
@@ -1,8 +1,28 @@
 use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
 
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use log::warn;
+use tauri::{Manager, Runtime, WebviewWindow};
+use tokio::sync::{mpsc, watch};
+use tokio_stream::wrappers::ReceiverStream;
 use KeyAndValueRef::{Ascii, Binary};
 
-use yaak_grpc::{KeyAndValueRef, MetadataMap};
+use yaak_grpc::manager::{DynamicMessage, GrpcHandle, DEFAULT_MAX_MESSAGE_SIZE};
+use yaak_grpc::{encode_message, GrpcChannelOptions, KeyAndValueRef, MetadataMap};
+use yaak_models::models::{
+    Environment, GrpcConnection, GrpcRequest, ProxySetting, RequestHistory, Workspace,
+};
+use yaak_models::queries::{get_or_create_settings, get_workspace, insert_request_history};
+use yaak_plugin_runtime::events::{RenderPurpose, WindowContext};
+
+use crate::http_request::os_user_name;
+use crate::proxy::grpc_proxy;
+use crate::render::render_grpc_request;
+use crate::template_callback::PluginTemplateCallback;
 
 pub fn metadata_to_map(metadata: MetadataMap) -> BTreeMap<String, String> {
     let mut entries = BTreeMap::new();
@@ -14,3 +34,355 @@ pub fn metadata_to_map(metadata: MetadataMap) -> BTreeMap<String, String> {
     }
     entries
 }
+
+/// Add a finished call to the request history. Failing to is logged rather than failing the call.
+pub async fn record_grpc_history<R: Runtime>(
+    window: &WebviewWindow<R>,
+    request: &GrpcRequest,
+    connection: &GrpcConnection,
+    environment_id: Option<String>,
+) {
+    let history = RequestHistory {
+        workspace_id: connection.workspace_id.clone(),
+        request_id: connection.request_id.clone(),
+        response_id: Some(connection.id.clone()),
+        environment_id,
+        sent_by: os_user_name(),
+        method: format!(
+            "{}/{}",
+            request.service.as_deref().unwrap_or_default(),
+            request.method.as_deref().unwrap_or_default()
+        ),
+        url: connection.url.clone(),
+        status: connection.status,
+        elapsed: connection.elapsed,
+        error: connection.error.clone(),
+        ..Default::default()
+    };
+    if let Err(e) = insert_request_history(window, &history).await {
+        warn!("Failed to record request history {e:?}");
+    }
+}
+
+/// Metadata to send for a rendered request: its enabled entries plus an Authorization entry
+/// from its auth settings
+pub fn build_metadata(req: &GrpcRequest) -> BTreeMap<String, String> {
+    let mut metadata = BTreeMap::new();
+
+    // Add the rest of metadata
+    for h in req.clone().metadata {
+        if h.name.is_empty() && h.value.is_empty() {
+            continue;
+        }
+
+        if !h.enabled {
+            continue;
+        }
+
+        metadata.insert(h.name, h.value);
+    }
+
+    if let Some(b) = &req.authentication_type {
+        let req = req.clone();
+        let empty_value = &serde_json::to_value("").unwrap();
+        let a = req.authentication;
+
+        if b == "basic" {
+            let username = a.get("username").unwrap_or(empty_value).as_str().unwrap_or("");
+            let password = a.get("password").unwrap_or(empty_value).as_str().unwrap_or("");
+
+            let auth = format!("{username}:{password}");
+            let encoded = BASE64_STANDARD.encode(auth);
+            metadata.insert("Authorization".to_string(), format!("Basic {}", encoded));
+        } else if b == "bearer" {
+            let token = a.get("token").unwrap_or(empty_value).as_str().unwrap_or("");
+            metadata.insert("Authorization".to_string(), format!("Bearer {token}"));
+        }
+    }
+
+    metadata
+}
+
+/// Base64 of a message's wire bytes to store alongside its JSON, only if the workspace captures
+/// them since it roughly doubles the size of every message event
+pub fn raw_message_content(msg: &DynamicMessage, capture: bool) -> Option<String> {
+    capture.then(|| BASE64_STANDARD.encode(encode_message(msg)))
+}
+
+/// How to connect to the request's server. Settings the request leaves unset fall back to the
+/// workspace's, and the workspace's proxy to the global one.
+pub fn grpc_channel_options(
+    req: &GrpcRequest,
+    workspace: &Workspace,
+    global_proxy: Option<ProxySetting>,
+    uri: &str,
+) -> Result<GrpcChannelOptions, String> {
+    let path = |p: &Option<String>| p.as_ref().filter(|p| !p.is_empty()).map(PathBuf::from);
+    let millis = |ms: i32| (ms > 0).then(|| Duration::from_millis(ms as u64));
+
+    // The key is often in the same PEM file as the certificate
+    let client_certificate =
+        match (path(&req.setting_client_certificate), path(&req.setting_client_key)) {
+            (Some(cert), key) => Some((cert.clone(), key.unwrap_or(cert))),
+            (None, Some(_)) => return Err("Client key is set without a client certificate".into()),
+            (None, None) => None,
+        };
+
+    let validate =
+        req.setting_validate_certificates.unwrap_or(workspace.setting_validate_certificates);
+    Ok(GrpcChannelOptions {
+        accept_invalid_certs: !validate,
+        ca_certificate: path(&req.setting_ca_certificate),
+        client_certificate,
+        keepalive_interval: millis(req.setting_keepalive_interval),
+        timeout: millis(req.setting_timeout),
+        proxy: grpc_proxy(workspace.setting_proxy.clone().or(global_proxy), uri)?,
+    })
+}
+
+/// Normalize a gRPC endpoint to an http(s) URI. `grpcs://` maps to TLS and `grpc://` to
+/// plaintext, and bare hosts default to plaintext.
+pub fn safe_uri(endpoint: &str) -> String {
+    let lower = endpoint.to_ascii_lowercase();
+    if lower.starts_with("http://") || lower.starts_with("https://") {
+        endpoint.into()
+    } else if lower.starts_with("grpcs://") {
+        format!("https://{}", &endpoint["grpcs://".len()..])
+    } else if lower.starts_with("grpc://") {
+        format!("http://{}", &endpoint["grpc://".len()..])
+    } else {
+        format!("http://{}", endpoint)
+    }
+}
+
+/// A request rendered for sending, with everything needed to connect and call it. Both the
+/// request pane and folder runs go through this so they send the same thing.
+pub struct GrpcCall {
+    pub request: GrpcRequest,
+    pub workspace: Workspace,
+    pub service: String,
+    pub method: String,
+    pub metadata: BTreeMap<String, String>,
+    uri: String,
+    options: GrpcChannelOptions,
+}
+
+impl GrpcCall {
+    pub async fn prepare<R: Runtime>(
+        window: &WebviewWindow<R>,
+        request: &GrpcRequest,
+        environment: Option<&Environment>,
+    ) -> Result<Self, String> {
+        let workspace =
+            get_workspace(window, &request.workspace_id).await.map_err(|e| e.to_string())?;
+        let cb = PluginTemplateCallback::new(
+            window.app_handle(),
+            &WindowContext::from_window(window),
+            RenderPurpose::Send,
+        );
+        let request = render_grpc_request(request, &workspace, environment, &cb).await;
+
+        let (service, method) = match (&request.service, &request.method) {
+            (Some(service), Some(method)) => (service.clone(), method.clone()),
+            _ => return Err("Service and method are required".to_string()),
+        };
+
+        let uri = safe_uri(&request.url);
+        let settings = get_or_create_settings(window).await;
+        let options = grpc_channel_options(&request, &workspace, settings.proxy, &uri)?;
+
+        Ok(GrpcCall {
+            metadata: build_metadata(&request),
+            request,
+            workspace,
+            service,
+            method,
+            uri,
+            options,
+        })
+    }
+
+    /// Connect to the server, taking the schema from the proto files, or from reflection when
+    /// there aren't any
+    pub async fn connect<R: Runtime>(
+        &self,
+        window: &WebviewWindow<R>,
+        proto_files: Vec<PathBuf>,
+        import_dirs: Vec<PathBuf>,
+    ) -> Result<yaak_grpc::manager::GrpcConnection, String> {
+        let grpc_handle = window.state::<tokio::sync::Mutex<GrpcHandle>>();
+        let mut grpc_handle = grpc_handle.lock().await;
+        grpc_handle
+            .connect(
+                &self.request.id,
+                &self.uri,
+                &proto_files,
+                &import_dirs,
+                message_size_limit(self.workspace.setting_grpc_max_send_message_size),
+                message_size_limit(self.workspace.setting_grpc_max_receive_message_size),
+                &self.options,
+            )
+            .await
+    }
+}
+
+fn message_size_limit(setting: i32) -> usize {
+    match setting {
+        s if s > 0 => s as usize,
+        _ => DEFAULT_MAX_MESSAGE_SIZE,
+    }
+}
+
+/// The client half of a single gRPC call. Every connection gets its own, so messages, commits,
+/// and cancels sent to one call can never reach another call, even of the same request.
+pub struct ClientStream<T> {
+    in_msg_tx: Mutex<Option<mpsc::Sender<T>>>,
+    cancelled_tx: watch::Sender<bool>,
+}
+
+impl<T> ClientStream<T> {
+    /// Create the stream along with the outgoing messages to hand to the call and a receiver
+    /// that fires when the call is cancelled
+    pub fn new(buffer: usize) -> (Self, ReceiverStream<T>, watch::Receiver<bool>) {
+        let (in_msg_tx, in_msg_rx) = mpsc::channel(buffer);
+        let (cancelled_tx, cancelled_rx) = watch::channel(false);
+        let stream = ClientStream {
+            in_msg_tx: Mutex::new(Some(in_msg_tx)),
+            cancelled_tx,
+        };
+        (stream, ReceiverStream::new(in_msg_rx), cancelled_rx)
+    }
+
+    /// Queue a message to send, failing if the call was committed, cancelled, or has ended
+    pub fn send(&self, msg: T) -> Result<(), String> {
+        if self.is_cancelled() {
+            return Err("Stream was cancelled".to_string());
+        }
+        let in_msg_tx = self.in_msg_tx.lock().expect("previous holder not to panic");
+        match in_msg_tx.as_ref() {
+            None => Err("Stream was already committed".to_string()),
+            Some(tx) => tx.try_send(msg).map_err(|e| match e {
+                mpsc::error::TrySendError::Full(_) => "Too many messages queued".to_string(),
+                mpsc::error::TrySendError::Closed(_) => "Stream has ended".to_string(),
+            }),
+        }
+    }
+
+    /// Close the sending side so the server knows no more messages are coming
+    pub fn commit(&self) {
+        self.in_msg_tx.lock().expect("previous holder not to panic").take();
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled_tx.send_replace(true);
+        self.commit();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.cancelled_tx.borrow()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grpc::{grpc_channel_options, safe_uri, ClientStream};
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use tokio_stream::StreamExt;
+    use yaak_models::models::{GrpcRequest, ProxySetting, Workspace};
+
+    #[test]
+    fn safe_uri_http() {
+        assert_eq!(safe_uri("http://localhost:50051"), "http://localhost:50051");
+    }
+
+    #[test]
+    fn safe_uri_https() {
+        assert_eq!(safe_uri("https://example.com"), "https://example.com");
+    }
+
+    #[test]
+    fn safe_uri_grpc() {
+        assert_eq!(safe_uri("grpc://localhost:50051"), "http://localhost:50051");
+    }
+
+    #[test]
+    fn safe_uri_grpcs() {
+        assert_eq!(safe_uri("grpcs://example.com:443"), "https://example.com:443");
+    }
+
+    #[test]
+    fn safe_uri_uppercase_scheme() {
+        assert_eq!(safe_uri("GRPCS://example.com"), "https://example.com");
+    }
+
+    #[test]
+    fn safe_uri_bare_host() {
+        assert_eq!(safe_uri("localhost:50051"), "http://localhost:50051");
+    }
+
+    #[tokio::test]
+    async fn concurrent_streams_are_isolated() {
+        let (first, first_rx, first_cancelled) = ClientStream::new(16);
+        let (second, second_rx, second_cancelled) = ClientStream::new(16);
+
+        first.send("a1").unwrap();
+        second.send("b1").unwrap();
+        first.send("a2").unwrap();
+        second.cancel();
+
+        assert!(!*first_cancelled.borrow());
+        assert!(*second_cancelled.borrow());
+        assert!(second.send("b2").is_err());
+        first.send("a3").unwrap();
+        first.commit();
+        assert!(first.send("a4").is_err());
+
+        assert_eq!(first_rx.collect::<Vec<_>>().await, vec!["a1", "a2", "a3"]);
+        assert_eq!(second_rx.collect::<Vec<_>>().await, vec!["b1"]);
+    }
+
+    #[test]
+    fn send_after_stream_ends() {
+        let (stream, rx, _cancelled) = ClientStream::new(1);
+        stream.send(1).unwrap();
+        assert_eq!(stream.send(2), Err("Too many messages queued".to_string()));
+        drop(rx);
+        assert_eq!(stream.send(3), Err("Stream has ended".to_string()));
+    }
+
+    #[test]
+    fn channel_options_fall_back_to_workspace() {
+        let workspace = Workspace {
+            setting_validate_certificates: false,
+            setting_proxy: Some(ProxySetting::Disabled),
+            ..Default::default()
+        };
+        let req = GrpcRequest {
+            setting_client_certificate: Some("client.pem".to_string()),
+            setting_ca_certificate: Some("".to_string()),
+            setting_timeout: 1500,
+            ..Default::default()
+        };
+        let options = grpc_channel_options(&req, &workspace, None, "https://example.com").unwrap();
+        assert!(options.accept_invalid_certs);
+        assert_eq!(options.ca_certificate, None);
+        assert_eq!(
+            options.client_certificate,
+            Some((PathBuf::from("client.pem"), PathBuf::from("client.pem")))
+        );
+        assert_eq!(options.keepalive_interval, None);
+        assert_eq!(options.timeout, Some(Duration::from_millis(1500)));
+        assert_eq!(options.proxy, None);
+
+        let req = GrpcRequest {
+            setting_validate_certificates: Some(true),
+            setting_client_key: Some("key.pem".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            grpc_channel_options(&req, &workspace, None, "https://example.com").unwrap_err(),
+            "Client key is set without a client certificate"
+        );
+    }
+}
@@ -0,0 +1,325 @@
+use chrono::{DateTime, NaiveDateTime};
+use http::header::{
+    AGE, CACHE_CONTROL, ETAG, EXPIRES, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_RANGE,
+    IF_UNMODIFIED_SINCE, LAST_MODIFIED, RANGE, VARY,
+};
+use http::{HeaderMap, HeaderName, HeaderValue, Method};
+use reqwest::Url;
+use yaak_models::models::{HttpResponse, HttpResponseSource, HttpResponseState};
+
+/// What to do with a cached response when sending a request it could answer
+#[derive(Debug, PartialEq)]
+pub enum CacheAction {
+    /// It's still fresh, so its body is used without contacting the server
+    Serve,
+    /// It's stale, so the server is asked whether it changed, with these conditional headers
+    Revalidate(Vec<(HeaderName, HeaderValue)>),
+    /// It can't be used for this request
+    Skip,
+}
+
+/// Whether a request can be answered from the cache. Requests that are already conditional, or
+/// only ask for part of the body, are sent as they are.
+pub fn is_cacheable_request(method: &Method, headers: &HeaderMap) -> bool {
+    let conditional = [
+        IF_NONE_MATCH,
+        IF_MODIFIED_SINCE,
+        IF_MATCH,
+        IF_UNMODIFIED_SINCE,
+        IF_RANGE,
+    ];
+    (method == Method::GET || method == Method::HEAD)
+        && !conditional.iter().any(|h| headers.contains_key(h))
+        && !headers.contains_key(RANGE)
+        && !has_directive(headers.get_all(CACHE_CONTROL).iter(), "no-store")
+}
+
+/// The newest finished response to the same URL with the same method, which has a body to reuse.
+/// Responses served from the cache are skipped, since their age counts from when the server
+/// sent the body, not from when the cache served it.
+pub fn find_cache_entry<'a>(
+    responses: &'a [HttpResponse],
+    exclude_id: &str,
+    method: &Method,
+    url: &Url,
+) -> Option<&'a HttpResponse> {
+    responses.iter().find(|r| {
+        r.id != exclude_id
+            && r.url == url.as_str()
+            && r.body_path.is_some()
+            && matches!(r.state, HttpResponseState::Closed)
+            && r.request_snapshot
+                .as_ref()
+                .is_some_and(|s| s.method.eq_ignore_ascii_case(method.as_str()))
+            && match r.source {
+                HttpResponseSource::Network => (200..300).contains(&r.status),
+                HttpResponseSource::NotModified => true,
+                HttpResponseSource::Cache => false,
+            }
+    })
+}
+
+/// Decide how a cached response can answer a request, following its `Cache-Control`, `Expires`,
+/// and `Vary` headers. Responses without an explicit lifetime are always revalidated.
+pub fn cache_action(
+    request_headers: &HeaderMap,
+    cached: &HttpResponse,
+    now: NaiveDateTime,
+) -> CacheAction {
+    let cache_control: Vec<&str> = header_values(cached, &CACHE_CONTROL).collect();
+    if has_directive(cache_control.iter().copied(), "no-store")
+        || !vary_matches(request_headers, cached)
+    {
+        return CacheAction::Skip;
+    }
+
+    let request_no_cache = has_directive(request_headers.get_all(CACHE_CONTROL).iter(), "no-cache");
+    if !request_no_cache && !has_directive(cache_control.iter().copied(), "no-cache") {
+        let age = (now - cached.created_at).num_seconds()
+            + header_values(cached, &AGE).find_map(|v| v.trim().parse::<i64>().ok()).unwrap_or(0);
+        let max_age = directive_value(cache_control.iter().copied(), "max-age")
+            .and_then(|v| v.parse::<i64>().ok());
+        let fresh = match max_age {
+            Some(max_age) => age < max_age,
+            None => header_values(cached, &EXPIRES)
+                .find_map(|v| DateTime::parse_from_rfc2822(v.trim()).ok())
+                .is_some_and(|expires| now < expires.naive_utc()),
+        };
+        if fresh {
+            return CacheAction::Serve;
+        }
+    }
+
+    let mut conditional = Vec::new();
+    if let Some(Ok(etag)) = header_values(cached, &ETAG).next().map(HeaderValue::from_str) {
+        conditional.push((IF_NONE_MATCH, etag));
+    }
+    if let Some(Ok(date)) = header_values(cached, &LAST_MODIFIED).next().map(HeaderValue::from_str)
+    {
+        conditional.push((IF_MODIFIED_SINCE, date));
+    }
+    match conditional.is_empty() {
+        true => CacheAction::Skip,
+        false => CacheAction::Revalidate(conditional),
+    }
+}
+
+/// Whether the request sends the same values as the cached one did for each header the cached
+/// response varies by
+fn vary_matches(request_headers: &HeaderMap, cached: &HttpResponse) -> bool {
+    let snapshot_headers = cached.request_snapshot.as_ref().map(|s| s.headers.as_slice());
+    let vary = header_values(cached, &VARY);
+    vary.flat_map(|v| v.split(',')).map(|n| n.trim()).filter(|n| !n.is_empty()).all(|name| {
+        if name == "*" {
+            return false;
+        }
+        let sent = request_headers.get(name).and_then(|v| v.to_str().ok());
+        let cached_sent = snapshot_headers
+            .unwrap_or_default()
+            .iter()
+            .find(|h| h.enabled && h.name.eq_ignore_ascii_case(name))
+            .map(|h| h.value.as_str());
+        sent == cached_sent
+    })
+}
+
+fn header_values<'a>(
+    response: &'a HttpResponse,
+    name: &'a HeaderName,
+) -> impl Iterator<Item = &'a str> + 'a {
+    response
+        .headers
+        .iter()
+        .filter(|h| h.name.eq_ignore_ascii_case(name.as_str()))
+        .map(|h| h.value.as_str())
+}
+
+fn has_directive<V: AsRef<[u8]>>(values: impl Iterator<Item = V>, directive: &str) -> bool {
+    directives(values).any(|(name, _)| name.eq_ignore_ascii_case(directive))
+}
+
+fn directive_value<V: AsRef<[u8]>>(
+    values: impl Iterator<Item = V>,
+    directive: &str,
+) -> Option<String> {
+    directives(values).find(|(name, _)| name.eq_ignore_ascii_case(directive)).and_then(|(_, v)| v)
+}
+
+/// Directives of `Cache-Control` header values, as names and unquoted values
+fn directives<V: AsRef<[u8]>>(
+    values: impl Iterator<Item = V>,
+) -> impl Iterator<Item = (String, Option<String>)> {
+    values.flat_map(|v| {
+        let value = String::from_utf8_lossy(v.as_ref()).to_string();
+        value
+            .split(',')
+            .map(|d| match d.split_once('=') {
+                Some((name, value)) => {
+                    (name.trim().to_string(), Some(value.trim().trim_matches('"').to_string()))
+                }
+                None => (d.trim().to_string(), None),
+            })
+            .collect::<Vec<_>>()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::http_cache::{cache_action, find_cache_entry, is_cacheable_request, CacheAction};
+    use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+    use http::header::{CACHE_CONTROL, IF_MODIFIED_SINCE, IF_NONE_MATCH, RANGE};
+    use http::{HeaderMap, HeaderValue, Method};
+    use reqwest::Url;
+    use yaak_models::models::{
+        HttpRequest, HttpRequestHeader, HttpResponse, HttpResponseHeader, HttpResponseSource,
+        HttpResponseState,
+    };
+
+    fn sent_at() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 10, 21).unwrap().and_hms_opt(7, 28, 0).unwrap()
+    }
+
+    fn cached(headers: &[(&str, &str)]) -> HttpResponse {
+        HttpResponse {
+            id: "rs_1".to_string(),
+            created_at: sent_at(),
+            url: "https://yaak.test/users".to_string(),
+            status: 200,
+            state: HttpResponseState::Closed,
+            body_path: Some("/tmp/rs_1".to_string()),
+            headers: headers
+                .iter()
+                .map(|(name, value)| HttpResponseHeader {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                })
+                .collect(),
+            request_snapshot: Some(HttpRequest {
+                method: "GET".to_string(),
+                headers: vec![HttpRequestHeader {
+                    enabled: true,
+                    name: "Accept-Language".to_string(),
+                    value: "en".to_string(),
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn after(secs: i64) -> NaiveDateTime {
+        sent_at() + TimeDelta::seconds(secs)
+    }
+
+    #[test]
+    fn cacheable_requests() {
+        let mut headers = HeaderMap::new();
+        assert!(is_cacheable_request(&Method::GET, &headers));
+        assert!(!is_cacheable_request(&Method::POST, &headers));
+        headers.insert(RANGE, HeaderValue::from_static("bytes=0-10"));
+        assert!(!is_cacheable_request(&Method::GET, &headers));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_NONE_MATCH, HeaderValue::from_static("\"abc\""));
+        assert!(!is_cacheable_request(&Method::GET, &headers));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+        assert!(!is_cacheable_request(&Method::GET, &headers));
+    }
+
+    #[test]
+    fn find_entry_for_same_url_and_method() {
+        let url = Url::parse("https://yaak.test/users").unwrap();
+        let responses = vec![
+            HttpResponse {
+                id: "current".to_string(),
+                ..cached(&[])
+            },
+            HttpResponse {
+                id: "served_from_cache".to_string(),
+                source: HttpResponseSource::Cache,
+                ..cached(&[])
+            },
+            HttpResponse {
+                status: 500,
+                ..cached(&[])
+            },
+            HttpResponse {
+                url: "https://yaak.test/other".to_string(),
+                ..cached(&[])
+            },
+            HttpResponse {
+                id: "rs_2".to_string(),
+                status: 304,
+                source: HttpResponseSource::NotModified,
+                ..cached(&[])
+            },
+        ];
+        let entry = find_cache_entry(&responses, "current", &Method::GET, &url);
+        assert_eq!(entry.map(|r| r.id.as_str()), Some("rs_2"));
+        assert!(find_cache_entry(&responses, "current", &Method::HEAD, &url).is_none());
+    }
+
+    #[test]
+    fn serve_while_fresh() {
+        let headers = HeaderMap::new();
+        let r = cached(&[("Cache-Control", "public, max-age=60"), ("ETag", "\"v1\"")]);
+        assert_eq!(cache_action(&headers, &r, after(59)), CacheAction::Serve);
+        assert_eq!(
+            cache_action(&headers, &r, after(60)),
+            CacheAction::Revalidate(vec![(IF_NONE_MATCH, HeaderValue::from_static("\"v1\""))])
+        );
+
+        // Time spent in other caches counts towards the age
+        let r = cached(&[("cache-control", "max-age=60"), ("Age", "50")]);
+        assert_eq!(cache_action(&headers, &r, after(10)), CacheAction::Skip);
+
+        let r = cached(&[("Expires", "Mon, 21 Oct 2024 08:00:00 GMT")]);
+        assert_eq!(cache_action(&headers, &r, after(60)), CacheAction::Serve);
+        assert_eq!(cache_action(&headers, &r, after(3600)), CacheAction::Skip);
+    }
+
+    #[test]
+    fn revalidate_with_validators() {
+        let mut headers = HeaderMap::new();
+        let last_modified = "Sun, 20 Oct 2024 00:00:00 GMT";
+        let r = cached(&[
+            ("Cache-Control", "max-age=60, no-cache"),
+            ("Last-Modified", last_modified),
+        ]);
+        assert_eq!(
+            cache_action(&headers, &r, after(1)),
+            CacheAction::Revalidate(vec![(
+                IF_MODIFIED_SINCE,
+                HeaderValue::from_static(last_modified)
+            )])
+        );
+
+        // The request can also ask for the server to be checked
+        let r = cached(&[("Cache-Control", "max-age=60"), ("ETag", "W/\"v1\"")]);
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        assert!(matches!(cache_action(&headers, &r, after(1)), CacheAction::Revalidate(_)));
+    }
+
+    #[test]
+    fn skip_uncacheable_responses() {
+        let headers = HeaderMap::new();
+        let r = cached(&[("Cache-Control", "no-store"), ("ETag", "\"v1\"")]);
+        assert_eq!(cache_action(&headers, &r, after(1)), CacheAction::Skip);
+
+        let r = cached(&[("Cache-Control", "max-age=60"), ("Vary", "*")]);
+        assert_eq!(cache_action(&headers, &r, after(1)), CacheAction::Skip);
+    }
+
+    #[test]
+    fn vary_by_request_headers() {
+        let r = cached(&[("Cache-Control", "max-age=60"), ("Vary", "Accept-Language")]);
+        let mut headers = HeaderMap::new();
+        assert_eq!(cache_action(&headers, &r, after(1)), CacheAction::Skip);
+        headers.insert("accept-language", HeaderValue::from_static("en"));
+        assert_eq!(cache_action(&headers, &r, after(1)), CacheAction::Serve);
+        headers.insert("accept-language", HeaderValue::from_static("fr"));
+        assert_eq!(cache_action(&headers, &r, after(1)), CacheAction::Skip);
+    }
+}
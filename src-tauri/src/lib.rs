@@ -2,18 +2,18 @@ extern crate core;
 #[cfg(target_os = "macos")]
 extern crate objc;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::{create_dir_all, File};
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 use std::{fs, panic};
 
-use base64::prelude::BASE64_STANDARD;
-use base64::Engine;
-use chrono::Utc;
-use eventsource_client::{EventParser, SSE};
+use chrono::{TimeDelta, Utc};
+use eventsource_client::EventParser;
 use fern::colors::ColoredLevelConfig;
 use log::{debug, error, info, warn};
 use rand::random;
@@ -26,66 +26,119 @@ use tauri::{AppHandle, Emitter, LogicalSize, RunEvent, State, WebviewUrl, Webvie
 use tauri::{Listener, Runtime};
 use tauri::{Manager, WindowEvent};
 use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
 use tauri_plugin_log::{fern, Target, TargetKind};
 use tauri_plugin_shell::ShellExt;
 use tokio::fs::read_to_string;
-use tokio::sync::Mutex;
+use tokio::sync::{watch, Mutex};
 use tokio::task::block_in_place;
 use yaak_grpc::manager::{DynamicMessage, GrpcHandle};
 use yaak_grpc::{deserialize_message, serialize_message, Code, ServiceDefinition};
 use yaak_plugin_runtime::manager::PluginManager;
 
 use crate::analytics::{AnalyticsAction, AnalyticsResource};
-use crate::export_resources::{get_workspace_export_resources, WorkspaceExportResources};
-use crate::grpc::metadata_to_map;
-use crate::http_request::send_http_request;
+use crate::cookies::{clear_cookies, delete_cookie, list_cookies, upsert_cookie, CookieFilter};
+use crate::curl_cookies::{add_curl_cookies, parse_curl_cookies};
+use crate::curl_export::request_to_curl;
+use crate::error::{AppError, ErrorKind};
+use crate::export_encryption::{decrypt_export, encrypt_export, is_encrypted_export};
+use crate::export_resources::{
+    get_workspace_export_resources, restore_masked_proxy_password, restore_masked_secrets,
+    ImportedResources, ModelHashIndex,
+};
+use crate::graphql::{introspection_key, introspection_request, parse_introspection};
+use crate::grpc::{
+    grpc_channel_options, metadata_to_map, raw_message_content, record_grpc_history, safe_uri,
+    ClientStream, GrpcCall,
+};
+use crate::http_request::{
+    build_request_headers, execute_http_request, parse_sse, preview_cookie_header,
+    replay_http_request, send_http_request, SendOutcome,
+};
+use crate::loadtest::run_load_test;
+use crate::mock_server::{start_mock_server, MockServers};
 use crate::notifications::YaakNotifier;
-use crate::render::{render_grpc_request, render_http_request, render_json_value, render_template};
-use crate::template_callback::PluginTemplateCallback;
-use crate::updates::{UpdateMode, YaakUpdater};
+use crate::openapi_import::import_openapi;
+use crate::render::{
+    make_vars_hashmap, render_http_request, render_json_value, render_template, undefined_variables,
+};
+use crate::runner::{run_folder, send_many_http_requests};
+use crate::storage::{
+    cleanup_storage, ensure_writable_dir, freeze_example_body, get_storage_stats, prune_responses,
+    read_body_chunk, responses_dir, vacuum, CleanupStorageOptions, CleanupStorageResult,
+    PruneResponsesResult, ResponseBodyChunk, StorageStats, VacuumResult,
+};
+use crate::template_callback::{PluginTemplateCallback, TemplateFunctionError};
+use crate::updates::{get_changelog, Changelog, UpdateMode, YaakUpdater};
 use crate::window_menu::app_menu;
 use yaak_models::models::{
-    CookieJar, Environment, EnvironmentVariable, Folder, GrpcConnection, GrpcConnectionState,
-    GrpcEvent, GrpcEventType, GrpcRequest, HttpRequest, HttpResponse, HttpResponseState, KeyValue,
-    ModelType, Plugin, Settings, Workspace,
+    Cookie, CookieJar, Environment, EnvironmentVariable, Folder, GrpcConnection,
+    GrpcConnectionState, GrpcEvent, GrpcEventType, GrpcRequest, HttpExample, HttpRequest,
+    HttpRequestHeader, HttpResponse, HttpResponseEvent, HttpResponseState, HttpTimelineEvent,
+    KeyValue, LoadTest, ModelType, Plugin, RequestHistory, RequestSummary, RunResult, SearchMatch,
+    Settings, Workspace,
 };
 use yaak_models::queries::{
-    cancel_pending_grpc_connections, cancel_pending_responses, create_default_http_response,
-    delete_all_grpc_connections, delete_all_grpc_connections_for_workspace,
-    delete_all_http_responses_for_request, delete_all_http_responses_for_workspace,
-    delete_cookie_jar, delete_environment, delete_folder, delete_grpc_connection,
-    delete_grpc_request, delete_http_request, delete_http_response, delete_plugin,
-    delete_workspace, duplicate_grpc_request, duplicate_http_request, generate_id,
-    generate_model_id, get_cookie_jar, get_environment, get_folder, get_grpc_connection,
-    get_grpc_request, get_http_request, get_http_response, get_key_value_raw,
-    get_or_create_settings, get_plugin, get_workspace, list_cookie_jars, list_environments,
-    list_folders, list_grpc_connections_for_workspace, list_grpc_events, list_grpc_requests,
-    list_http_requests, list_http_responses_for_request, list_http_responses_for_workspace,
-    list_plugins, list_workspaces, set_key_value_raw, update_response_if_id, update_settings,
+    backup_database, cancel_pending_grpc_connections, cancel_pending_responses,
+    create_default_http_response, delete_all_grpc_connections,
+    delete_all_grpc_connections_for_workspace, delete_all_http_responses_for_request,
+    delete_all_http_responses_for_workspace, delete_cookie_jar, delete_environment, delete_folder,
+    delete_grpc_connection, delete_grpc_request, delete_http_request, delete_http_response,
+    delete_plugin, delete_request_history_before, delete_workspace, duplicate_environment,
+    duplicate_grpc_request, duplicate_http_request, generate_id, generate_model_id, get_cookie_jar,
+    get_environment, get_environment_by_name, get_folder, get_grpc_connection, get_grpc_request,
+    get_http_request, get_http_response, get_key_value_raw, get_last_location,
+    get_or_create_settings, get_plugin, get_secure_key_value, get_workspace, get_workspace_stats,
+    list_all_requests, list_cookie_jars, list_environments, list_folders,
+    list_grpc_connections_for_workspace, list_grpc_events, list_grpc_requests, list_http_examples,
+    list_http_requests, list_http_response_events, list_http_responses_for_request,
+    list_http_responses_for_workspace, list_http_timeline_events, list_load_tests, list_plugins,
+    list_request_history, list_run_results, list_workspaces, restore_database, search_workspace,
+    set_key_value_raw, set_secure_key_value, update_response_if_id, update_settings,
     upsert_cookie_jar, upsert_environment, upsert_folder, upsert_grpc_connection,
-    upsert_grpc_event, upsert_grpc_request, upsert_http_request, upsert_plugin, upsert_workspace,
+    upsert_grpc_event, upsert_grpc_request, upsert_http_example, upsert_http_request,
+    upsert_plugin, upsert_workspace, LastLocation, WorkspaceStats,
 };
 use yaak_plugin_runtime::events::{
     BootResponse, CallHttpRequestActionRequest, FilterResponse, FindHttpResponsesResponse,
     GetHttpRequestActionsResponse, GetHttpRequestByIdResponse, GetTemplateFunctionsResponse, Icon,
-    InternalEvent, InternalEventPayload, PromptTextResponse, RenderHttpRequestResponse,
-    RenderPurpose, SendHttpRequestResponse, ShowToastRequest, TemplateRenderResponse,
-    WindowContext,
+    ImportResponse, Importer, InternalEvent, InternalEventPayload, PluginStatus,
+    PromptTextResponse, RenderHttpRequestResponse, RenderPurpose, SendHttpRequestResponse,
+    ShowToastRequest, TemplateRenderResponse, WindowContext,
 };
 use yaak_plugin_runtime::plugin_handle::PluginHandle;
 use yaak_sse::sse::ServerSentEvent;
-use yaak_templates::format::format_json;
+use yaak_templates::format::{format_body, format_json, format_xml, validate_json, FormatError};
 use yaak_templates::{Parser, Tokens};
 
 mod analytics;
+mod cookies;
+mod curl_cookies;
+mod curl_export;
+mod digest_auth;
+mod error;
+mod export_encryption;
 mod export_resources;
+mod graphql;
 mod grpc;
+mod http_cache;
 mod http_request;
+mod loadtest;
+mod mock_server;
 mod notifications;
+mod ntlm_auth;
+mod openapi_import;
+mod proxy;
+mod raw_request;
 mod render;
+mod runner;
+mod scripts;
+mod storage;
 #[cfg(target_os = "macos")]
 mod tauri_plugin_mac_window;
 mod template_callback;
+mod timeline;
+mod tls;
 mod updates;
 mod window_menu;
 
@@ -95,9 +148,32 @@ const DEFAULT_WINDOW_HEIGHT: f64 = 600.0;
 const MIN_WINDOW_WIDTH: f64 = 300.0;
 const MIN_WINDOW_HEIGHT: f64 = 300.0;
 
+const MIN_ZOOM: f64 = 0.4;
+const MAX_ZOOM: f64 = 1.8;
+
+const GRPC_REFLECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many requests of a batch are sent at once, unless the caller says otherwise
+const DEFAULT_SEND_CONCURRENCY: usize = 6;
+
 const MAIN_WINDOW_PREFIX: &str = "main_";
 const OTHER_WINDOW_PREFIX: &str = "other_";
 
+/// Cancel senders for in-flight HTTP requests, keyed by window label and then response id,
+/// so that closing a window can abort everything it started.
+#[derive(Default)]
+struct ActiveRequests(HashMap<String, HashMap<String, Arc<watch::Sender<bool>>>>);
+
+impl ActiveRequests {
+    /// Signal cancellation for a response in any window, returning whether it was still in flight
+    fn cancel(&self, response_id: &str) -> bool {
+        self.0
+            .values()
+            .filter_map(|senders| senders.get(response_id))
+            .any(|tx| !tx.is_closed() && tx.send(true).is_ok())
+    }
+}
+
 #[derive(serde::Serialize)]
 #[serde(default, rename_all = "camelCase")]
 struct AppMetaData {
@@ -131,6 +207,113 @@ async fn cmd_template_tokens_to_string(tokens: Tokens) -> Result<String, String>
     Ok(tokens.to_string())
 }
 
+#[tauri::command]
+async fn cmd_insert_template_variable(
+    mut tokens: Tokens,
+    position: usize,
+    name: &str,
+) -> Result<Tokens, String> {
+    tokens.insert_variable(position, name)?;
+    Ok(tokens)
+}
+
+#[tauri::command]
+async fn cmd_rename_template_variable(
+    mut tokens: Tokens,
+    old_name: &str,
+    new_name: &str,
+) -> Result<Tokens, String> {
+    tokens.rename_variable(old_name, new_name)?;
+    Ok(tokens)
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TemplateValidation {
+    request_id: String,
+    undefined_variables: Vec<String>,
+}
+
+/// Check every request in a workspace for template variables that aren't defined in the
+/// workspace or the given environment, so typos are caught before sending. Only requests with
+/// problems are returned.
+#[tauri::command]
+async fn cmd_validate_workspace_templates(
+    workspace_id: &str,
+    environment_id: Option<&str>,
+    environment_name: Option<&str>,
+    window: WebviewWindow,
+) -> Result<Vec<TemplateValidation>, AppError> {
+    let environment =
+        resolve_environment(&window, workspace_id, environment_id, environment_name).await?;
+    let workspace = get_workspace(&window, workspace_id).await?;
+    let vars = make_vars_hashmap(&workspace, environment.as_ref());
+
+    let requests = list_http_requests(&window, workspace_id).await?;
+    Ok(requests
+        .iter()
+        .map(|r| TemplateValidation {
+            request_id: r.id.clone(),
+            undefined_variables: undefined_variables(r, &vars),
+        })
+        .filter(|v| !v.undefined_variables.is_empty())
+        .collect())
+}
+
+/// Render a request the way it would be sent, including default and auth headers, so it can
+/// be previewed before sending
+#[tauri::command]
+async fn cmd_render_http_request(
+    request: HttpRequest,
+    environment_id: Option<&str>,
+    environment_name: Option<&str>,
+    window: WebviewWindow,
+) -> Result<HttpRequest, AppError> {
+    let environment =
+        resolve_environment(&window, &request.workspace_id, environment_id, environment_name)
+            .await?;
+    let workspace = get_workspace(&window, &request.workspace_id).await?;
+    let cb = PluginTemplateCallback::new(
+        window.app_handle(),
+        &WindowContext::from_window(&window),
+        RenderPurpose::Preview,
+    );
+    let mut rendered = render_http_request(&request, &workspace, environment.as_ref(), &cb).await;
+    rendered.headers = build_request_headers(&workspace, &rendered)
+        .iter()
+        .map(|(name, value)| HttpRequestHeader {
+            enabled: true,
+            name: name.to_string(),
+            value: String::from_utf8_lossy(value.as_bytes()).to_string(),
+        })
+        .collect();
+    Ok(rendered)
+}
+
+/// The Cookie header the jar would send with the request, without sending it
+#[tauri::command]
+async fn cmd_preview_cookies(
+    request_id: &str,
+    cookie_jar_id: &str,
+    environment_id: Option<&str>,
+    window: WebviewWindow,
+) -> Result<String, AppError> {
+    let request = get_http_request(&window, request_id)
+        .await?
+        .ok_or(format!("Failed to find request {request_id}"))?;
+    let cookie_jar = get_cookie_jar(&window, cookie_jar_id).await?;
+    let environment =
+        resolve_environment(&window, &request.workspace_id, environment_id, None).await?;
+    let workspace = get_workspace(&window, &request.workspace_id).await?;
+    let cb = PluginTemplateCallback::new(
+        window.app_handle(),
+        &WindowContext::from_window(&window),
+        RenderPurpose::Preview,
+    );
+    let rendered = render_http_request(&request, &workspace, environment.as_ref(), &cb).await;
+    Ok(preview_cookie_header(&rendered, cookie_jar)?)
+}
+
 #[tauri::command]
 async fn cmd_render_template<R: Runtime>(
     window: WebviewWindow<R>,
@@ -138,11 +321,10 @@ async fn cmd_render_template<R: Runtime>(
     template: &str,
     workspace_id: &str,
     environment_id: Option<&str>,
+    environment_name: Option<&str>,
 ) -> Result<String, String> {
-    let environment = match environment_id {
-        Some(id) => Some(get_environment(&window, id).await.map_err(|e| e.to_string())?),
-        None => None,
-    };
+    let environment =
+        resolve_environment(&window, workspace_id, environment_id, environment_name).await?;
     let workspace = get_workspace(&window, &workspace_id).await.map_err(|e| e.to_string())?;
     let rendered = render_template(
         template,
@@ -171,6 +353,7 @@ async fn cmd_dismiss_notification<R: Runtime>(
 async fn cmd_grpc_reflect<R: Runtime>(
     request_id: &str,
     proto_files: Vec<String>,
+    import_dirs: Option<Vec<String>>,
     window: WebviewWindow<R>,
     grpc_handle: State<'_, Mutex<GrpcHandle>>,
 ) -> Result<Vec<ServiceDefinition>, String> {
@@ -180,78 +363,65 @@ async fn cmd_grpc_reflect<R: Runtime>(
         .ok_or("Failed to find GRPC request")?;
 
     let uri = safe_uri(&req.url);
+    let workspace = get_workspace(&window, &req.workspace_id).await.map_err(|e| e.to_string())?;
+    let settings = get_or_create_settings(&window).await;
+    let options = grpc_channel_options(&req, &workspace, settings.proxy, &uri)?;
 
-    grpc_handle
-        .lock()
-        .await
-        .services(
-            &req.id,
-            &uri,
-            &proto_files.iter().map(|p| PathBuf::from_str(p).unwrap()).collect(),
-        )
-        .await
+    let (cancel_tx, mut cancel_rx) = tokio::sync::watch::channel(false);
+    let event_handler = window.listen_any(format!("cancel_grpc_reflect_{}", req.id), move |_| {
+        if let Err(e) = cancel_tx.send(true) {
+            warn!("Failed to send cancel event for gRPC reflection {e:?}");
+        }
+    });
+
+    let services = async {
+        grpc_handle
+            .lock()
+            .await
+            .services(
+                &req.id,
+                &uri,
+                &proto_files.iter().map(|p| PathBuf::from_str(p).unwrap()).collect(),
+                &import_dirs.unwrap_or_default().iter().map(PathBuf::from).collect(),
+                &options,
+            )
+            .await
+    };
+
+    let result = tokio::select! {
+        r = tokio::time::timeout(GRPC_REFLECT_TIMEOUT, services) => match r {
+            Ok(r) => r,
+            Err(_) => Err(format!(
+                "Reflection timed out after {} seconds",
+                GRPC_REFLECT_TIMEOUT.as_secs()
+            )),
+        },
+        _ = cancel_rx.changed() => Err("Reflection was cancelled".to_string()),
+    };
+
+    window.unlisten(event_handler);
+    result
 }
 
 #[tauri::command]
 async fn cmd_grpc_go<R: Runtime>(
     request_id: &str,
     environment_id: Option<&str>,
+    environment_name: Option<&str>,
+    confirm_production: Option<bool>,
     proto_files: Vec<String>,
+    import_dirs: Option<Vec<String>>,
     window: WebviewWindow<R>,
-    grpc_handle: State<'_, Mutex<GrpcHandle>>,
-) -> Result<String, String> {
-    let environment = match environment_id {
-        Some(id) => Some(get_environment(&window, id).await.map_err(|e| e.to_string())?),
-        None => None,
-    };
+) -> Result<String, AppError> {
     let req = get_grpc_request(&window, request_id)
         .await
         .map_err(|e| e.to_string())?
         .ok_or("Failed to find GRPC request")?;
-    let workspace = get_workspace(&window, &req.workspace_id).await.map_err(|e| e.to_string())?;
-    let req = render_grpc_request(
-        &req,
-        &workspace,
-        environment.as_ref(),
-        &PluginTemplateCallback::new(
-            window.app_handle(),
-            &WindowContext::from_window(&window),
-            RenderPurpose::Send,
-        ),
-    )
-    .await;
-    let mut metadata = BTreeMap::new();
-
-    // Add the rest of metadata
-    for h in req.clone().metadata {
-        if h.name.is_empty() && h.value.is_empty() {
-            continue;
-        }
-
-        if !h.enabled {
-            continue;
-        }
-
-        metadata.insert(h.name, h.value);
-    }
-
-    if let Some(b) = &req.authentication_type {
-        let req = req.clone();
-        let empty_value = &serde_json::to_value("").unwrap();
-        let a = req.authentication;
-
-        if b == "basic" {
-            let username = a.get("username").unwrap_or(empty_value).as_str().unwrap_or("");
-            let password = a.get("password").unwrap_or(empty_value).as_str().unwrap_or("");
-
-            let auth = format!("{username}:{password}");
-            let encoded = BASE64_STANDARD.encode(auth);
-            metadata.insert("Authorization".to_string(), format!("Basic {}", encoded));
-        } else if b == "bearer" {
-            let token = a.get("token").unwrap_or(empty_value).as_str().unwrap_or("");
-            metadata.insert("Authorization".to_string(), format!("Bearer {token}"));
-        }
-    }
+    let environment =
+        resolve_environment(&window, &req.workspace_id, environment_id, environment_name).await?;
+    check_production_confirmation(environment.as_ref(), confirm_production.unwrap_or_default())?;
+    let call = GrpcCall::prepare(&window, &req, environment.as_ref()).await?;
+    let req = call.request.clone();
 
     let conn = {
         let req = req.clone();
@@ -280,37 +450,33 @@ async fn cmd_grpc_go<R: Runtime>(
         ..Default::default()
     };
 
-    let (in_msg_tx, in_msg_rx) = tauri::async_runtime::channel::<DynamicMessage>(16);
-    let maybe_in_msg_tx = std::sync::Mutex::new(Some(in_msg_tx.clone()));
-    let (cancelled_tx, mut cancelled_rx) = tokio::sync::watch::channel(false);
-
-    let uri = safe_uri(&req.url);
-
-    let in_msg_stream = tokio_stream::wrappers::ReceiverStream::new(in_msg_rx);
-
-    let (service, method) = {
-        let req = req.clone();
-        match (req.service, req.method) {
-            (Some(service), Some(method)) => (service, method),
-            _ => return Err("Service and method are required".to_string()),
-        }
-    };
+    // Each connection gets its own stream and listener, so overlapping calls of the same request
+    // don't see each other's messages or cancels
+    let (client_stream, in_msg_stream, mut cancelled_rx) = ClientStream::<DynamicMessage>::new(16);
+    let client_stream = Arc::new(client_stream);
 
     let start = std::time::Instant::now();
-    let connection = grpc_handle
-        .lock()
-        .await
+    let connection = call
         .connect(
-            &req.clone().id,
-            uri.as_str(),
-            &proto_files.iter().map(|p| PathBuf::from_str(p).unwrap()).collect(),
+            &window,
+            proto_files.iter().map(|p| PathBuf::from_str(p).unwrap()).collect(),
+            import_dirs.unwrap_or_default().iter().map(PathBuf::from).collect(),
         )
         .await;
-
+    let GrpcCall {
+        workspace,
+        service,
+        method,
+        metadata,
+        ..
+    } = call;
+    let capture_raw = workspace.setting_grpc_capture_raw_messages;
+
+    let environment_id = environment.as_ref().map(|e| e.id.clone());
     let connection = match connection {
         Ok(c) => c,
         Err(err) => {
-            upsert_grpc_connection(
+            let conn = upsert_grpc_connection(
                 &window,
                 &GrpcConnection {
                     elapsed: start.elapsed().as_millis() as i32,
@@ -321,6 +487,7 @@ async fn cmd_grpc_go<R: Runtime>(
             )
             .await
             .map_err(|e| e.to_string())?;
+            record_grpc_history(&window, &req, &conn, environment_id).await;
             return Ok(conn_id);
         }
     };
@@ -335,7 +502,7 @@ async fn cmd_grpc_go<R: Runtime>(
     }
 
     let cb = {
-        let cancelled_rx = cancelled_rx.clone();
+        let client_stream = client_stream.clone();
         let window = window.clone();
         let workspace = workspace.clone();
         let environment = environment.clone();
@@ -343,20 +510,10 @@ async fn cmd_grpc_go<R: Runtime>(
         let method_desc = method_desc.clone();
 
         move |ev: tauri::Event| {
-            if *cancelled_rx.borrow() {
-                // Stream is canceled
+            if client_stream.is_cancelled() {
                 return;
             }
 
-            let mut maybe_in_msg_tx = maybe_in_msg_tx.lock().expect("previous holder not to panic");
-            let in_msg_tx = if let Some(in_msg_tx) = maybe_in_msg_tx.as_ref() {
-                in_msg_tx
-            } else {
-                // This would mean that the stream is already committed because
-                // we have already dropped the sending half
-                return;
-            };
-
             match serde_json::from_str::<IncomingMsg>(ev.payload()) {
                 Ok(IncomingMsg::Message(msg)) => {
                     let window = window.clone();
@@ -398,12 +555,29 @@ async fn cmd_grpc_go<R: Runtime>(
                             return;
                         }
                     };
-                    in_msg_tx.try_send(d_msg).unwrap();
+                    let content_raw = raw_message_content(&d_msg, capture_raw);
+                    if let Err(e) = client_stream.send(d_msg) {
+                        warn!("Failed to send gRPC message {e}");
+                        tauri::async_runtime::spawn(async move {
+                            upsert_grpc_event(
+                                &window,
+                                &GrpcEvent {
+                                    event_type: GrpcEventType::Error,
+                                    content: e,
+                                    ..base_msg.clone()
+                                },
+                            )
+                            .await
+                            .unwrap();
+                        });
+                        return;
+                    }
                     tauri::async_runtime::spawn(async move {
                         upsert_grpc_event(
                             &window,
                             &GrpcEvent {
                                 content: msg,
+                                content_raw,
                                 event_type: GrpcEventType::ClientMessage,
                                 ..base_msg.clone()
                             },
@@ -412,12 +586,8 @@ async fn cmd_grpc_go<R: Runtime>(
                         .unwrap();
                     });
                 }
-                Ok(IncomingMsg::Commit) => {
-                    maybe_in_msg_tx.take();
-                }
-                Ok(IncomingMsg::Cancel) => {
-                    cancelled_tx.send_replace(true);
-                }
+                Ok(IncomingMsg::Commit) => client_stream.commit(),
+                Ok(IncomingMsg::Cancel) => client_stream.cancel(),
                 Err(e) => {
                     error!("Failed to parse gRPC message: {:?}", e);
                 }
@@ -482,11 +652,15 @@ async fn cmd_grpc_go<R: Runtime>(
                 };
 
             if !method_desc.is_client_streaming() {
+                let content_raw = deserialize_message(&msg, method_desc.clone())
+                    .ok()
+                    .and_then(|m| raw_message_content(&m, capture_raw));
                 upsert_grpc_event(
                     &window,
                     &GrpcEvent {
                         event_type: GrpcEventType::ClientMessage,
                         content: msg,
+                        content_raw,
                         ..base_event.clone()
                     },
                 )
@@ -512,10 +686,12 @@ async fn cmd_grpc_go<R: Runtime>(
                     )
                     .await
                     .unwrap();
+                    let msg = msg.into_inner();
                     upsert_grpc_event(
                         &window,
                         &GrpcEvent {
-                            content: serialize_message(&msg.into_inner()).unwrap(),
+                            content: serialize_message(&msg).unwrap(),
+                            content_raw: raw_message_content(&msg, capture_raw),
                             event_type: GrpcEventType::ServerMessage,
                             ..base_event.clone()
                         },
@@ -620,6 +796,7 @@ async fn cmd_grpc_go<R: Runtime>(
                             &window,
                             &GrpcEvent {
                                 content: message,
+                                content_raw: raw_message_content(&msg, capture_raw),
                                 event_type: GrpcEventType::ServerMessage,
                                 ..base_event.clone()
                             },
@@ -645,6 +822,7 @@ async fn cmd_grpc_go<R: Runtime>(
                         break;
                     }
                     Err(status) => {
+                        let status = connection.annotate_status(status);
                         upsert_grpc_event(
                             &window,
                             &GrpcEvent {
@@ -657,6 +835,7 @@ async fn cmd_grpc_go<R: Runtime>(
                         )
                         .await
                         .unwrap();
+                        break;
                     }
                 }
             }
@@ -667,7 +846,7 @@ async fn cmd_grpc_go<R: Runtime>(
         let conn_id = conn_id.clone();
         tauri::async_runtime::spawn(async move {
             let w = window.clone();
-            tokio::select! {
+            let closed = tokio::select! {
                 _ = grpc_listen => {
                     let events = list_grpc_events(&w, &conn_id)
                         .await
@@ -684,7 +863,7 @@ async fn cmd_grpc_go<R: Runtime>(
                             state: GrpcConnectionState::Closed,
                             ..get_grpc_connection(&w, &conn_id).await.unwrap().clone()
                         },
-                    ).await.unwrap();
+                    ).await.unwrap()
                 },
                 _ = cancelled_rx.changed() => {
                     upsert_grpc_event(
@@ -706,9 +885,10 @@ async fn cmd_grpc_go<R: Runtime>(
                         },
                     )
                     .await
-                    .unwrap();
+                    .unwrap()
                 },
-            }
+            };
+            record_grpc_history(&w, &req, &closed, environment_id).await;
             w.unlisten(event_handler);
         });
     };
@@ -744,11 +924,77 @@ async fn cmd_send_ephemeral_request(
     send_http_request(&window, &request, &response, environment, cookie_jar, &mut cancel_rx).await
 }
 
+#[tauri::command]
+async fn cmd_graphql_introspect(
+    request_id: &str,
+    environment_id: Option<&str>,
+    environment_name: Option<&str>,
+    cookie_jar_id: Option<&str>,
+    confirm_production: Option<bool>,
+    window: WebviewWindow,
+) -> Result<serde_json::Value, AppError> {
+    let request = match get_http_request(&window, request_id).await? {
+        Some(r) => r,
+        None => return Err(yaak_models::error::Error::ModelNotFound(request_id.to_string()).into()),
+    };
+    let environment =
+        resolve_environment(&window, &request.workspace_id, environment_id, environment_name)
+            .await?;
+    check_production_confirmation(environment.as_ref(), confirm_production.unwrap_or_default())?;
+    let cookie_jar = match cookie_jar_id {
+        Some(id) => Some(get_cookie_jar(&window, id).await?),
+        None => None,
+    };
+
+    let (_cancel_tx, mut cancel_rx) = tokio::sync::watch::channel(false);
+    let response = send_http_request(
+        &window,
+        &introspection_request(&request),
+        &HttpResponse::new(),
+        environment,
+        cookie_jar,
+        &mut cancel_rx,
+    )
+    .await?;
+    if let Some(e) = response.error {
+        return Err(AppError::new(ErrorKind::Network, e));
+    }
+
+    let body = match &response.body_path {
+        Some(p) => read_file(p).await?,
+        None => "".to_string(),
+    };
+    let schema = parse_introspection(response.status, &body)?;
+
+    // Cache the schema so the editor has it on the next launch, or while the server is down
+    set_key_value_raw(&window, "global", &introspection_key(request_id), &schema.to_string()).await;
+    Ok(schema)
+}
+
 #[tauri::command]
 async fn cmd_format_json(text: &str) -> Result<String, String> {
     Ok(format_json(text, "  "))
 }
 
+#[tauri::command]
+async fn cmd_validate_json(text: &str) -> Result<(), FormatError> {
+    validate_json(text)
+}
+
+#[tauri::command]
+async fn cmd_format_xml(text: &str) -> Result<String, FormatError> {
+    format_xml(text, "  ")
+}
+
+#[tauri::command]
+async fn cmd_format_body(
+    body: &str,
+    content_type: &str,
+    minify: Option<bool>,
+) -> Result<String, FormatError> {
+    format_body(body, content_type, minify.unwrap_or(false), "  ")
+}
+
 #[tauri::command]
 async fn cmd_filter_response<R: Runtime>(
     window: WebviewWindow<R>,
@@ -756,12 +1002,22 @@ async fn cmd_filter_response<R: Runtime>(
     plugin_manager: State<'_, PluginManager>,
     filter: &str,
 ) -> Result<FilterResponse, String> {
-    let response =
-        get_http_response(&window, response_id).await.expect("Failed to get http response");
+    let response = get_http_response(&window, response_id).await.map_err(|e| e.to_string())?;
+    let (body, content_type) = read_response_body(&response).await?;
 
-    if let None = response.body_path {
-        return Err("Response body path not set".to_string());
-    }
+    // TODO: Have plugins register their own content type (regex?)
+    plugin_manager
+        .filter_data(&window, filter, &body, &content_type)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// A response's body and lowercased content type, for filtering
+async fn read_response_body(response: &HttpResponse) -> Result<(String, String), String> {
+    let body_path = match &response.body_path {
+        None => return Err("Response body path not set".to_string()),
+        Some(p) => p,
+    };
 
     let mut content_type = "".to_string();
     for header in response.headers.iter() {
@@ -771,34 +1027,42 @@ async fn cmd_filter_response<R: Runtime>(
         }
     }
 
-    let body = read_to_string(response.body_path.unwrap()).await.unwrap();
+    Ok((read_file(body_path).await?, content_type))
+}
 
-    // TODO: Have plugins register their own content type (regex?)
-    plugin_manager
-        .filter_data(&window, filter, &body, &content_type)
-        .await
-        .map_err(|e| e.to_string())
+/// Read a file that may have been moved, deleted, or locked since its path was stored
+async fn read_file(path: &str) -> Result<String, String> {
+    read_to_string(path).await.map_err(|e| format!("Unable to read file {path}: {e}"))
+}
+
+#[tauri::command]
+async fn cmd_read_response_chunk(
+    response_id: &str,
+    offset: u64,
+    length: u64,
+    w: WebviewWindow,
+) -> Result<ResponseBodyChunk, String> {
+    let response = get_http_response(&w, response_id).await.map_err(|e| e.to_string())?;
+    let body_path = response.body_path.ok_or("Response has no body".to_string())?;
+    read_body_chunk(Path::new(&body_path), offset, length).await
 }
 
 #[tauri::command]
 async fn cmd_get_sse_events(file_path: &str) -> Result<Vec<ServerSentEvent>, String> {
     let body = fs::read(file_path).map_err(|e| e.to_string())?;
-    let mut p = EventParser::new();
-    p.process_bytes(body.into()).map_err(|e| e.to_string())?;
-
-    let mut events = Vec::new();
-    while let Some(e) = p.get_event() {
-        if let SSE::Event(e) = e {
-            events.push(ServerSentEvent {
-                event_type: e.event_type,
-                data: e.data,
-                id: e.id,
-                retry: e.retry,
-            });
-        }
-    }
+    parse_sse(&mut EventParser::new(), body.into())
+}
 
-    Ok(events)
+/// The contents of a file to import, decrypted if it's an encrypted export
+async fn read_import_file(file_path: &str, passphrase: Option<&str>) -> Result<String, String> {
+    let file = read_file(file_path).await?;
+    if !is_encrypted_export(&file) {
+        return Ok(file);
+    }
+    match passphrase.filter(|p| !p.is_empty()) {
+        Some(p) => decrypt_export(&file, p),
+        None => Err("This export is encrypted. Enter its passphrase to import it.".into()),
+    }
 }
 
 #[tauri::command]
@@ -806,15 +1070,22 @@ async fn cmd_import_data<R: Runtime>(
     window: WebviewWindow<R>,
     plugin_manager: State<'_, PluginManager>,
     file_path: &str,
-) -> Result<WorkspaceExportResources, String> {
-    let file = read_to_string(file_path)
-        .await
-        .unwrap_or_else(|_| panic!("Unable to read file {}", file_path));
+    merge: Option<bool>,
+    passphrase: Option<&str>,
+) -> Result<ImportedResources, String> {
+    let file = read_import_file(file_path, passphrase).await?;
     let file_contents = file.as_str();
-    let (import_result, plugin_name) =
-        plugin_manager.import_data(&window, file_contents).await.map_err(|e| e.to_string())?;
+    // OpenAPI specs are converted natively, so they import without the plugin runtime
+    let (import_result, plugin_name) = match import_openapi(file_contents) {
+        Some(resources) => (ImportResponse { resources }, "openapi".to_string()),
+        None => plugin_manager
+            .import_data(&window, Some(file_path), file_contents)
+            .await
+            .map_err(|e| e.to_string())?,
+    };
 
-    let mut imported_resources = WorkspaceExportResources::default();
+    let merge = merge.unwrap_or(false);
+    let mut imported = ImportedResources::default();
     let mut id_map: BTreeMap<String, String> = BTreeMap::new();
 
     fn maybe_gen_id(id: &str, model: ModelType, ids: &mut BTreeMap<String, String>) -> String {
@@ -843,23 +1114,65 @@ async fn cmd_import_data<R: Runtime>(
         }
     }
 
+    // Point a generated id at an existing model so the import updates it in place
+    fn reuse_id(id: &str, existing_id: &str, ids: &mut BTreeMap<String, String>) {
+        if id.starts_with("GENERATE_ID::") {
+            ids.entry(id.replace("GENERATE_ID", "")).or_insert(existing_id.to_string());
+        }
+    }
+
     let resources = import_result.resources;
 
+    // Existing models are hashed once up front (per workspace) for merging
+    let mut workspace_index = ModelHashIndex::default();
+    if merge {
+        let existing = list_workspaces(&window).await.map_err(|e| e.to_string())?;
+        workspace_index = ModelHashIndex::new(&existing, |m| &m.id);
+    }
     for mut v in resources.workspaces {
+        if let Some(id) = workspace_index.find(&v.id, &v) {
+            reuse_id(v.id.as_str(), id, &mut id_map);
+        }
         v.id = maybe_gen_id(v.id.as_str(), ModelType::TypeWorkspace, &mut id_map);
+        workspace_index.claim(&v.id);
+        let existing = get_workspace(&window, &v.id).await.ok();
+        let exists = existing.is_some();
+        if let Some(e) = existing {
+            restore_masked_secrets(&mut v.variables, &e.variables);
+            restore_masked_proxy_password(&mut v.setting_proxy, &e.setting_proxy);
+        }
         let x = upsert_workspace(&window, v).await.map_err(|e| e.to_string())?;
-        imported_resources.workspaces.push(x.clone());
+        imported.count(exists);
+        imported.resources.workspaces.push(x.clone());
     }
-    info!("Imported {} workspaces", imported_resources.workspaces.len());
+    info!("Imported {} workspaces", imported.resources.workspaces.len());
 
+    let mut environment_indexes: HashMap<String, ModelHashIndex> = HashMap::new();
     for mut v in resources.environments {
-        v.id = maybe_gen_id(v.id.as_str(), ModelType::TypeEnvironment, &mut id_map);
         v.workspace_id =
             maybe_gen_id(v.workspace_id.as_str(), ModelType::TypeWorkspace, &mut id_map);
+        if merge && !environment_indexes.contains_key(&v.workspace_id) {
+            let existing =
+                list_environments(&window, &v.workspace_id).await.map_err(|e| e.to_string())?;
+            let index = ModelHashIndex::new(&existing, |m| &m.id);
+            environment_indexes.insert(v.workspace_id.clone(), index);
+        }
+        let index = environment_indexes.entry(v.workspace_id.clone()).or_default();
+        if let Some(id) = index.find(&v.id, &v) {
+            reuse_id(v.id.as_str(), id, &mut id_map);
+        }
+        v.id = maybe_gen_id(v.id.as_str(), ModelType::TypeEnvironment, &mut id_map);
+        index.claim(&v.id);
+        let existing = get_environment(&window, &v.id).await.ok();
+        let exists = existing.is_some();
+        if let Some(e) = existing {
+            restore_masked_secrets(&mut v.variables, &e.variables);
+        }
         let x = upsert_environment(&window, v).await.map_err(|e| e.to_string())?;
-        imported_resources.environments.push(x.clone());
+        imported.count(exists);
+        imported.resources.environments.push(x.clone());
     }
-    info!("Imported {} environments", imported_resources.environments.len());
+    info!("Imported {} environments", imported.resources.environments.len());
 
     // Folders can foreign-key to themselves, so we need to import from
     // the top of the tree to the bottom to avoid foreign key conflicts.
@@ -867,56 +1180,103 @@ async fn cmd_import_data<R: Runtime>(
     //  - The parent folder has been imported
     //  - The folder hasn't already been imported
     // The loop exits when imported.len == to_import.len
-    while imported_resources.folders.len() < resources.folders.len() {
+    let mut folder_indexes: HashMap<String, ModelHashIndex> = HashMap::new();
+    while imported.resources.folders.len() < resources.folders.len() {
         for mut v in resources.folders.clone() {
-            v.id = maybe_gen_id(v.id.as_str(), ModelType::TypeFolder, &mut id_map);
             v.workspace_id =
                 maybe_gen_id(v.workspace_id.as_str(), ModelType::TypeWorkspace, &mut id_map);
             v.folder_id = maybe_gen_id_opt(v.folder_id, ModelType::TypeFolder, &mut id_map);
             if let Some(fid) = v.folder_id.clone() {
-                let imported_parent = imported_resources.folders.iter().find(|f| f.id == fid);
+                let imported_parent = imported.resources.folders.iter().find(|f| f.id == fid);
                 if imported_parent.is_none() {
                     continue;
                 }
             }
-            if let Some(_) = imported_resources.folders.iter().find(|f| f.id == v.id) {
+            // Match against existing folders only once the parent is known, since the
+            // parent id is part of the folder's content hash
+            if merge && !folder_indexes.contains_key(&v.workspace_id) {
+                let existing =
+                    list_folders(&window, &v.workspace_id).await.map_err(|e| e.to_string())?;
+                let index = ModelHashIndex::new(&existing, |m| &m.id);
+                folder_indexes.insert(v.workspace_id.clone(), index);
+            }
+            let index = folder_indexes.entry(v.workspace_id.clone()).or_default();
+            if let Some(id) = index.find(&v.id, &v) {
+                reuse_id(v.id.as_str(), id, &mut id_map);
+            }
+            v.id = maybe_gen_id(v.id.as_str(), ModelType::TypeFolder, &mut id_map);
+            if let Some(_) = imported.resources.folders.iter().find(|f| f.id == v.id) {
                 continue;
             }
+            index.claim(&v.id);
+            let exists = get_folder(&window, &v.id).await.is_ok();
             let x = upsert_folder(&window, v).await.map_err(|e| e.to_string())?;
-            imported_resources.folders.push(x.clone());
+            imported.count(exists);
+            imported.resources.folders.push(x.clone());
         }
     }
-    info!("Imported {} folders", imported_resources.folders.len());
+    info!("Imported {} folders", imported.resources.folders.len());
 
+    let mut http_request_indexes: HashMap<String, ModelHashIndex> = HashMap::new();
     for mut v in resources.http_requests {
-        v.id = maybe_gen_id(v.id.as_str(), ModelType::TypeHttpRequest, &mut id_map);
         v.workspace_id =
             maybe_gen_id(v.workspace_id.as_str(), ModelType::TypeWorkspace, &mut id_map);
         v.folder_id = maybe_gen_id_opt(v.folder_id, ModelType::TypeFolder, &mut id_map);
+        if merge && !http_request_indexes.contains_key(&v.workspace_id) {
+            let existing =
+                list_http_requests(&window, &v.workspace_id).await.map_err(|e| e.to_string())?;
+            let index = ModelHashIndex::new(&existing, |m| &m.id);
+            http_request_indexes.insert(v.workspace_id.clone(), index);
+        }
+        let index = http_request_indexes.entry(v.workspace_id.clone()).or_default();
+        if let Some(id) = index.find(&v.id, &v) {
+            reuse_id(v.id.as_str(), id, &mut id_map);
+        }
+        v.id = maybe_gen_id(v.id.as_str(), ModelType::TypeHttpRequest, &mut id_map);
+        index.claim(&v.id);
+        // Imported scripts could do anything, so the user has to trust them first
+        v.scripts_trusted = false;
+        let exists = matches!(get_http_request(&window, &v.id).await, Ok(Some(_)));
         let x = upsert_http_request(&window, v).await.map_err(|e| e.to_string())?;
-        imported_resources.http_requests.push(x.clone());
+        imported.count(exists);
+        imported.resources.http_requests.push(x.clone());
     }
-    info!("Imported {} http_requests", imported_resources.http_requests.len());
+    info!("Imported {} http_requests", imported.resources.http_requests.len());
 
+    let mut grpc_request_indexes: HashMap<String, ModelHashIndex> = HashMap::new();
     for mut v in resources.grpc_requests {
-        v.id = maybe_gen_id(v.id.as_str(), ModelType::TypeGrpcRequest, &mut id_map);
         v.workspace_id =
             maybe_gen_id(v.workspace_id.as_str(), ModelType::TypeWorkspace, &mut id_map);
         v.folder_id = maybe_gen_id_opt(v.folder_id, ModelType::TypeFolder, &mut id_map);
+        if merge && !grpc_request_indexes.contains_key(&v.workspace_id) {
+            let existing =
+                list_grpc_requests(&window, &v.workspace_id).await.map_err(|e| e.to_string())?;
+            let index = ModelHashIndex::new(&existing, |m| &m.id);
+            grpc_request_indexes.insert(v.workspace_id.clone(), index);
+        }
+        let index = grpc_request_indexes.entry(v.workspace_id.clone()).or_default();
+        if let Some(id) = index.find(&v.id, &v) {
+            reuse_id(v.id.as_str(), id, &mut id_map);
+        }
+        v.id = maybe_gen_id(v.id.as_str(), ModelType::TypeGrpcRequest, &mut id_map);
+        index.claim(&v.id);
+        let exists = matches!(get_grpc_request(&window, &v.id).await, Ok(Some(_)));
         let x = upsert_grpc_request(&window, &v).await.map_err(|e| e.to_string())?;
-        imported_resources.grpc_requests.push(x.clone());
+        imported.count(exists);
+        imported.resources.grpc_requests.push(x.clone());
     }
-    info!("Imported {} grpc_requests", imported_resources.grpc_requests.len());
+    info!("Imported {} grpc_requests", imported.resources.grpc_requests.len());
+    info!("Import created {} and updated {} models", imported.created, imported.updated);
 
     analytics::track_event(
         &window,
         AnalyticsResource::App,
         AnalyticsAction::Import,
-        Some(json!({ "plugin": plugin_name })),
+        Some(json!({ "plugin": plugin_name, "merge": merge })),
     )
     .await;
 
-    Ok(imported_resources)
+    Ok(imported)
 }
 
 #[tauri::command]
@@ -927,6 +1287,41 @@ async fn cmd_http_request_actions<R: Runtime>(
     plugin_manager.get_http_request_actions(&window).await.map_err(|e| e.to_string())
 }
 
+/// Call a single template function with the given args, rendering any templates inside the
+/// arg values first, so the UI can preview its output
+#[tauri::command]
+async fn cmd_eval_template_function<R: Runtime>(
+    window: WebviewWindow<R>,
+    app_handle: AppHandle<R>,
+    name: &str,
+    args: HashMap<String, String>,
+    workspace_id: &str,
+    environment_id: Option<&str>,
+    environment_name: Option<&str>,
+    confirm_production: Option<bool>,
+) -> Result<String, TemplateFunctionError> {
+    let failed = |message: String| TemplateFunctionError::Failed { message };
+    let environment = resolve_environment(&window, workspace_id, environment_id, environment_name)
+        .await
+        .map_err(failed)?;
+    check_production_confirmation(environment.as_ref(), confirm_production.unwrap_or_default())
+        .map_err(|e| failed(e.to_string()))?;
+    let workspace =
+        get_workspace(&window, workspace_id).await.map_err(|e| failed(e.to_string()))?;
+    let cb = PluginTemplateCallback::new(
+        &app_handle,
+        &WindowContext::from_window(&window),
+        RenderPurpose::Preview,
+    );
+
+    let mut rendered_args = HashMap::new();
+    for (k, v) in args {
+        let v = render_template(&v, &workspace, environment.as_ref(), &cb).await;
+        rendered_args.insert(k, v);
+    }
+    cb.call(name, rendered_args).await
+}
+
 #[tauri::command]
 async fn cmd_template_functions<R: Runtime>(
     window: WebviewWindow<R>,
@@ -944,6 +1339,14 @@ async fn cmd_call_http_request_action<R: Runtime>(
     plugin_manager.call_http_request_action(&window, req).await.map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn cmd_list_importers<R: Runtime>(
+    window: WebviewWindow<R>,
+    plugin_manager: State<'_, PluginManager>,
+) -> Result<Vec<Importer>, AppError> {
+    Ok(plugin_manager.list_importers(WindowContext::from_window(&window)).await?)
+}
+
 #[tauri::command]
 async fn cmd_curl_to_request<R: Runtime>(
     window: WebviewWindow<R>,
@@ -952,7 +1355,7 @@ async fn cmd_curl_to_request<R: Runtime>(
     workspace_id: &str,
 ) -> Result<HttpRequest, String> {
     let (import_result, plugin_name) =
-        { plugin_manager.import_data(&window, command).await.map_err(|e| e.to_string())? };
+        { plugin_manager.import_data(&window, None, command).await.map_err(|e| e.to_string())? };
 
     analytics::track_event(
         &window,
@@ -972,23 +1375,121 @@ async fn cmd_curl_to_request<R: Runtime>(
     )
 }
 
+#[tauri::command]
+async fn cmd_curl_cookies_to_jar(
+    command: &str,
+    cookie_jar_id: &str,
+    w: WebviewWindow,
+) -> Result<CookieJar, AppError> {
+    let curl_cookies = parse_curl_cookies(command)?;
+    let mut cookie_jar = get_cookie_jar(&w, cookie_jar_id).await?;
+    add_curl_cookies(&mut cookie_jar, &curl_cookies);
+    Ok(upsert_cookie_jar(&w, &cookie_jar).await?)
+}
+
+/// A curl command for the request, rendered with the environment
+#[tauri::command]
+async fn cmd_export_curl(
+    request_id: &str,
+    environment_id: Option<&str>,
+    environment_name: Option<&str>,
+    confirm_production: Option<bool>,
+    window: WebviewWindow,
+) -> Result<String, AppError> {
+    let request = match get_http_request(&window, request_id).await? {
+        Some(r) => r,
+        None => return Err(yaak_models::error::Error::ModelNotFound(request_id.to_string()).into()),
+    };
+    let environment =
+        resolve_environment(&window, &request.workspace_id, environment_id, environment_name)
+            .await?;
+    check_production_confirmation(environment.as_ref(), confirm_production.unwrap_or_default())?;
+    let workspace = get_workspace(&window, &request.workspace_id).await?;
+    let cb = PluginTemplateCallback::new(
+        window.app_handle(),
+        &WindowContext::from_window(&window),
+        RenderPurpose::Send,
+    );
+    let rendered = render_http_request(&request, &workspace, environment.as_ref(), &cb).await;
+    let settings = get_or_create_settings(&window).await;
+    Ok(request_to_curl(&workspace, &rendered, settings.proxy)?)
+}
+
+#[tauri::command]
+async fn cmd_list_cookies(
+    cookie_jar_id: &str,
+    domain: Option<&str>,
+    path: Option<&str>,
+    w: WebviewWindow,
+) -> Result<Vec<Cookie>, AppError> {
+    let cookie_jar = get_cookie_jar(&w, cookie_jar_id).await?;
+    Ok(list_cookies(&cookie_jar, &CookieFilter { domain, path }))
+}
+
+#[tauri::command]
+async fn cmd_upsert_cookie(
+    cookie_jar_id: &str,
+    url: &str,
+    raw_cookie: &str,
+    w: WebviewWindow,
+) -> Result<CookieJar, AppError> {
+    let mut cookie_jar = get_cookie_jar(&w, cookie_jar_id).await?;
+    upsert_cookie(&mut cookie_jar, url, raw_cookie)?;
+    Ok(upsert_cookie_jar(&w, &cookie_jar).await?)
+}
+
+#[tauri::command]
+async fn cmd_delete_cookie(
+    cookie_jar_id: &str,
+    domain: &str,
+    path: &str,
+    name: &str,
+    w: WebviewWindow,
+) -> Result<CookieJar, AppError> {
+    let mut cookie_jar = get_cookie_jar(&w, cookie_jar_id).await?;
+    if !delete_cookie(&mut cookie_jar, domain, path, name) {
+        return Err(format!("No cookie {name} for {domain}{path}").into());
+    }
+    Ok(upsert_cookie_jar(&w, &cookie_jar).await?)
+}
+
+/// Delete the jar's cookies, or only those for a domain or path
+#[tauri::command]
+async fn cmd_clear_cookie_jar(
+    cookie_jar_id: &str,
+    domain: Option<&str>,
+    path: Option<&str>,
+    w: WebviewWindow,
+) -> Result<CookieJar, AppError> {
+    let mut cookie_jar = get_cookie_jar(&w, cookie_jar_id).await?;
+    clear_cookies(&mut cookie_jar, &CookieFilter { domain, path });
+    Ok(upsert_cookie_jar(&w, &cookie_jar).await?)
+}
+
 #[tauri::command]
 async fn cmd_export_data(
     window: WebviewWindow,
     export_path: &str,
     workspace_ids: Vec<&str>,
+    passphrase: Option<&str>,
 ) -> Result<(), String> {
     let export_data = get_workspace_export_resources(&window, workspace_ids).await;
-    let f = File::options()
+    let mut f = File::options()
         .create(true)
         .truncate(true)
         .write(true)
         .open(export_path)
         .expect("Unable to create file");
 
-    serde_json::to_writer_pretty(&f, &export_data)
-        .map_err(|e| e.to_string())
-        .expect("Failed to write");
+    match passphrase.filter(|p| !p.is_empty()) {
+        Some(p) => {
+            let contents = serde_json::to_vec_pretty(&export_data).map_err(|e| e.to_string())?;
+            f.write_all(encrypt_export(&contents, p)?.as_bytes()).map_err(|e| e.to_string())?;
+        }
+        None => serde_json::to_writer_pretty(&f, &export_data)
+            .map_err(|e| e.to_string())
+            .expect("Failed to write"),
+    };
 
     f.sync_all().expect("Failed to sync");
 
@@ -1017,26 +1518,70 @@ async fn cmd_save_response(
     Ok(())
 }
 
+#[tauri::command]
+async fn cmd_save_response_example(
+    response_id: &str,
+    name: &str,
+    w: WebviewWindow,
+) -> Result<HttpExample, AppError> {
+    if name.trim().is_empty() {
+        return Err(AppError::new(ErrorKind::Validation, "Example name can't be empty"));
+    }
+
+    let response = get_http_response(&w, response_id).await?;
+    if !matches!(response.state, HttpResponseState::Closed) {
+        return Err(AppError::new(
+            ErrorKind::Validation,
+            "Wait for the response to finish before saving it as an example",
+        ));
+    }
+
+    let id = generate_model_id(ModelType::TypeHttpExample);
+    let body_path = freeze_example_body(&w, &response, &id).await?;
+    let example = HttpExample {
+        id,
+        workspace_id: response.workspace_id,
+        request_id: response.request_id,
+        name: name.to_string(),
+        status: response.status,
+        headers: response.headers,
+        body_path,
+        content_length: response.content_length,
+        ..Default::default()
+    };
+    Ok(upsert_http_example(&w, &example).await?)
+}
+
+#[tauri::command]
+async fn cmd_start_mock_server(
+    workspace_id: &str,
+    port: u16,
+    w: WebviewWindow,
+) -> Result<u16, AppError> {
+    Ok(start_mock_server(w.app_handle(), workspace_id, port).await?)
+}
+
+#[tauri::command]
+async fn cmd_stop_mock_server(
+    workspace_id: &str,
+    mock_servers: State<'_, Mutex<MockServers>>,
+) -> Result<bool, AppError> {
+    Ok(mock_servers.lock().await.stop(workspace_id))
+}
+
 #[tauri::command]
 async fn cmd_send_http_request(
     window: WebviewWindow,
     environment_id: Option<&str>,
+    environment_name: Option<&str>,
     cookie_jar_id: Option<&str>,
+    confirm_production: Option<bool>,
+    dry_run: Option<bool>,
     // NOTE: We receive the entire request because to account for the race
     //   condition where the user may have just edited a field before sending
     //   that has not yet been saved in the DB.
     request: HttpRequest,
-) -> Result<HttpResponse, String> {
-    let response =
-        create_default_http_response(&window, &request.id).await.map_err(|e| e.to_string())?;
-
-    let (cancel_tx, mut cancel_rx) = tokio::sync::watch::channel(false);
-    window.listen_any(format!("cancel_http_response_{}", response.id), move |_event| {
-        if let Err(e) = cancel_tx.send(true) {
-            warn!("Failed to send cancel event for request {e:?}");
-        }
-    });
-
+) -> Result<SendOutcome, AppError> {
     let environment = match environment_id {
         Some(id) => match get_environment(&window, id).await {
             Ok(env) => Some(env),
@@ -1045,15 +1590,213 @@ async fn cmd_send_http_request(
                 None
             }
         },
-        None => None,
+        None => resolve_environment(&window, &request.workspace_id, None, environment_name).await?,
     };
 
+    // Check before creating the response, so a blocked send leaves nothing behind
+    check_production_confirmation(environment.as_ref(), confirm_production.unwrap_or_default())?;
+
     let cookie_jar = match cookie_jar_id {
         Some(id) => Some(get_cookie_jar(&window, id).await.expect("Failed to get cookie jar")),
         None => None,
     };
 
-    send_http_request(&window, &request, &response, environment, cookie_jar, &mut cancel_rx).await
+    // A dry run builds the request without sending it, so nothing is saved and there's
+    // nothing to cancel. Build errors come back on an unsaved response.
+    let dry_run = dry_run.unwrap_or_default();
+    let (response, mut cancel_rx, _cancel_tx) = if dry_run {
+        let response = HttpResponse {
+            request_id: request.id.clone(),
+            workspace_id: request.workspace_id.clone(),
+            ..Default::default()
+        };
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        (response, cancel_rx, Some(cancel_tx))
+    } else {
+        let response =
+            create_default_http_response(&window, &request.id).await.map_err(|e| e.to_string())?;
+        let cancel_rx = register_cancel_channel(&window, &response.id).await;
+        (response, cancel_rx, None)
+    };
+
+    Ok(execute_http_request(
+        &window,
+        &request,
+        &response,
+        environment,
+        cookie_jar,
+        &mut cancel_rx,
+        dry_run,
+    )
+    .await?)
+}
+
+#[tauri::command]
+async fn cmd_replay_response(
+    response_id: &str,
+    window: WebviewWindow,
+) -> Result<HttpResponse, AppError> {
+    let original = get_http_response(&window, response_id).await?;
+    let snapshot = match original.request_snapshot {
+        Some(r) => r,
+        None => {
+            return Err(AppError::new(
+                ErrorKind::NotFound,
+                format!("Response {response_id} has no stored request to replay"),
+            ))
+        }
+    };
+
+    let response = create_default_http_response(&window, &snapshot.id).await?;
+    let mut cancel_rx = register_cancel_channel(&window, &response.id).await;
+    let cookie_jar = cookie_jar_from_window(&window).await;
+
+    // The snapshot is already rendered, but the environment has the secrets masked in it
+    let environment = environment_from_window(&window).await;
+    Ok(replay_http_request(&window, &snapshot, &response, environment, cookie_jar, &mut cancel_rx)
+        .await?)
+}
+
+#[tauri::command]
+async fn cmd_run_folder(
+    window: WebviewWindow,
+    folder_id: &str,
+    environment_id: Option<&str>,
+    environment_name: Option<&str>,
+    cookie_jar_id: Option<&str>,
+    confirm_production: Option<bool>,
+    stop_on_failure: Option<bool>,
+) -> Result<Vec<RunResult>, AppError> {
+    let folder = get_folder(&window, folder_id).await?;
+    let environment =
+        resolve_environment(&window, &folder.workspace_id, environment_id, environment_name)
+            .await?;
+    check_production_confirmation(environment.as_ref(), confirm_production.unwrap_or_default())?;
+
+    let cookie_jar = match cookie_jar_id {
+        Some(id) => Some(get_cookie_jar(&window, id).await?),
+        None => None,
+    };
+
+    let stop_on_failure = stop_on_failure.unwrap_or_default();
+    Ok(run_folder(&window, &folder, environment, cookie_jar, stop_on_failure).await?)
+}
+
+#[tauri::command]
+async fn cmd_send_many_http_requests(
+    window: WebviewWindow,
+    request_ids: Vec<String>,
+    environment_id: Option<&str>,
+    environment_name: Option<&str>,
+    cookie_jar_id: Option<&str>,
+    confirm_production: Option<bool>,
+    concurrency: Option<usize>,
+) -> Result<Vec<HttpResponse>, AppError> {
+    let mut requests = Vec::new();
+    for id in &request_ids {
+        match get_http_request(&window, id).await? {
+            Some(r) => requests.push(r),
+            None => return Err(yaak_models::error::Error::ModelNotFound(id.to_string()).into()),
+        }
+    }
+
+    // The requests can be from different workspaces, and each is sent with an environment from
+    // its own. Other workspaces use their environment named like the chosen one, if they have it.
+    let chosen = match environment_id {
+        Some(id) => Some(get_environment(&window, id).await?),
+        None => None,
+    };
+    let mut environments: HashMap<String, Option<Environment>> = HashMap::new();
+    for workspace_id in requests.iter().map(|r| &r.workspace_id) {
+        if environments.contains_key(workspace_id) {
+            continue;
+        }
+        let environment = match &chosen {
+            Some(e) if &e.workspace_id == workspace_id => Some(e.clone()),
+            Some(e) => get_environment_by_name(&window, workspace_id, &e.name).await.ok(),
+            None => resolve_environment(&window, workspace_id, None, environment_name).await?,
+        };
+        let confirmed = confirm_production.unwrap_or_default();
+        check_production_confirmation(environment.as_ref(), confirmed)?;
+        environments.insert(workspace_id.clone(), environment);
+    }
+    let requests = requests
+        .into_iter()
+        .map(|r| {
+            let environment = environments.get(&r.workspace_id).cloned().flatten();
+            (r, environment)
+        })
+        .collect();
+
+    let cookie_jar = match cookie_jar_id {
+        Some(id) => Some(get_cookie_jar(&window, id).await?),
+        None => None,
+    };
+
+    let concurrency = concurrency.unwrap_or(DEFAULT_SEND_CONCURRENCY);
+    Ok(send_many_http_requests(&window, requests, cookie_jar, concurrency).await)
+}
+
+#[tauri::command]
+async fn cmd_run_load_test(
+    window: WebviewWindow,
+    request_id: &str,
+    duration: u64,
+    concurrency: usize,
+    environment_id: Option<&str>,
+    environment_name: Option<&str>,
+    cookie_jar_id: Option<&str>,
+    confirm_production: Option<bool>,
+) -> Result<LoadTest, AppError> {
+    let request = match get_http_request(&window, request_id).await? {
+        Some(r) => r,
+        None => return Err(yaak_models::error::Error::ModelNotFound(request_id.to_string()).into()),
+    };
+
+    let environment =
+        resolve_environment(&window, &request.workspace_id, environment_id, environment_name)
+            .await?;
+    check_production_confirmation(environment.as_ref(), confirm_production.unwrap_or_default())?;
+
+    let cookie_jar = match cookie_jar_id {
+        Some(id) => Some(get_cookie_jar(&window, id).await?),
+        None => None,
+    };
+
+    let duration = Duration::from_millis(duration);
+    Ok(run_load_test(&window, &request, environment, cookie_jar, duration, concurrency).await?)
+}
+
+#[tauri::command]
+async fn cmd_cancel_request(response_id: &str, window: WebviewWindow) -> Result<bool, String> {
+    let active_requests: State<'_, Mutex<ActiveRequests>> = window.state();
+    let cancelled = active_requests.lock().await.cancel(response_id);
+    debug!("Cancel request {response_id} found={cancelled}");
+    Ok(cancelled)
+}
+
+/// Create a cancel channel for a response that fires on `cmd_cancel_request`, the frontend's
+/// cancel event, or when the window is closed
+async fn register_cancel_channel<R: Runtime>(
+    window: &WebviewWindow<R>,
+    response_id: &str,
+) -> tokio::sync::watch::Receiver<bool> {
+    let (cancel_tx, cancel_rx) = tokio::sync::watch::channel(false);
+    let cancel_tx = Arc::new(cancel_tx);
+    {
+        let active_requests: State<'_, Mutex<ActiveRequests>> = window.state();
+        let mut active_requests = active_requests.lock().await;
+        let senders = active_requests.0.entry(window.label().to_string()).or_default();
+        // Drop senders for requests that have finished since all their receivers are gone
+        senders.retain(|_, tx| !tx.is_closed());
+        senders.insert(response_id.to_string(), cancel_tx.clone());
+    }
+    window.listen_any(format!("cancel_http_response_{}", response_id), move |_event| {
+        if let Err(e) = cancel_tx.send(true) {
+            warn!("Failed to send cancel event for request {e:?}");
+        }
+    });
+    cancel_rx
 }
 
 async fn response_err<R: Runtime>(
@@ -1106,6 +1849,26 @@ async fn cmd_get_key_value(
     Ok(result)
 }
 
+#[tauri::command]
+async fn cmd_get_secure_key_value(
+    namespace: &str,
+    key: &str,
+    w: WebviewWindow,
+) -> Result<Option<String>, ()> {
+    Ok(get_secure_key_value(&w, namespace, key).await)
+}
+
+#[tauri::command]
+async fn cmd_set_secure_key_value(
+    namespace: &str,
+    key: &str,
+    value: &str,
+    w: WebviewWindow,
+) -> Result<KeyValue, AppError> {
+    let (key_value, _created) = set_secure_key_value(&w, namespace, key, value).await;
+    Ok(key_value)
+}
+
 #[tauri::command]
 async fn cmd_set_key_value(
     namespace: &str,
@@ -1153,13 +1916,12 @@ async fn cmd_uninstall_plugin<R: Runtime>(
     plugin_id: &str,
     plugin_manager: State<'_, PluginManager>,
     window: WebviewWindow<R>,
-) -> Result<Plugin, String> {
-    let plugin = delete_plugin(&window, plugin_id).await.map_err(|e| e.to_string())?;
+) -> Result<Plugin, AppError> {
+    let plugin = delete_plugin(&window, plugin_id).await?;
 
     plugin_manager
         .uninstall(WindowContext::from_window(&window), plugin.directory.as_str())
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
 
     Ok(plugin)
 }
@@ -1168,13 +1930,16 @@ async fn cmd_uninstall_plugin<R: Runtime>(
 async fn cmd_update_cookie_jar(
     cookie_jar: CookieJar,
     w: WebviewWindow,
-) -> Result<CookieJar, String> {
-    upsert_cookie_jar(&w, &cookie_jar).await.map_err(|e| e.to_string())
+) -> Result<CookieJar, AppError> {
+    Ok(upsert_cookie_jar(&w, &cookie_jar).await?)
 }
 
 #[tauri::command]
-async fn cmd_delete_cookie_jar(w: WebviewWindow, cookie_jar_id: &str) -> Result<CookieJar, String> {
-    delete_cookie_jar(&w, cookie_jar_id).await.map_err(|e| e.to_string())
+async fn cmd_delete_cookie_jar(
+    w: WebviewWindow,
+    cookie_jar_id: &str,
+) -> Result<CookieJar, AppError> {
+    Ok(delete_cookie_jar(&w, cookie_jar_id).await?)
 }
 
 #[tauri::command]
@@ -1237,6 +2002,14 @@ async fn cmd_create_grpc_request(
     .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn cmd_duplicate_environment(
+    environment_id: &str,
+    w: WebviewWindow,
+) -> Result<Environment, AppError> {
+    Ok(duplicate_environment(&w, environment_id).await?)
+}
+
 #[tauri::command]
 async fn cmd_duplicate_grpc_request(id: &str, w: WebviewWindow) -> Result<GrpcRequest, String> {
     duplicate_grpc_request(&w, id).await.map_err(|e| e.to_string())
@@ -1247,57 +2020,124 @@ async fn cmd_create_http_request(
     request: HttpRequest,
     w: WebviewWindow,
 ) -> Result<HttpRequest, String> {
+    let workspace = get_workspace(&w, &request.workspace_id).await.map_err(|e| e.to_string())?;
+    let request = apply_workspace_request_defaults(request, &workspace);
     upsert_http_request(&w, request).await.map_err(|e| e.to_string())
 }
 
+/// Fill in the workspace's default method and body type for a new request, each on its own. The
+/// method is only replaced if it's still the default GET, and the body type (with its
+/// Content-Type header) only if the request arrives without one.
+fn apply_workspace_request_defaults(
+    mut request: HttpRequest,
+    workspace: &Workspace,
+) -> HttpRequest {
+    if !workspace.setting_default_method.is_empty() && request.method == "GET" {
+        request.method = workspace.setting_default_method.to_uppercase();
+    }
+
+    let body_type = match (&request.body_type, &workspace.setting_default_body_type) {
+        (None, Some(b)) if !b.is_empty() => b.to_string(),
+        _ => return request,
+    };
+
+    let content_type = match body_type.as_str() {
+        "graphql" => Some("application/json"),
+        "other" => Some("text/plain"),
+        "bytes" => Some("application/octet-stream"),
+        "binary" => None,
+        b if b.contains('/') => Some(b),
+        _ => None,
+    };
+    let has_content_type =
+        request.headers.iter().any(|h| h.name.eq_ignore_ascii_case("content-type"));
+    if let (Some(content_type), false) = (content_type, has_content_type) {
+        request.headers.push(HttpRequestHeader {
+            enabled: true,
+            name: "Content-Type".to_string(),
+            value: content_type.to_string(),
+        });
+    }
+
+    request.body_type = Some(body_type);
+    request
+}
+
+#[tauri::command]
+async fn cmd_duplicate_http_request(
+    id: &str,
+    response_count: Option<usize>,
+    w: WebviewWindow,
+) -> Result<HttpRequest, String> {
+    duplicate_http_request(&w, id, response_count.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
-async fn cmd_duplicate_http_request(id: &str, w: WebviewWindow) -> Result<HttpRequest, String> {
-    duplicate_http_request(&w, id).await.map_err(|e| e.to_string())
+async fn cmd_update_workspace(
+    workspace: Workspace,
+    w: WebviewWindow,
+) -> Result<Workspace, AppError> {
+    Ok(upsert_workspace(&w, workspace).await?)
 }
 
+/// Replace only the workspace's variables, which apply under every environment
 #[tauri::command]
-async fn cmd_update_workspace(workspace: Workspace, w: WebviewWindow) -> Result<Workspace, String> {
-    upsert_workspace(&w, workspace).await.map_err(|e| e.to_string())
+async fn cmd_update_workspace_variables(
+    workspace_id: &str,
+    variables: Vec<EnvironmentVariable>,
+    w: WebviewWindow,
+) -> Result<Workspace, AppError> {
+    let workspace = get_workspace(&w, workspace_id).await?;
+    Ok(upsert_workspace(
+        &w,
+        Workspace {
+            variables,
+            ..workspace
+        },
+    )
+    .await?)
 }
 
 #[tauri::command]
 async fn cmd_update_environment(
     environment: Environment,
     w: WebviewWindow,
-) -> Result<Environment, String> {
-    upsert_environment(&w, environment).await.map_err(|e| e.to_string())
+) -> Result<Environment, AppError> {
+    Ok(upsert_environment(&w, environment).await?)
 }
 
 #[tauri::command]
 async fn cmd_update_grpc_request(
     request: GrpcRequest,
     w: WebviewWindow,
-) -> Result<GrpcRequest, String> {
-    upsert_grpc_request(&w, &request).await.map_err(|e| e.to_string())
+) -> Result<GrpcRequest, AppError> {
+    Ok(upsert_grpc_request(&w, &request).await?)
 }
 
 #[tauri::command]
 async fn cmd_update_http_request(
     request: HttpRequest,
     window: WebviewWindow,
-) -> Result<HttpRequest, String> {
-    upsert_http_request(&window, request).await.map_err(|e| e.to_string())
+) -> Result<HttpRequest, AppError> {
+    Ok(upsert_http_request(&window, request).await?)
 }
 
 #[tauri::command]
 async fn cmd_delete_grpc_request(
     w: WebviewWindow,
     request_id: &str,
-) -> Result<GrpcRequest, String> {
-    delete_grpc_request(&w, request_id).await.map_err(|e| e.to_string())
+) -> Result<GrpcRequest, AppError> {
+    Ok(delete_grpc_request(&w, request_id).await?)
 }
 
 #[tauri::command]
 async fn cmd_delete_http_request(
     w: WebviewWindow,
     request_id: &str,
-) -> Result<HttpRequest, String> {
-    delete_http_request(&w, request_id).await.map_err(|e| e.to_string())
+) -> Result<HttpRequest, AppError> {
+    Ok(delete_http_request(&w, request_id).await?)
 }
 
 #[tauri::command]
@@ -1328,8 +2168,8 @@ async fn cmd_create_folder(
 }
 
 #[tauri::command]
-async fn cmd_update_folder(folder: Folder, w: WebviewWindow) -> Result<Folder, String> {
-    upsert_folder(&w, folder).await.map_err(|e| e.to_string())
+async fn cmd_update_folder(folder: Folder, w: WebviewWindow) -> Result<Folder, AppError> {
+    Ok(upsert_folder(&w, folder).await?)
 }
 
 #[tauri::command]
@@ -1342,16 +2182,16 @@ async fn cmd_write_file_dev(pathname: &str, contents: &str) -> Result<(), String
 }
 
 #[tauri::command]
-async fn cmd_delete_folder(w: WebviewWindow, folder_id: &str) -> Result<Folder, String> {
-    delete_folder(&w, folder_id).await.map_err(|e| e.to_string())
+async fn cmd_delete_folder(w: WebviewWindow, folder_id: &str) -> Result<Folder, AppError> {
+    Ok(delete_folder(&w, folder_id).await?)
 }
 
 #[tauri::command]
 async fn cmd_delete_environment(
     w: WebviewWindow,
     environment_id: &str,
-) -> Result<Environment, String> {
-    delete_environment(&w, environment_id).await.map_err(|e| e.to_string())
+) -> Result<Environment, AppError> {
+    Ok(delete_environment(&w, environment_id).await?)
 }
 
 #[tauri::command]
@@ -1386,6 +2226,93 @@ async fn cmd_list_http_requests(
     list_http_requests(&w, workspace_id).await.map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn cmd_list_http_response_events(
+    response_id: &str,
+    w: WebviewWindow,
+) -> Result<Vec<HttpResponseEvent>, AppError> {
+    let events = list_http_response_events(&w, response_id).await?;
+    let response = get_http_response(&w, response_id).await?;
+    let body_path = match response.body_path {
+        Some(p) if events.is_empty() && matches!(response.state, HttpResponseState::Closed) => p,
+        _ => return Ok(events),
+    };
+
+    // Responses from before events were stored only have them in the body
+    let body = fs::read(body_path)?;
+    let events = parse_sse(&mut EventParser::new(), body.into())?;
+    Ok(events
+        .into_iter()
+        .map(|e| HttpResponseEvent {
+            model: "http_response_event".to_string(),
+            workspace_id: response.workspace_id.clone(),
+            response_id: response.id.clone(),
+            event_type: e.event_type,
+            data: e.data,
+            event_id: e.id,
+            retry: e.retry.map(|r| r.min(i32::MAX as u64) as i32),
+            ..Default::default()
+        })
+        .collect())
+}
+
+#[tauri::command]
+async fn cmd_list_http_timeline_events(
+    response_id: &str,
+    w: WebviewWindow,
+) -> Result<Vec<HttpTimelineEvent>, AppError> {
+    Ok(list_http_timeline_events(&w, response_id).await?)
+}
+
+#[tauri::command]
+async fn cmd_list_request_examples(
+    request_id: &str,
+    w: WebviewWindow,
+) -> Result<Vec<HttpExample>, AppError> {
+    Ok(list_http_examples(&w, request_id).await?)
+}
+
+#[tauri::command]
+async fn cmd_list_load_tests(
+    request_id: &str,
+    w: WebviewWindow,
+) -> Result<Vec<LoadTest>, AppError> {
+    Ok(list_load_tests(&w, request_id).await?)
+}
+
+#[tauri::command]
+async fn cmd_list_run_results(
+    folder_id: &str,
+    w: WebviewWindow,
+) -> Result<Vec<RunResult>, AppError> {
+    Ok(list_run_results(&w, folder_id).await?)
+}
+
+#[tauri::command]
+async fn cmd_list_request_history(
+    workspace_id: &str,
+    request_id: Option<&str>,
+    limit: Option<i64>,
+    w: WebviewWindow,
+) -> Result<Vec<RequestHistory>, AppError> {
+    Ok(list_request_history(&w, workspace_id, request_id, limit).await?)
+}
+
+#[tauri::command]
+async fn cmd_search_workspace(
+    workspace_id: &str,
+    query: &str,
+    limit: Option<i64>,
+    w: WebviewWindow,
+) -> Result<Vec<SearchMatch>, AppError> {
+    Ok(search_workspace(&w, workspace_id, query, limit).await?)
+}
+
+#[tauri::command]
+async fn cmd_list_all_requests(w: WebviewWindow) -> Result<Vec<RequestSummary>, AppError> {
+    Ok(list_all_requests(&w).await?)
+}
+
 #[tauri::command]
 async fn cmd_list_environments(
     workspace_id: &str,
@@ -1399,6 +2326,29 @@ async fn cmd_list_plugins(w: WebviewWindow) -> Result<Vec<Plugin>, String> {
     list_plugins(&w).await.map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn cmd_plugin_statuses(
+    plugin_manager: State<'_, PluginManager>,
+) -> Result<Vec<PluginStatus>, String> {
+    Ok(plugin_manager.plugin_statuses().await)
+}
+
+#[tauri::command]
+async fn cmd_reload_plugin<R: Runtime>(
+    plugin_id: &str,
+    window: WebviewWindow<R>,
+    plugin_manager: State<'_, PluginManager>,
+) -> Result<BootResponse, AppError> {
+    let plugin = get_plugin(&window, plugin_id).await?;
+    let info = plugin_manager
+        .reload_plugin(WindowContext::from_window(&window), plugin.directory.as_str())
+        .await?;
+
+    // Bumping the plugin makes windows re-fetch its template functions and actions
+    upsert_plugin(&window, plugin).await?;
+    Ok(info)
+}
+
 #[tauri::command]
 async fn cmd_reload_plugins<R: Runtime>(
     window: WebviewWindow<R>,
@@ -1432,28 +2382,41 @@ async fn cmd_get_settings(w: WebviewWindow) -> Result<Settings, ()> {
 }
 
 #[tauri::command]
-async fn cmd_update_settings(settings: Settings, w: WebviewWindow) -> Result<Settings, String> {
+async fn cmd_update_settings(
+    settings: Settings,
+    w: WebviewWindow,
+    plugin_manager: State<'_, PluginManager>,
+) -> Result<Settings, String> {
+    if settings.response_body_dir != get_or_create_settings(&w).await.response_body_dir {
+        let data_dir = w.path().app_data_dir().map_err(|e| e.to_string())?;
+        ensure_writable_dir(&responses_dir(&data_dir, settings.response_body_dir.as_deref()))?;
+    }
+    let settings = Settings {
+        interface_scale: (settings.interface_scale as f64).clamp(MIN_ZOOM, MAX_ZOOM) as f32,
+        ..settings
+    };
+    plugin_manager.set_timeout(settings.plugin_timeout).await;
     update_settings(&w, settings).await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn cmd_get_folder(id: &str, w: WebviewWindow) -> Result<Folder, String> {
-    get_folder(&w, id).await.map_err(|e| e.to_string())
+async fn cmd_get_folder(id: &str, w: WebviewWindow) -> Result<Folder, AppError> {
+    Ok(get_folder(&w, id).await?)
 }
 
 #[tauri::command]
-async fn cmd_get_grpc_request(id: &str, w: WebviewWindow) -> Result<Option<GrpcRequest>, String> {
-    get_grpc_request(&w, id).await.map_err(|e| e.to_string())
+async fn cmd_get_grpc_request(id: &str, w: WebviewWindow) -> Result<Option<GrpcRequest>, AppError> {
+    Ok(get_grpc_request(&w, id).await?)
 }
 
 #[tauri::command]
-async fn cmd_get_http_request(id: &str, w: WebviewWindow) -> Result<Option<HttpRequest>, String> {
-    get_http_request(&w, id).await.map_err(|e| e.to_string())
+async fn cmd_get_http_request(id: &str, w: WebviewWindow) -> Result<Option<HttpRequest>, AppError> {
+    Ok(get_http_request(&w, id).await?)
 }
 
 #[tauri::command]
-async fn cmd_get_cookie_jar(id: &str, w: WebviewWindow) -> Result<CookieJar, String> {
-    get_cookie_jar(&w, id).await.map_err(|e| e.to_string())
+async fn cmd_get_cookie_jar(id: &str, w: WebviewWindow) -> Result<CookieJar, AppError> {
+    Ok(get_cookie_jar(&w, id).await?)
 }
 
 #[tauri::command]
@@ -1461,33 +2424,84 @@ async fn cmd_list_cookie_jars(
     workspace_id: &str,
     w: WebviewWindow,
 ) -> Result<Vec<CookieJar>, String> {
-    let cookie_jars = list_cookie_jars(&w, workspace_id).await.expect("Failed to find cookie jars");
+    list_or_create_cookie_jars(&w, workspace_id).await.map_err(|e| e.to_string())
+}
 
-    if cookie_jars.is_empty() {
-        let cookie_jar = upsert_cookie_jar(
-            &w,
-            &CookieJar {
-                name: "Default".to_string(),
-                workspace_id: workspace_id.to_string(),
-                ..Default::default()
-            },
-        )
-        .await
-        .expect("Failed to create CookieJar");
-        Ok(vec![cookie_jar])
-    } else {
-        Ok(cookie_jars)
+/// List the workspace's cookie jars, creating a default one if it has none
+async fn list_or_create_cookie_jars(
+    w: &WebviewWindow,
+    workspace_id: &str,
+) -> yaak_models::error::Result<Vec<CookieJar>> {
+    let cookie_jars = list_cookie_jars(w, workspace_id).await?;
+    if !cookie_jars.is_empty() {
+        return Ok(cookie_jars);
+    }
+
+    let cookie_jar = upsert_cookie_jar(
+        w,
+        &CookieJar {
+            name: "Default".to_string(),
+            workspace_id: workspace_id.to_string(),
+            ..Default::default()
+        },
+    )
+    .await?;
+    Ok(vec![cookie_jar])
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceModels {
+    cookie_jars: Vec<CookieJar>,
+    environments: Vec<Environment>,
+    folders: Vec<Folder>,
+    grpc_requests: Vec<GrpcRequest>,
+    http_requests: Vec<HttpRequest>,
+}
+
+/// Load everything a window needs to show a workspace in one call, instead of one per model
+#[tauri::command]
+async fn cmd_load_workspace(
+    workspace_id: &str,
+    w: WebviewWindow,
+) -> Result<WorkspaceModels, AppError> {
+    let (cookie_jars, environments, folders, grpc_requests, http_requests) = tokio::try_join!(
+        list_or_create_cookie_jars(&w, workspace_id),
+        list_environments(&w, workspace_id),
+        list_folders(&w, workspace_id),
+        list_grpc_requests(&w, workspace_id),
+        list_http_requests(&w, workspace_id),
+    )?;
+
+    if let Err(e) = w.emit_to(w.label(), "workspace_loaded", workspace_id) {
+        warn!("Failed to emit workspace_loaded {e:?}");
     }
+
+    Ok(WorkspaceModels {
+        cookie_jars,
+        environments,
+        folders,
+        grpc_requests,
+        http_requests,
+    })
+}
+
+#[tauri::command]
+async fn cmd_get_environment(id: &str, w: WebviewWindow) -> Result<Environment, AppError> {
+    Ok(get_environment(&w, id).await?)
 }
 
 #[tauri::command]
-async fn cmd_get_environment(id: &str, w: WebviewWindow) -> Result<Environment, String> {
-    get_environment(&w, id).await.map_err(|e| e.to_string())
+async fn cmd_get_workspace(id: &str, w: WebviewWindow) -> Result<Workspace, AppError> {
+    Ok(get_workspace(&w, id).await?)
 }
 
 #[tauri::command]
-async fn cmd_get_workspace(id: &str, w: WebviewWindow) -> Result<Workspace, String> {
-    get_workspace(&w, id).await.map_err(|e| e.to_string())
+async fn cmd_workspace_stats(
+    workspace_id: &str,
+    w: WebviewWindow,
+) -> Result<WorkspaceStats, AppError> {
+    Ok(get_workspace_stats(&w, workspace_id).await?)
 }
 
 #[tauri::command]
@@ -1500,13 +2514,16 @@ async fn cmd_list_http_responses(
 }
 
 #[tauri::command]
-async fn cmd_delete_http_response(id: &str, w: WebviewWindow) -> Result<HttpResponse, String> {
-    delete_http_response(&w, id).await.map_err(|e| e.to_string())
+async fn cmd_delete_http_response(id: &str, w: WebviewWindow) -> Result<HttpResponse, AppError> {
+    Ok(delete_http_response(&w, id).await?)
 }
 
 #[tauri::command]
-async fn cmd_delete_grpc_connection(id: &str, w: WebviewWindow) -> Result<GrpcConnection, String> {
-    delete_grpc_connection(&w, id).await.map_err(|e| e.to_string())
+async fn cmd_delete_grpc_connection(
+    id: &str,
+    w: WebviewWindow,
+) -> Result<GrpcConnection, AppError> {
+    Ok(delete_grpc_connection(&w, id).await?)
 }
 
 #[tauri::command]
@@ -1536,6 +2553,7 @@ async fn cmd_list_workspaces(w: WebviewWindow) -> Result<Vec<Workspace>, String>
                 name: "Yaak".to_string(),
                 setting_follow_redirects: true,
                 setting_validate_certificates: true,
+                setting_send_default_accept: true,
                 ..Default::default()
             },
         )
@@ -1623,8 +2641,8 @@ async fn cmd_new_main_window(app_handle: AppHandle, url: &str) -> Result<(), Str
 }
 
 #[tauri::command]
-async fn cmd_delete_workspace(w: WebviewWindow, workspace_id: &str) -> Result<Workspace, String> {
-    delete_workspace(&w, workspace_id).await.map_err(|e| e.to_string())
+async fn cmd_delete_workspace(w: WebviewWindow, workspace_id: &str) -> Result<Workspace, AppError> {
+    Ok(delete_workspace(&w, workspace_id).await?)
 }
 
 #[tauri::command]
@@ -1636,6 +2654,81 @@ async fn cmd_check_for_updates(
     yaak_updater.lock().await.force_check(&app_handle, update_mode).await.map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn cmd_backup_database(path: &str, w: WebviewWindow) -> Result<(), AppError> {
+    Ok(backup_database(&w, &PathBuf::from(path)).await?)
+}
+
+#[tauri::command]
+async fn cmd_restore_database(path: &str, w: WebviewWindow) -> Result<(), AppError> {
+    restore_database(&w, &PathBuf::from(path)).await?;
+
+    let h = w.app_handle().clone();
+    w.dialog()
+        .message("The backup will be restored the next time Yaak starts. Restart now?")
+        .title("Restore Database")
+        .buttons(MessageDialogButtons::OkCancelCustom("Restart".to_string(), "Later".to_string()))
+        .show(move |confirmed| {
+            if confirmed {
+                h.restart();
+            }
+        });
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn cmd_storage_stats(w: WebviewWindow) -> Result<StorageStats, AppError> {
+    Ok(get_storage_stats(&w).await?)
+}
+
+#[tauri::command]
+async fn cmd_vacuum_database(w: WebviewWindow) -> Result<VacuumResult, AppError> {
+    Ok(vacuum(&w).await?)
+}
+
+#[tauri::command]
+async fn cmd_cleanup_storage(
+    options: CleanupStorageOptions,
+    w: WebviewWindow,
+) -> Result<CleanupStorageResult, AppError> {
+    Ok(cleanup_storage(&w, options).await?)
+}
+
+#[tauri::command]
+async fn cmd_prune_request_history(
+    workspace_id: &str,
+    older_than_days: u32,
+    w: WebviewWindow,
+) -> Result<usize, AppError> {
+    let cutoff = Utc::now().naive_utc() - TimeDelta::days(older_than_days as i64);
+    Ok(delete_request_history_before(&w, workspace_id, cutoff).await?)
+}
+
+#[tauri::command]
+async fn cmd_prune_responses(
+    workspace_id: &str,
+    older_than_days: u32,
+    keep_per_request: usize,
+    w: WebviewWindow,
+) -> Result<PruneResponsesResult, AppError> {
+    Ok(prune_responses(&w, workspace_id, older_than_days, keep_per_request).await?)
+}
+
+#[tauri::command]
+async fn cmd_get_changelog(version: &str, w: WebviewWindow) -> Result<Changelog, String> {
+    Ok(get_changelog(&w, version).await)
+}
+
+#[tauri::command]
+async fn cmd_rollback_update(
+    app_handle: AppHandle,
+    yaak_updater: State<'_, Mutex<YaakUpdater>>,
+) -> Result<String, String> {
+    let update_mode = get_update_mode(&app_handle).await;
+    yaak_updater.lock().await.rollback(&app_handle, update_mode).await
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     #[allow(unused_mut)]
@@ -1687,6 +2780,8 @@ pub fn run() {
             .plugin(tauri_plugin_os::init())
             .plugin(tauri_plugin_fs::init())
             .plugin(yaak_models::plugin::Builder::default().build())
+            // Before the plugin runtime, which exits the process once it's terminated
+            .plugin(mock_server::init())
             .plugin(yaak_plugin_runtime::plugin::init());
 
     #[cfg(target_os = "macos")]
@@ -1707,6 +2802,9 @@ pub fn run() {
             let yaak_notifier = YaakNotifier::new();
             app.manage(Mutex::new(yaak_notifier));
 
+            // Track in-flight requests so they can be cancelled when their window closes
+            app.manage(Mutex::new(ActiveRequests::default()));
+
             // Add GRPC manager
             let grpc_handle = GrpcHandle::new(&app.app_handle());
             app.manage(Mutex::new(grpc_handle));
@@ -1716,17 +2814,23 @@ pub fn run() {
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
+            cmd_backup_database,
             cmd_call_http_request_action,
+            cmd_cancel_request,
             cmd_check_for_updates,
+            cmd_cleanup_storage,
+            cmd_clear_cookie_jar,
             cmd_create_cookie_jar,
             cmd_create_environment,
             cmd_create_folder,
             cmd_create_grpc_request,
             cmd_create_http_request,
             cmd_create_workspace,
+            cmd_curl_cookies_to_jar,
             cmd_curl_to_request,
             cmd_delete_all_grpc_connections,
             cmd_delete_all_http_responses,
+            cmd_delete_cookie,
             cmd_delete_cookie_jar,
             cmd_delete_environment,
             cmd_delete_folder,
@@ -1737,47 +2841,86 @@ pub fn run() {
             cmd_delete_send_history,
             cmd_delete_workspace,
             cmd_dismiss_notification,
+            cmd_duplicate_environment,
             cmd_duplicate_grpc_request,
             cmd_duplicate_http_request,
+            cmd_eval_template_function,
+            cmd_export_curl,
             cmd_export_data,
             cmd_filter_response,
+            cmd_format_body,
             cmd_format_json,
+            cmd_format_xml,
+            cmd_get_changelog,
             cmd_get_cookie_jar,
             cmd_get_environment,
             cmd_get_folder,
             cmd_get_grpc_request,
             cmd_get_http_request,
             cmd_get_key_value,
+            cmd_get_secure_key_value,
             cmd_get_settings,
             cmd_get_sse_events,
             cmd_get_workspace,
+            cmd_graphql_introspect,
             cmd_grpc_go,
             cmd_grpc_reflect,
             cmd_http_request_actions,
             cmd_import_data,
+            cmd_insert_template_variable,
             cmd_install_plugin,
+            cmd_list_all_requests,
             cmd_list_cookie_jars,
+            cmd_list_cookies,
             cmd_list_environments,
             cmd_list_folders,
             cmd_list_grpc_connections,
             cmd_list_grpc_events,
             cmd_list_grpc_requests,
             cmd_list_http_requests,
+            cmd_list_http_response_events,
             cmd_list_http_responses,
+            cmd_list_http_timeline_events,
+            cmd_list_importers,
+            cmd_list_load_tests,
             cmd_list_plugins,
+            cmd_list_request_examples,
+            cmd_list_request_history,
+            cmd_list_run_results,
             cmd_list_workspaces,
+            cmd_load_workspace,
             cmd_metadata,
             cmd_new_child_window,
             cmd_new_main_window,
             cmd_parse_template,
             cmd_plugin_info,
+            cmd_plugin_statuses,
+            cmd_preview_cookies,
+            cmd_prune_request_history,
+            cmd_prune_responses,
+            cmd_read_response_chunk,
+            cmd_reload_plugin,
             cmd_reload_plugins,
+            cmd_rename_template_variable,
+            cmd_render_http_request,
             cmd_render_template,
+            cmd_replay_response,
+            cmd_restore_database,
+            cmd_rollback_update,
+            cmd_run_folder,
+            cmd_run_load_test,
             cmd_save_response,
+            cmd_save_response_example,
+            cmd_search_workspace,
             cmd_send_ephemeral_request,
             cmd_send_http_request,
+            cmd_send_many_http_requests,
             cmd_set_key_value,
+            cmd_set_secure_key_value,
             cmd_set_update_mode,
+            cmd_start_mock_server,
+            cmd_stop_mock_server,
+            cmd_storage_stats,
             cmd_template_functions,
             cmd_template_tokens_to_string,
             cmd_track_event,
@@ -1789,6 +2932,12 @@ pub fn run() {
             cmd_update_http_request,
             cmd_update_settings,
             cmd_update_workspace,
+            cmd_update_workspace_variables,
+            cmd_upsert_cookie,
+            cmd_vacuum_database,
+            cmd_validate_json,
+            cmd_validate_workspace_templates,
+            cmd_workspace_stats,
             cmd_write_file_dev,
         ])
         .register_uri_scheme_protocol("yaak", |_app, _req| {
@@ -1800,7 +2949,10 @@ pub fn run() {
         .run(|app_handle, event| {
             match event {
                 RunEvent::Ready => {
-                    let w = create_main_window(app_handle, "/");
+                    let h = app_handle.clone();
+                    let url =
+                        tauri::async_runtime::block_on(async move { last_location_url(&h).await });
+                    let w = create_main_window(app_handle, url.as_str());
                     tauri::async_runtime::spawn(async move {
                         let info = analytics::track_launch_event(&w).await;
                         debug!("Launched Yaak {:?}", info);
@@ -1839,11 +2991,61 @@ pub fn run() {
                         }
                     });
                 }
+                RunEvent::WindowEvent {
+                    label,
+                    event: WindowEvent::Destroyed,
+                    ..
+                } => {
+                    let h = app_handle.clone();
+                    tauri::async_runtime::spawn(async move {
+                        let active_requests: State<'_, Mutex<ActiveRequests>> = h.state();
+                        let senders =
+                            active_requests.lock().await.0.remove(&label).unwrap_or_default();
+                        for (response_id, tx) in senders {
+                            if tx.is_closed() {
+                                continue;
+                            }
+                            debug!("Cancelling request {response_id} for closed window {label}");
+                            if let Err(e) = tx.send(true) {
+                                warn!("Failed to cancel request {response_id} {e:?}");
+                            }
+                        }
+                    });
+                }
                 _ => {}
             };
         });
 }
 
+/// The URL of the last active workspace/request, or "/" if there isn't one
+async fn last_location_url(h: &AppHandle) -> String {
+    match get_last_location(h).await {
+        Some(location) => location_url(&location),
+        None => "/".to_string(),
+    }
+}
+
+fn location_url(location: &LastLocation) -> String {
+    let workspace_id = &location.workspace_id;
+    let path = match &location.request_id {
+        Some(request_id) => format!("/workspaces/{workspace_id}/requests/{request_id}"),
+        None => format!("/workspaces/{workspace_id}"),
+    };
+
+    let mut params = Vec::new();
+    if let Some(id) = &location.environment_id {
+        params.push(format!("environment_id={id}"));
+    }
+    if let Some(id) = &location.cookie_jar_id {
+        params.push(format!("cookie_jar_id={id}"));
+    }
+
+    match params.is_empty() {
+        true => path,
+        false => format!("{path}?{}", params.join("&")),
+    }
+}
+
 fn is_dev() -> bool {
     #[cfg(dev)]
     {
@@ -1921,6 +3123,16 @@ fn create_window(handle: &AppHandle, config: CreateWindowConfig) -> WebviewWindo
 
     let win = win_builder.build().unwrap();
 
+    // Apply the persisted zoom right away so the window doesn't flash at the default size
+    let zoom_window = win.clone();
+    tauri::async_runtime::spawn(async move {
+        let settings = get_or_create_settings(&zoom_window).await;
+        let zoom = (settings.interface_scale as f64).clamp(MIN_ZOOM, MAX_ZOOM);
+        if let Err(e) = zoom_window.set_zoom(zoom) {
+            warn!("Failed to set window zoom {e:?}");
+        }
+    });
+
     let webview_window = win.clone();
     win.on_menu_event(move |w, event| {
         if !w.is_focused().unwrap() {
@@ -1970,14 +3182,6 @@ async fn get_update_mode(h: &AppHandle) -> UpdateMode {
     UpdateMode::new(settings.update_channel.as_str())
 }
 
-fn safe_uri(endpoint: &str) -> String {
-    if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
-        endpoint.into()
-    } else {
-        format!("http://{}", endpoint)
-    }
-}
-
 fn monitor_plugin_events<R: Runtime>(app_handle: &AppHandle<R>) {
     let app_handle = app_handle.clone();
     tauri::async_runtime::spawn(async move {
@@ -2152,6 +3356,7 @@ async fn handle_plugin_event<R: Runtime>(
 
             let resp =
                 create_default_http_response(&window, req.http_request.id.as_str()).await.unwrap();
+            let mut cancel_rx = register_cancel_channel(&window, &resp.id).await;
 
             let result = send_http_request(
                 &window,
@@ -2159,7 +3364,7 @@ async fn handle_plugin_event<R: Runtime>(
                 &resp,
                 environment,
                 cookie_jar,
-                &mut tokio::sync::watch::channel(false).1, // No-op cancel channel
+                &mut cancel_rx,
             )
             .await;
 
@@ -2225,6 +3430,37 @@ async fn workspace_from_window<R: Runtime>(window: &WebviewWindow<R>) -> Option<
     }
 }
 
+/// Resolve the environment a command should use from either its id or its name within the
+/// workspace, preferring the id when both are given
+async fn resolve_environment<R: Runtime>(
+    window: &WebviewWindow<R>,
+    workspace_id: &str,
+    environment_id: Option<&str>,
+    environment_name: Option<&str>,
+) -> Result<Option<Environment>, String> {
+    let environment = match (environment_id, environment_name) {
+        (Some(id), _) => get_environment(window, id).await,
+        (None, Some(name)) => get_environment_by_name(window, workspace_id, name).await,
+        (None, None) => return Ok(None),
+    };
+    environment.map(Some).map_err(|e| e.to_string())
+}
+
+/// Refuse to send to an environment flagged as production unless the caller confirmed it
+fn check_production_confirmation(
+    environment: Option<&Environment>,
+    confirm_production: bool,
+) -> Result<(), AppError> {
+    match environment {
+        Some(e) if e.is_production && !confirm_production => Err(AppError::new(
+            ErrorKind::ConfirmationRequired,
+            format!("Environment {} is marked as production and requires confirmation", e.name),
+        )
+        .with_details(e.id.clone())),
+        _ => Ok(()),
+    }
+}
+
 fn environment_id_from_window<R: Runtime>(window: &WebviewWindow<R>) -> Option<String> {
     let url = window.url().unwrap();
     let mut query_pairs = url.query_pairs();
@@ -2250,3 +3486,163 @@ async fn cookie_jar_from_window<R: Runtime>(window: &WebviewWindow<R>) -> Option
         Some(id) => get_cookie_jar(window, id.as_str()).await.ok(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+    use crate::{
+        apply_workspace_request_defaults, check_production_confirmation, location_url,
+        read_import_file, read_response_body, ActiveRequests,
+    };
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use yaak_models::models::{
+        Environment, HttpRequest, HttpRequestHeader, HttpResponse, Workspace,
+    };
+    use yaak_models::queries::LastLocation;
+
+    #[test]
+    fn last_location_query_params() {
+        let location = LastLocation {
+            workspace_id: "wk_1".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(location_url(&location), "/workspaces/wk_1");
+
+        let location = LastLocation {
+            request_id: Some("rq_1".to_string()),
+            environment_id: Some("ev_1".to_string()),
+            cookie_jar_id: Some("cj_1".to_string()),
+            ..location
+        };
+        assert_eq!(
+            location_url(&location),
+            "/workspaces/wk_1/requests/rq_1?environment_id=ev_1&cookie_jar_id=cj_1"
+        );
+    }
+
+    #[tokio::test]
+    async fn unreadable_import_file() {
+        let missing = read_import_file("/nonexistent/yaak-import.json", None).await;
+        assert!(missing.unwrap_err().starts_with("Unable to read file"));
+
+        // A directory exists, but can't be read as a file
+        let dir = std::env::temp_dir();
+        assert!(read_import_file(dir.to_str().unwrap(), None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn unreadable_response_body() {
+        let response = |body_path: Option<&str>| HttpResponse {
+            body_path: body_path.map(|p| p.to_string()),
+            ..Default::default()
+        };
+        let missing = std::env::temp_dir().join("yaak-missing-response-body");
+        let dir = std::env::temp_dir();
+
+        assert!(read_response_body(&response(None)).await.is_err());
+        let result = read_response_body(&response(missing.to_str())).await;
+        assert!(result.unwrap_err().starts_with("Unable to read file"));
+        assert!(read_response_body(&response(dir.to_str())).await.is_err());
+    }
+
+    #[test]
+    fn cancel_active_request() {
+        let (tx, rx) = tokio::sync::watch::channel(false);
+        let (done_tx, done_rx) = tokio::sync::watch::channel(false);
+        drop(done_rx);
+        let mut active_requests = ActiveRequests::default();
+        active_requests.0.insert(
+            "main_0".to_string(),
+            HashMap::from([
+                ("rs_active".to_string(), Arc::new(tx)),
+                ("rs_done".to_string(), Arc::new(done_tx)),
+            ]),
+        );
+
+        assert!(active_requests.cancel("rs_active"));
+        assert!(*rx.borrow());
+        assert!(!active_requests.cancel("rs_done"));
+        assert!(!active_requests.cancel("rs_missing"));
+    }
+
+    #[test]
+    fn workspace_request_defaults() {
+        let workspace = Workspace {
+            setting_default_body_type: Some("application/json".to_string()),
+            setting_default_method: "post".to_string(),
+            ..Workspace::new("Test".to_string())
+        };
+
+        let new_request = HttpRequest {
+            method: "GET".to_string(),
+            ..HttpRequest::default()
+        };
+        let request = apply_workspace_request_defaults(new_request, &workspace);
+        assert_eq!(request.body_type.as_deref(), Some("application/json"));
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.headers[0].name, "Content-Type");
+        assert_eq!(request.headers[0].value, "application/json");
+
+        // Explicit values from the client are left alone
+        let explicit = HttpRequest {
+            method: "PUT".to_string(),
+            headers: vec![HttpRequestHeader {
+                enabled: true,
+                name: "content-type".to_string(),
+                value: "application/vnd.api+json".to_string(),
+            }],
+            ..HttpRequest::default()
+        };
+        let request = apply_workspace_request_defaults(explicit, &workspace);
+        assert_eq!(request.method, "PUT");
+        assert_eq!(request.headers.len(), 1);
+        assert_eq!(request.headers[0].value, "application/vnd.api+json");
+
+        let form = HttpRequest {
+            method: "GET".to_string(),
+            body_type: Some("multipart/form-data".to_string()),
+            ..HttpRequest::default()
+        };
+        let request = apply_workspace_request_defaults(form, &workspace);
+        assert_eq!(request.body_type.as_deref(), Some("multipart/form-data"));
+        assert_eq!(request.method, "POST");
+        assert!(request.headers.is_empty());
+
+        // The default method applies without a default body type
+        let workspace = Workspace {
+            setting_default_body_type: None,
+            ..workspace
+        };
+        let get = HttpRequest {
+            method: "GET".to_string(),
+            ..HttpRequest::default()
+        };
+        let request = apply_workspace_request_defaults(get, &workspace);
+        assert_eq!(request.body_type, None);
+        assert_eq!(request.method, "POST");
+    }
+
+    #[test]
+    fn production_environment_requires_confirmation() {
+        let production = Environment {
+            id: "ev_prod".to_string(),
+            name: "Production".to_string(),
+            is_production: true,
+            ..Default::default()
+        };
+
+        let err = check_production_confirmation(Some(&production), false).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::ConfirmationRequired);
+        assert_eq!(err.details.as_deref(), Some("ev_prod"));
+        assert!(check_production_confirmation(Some(&production), true).is_ok());
+
+        // Environments that aren't flagged, or no environment at all, never need it
+        let staging = Environment {
+            name: "Staging".to_string(),
+            ..Default::default()
+        };
+        assert!(check_production_confirmation(Some(&staging), false).is_ok());
+        assert!(check_production_confirmation(None, false).is_ok());
+    }
+}
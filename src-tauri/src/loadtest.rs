@@ -0,0 +1,341 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use http::{HeaderMap, HeaderName, HeaderValue};
+use log::{debug, warn};
+use reqwest::{Client, Method, Request, Url};
+use tauri::{Emitter, Runtime, WebviewWindow};
+use tokio::sync::mpsc;
+use yaak_models::models::{
+    CookieJar, Environment, HttpRequest, HttpResponse, LoadTest, LoadTestState,
+};
+use yaak_models::queries::{get_or_create_settings, get_workspace, upsert_load_test};
+
+use crate::http_request::{
+    build_cookie_store, configure_client, execute_http_request, uses_challenge_auth, DryRunRequest,
+    SendOutcome,
+};
+use crate::register_cancel_channel;
+
+/// How often a running load test is saved and its progress sent to the window
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Most requests a load test may have in flight at once
+pub const MAX_LOAD_TEST_CONCURRENCY: usize = 256;
+
+/// Send the request over and over for `duration`, from `concurrency` tasks at once, saving
+/// latency and status metrics as it goes. Each save is also sent to the window as progress.
+///
+/// The request is rendered once, so template functions aren't evaluated again for each send.
+/// Requests still in flight when the time is up are waited for. Cancelling the test, with the
+/// test's ID in place of a response's, stops it right away.
+pub async fn run_load_test<R: Runtime>(
+    window: &WebviewWindow<R>,
+    request: &HttpRequest,
+    environment: Option<Environment>,
+    cookie_jar: Option<CookieJar>,
+    duration: Duration,
+    concurrency: usize,
+) -> Result<LoadTest, String> {
+    let (client, sendable) =
+        build_load_test_request(window, request, environment, cookie_jar).await?;
+    let concurrency = concurrency.clamp(1, MAX_LOAD_TEST_CONCURRENCY);
+
+    let test = LoadTest {
+        workspace_id: request.workspace_id.clone(),
+        request_id: request.id.clone(),
+        duration: duration.as_millis().min(i32::MAX as u128) as i32,
+        concurrency: concurrency as i32,
+        state: LoadTestState::Running,
+        ..Default::default()
+    };
+    let mut test = upsert_load_test(window, &test).await.map_err(|e| e.to_string())?;
+    let cancel_rx = register_cancel_channel(window, &test.id).await;
+    debug!("Load testing {} for {duration:?} with {concurrency} tasks", request.id);
+
+    let start = Instant::now();
+    let deadline = start + duration;
+    let metrics = Arc::new(Mutex::new(LoadTestMetrics::default()));
+    // Each task holds a sender, so the channel closes once they've all finished
+    let (done_tx, mut done_rx) = mpsc::channel::<()>(1);
+    for _ in 0..concurrency {
+        let client = client.clone();
+        let sendable = sendable.try_clone().ok_or("Request body can't be sent more than once")?;
+        let metrics = Arc::clone(&metrics);
+        let mut cancel_rx = cancel_rx.clone();
+        let done_tx = done_tx.clone();
+        tokio::spawn(async move {
+            while Instant::now() < deadline && !*cancel_rx.borrow() {
+                let req = match sendable.try_clone() {
+                    Some(r) => r,
+                    None => break,
+                };
+                let started = Instant::now();
+                let status = tokio::select! {
+                    s = send_once(&client, req) => s,
+                    _ = cancel_rx.changed() => break,
+                };
+                metrics.lock().unwrap().record(status, started.elapsed());
+            }
+            drop(done_tx);
+        });
+    }
+    drop(done_tx);
+
+    let event_name = format!("load_test_progress_{}", request.id);
+    // The first tick is immediate, so the window learns the test's ID before any results
+    let mut ticker = tokio::time::interval(PROGRESS_INTERVAL);
+    loop {
+        let finished = tokio::select! {
+            _ = ticker.tick() => false,
+            _ = done_rx.recv() => true,
+        };
+        if finished {
+            test.state = match *cancel_rx.borrow() {
+                true => LoadTestState::Cancelled,
+                false => LoadTestState::Finished,
+            };
+        }
+        test.elapsed = start.elapsed().as_millis().min(i32::MAX as u128) as i32;
+        metrics.lock().unwrap().summarize(&mut test);
+        test = upsert_load_test(window, &test).await.map_err(|e| e.to_string())?;
+        if let Err(e) = window.emit_to(window.label(), &event_name, test.clone()) {
+            warn!("Failed to emit load test progress {e:?}");
+        }
+        if finished {
+            return Ok(test);
+        }
+    }
+}
+
+/// Render and build the request the way a send would, along with a client configured for it
+async fn build_load_test_request<R: Runtime>(
+    window: &WebviewWindow<R>,
+    request: &HttpRequest,
+    environment: Option<Environment>,
+    cookie_jar: Option<CookieJar>,
+) -> Result<(Client, Request), String> {
+    // The prebuilt request is sent as-is, so it can't answer an auth challenge
+    if uses_challenge_auth(request) {
+        return Err("Requests using digest or NTLM auth can't be load tested".to_string());
+    }
+
+    // A dry run renders and builds the request without sending it or saving anything
+    let response = HttpResponse {
+        request_id: request.id.clone(),
+        workspace_id: request.workspace_id.clone(),
+        ..Default::default()
+    };
+    let (_cancel_tx, mut cancel_rx) = tokio::sync::watch::channel(false);
+    let outcome =
+        execute_http_request(window, request, &response, environment, None, &mut cancel_rx, true)
+            .await?;
+    let built = match outcome {
+        SendOutcome::DryRun(r) => r,
+        SendOutcome::Response(r) => {
+            return Err(r.error.unwrap_or_else(|| "Failed to build request".to_string()))
+        }
+    };
+
+    let workspace =
+        get_workspace(window, &request.workspace_id).await.map_err(|e| e.to_string())?;
+    let settings = get_or_create_settings(window).await;
+    let mut client_builder = configure_client(&workspace, request, settings.proxy)?;
+    // Cookies set by responses are sent with the later requests, but aren't saved to the jar
+    if let Some((cookie_store, _)) = build_cookie_store(request, cookie_jar) {
+        client_builder = client_builder.cookie_provider(cookie_store);
+    }
+    let client = client_builder.build().map_err(|e| format!("Failed to build client: {e}"))?;
+
+    Ok((client, rebuild_request(&built)?))
+}
+
+/// Turn a dry run back into a request that can be cloned for each send
+fn rebuild_request(built: &DryRunRequest) -> Result<Request, String> {
+    if built.body_streamed {
+        return Err("Requests with streamed bodies, like files and multipart forms, can't be \
+                    load tested"
+            .to_string());
+    }
+
+    let method = Method::from_str(&built.method).map_err(|e| e.to_string())?;
+    let url = Url::parse(&built.url).map_err(|e| e.to_string())?;
+    let mut request = Request::new(method, url);
+
+    let mut headers = HeaderMap::new();
+    for h in &built.headers {
+        let name = HeaderName::from_str(&h.name).map_err(|e| e.to_string())?;
+        let value = HeaderValue::from_str(&h.value).map_err(|e| e.to_string())?;
+        headers.append(name, value);
+    }
+    *request.headers_mut() = headers;
+
+    let body = match (&built.body, &built.body_base64) {
+        (Some(text), _) => Some(text.as_bytes().to_vec()),
+        (None, Some(b64)) => Some(BASE64_STANDARD.decode(b64).map_err(|e| e.to_string())?),
+        (None, None) => None,
+    };
+    *request.body_mut() = body.map(Into::into);
+    Ok(request)
+}
+
+/// Send a request and read its body, returning its status, or None if there was no response
+async fn send_once(client: &Client, request: Request) -> Option<u16> {
+    let response = client.execute(request).await.ok()?;
+    let status = response.status().as_u16();
+    response.bytes().await.ok()?;
+    Some(status)
+}
+
+/// What a load test has seen so far. Latencies are kept as a histogram, so a long test's
+/// memory doesn't grow with the number of requests it sends.
+#[derive(Debug, Default)]
+struct LoadTestMetrics {
+    /// How many responses fell in each latency bucket, keyed by [latency_bucket]
+    latency_counts: BTreeMap<u32, u64>,
+    latency_total: u64,
+    latency_min: u32,
+    latency_max: u32,
+    requests: u64,
+    errors: i32,
+    status_counts: BTreeMap<u16, i32>,
+}
+
+impl LoadTestMetrics {
+    fn record(&mut self, status: Option<u16>, elapsed: Duration) {
+        let Some(status) = status else {
+            self.errors += 1;
+            return;
+        };
+
+        let ms = elapsed.as_millis().min(u32::MAX as u128) as u32;
+        *self.latency_counts.entry(latency_bucket(ms)).or_default() += 1;
+        self.latency_total += ms as u64;
+        self.latency_min = if self.requests == 0 { ms } else { self.latency_min.min(ms) };
+        self.latency_max = self.latency_max.max(ms);
+        self.requests += 1;
+        *self.status_counts.entry(status).or_default() += 1;
+    }
+
+    fn summarize(&self, test: &mut LoadTest) {
+        test.requests = self.requests.min(i32::MAX as u64) as i32;
+        test.errors = self.errors;
+        test.status_counts = self.status_counts.clone();
+        test.latency_min = self.latency_min as i32;
+        test.latency_max = self.latency_max as i32;
+        test.latency_mean =
+            self.latency_total.checked_div(self.requests).unwrap_or_default() as i32;
+        test.latency_p50 = self.percentile(50.0) as i32;
+        test.latency_p90 = self.percentile(90.0) as i32;
+        test.latency_p95 = self.percentile(95.0) as i32;
+        test.latency_p99 = self.percentile(99.0) as i32;
+    }
+
+    /// The nearest-rank percentile of the latencies, to within a bucket, or zero when there
+    /// are none
+    fn percentile(&self, p: f64) -> u32 {
+        if self.requests == 0 {
+            return 0;
+        }
+        let rank = ((p / 100.0 * self.requests as f64).ceil() as u64).clamp(1, self.requests);
+        let mut seen = 0;
+        for (bucket, count) in &self.latency_counts {
+            seen += count;
+            if seen >= rank {
+                return (*bucket).clamp(self.latency_min, self.latency_max);
+            }
+        }
+        self.latency_max
+    }
+}
+
+/// The histogram bucket for a latency. Values under 256ms get a bucket each, and larger ones
+/// are rounded down to their top 8 bits, which is within 1% and caps the buckets at a few
+/// thousand.
+fn latency_bucket(ms: u32) -> u32 {
+    let shift = (u32::BITS - ms.leading_zeros()).saturating_sub(8);
+    (ms >> shift) << shift
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::http_request::DryRunRequest;
+    use crate::loadtest::{latency_bucket, rebuild_request, LoadTestMetrics};
+    use std::time::Duration;
+    use yaak_models::models::{HttpResponseHeader, LoadTest};
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let metrics_of = |latencies: &[u64]| {
+            let mut metrics = LoadTestMetrics::default();
+            for ms in latencies {
+                metrics.record(Some(200), Duration::from_millis(*ms));
+            }
+            metrics
+        };
+        let hundred: Vec<u64> = (1..=100).collect();
+        assert_eq!(metrics_of(&hundred).percentile(50.0), 50);
+        assert_eq!(metrics_of(&hundred).percentile(99.0), 99);
+        assert_eq!(metrics_of(&[7]).percentile(99.0), 7);
+        assert_eq!(metrics_of(&[1, 2, 3]).percentile(0.0), 1);
+        assert_eq!(metrics_of(&[]).percentile(50.0), 0);
+        assert_eq!(metrics_of(&[1000, 1003, 5000]).percentile(50.0), 1000);
+
+        let mut metrics = LoadTestMetrics::default();
+        for ms in [30, 10, 20, 40] {
+            metrics.record(Some(200), Duration::from_millis(ms));
+        }
+        metrics.record(Some(503), Duration::from_millis(100));
+        metrics.record(None, Duration::from_secs(5));
+
+        let mut test = LoadTest::default();
+        metrics.summarize(&mut test);
+        assert_eq!((test.requests, test.errors), (5, 1));
+        assert_eq!(test.status_counts.into_iter().collect::<Vec<_>>(), [(200, 4), (503, 1)]);
+        assert_eq!((test.latency_min, test.latency_mean, test.latency_max), (10, 40, 100));
+        assert_eq!((test.latency_p50, test.latency_p90, test.latency_p99), (30, 100, 100));
+    }
+
+    #[test]
+    fn latency_buckets_stay_within_one_percent() {
+        assert_eq!(latency_bucket(0), 0);
+        assert_eq!(latency_bucket(255), 255);
+        assert_eq!(latency_bucket(1003), 1000);
+        for ms in [256, 999, 4_159, 1_234, 65_432, 3_600_000, u32::MAX] {
+            let bucket = latency_bucket(ms);
+            assert!(bucket <= ms && (ms - bucket) as f64 <= ms as f64 / 100.0, "{ms}");
+        }
+        let buckets: std::collections::BTreeSet<u32> =
+            (0..1_000_000).step_by(7).map(latency_bucket).collect();
+        assert!(buckets.len() < 2_000);
+    }
+
+    #[test]
+    fn rebuild_dry_run() {
+        let mut built = DryRunRequest {
+            method: "POST".to_string(),
+            url: "https://yaak.test/users?page=2".to_string(),
+            version: "HTTP/1.1".to_string(),
+            headers: vec![HttpResponseHeader {
+                name: "content-type".to_string(),
+                value: "application/octet-stream".to_string(),
+            }],
+            body: None,
+            body_base64: Some("AAEC".to_string()),
+            body_streamed: false,
+        };
+        let request = rebuild_request(&built).unwrap();
+        assert_eq!(request.method(), "POST");
+        assert_eq!(request.url().query(), Some("page=2"));
+        assert_eq!(request.headers()["content-type"], "application/octet-stream");
+        assert_eq!(request.body().unwrap().as_bytes(), Some(&[0u8, 1, 2][..]));
+        assert!(request.try_clone().is_some());
+
+        built.body_streamed = true;
+        assert!(rebuild_request(&built).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fs;
+
+use http_body_util::Full;
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{HeaderName, HeaderValue};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use log::{debug, info, warn};
+use tauri::plugin::{Builder, TauriPlugin};
+use tauri::{AppHandle, Manager, RunEvent, Runtime, State};
+use tokio::net::TcpListener;
+use tokio::sync::{oneshot, Mutex};
+use yaak_models::models::{HttpExample, HttpRequest};
+use yaak_models::queries::{list_http_examples, list_http_requests};
+
+/// Response headers that describe how the original body was transferred, which no longer hold
+/// for the decoded copy stored with the example
+const SKIPPED_HEADERS: [&str; 4] = [
+    "connection",
+    "content-encoding",
+    "content-length",
+    "transfer-encoding",
+];
+
+/// Running mock servers, keyed by workspace id
+#[derive(Default)]
+pub struct MockServers(HashMap<String, MockServer>);
+
+struct MockServer {
+    port: u16,
+    shutdown: oneshot::Sender<()>,
+}
+
+impl MockServers {
+    /// Stop the workspace's mock server, returning whether one was running
+    pub fn stop(&mut self, workspace_id: &str) -> bool {
+        match self.0.remove(workspace_id) {
+            None => false,
+            Some(server) => {
+                info!("Stopping mock server for {workspace_id} on port {}", server.port);
+                let _ = server.shutdown.send(());
+                true
+            }
+        }
+    }
+
+    fn stop_all(&mut self) {
+        let ids: Vec<String> = self.0.keys().cloned().collect();
+        for id in ids {
+            self.stop(&id);
+        }
+    }
+}
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    Builder::new("yaak_mock_server")
+        .setup(|app_handle, _| {
+            app_handle.manage(Mutex::new(MockServers::default()));
+            Ok(())
+        })
+        .on_event(|app, e| {
+            if let RunEvent::ExitRequested { .. } = e {
+                tauri::async_runtime::block_on(async move {
+                    let servers: State<Mutex<MockServers>> = app.state();
+                    servers.lock().await.stop_all();
+                });
+            }
+        })
+        .build()
+}
+
+/// Serve the workspace's saved examples on a local port, replacing any mock server already
+/// running for it. Port 0 picks a free port. Returns the port that was bound.
+pub async fn start_mock_server<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    workspace_id: &str,
+    port: u16,
+) -> Result<u16, String> {
+    let servers: State<Mutex<MockServers>> = app_handle.state();
+    let mut servers = servers.lock().await;
+    servers.stop(workspace_id);
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to start mock server on port {port}: {e}"))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    info!("Starting mock server for {workspace_id} on port {port}");
+
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+    let app_handle = app_handle.clone();
+    let ws_id = workspace_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let stream = tokio::select! {
+                _ = &mut shutdown_rx => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        warn!("Mock server failed to accept connection {e:?}");
+                        continue;
+                    }
+                },
+            };
+
+            let app_handle = app_handle.clone();
+            let workspace_id = ws_id.clone();
+            tauri::async_runtime::spawn(async move {
+                let service = service_fn(|req| {
+                    let app_handle = app_handle.clone();
+                    let workspace_id = workspace_id.clone();
+                    async move { handle(&app_handle, &workspace_id, req).await }
+                });
+                if let Err(e) =
+                    http1::Builder::new().serve_connection(TokioIo::new(stream), service).await
+                {
+                    debug!("Mock server connection closed {e:?}");
+                }
+            });
+        }
+    });
+
+    servers.0.insert(
+        workspace_id.to_string(),
+        MockServer {
+            port,
+            shutdown: shutdown_tx,
+        },
+    );
+    Ok(port)
+}
+
+async fn handle<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    workspace_id: &str,
+    req: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let method = req.method().as_str();
+    let path = req.uri().path();
+
+    let requests = match list_http_requests(app_handle, workspace_id).await {
+        Ok(r) => r,
+        Err(e) => return Ok(text_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    };
+
+    // Fall through to less specific routes when the best match has no examples saved
+    for request in matching_requests(&requests, method, path) {
+        let examples = match list_http_examples(app_handle, &request.id).await {
+            Ok(e) => e,
+            Err(e) => return Ok(text_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        };
+        if let Some(example) = examples.first() {
+            debug!("Mock server matched {method} {path} to example {}", example.id);
+            return Ok(example_response(example));
+        }
+    }
+
+    Ok(text_response(StatusCode::NOT_FOUND, format!("No example matches {method} {path}")))
+}
+
+fn example_response(example: &HttpExample) -> Response<Full<Bytes>> {
+    let body = match &example.body_path {
+        None => Vec::new(),
+        Some(p) => match fs::read(p) {
+            Ok(b) => b,
+            Err(e) => {
+                return text_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to read body of example {}: {e}", example.name),
+                )
+            }
+        },
+    };
+
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() =
+        StatusCode::from_u16(example.status as u16).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    for h in &example.headers {
+        if SKIPPED_HEADERS.contains(&h.name.to_lowercase().as_str()) {
+            continue;
+        }
+        let (name, value) =
+            match (HeaderName::from_bytes(h.name.as_bytes()), HeaderValue::from_str(&h.value)) {
+                (Ok(n), Ok(v)) => (n, v),
+                _ => continue,
+            };
+        response.headers_mut().append(name, value);
+    }
+    response
+}
+
+fn text_response(status: StatusCode, message: String) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(message)));
+    *response.status_mut() = status;
+    response
+}
+
+/// Requests whose method and URL path match, most specific first. Path segments written as
+/// `:name`, `{name}`, or a template tag match any single segment, and a trailing `*` matches
+/// the rest of the path.
+fn matching_requests<'a>(
+    requests: &'a [HttpRequest],
+    method: &str,
+    path: &str,
+) -> Vec<&'a HttpRequest> {
+    let mut matches: Vec<(RouteScore, &HttpRequest)> = requests
+        .iter()
+        .filter(|r| r.method.eq_ignore_ascii_case(method))
+        .filter_map(|r| route_score(&url_path(&r.url), path).map(|s| (s, r)))
+        .collect();
+    matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+    matches.into_iter().map(|(_, r)| r).collect()
+}
+
+/// How specifically a route matched. Compared in field order, so literal segments count most.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct RouteScore {
+    literals: usize,
+    exact: bool,
+    params: usize,
+}
+
+fn route_score(route: &str, path: &str) -> Option<RouteScore> {
+    let route: Vec<&str> = segments(route);
+    let path: Vec<&str> = segments(path);
+    let mut score = RouteScore {
+        literals: 0,
+        exact: true,
+        params: 0,
+    };
+
+    for (i, r) in route.iter().enumerate() {
+        if *r == "*" && i == route.len() - 1 {
+            score.exact = false;
+            return Some(score);
+        }
+
+        let p = path.get(i)?;
+        if is_param(r) {
+            score.params += 1;
+        } else if r == p {
+            score.literals += 1;
+        } else {
+            return None;
+        }
+    }
+
+    if route.len() == path.len() {
+        Some(score)
+    } else {
+        None
+    }
+}
+
+fn segments(path: &str) -> Vec<&str> {
+    path.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+fn is_param(segment: &str) -> bool {
+    (segment.starts_with(':') && segment.len() > 1)
+        || (segment.starts_with('{') && segment.ends_with('}'))
+        || segment.contains("${[")
+}
+
+/// The path of a request URL, which may be missing its scheme or start with a template tag
+/// for the base URL
+fn url_path(url: &str) -> &str {
+    let url = url.split(['?', '#']).next().unwrap_or_default().trim();
+    let rest = if let Some((_, rest)) = url.split_once("://") {
+        rest
+    } else if url.starts_with("${[") {
+        match url.split_once("]}") {
+            Some((_, rest)) => return rest,
+            None => return "",
+        }
+    } else if url.starts_with('/') {
+        return url;
+    } else {
+        url
+    };
+
+    match rest.find('/') {
+        Some(i) => &rest[i..],
+        None => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_server::{matching_requests, url_path};
+    use yaak_models::models::HttpRequest;
+
+    fn request(id: &str, method: &str, url: &str) -> HttpRequest {
+        HttpRequest {
+            id: id.to_string(),
+            method: method.to_string(),
+            url: url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn paths_of_request_urls() {
+        assert_eq!(url_path("https://api.example.com/users/:id?page=2"), "/users/:id");
+        assert_eq!(url_path("localhost:8080/health#top"), "/health");
+        assert_eq!(url_path("${[ base_url ]}/users"), "/users");
+        assert_eq!(url_path("/users"), "/users");
+        assert_eq!(url_path("https://example.com"), "");
+    }
+
+    #[test]
+    fn most_specific_route_first() {
+        let requests = vec![
+            request("catch_all", "GET", "${[ base_url ]}/*"),
+            request("by_id", "GET", "${[ base_url ]}/users/:id"),
+            request("me", "GET", "${[ base_url ]}/users/me"),
+            request("create", "POST", "${[ base_url ]}/users"),
+            request("posts", "GET", "https://example.com/users/{id}/posts"),
+        ];
+        let ids = |method: &str, path: &str| -> Vec<String> {
+            matching_requests(&requests, method, path).iter().map(|r| r.id.clone()).collect()
+        };
+
+        assert_eq!(ids("GET", "/users/me"), vec!["me", "by_id", "catch_all"]);
+        assert_eq!(ids("GET", "/users/42"), vec!["by_id", "catch_all"]);
+        assert_eq!(ids("GET", "/users/42/posts"), vec!["posts", "catch_all"]);
+        assert_eq!(ids("post", "/users/"), vec!["create"]);
+        assert_eq!(ids("DELETE", "/users/42"), Vec::<String>::new());
+    }
+}
@@ -44,10 +44,21 @@ impl YaakNotifier {
 
     pub async fn seen<R: Runtime>(&mut self, w: &WebviewWindow<R>, id: &str) -> Result<(), String> {
         let mut seen = get_kv(w).await?;
+
+        // Other windows call this too when they hide the notification in response to the
+        // event below, so bail early to avoid bouncing it back and forth.
+        if seen.contains(&id.to_string()) {
+            return Ok(());
+        }
+
         seen.push(id.to_string());
         debug!("Marked notification as seen {}", id);
         let seen_json = serde_json::to_string(&seen).map_err(|e| e.to_string())?;
         set_key_value_raw(w, KV_NAMESPACE, KV_KEY, seen_json.as_str()).await;
+
+        // Tell every open window so they can hide it too
+        w.emit("notification_dismissed", id)
+            .map_err(|e| e.to_string())?;
         Ok(())
     }
 
@@ -94,7 +105,7 @@ impl YaakNotifier {
 }
 
 async fn get_kv<R: Runtime>(w: &WebviewWindow<R>) -> Result<Vec<String>, String> {
-    match get_key_value_raw(w, "notifications", "seen").await {
+    match get_key_value_raw(w, KV_NAMESPACE, KV_KEY).await {
         None => Ok(Vec::new()),
         Some(v) => serde_json::from_str(&v.value).map_err(|e| e.to_string()),
     }
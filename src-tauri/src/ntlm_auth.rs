@@ -0,0 +1,334 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use md4::{Digest, Md4};
+use md5::Md5;
+use rand::RngCore;
+
+const SIGNATURE: &[u8; 8] = b"NTLMSSP\0";
+
+const NEGOTIATE_UNICODE: u32 = 0x0000_0001;
+const REQUEST_TARGET: u32 = 0x0000_0004;
+const NEGOTIATE_NTLM: u32 = 0x0000_0200;
+const NEGOTIATE_ALWAYS_SIGN: u32 = 0x0000_8000;
+const NEGOTIATE_EXTENDED_SESSION_SECURITY: u32 = 0x0008_0000;
+const NEGOTIATE_TARGET_INFO: u32 = 0x0080_0000;
+const NEGOTIATE_128: u32 = 0x2000_0000;
+const NEGOTIATE_56: u32 = 0x8000_0000;
+
+const NEGOTIATE_FLAGS: u32 = NEGOTIATE_UNICODE
+    | REQUEST_TARGET
+    | NEGOTIATE_NTLM
+    | NEGOTIATE_ALWAYS_SIGN
+    | NEGOTIATE_EXTENDED_SESSION_SECURITY
+    | NEGOTIATE_TARGET_INFO
+    | NEGOTIATE_128
+    | NEGOTIATE_56;
+
+/// AV pair holding the server's time in the challenge's target info
+const AV_TIMESTAMP: u16 = 7;
+/// AV pair that ends the target info
+const AV_EOL: u16 = 0;
+
+/// Username and password for a request using NTLM authentication. The username may include the
+/// domain, like `DOMAIN\user`.
+#[derive(Debug, Clone)]
+pub struct NtlmCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl NtlmCredentials {
+    fn domain_and_user(&self) -> (&str, &str) {
+        match self.username.split_once('\\') {
+            Some((domain, user)) => (domain, user),
+            None => ("", self.username.as_str()),
+        }
+    }
+}
+
+/// The challenge (type 2) message from a server's `WWW-Authenticate: NTLM` header
+#[derive(Debug, Clone, PartialEq)]
+pub struct NtlmChallenge {
+    flags: u32,
+    server_challenge: [u8; 8],
+    target_info: Vec<u8>,
+}
+
+/// The `Authorization` header value that starts the handshake (the type 1 message)
+pub fn ntlm_negotiate() -> String {
+    let mut msg = Vec::with_capacity(32);
+    msg.extend(SIGNATURE);
+    msg.extend(1u32.to_le_bytes());
+    msg.extend(NEGOTIATE_FLAGS.to_le_bytes());
+    // Empty domain and workstation fields
+    msg.extend([0u8; 16]);
+    format!("NTLM {}", BASE64_STANDARD.encode(msg))
+}
+
+/// Parse the challenge from a response's `WWW-Authenticate` header values
+pub fn parse_ntlm_challenge<'a>(
+    header_values: impl IntoIterator<Item = &'a str>,
+) -> Option<NtlmChallenge> {
+    header_values.into_iter().find_map(|v| {
+        let (scheme, token) = v.trim().split_once(' ')?;
+        if !scheme.eq_ignore_ascii_case("ntlm") {
+            return None;
+        }
+        parse_challenge_message(&BASE64_STANDARD.decode(token.trim()).ok()?)
+    })
+}
+
+/// The `Authorization` header value answering a challenge (the type 3 message), using NTLMv2
+pub fn ntlm_authenticate(challenge: &NtlmChallenge, credentials: &NtlmCredentials) -> String {
+    let mut client_challenge = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut client_challenge);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    // Windows file time: 100ns intervals since 1601
+    let time = (now.as_secs() + 11_644_473_600) * 10_000_000 + now.subsec_nanos() as u64 / 100;
+    authenticate_with(challenge, credentials, client_challenge, time)
+}
+
+fn authenticate_with(
+    challenge: &NtlmChallenge,
+    credentials: &NtlmCredentials,
+    client_challenge: [u8; 8],
+    time: u64,
+) -> String {
+    let (domain, user) = credentials.domain_and_user();
+    let (lm, nt) = ntlm_v2_responses(challenge, credentials, client_challenge, time);
+
+    let fields = [lm, nt, utf16(domain), utf16(user), Vec::new(), Vec::new()];
+    let mut msg = Vec::new();
+    msg.extend(SIGNATURE);
+    msg.extend(3u32.to_le_bytes());
+
+    // Each field is a (length, max length, offset) header pointing into the payload after them
+    let mut offset = 64u32;
+    for field in &fields {
+        let len = field.len() as u16;
+        msg.extend(len.to_le_bytes());
+        msg.extend(len.to_le_bytes());
+        msg.extend(offset.to_le_bytes());
+        offset += field.len() as u32;
+    }
+    msg.extend((challenge.flags & NEGOTIATE_FLAGS).to_le_bytes());
+    for field in fields {
+        msg.extend(field);
+    }
+
+    format!("NTLM {}", BASE64_STANDARD.encode(msg))
+}
+
+/// The LMv2 and NTLMv2 responses (MS-NLMP 3.3.2)
+fn ntlm_v2_responses(
+    challenge: &NtlmChallenge,
+    credentials: &NtlmCredentials,
+    client_challenge: [u8; 8],
+    time: u64,
+) -> (Vec<u8>, Vec<u8>) {
+    let (domain, user) = credentials.domain_and_user();
+    let nt_hash = Md4::digest(utf16(&credentials.password));
+    let key = hmac_md5(&nt_hash, &[utf16(&user.to_uppercase()), utf16(domain)].concat());
+
+    // The server's own time is used when it sends one, and the LMv2 response is left out then
+    let server_time = av_pair(&challenge.target_info, AV_TIMESTAMP)
+        .and_then(|t| t.try_into().ok())
+        .map(u64::from_le_bytes);
+
+    let mut temp = vec![1, 1, 0, 0, 0, 0, 0, 0];
+    temp.extend(server_time.unwrap_or(time).to_le_bytes());
+    temp.extend(client_challenge);
+    temp.extend([0; 4]);
+    temp.extend(&challenge.target_info);
+    temp.extend([0; 4]);
+
+    let proof = hmac_md5(&key, &[&challenge.server_challenge[..], &temp].concat());
+    let nt = [proof, temp].concat();
+
+    let lm = match server_time {
+        Some(_) => vec![0; 24],
+        None => {
+            let lm = hmac_md5(&key, &[challenge.server_challenge, client_challenge].concat());
+            [lm, client_challenge.to_vec()].concat()
+        }
+    };
+
+    (lm, nt)
+}
+
+fn parse_challenge_message(msg: &[u8]) -> Option<NtlmChallenge> {
+    if msg.get(..8)? != SIGNATURE || u32_at(msg, 8)? != 2 {
+        return None;
+    }
+    let flags = u32_at(msg, 20)?;
+    let server_challenge = msg.get(24..32)?.try_into().ok()?;
+    let target_info = match flags & NEGOTIATE_TARGET_INFO {
+        0 => Vec::new(),
+        _ => {
+            let len = u16::from_le_bytes(msg.get(40..42)?.try_into().ok()?) as usize;
+            let offset = u32_at(msg, 44)? as usize;
+            msg.get(offset..offset + len)?.to_vec()
+        }
+    };
+    Some(NtlmChallenge {
+        flags,
+        server_challenge,
+        target_info,
+    })
+}
+
+/// The value of an AV pair in a challenge's target info
+fn av_pair(target_info: &[u8], id: u16) -> Option<&[u8]> {
+    let mut rest = target_info;
+    while rest.len() >= 4 {
+        let av_id = u16::from_le_bytes([rest[0], rest[1]]);
+        let len = u16::from_le_bytes([rest[2], rest[3]]) as usize;
+        if av_id == AV_EOL {
+            break;
+        }
+        let value = rest.get(4..4 + len)?;
+        if av_id == id {
+            return Some(value);
+        }
+        rest = &rest[4 + len..];
+    }
+    None
+}
+
+fn u32_at(msg: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(msg.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn utf16(s: &str) -> Vec<u8> {
+    s.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+fn hmac_md5(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Md5>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ntlm_auth::{
+        authenticate_with, ntlm_negotiate, ntlm_v2_responses, parse_ntlm_challenge, u32_at, utf16,
+        NtlmChallenge, NtlmCredentials, NEGOTIATE_FLAGS, SIGNATURE,
+    };
+    use base64::prelude::BASE64_STANDARD;
+    use base64::Engine;
+
+    // The NTLMv2 example from MS-NLMP section 4.2.4
+    const SERVER_CHALLENGE: [u8; 8] = [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef];
+    const CLIENT_CHALLENGE: [u8; 8] = [0xaa; 8];
+
+    fn target_info() -> Vec<u8> {
+        let mut info = vec![0x02, 0x00, 0x0c, 0x00];
+        info.extend(utf16("Domain"));
+        info.extend([0x01, 0x00, 0x0c, 0x00]);
+        info.extend(utf16("Server"));
+        info.extend([0x00; 4]);
+        info
+    }
+
+    fn credentials() -> NtlmCredentials {
+        NtlmCredentials {
+            username: "Domain\\User".to_string(),
+            password: "Password".to_string(),
+        }
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    fn challenge_message(flags: u32, target_info: &[u8]) -> Vec<u8> {
+        let mut msg = Vec::new();
+        msg.extend(SIGNATURE);
+        msg.extend(2u32.to_le_bytes());
+        msg.extend([0, 0, 0, 0, 48, 0, 0, 0]);
+        msg.extend(flags.to_le_bytes());
+        msg.extend(SERVER_CHALLENGE);
+        msg.extend([0; 8]);
+        let len = target_info.len() as u16;
+        msg.extend(len.to_le_bytes());
+        msg.extend(len.to_le_bytes());
+        msg.extend(48u32.to_le_bytes());
+        msg.extend(target_info);
+        msg
+    }
+
+    #[test]
+    fn ntlm_v2_example() {
+        let challenge = NtlmChallenge {
+            flags: NEGOTIATE_FLAGS,
+            server_challenge: SERVER_CHALLENGE,
+            target_info: target_info(),
+        };
+        let (lm, nt) = ntlm_v2_responses(&challenge, &credentials(), CLIENT_CHALLENGE, 0);
+        assert_eq!(hex(&lm), "86c35097ac9cec102554764a57cccc19aaaaaaaaaaaaaaaa");
+        assert_eq!(hex(&nt[..16]), "68cd0ab851e51c96aabc927bebef6a1c");
+        assert_eq!(nt.len(), 16 + 28 + target_info().len() + 4);
+    }
+
+    #[test]
+    fn server_timestamp_is_used() {
+        let mut info = vec![0x07, 0x00, 0x08, 0x00, 1, 2, 3, 4, 5, 6, 7, 8];
+        info.extend(target_info());
+        let challenge = NtlmChallenge {
+            flags: NEGOTIATE_FLAGS,
+            server_challenge: SERVER_CHALLENGE,
+            target_info: info,
+        };
+        let (lm, nt) = ntlm_v2_responses(&challenge, &credentials(), CLIENT_CHALLENGE, 0);
+        assert_eq!(lm, vec![0; 24]);
+        assert_eq!(nt[24..32], [1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn parse_challenge_header() {
+        let msg = challenge_message(NEGOTIATE_FLAGS, &target_info());
+        let header = format!("NTLM {}", BASE64_STANDARD.encode(&msg));
+        let challenge = parse_ntlm_challenge(["Negotiate", "Basic realm=\"x\"", &header]).unwrap();
+        assert_eq!(challenge.server_challenge, SERVER_CHALLENGE);
+        assert_eq!(challenge.target_info, target_info());
+
+        // The first leg's bare `NTLM` isn't a challenge
+        assert_eq!(parse_ntlm_challenge(["NTLM"]), None);
+        assert_eq!(parse_ntlm_challenge(["NTLM bm90IG50bG0="]), None);
+    }
+
+    #[test]
+    fn authenticate_message_fields() {
+        let challenge = NtlmChallenge {
+            flags: NEGOTIATE_FLAGS,
+            server_challenge: SERVER_CHALLENGE,
+            target_info: target_info(),
+        };
+        let header = authenticate_with(&challenge, &credentials(), CLIENT_CHALLENGE, 0);
+        let msg = BASE64_STANDARD.decode(header.strip_prefix("NTLM ").unwrap()).unwrap();
+        assert_eq!(&msg[..8], SIGNATURE);
+        assert_eq!(u32_at(&msg, 8), Some(3));
+
+        let field = |i: usize| {
+            let len = u16::from_le_bytes([msg[12 + i * 8], msg[13 + i * 8]]) as usize;
+            let offset = u32_at(&msg, 16 + i * 8).unwrap() as usize;
+            &msg[offset..offset + len]
+        };
+        assert_eq!(field(2), utf16("Domain"));
+        assert_eq!(field(3), utf16("User"));
+        assert_eq!(hex(&field(1)[..16]), "68cd0ab851e51c96aabc927bebef6a1c");
+    }
+
+    #[test]
+    fn negotiate_message() {
+        let header = ntlm_negotiate();
+        let msg = BASE64_STANDARD.decode(header.strip_prefix("NTLM ").unwrap()).unwrap();
+        assert_eq!(msg.len(), 32);
+        assert_eq!(u32_at(&msg, 8), Some(1));
+        assert_eq!(u32_at(&msg, 12), Some(NEGOTIATE_FLAGS));
+    }
+}
@@ -0,0 +1,639 @@
+use std::collections::BTreeMap;
+
+use serde_json::json;
+use serde_yaml::{Mapping, Value};
+use yaak_models::models::{
+    EnvironmentVariable, Folder, HttpRequest, HttpRequestHeader, HttpUrlParameter, Workspace,
+};
+use yaak_plugin_runtime::events::ImportResources;
+
+const WORKSPACE_ID: &str = "GENERATE_ID::WORKSPACE_0";
+
+/// Operations of a path item, in the order their requests are created
+const METHODS: [&str; 8] = [
+    "get", "post", "put", "patch", "delete", "head", "options", "trace",
+];
+
+/// How deep schemas are followed when building an example, so recursive schemas end
+const MAX_SCHEMA_DEPTH: usize = 8;
+
+/// Convert an OpenAPI 3.0 or 3.1 spec, as JSON or YAML, into a workspace with a request for each
+/// operation. Requests go in a folder for their first tag, and their URLs start with a
+/// `base_url` workspace variable set from the first server. Returns None if the contents aren't
+/// an OpenAPI 3 spec, so other importers can try them.
+pub fn import_openapi(contents: &str) -> Option<ImportResources> {
+    let spec = parse_spec(contents)?;
+    if !spec.get("openapi")?.as_str()?.starts_with("3.") {
+        return None;
+    }
+
+    let info = spec.get("info").cloned().unwrap_or_default();
+    let title = str_at(&info, "title").trim();
+    let mut workspace = Workspace::new(match title.is_empty() {
+        true => "OpenAPI".to_string(),
+        false => title.to_string(),
+    });
+    workspace.id = WORKSPACE_ID.to_string();
+    workspace.description = str_at(&info, "description").to_string();
+    workspace.variables = vec![EnvironmentVariable {
+        enabled: true,
+        name: "base_url".to_string(),
+        value: server_url(&spec),
+        ..Default::default()
+    }];
+
+    let mut resources = ImportResources {
+        workspaces: vec![workspace],
+        ..Default::default()
+    };
+    let mut folder_ids: BTreeMap<String, String> = BTreeMap::new();
+    let paths = spec.get("paths").and_then(Value::as_mapping).cloned().unwrap_or_default();
+    for (path, item) in &paths {
+        let path = scalar_string(path);
+        let item = resolve(&spec, item);
+        for method in METHODS {
+            let operation = match item.get(method) {
+                Some(o) => resolve(&spec, o),
+                None => continue,
+            };
+
+            let folder_id = first_tag(operation).map(|tag| {
+                let next_id = format!("GENERATE_ID::FOLDER_{}", folder_ids.len());
+                let id = folder_ids.entry(tag.to_string()).or_insert(next_id).clone();
+                if !resources.folders.iter().any(|f| f.id == id) {
+                    resources.folders.push(Folder {
+                        model: "folder".to_string(),
+                        id: id.clone(),
+                        workspace_id: WORKSPACE_ID.to_string(),
+                        name: tag.to_string(),
+                        sort_priority: resources.folders.len() as f32,
+                        ..Default::default()
+                    });
+                }
+                id
+            });
+
+            let mut request = operation_request(&spec, &path, method, item, operation);
+            request.id = format!("GENERATE_ID::HTTP_REQUEST_{}", resources.http_requests.len());
+            request.folder_id = folder_id;
+            request.sort_priority = resources.http_requests.len() as f32;
+            resources.http_requests.push(request);
+        }
+    }
+
+    Some(resources)
+}
+
+/// Specs are JSON or YAML, and either is read into a YAML value. Its mappings keep their keys in
+/// the order the spec lists them, which is the order requests, form fields, and the properties
+/// of example bodies are created in.
+fn parse_spec(contents: &str) -> Option<Value> {
+    if let Ok(v) = serde_json::from_str::<Value>(contents) {
+        return Some(v);
+    }
+    serde_yaml::from_str::<Value>(contents).ok()
+}
+
+/// The URL of the first server, with its variables set to their defaults
+fn server_url(spec: &Value) -> String {
+    let server = match spec.get("servers").and_then(|s| s.get(0)) {
+        Some(s) => s,
+        None => return String::new(),
+    };
+    let mut url = str_at(server, "url").to_string();
+    if let Some(variables) = server.get("variables").and_then(Value::as_mapping) {
+        for (name, variable) in variables {
+            let name = scalar_string(name);
+            url = url.replace(&format!("{{{name}}}"), str_at(variable, "default"));
+        }
+    }
+    url.trim_end_matches('/').to_string()
+}
+
+fn operation_request(
+    spec: &Value,
+    path: &str,
+    method: &str,
+    item: &Value,
+    operation: &Value,
+) -> HttpRequest {
+    let name = [
+        str_at(operation, "summary"),
+        str_at(operation, "operationId"),
+    ]
+    .into_iter()
+    .map(str::trim)
+    .find(|n| !n.is_empty())
+    .map(str::to_string)
+    .unwrap_or_else(|| format!("{} {path}", method.to_uppercase()));
+
+    let mut request = HttpRequest {
+        model: "http_request".to_string(),
+        workspace_id: WORKSPACE_ID.to_string(),
+        method: method.to_uppercase(),
+        name,
+        url: format!("${{[ base_url ]}}{}", path_placeholders(path)),
+        ..Default::default()
+    };
+
+    for param in parameters(spec, item, operation) {
+        let name = str_at(param, "name").to_string();
+        let value = scalar_string(&parameter_example(spec, param));
+        let required = param.get("required").and_then(Value::as_bool).unwrap_or_default();
+        match str_at(param, "in") {
+            "path" => request.url_parameters.push(HttpUrlParameter {
+                enabled: true,
+                name: format!(":{name}"),
+                value,
+            }),
+            "query" => request.url_parameters.push(HttpUrlParameter {
+                enabled: required,
+                name,
+                value,
+            }),
+            "header" => request.headers.push(HttpRequestHeader {
+                enabled: required,
+                name,
+                value,
+            }),
+            _ => {}
+        }
+    }
+
+    if let Some(body) = operation.get("requestBody") {
+        apply_request_body(spec, resolve(spec, body), &mut request);
+    }
+    apply_security(spec, operation, &mut request);
+    request
+}
+
+/// Turn `{id}` path templates into the `:id` placeholders filled from URL parameters
+fn path_placeholders(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(name) => format!(":{name}"),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The operation's parameters along with the path's, which the operation's may override
+fn parameters<'a>(spec: &'a Value, item: &'a Value, operation: &'a Value) -> Vec<&'a Value> {
+    let list = |v: &'a Value| -> Vec<&'a Value> {
+        let params = v.get("parameters").and_then(Value::as_sequence);
+        params.into_iter().flatten().map(|p| resolve(spec, p)).collect()
+    };
+    let own = list(operation);
+    let key = |p: &Value| (str_at(p, "name").to_string(), str_at(p, "in").to_string());
+    let mut params: Vec<_> =
+        list(item).into_iter().filter(|p| !own.iter().any(|o| key(o) == key(p))).collect();
+    params.extend(own);
+    params
+}
+
+fn parameter_example(spec: &Value, param: &Value) -> Value {
+    match media_example(spec, param) {
+        Value::Null => param.get("schema").map(|s| schema_example(spec, s, 0)).unwrap_or_default(),
+        example => example,
+    }
+}
+
+/// Set the body from the first content type Yaak has an editor for, preferring JSON
+fn apply_request_body(spec: &Value, body: &Value, request: &mut HttpRequest) {
+    let content: Vec<(String, &Value)> = match body.get("content").and_then(Value::as_mapping) {
+        Some(c) if !c.is_empty() => c.iter().map(|(mime, m)| (scalar_string(mime), m)).collect(),
+        _ => return,
+    };
+    let preferred = [
+        "application/json",
+        "application/x-www-form-urlencoded",
+        "multipart/form-data",
+    ];
+    let (mime, media) = preferred
+        .iter()
+        .find_map(|p| content.iter().find(|(mime, _)| body_type(mime) == *p))
+        .or_else(|| content.first())
+        .expect("content isn't empty");
+    let body_type = body_type(mime);
+
+    let example = match media_example(spec, media) {
+        Value::Null => media.get("schema").map(|s| schema_example(spec, s, 0)).unwrap_or_default(),
+        example => example,
+    };
+
+    if body_type == "application/x-www-form-urlencoded" || body_type == "multipart/form-data" {
+        let fields = example.as_mapping().cloned().unwrap_or_default();
+        let form: Vec<serde_json::Value> = fields
+            .iter()
+            .map(|(name, value)| {
+                json!({"enabled": true, "name": scalar_string(name), "value": scalar_string(value)})
+            })
+            .collect();
+        request.body_type = Some(body_type.to_string());
+        request.body.insert("form".to_string(), serde_json::Value::Array(form));
+        return;
+    }
+
+    request.body_type = Some(body_type.to_string());
+    request.headers.push(HttpRequestHeader {
+        enabled: true,
+        name: "Content-Type".to_string(),
+        value: mime.to_string(),
+    });
+    if body_type == "binary" {
+        return;
+    }
+
+    let text = match (&example, body_type == "application/json") {
+        (Value::Null, _) => String::new(),
+        (Value::String(s), false) => s.clone(),
+        (v, _) => serde_json::to_string_pretty(v).unwrap_or_default(),
+    };
+    request.body.insert("text".to_string(), serde_json::Value::String(text));
+}
+
+/// The body type the request editor has for a content type, ignoring parameters like charset.
+/// These match the `BODY_TYPE_*` constants in the frontend.
+fn body_type(mime: &str) -> &'static str {
+    let essence = mime.split(';').next().unwrap_or_default().trim().to_lowercase();
+    match essence.as_str() {
+        "application/x-www-form-urlencoded" => "application/x-www-form-urlencoded",
+        "multipart/form-data" => "multipart/form-data",
+        m if m == "application/json" || m.ends_with("+json") || m.ends_with("/json") => {
+            "application/json"
+        }
+        m if m.ends_with("/xml") || m.ends_with("+xml") => "text/xml",
+        m if m == "application/octet-stream"
+            || ["image/", "audio/", "video/"].iter().any(|p| m.starts_with(p)) =>
+        {
+            "binary"
+        }
+        _ => "other",
+    }
+}
+
+/// Set up auth from the first security scheme the operation, or else the spec, asks for.
+/// Credentials are left empty for the user to fill in.
+fn apply_security(spec: &Value, operation: &Value, request: &mut HttpRequest) {
+    let requirements = operation.get("security").or(spec.get("security"));
+    let scheme_name = match requirements
+        .and_then(|r| r.get(0))
+        .and_then(Value::as_mapping)
+        .and_then(|r| r.keys().next())
+    {
+        Some(n) => scalar_string(n),
+        None => return,
+    };
+    let schemes = spec.get("components").and_then(|c| c.get("securitySchemes"));
+    let scheme = match schemes.and_then(|s| s.get(scheme_name.as_str())) {
+        Some(s) => resolve(spec, s),
+        None => return,
+    };
+
+    match (str_at(scheme, "type"), str_at(scheme, "scheme").to_lowercase().as_str()) {
+        ("http", "basic") => {
+            request.authentication_type = Some("basic".to_string());
+            request.authentication = BTreeMap::from([
+                ("username".into(), "".into()),
+                ("password".into(), "".into()),
+            ]);
+        }
+        ("http", "bearer") | ("oauth2", _) | ("openIdConnect", _) => {
+            request.authentication_type = Some("bearer".to_string());
+            request.authentication = BTreeMap::from([("token".into(), "".into())]);
+        }
+        ("apiKey", _) if str_at(scheme, "in") == "header" => {
+            request.headers.push(HttpRequestHeader {
+                enabled: true,
+                name: str_at(scheme, "name").to_string(),
+                value: String::new(),
+            });
+        }
+        ("apiKey", _) if str_at(scheme, "in") == "query" => {
+            request.url_parameters.push(HttpUrlParameter {
+                enabled: true,
+                name: str_at(scheme, "name").to_string(),
+                value: String::new(),
+            });
+        }
+        _ => {}
+    }
+}
+
+/// The example of a parameter or media type, from `example` or the first of `examples`
+fn media_example(spec: &Value, v: &Value) -> Value {
+    if let Some(example) = v.get("example") {
+        return example.clone();
+    }
+    let first = v.get("examples").and_then(Value::as_mapping).and_then(|e| e.values().next());
+    match first {
+        Some(e) => resolve(spec, e).get("value").cloned().unwrap_or_default(),
+        None => Value::Null,
+    }
+}
+
+/// An example value for a schema, from its own example or default when it has one
+fn schema_example(spec: &Value, schema: &Value, depth: usize) -> Value {
+    if depth > MAX_SCHEMA_DEPTH {
+        return Value::Null;
+    }
+    let schema = resolve(spec, schema);
+    if let Some(v) = schema.get("example").or(schema.get("default")) {
+        return v.clone();
+    }
+    // 3.1 puts examples in a list
+    if let Some(v) = schema.get("examples").and_then(|e| e.get(0)) {
+        return v.clone();
+    }
+    if let Some(v) = schema.get("enum").and_then(|e| e.get(0)) {
+        return v.clone();
+    }
+    if let Some(all) = schema.get("allOf").and_then(Value::as_sequence) {
+        let mut merged = Mapping::new();
+        for s in all {
+            if let Value::Mapping(m) = schema_example(spec, s, depth + 1) {
+                merged.extend(m);
+            }
+        }
+        return Value::Mapping(merged);
+    }
+    if let Some(s) = schema.get("oneOf").or(schema.get("anyOf")).and_then(|s| s.get(0)) {
+        return schema_example(spec, s, depth + 1);
+    }
+
+    // 3.1 allows a list of types, like ["string", "null"]
+    let schema_type = match schema.get("type") {
+        Some(Value::Sequence(types)) => {
+            types.iter().filter_map(Value::as_str).find(|t| *t != "null")
+        }
+        Some(t) => t.as_str(),
+        None if schema.get("properties").is_some() => Some("object"),
+        None => None,
+    };
+    match schema_type {
+        Some("object") => {
+            let properties = schema.get("properties").and_then(Value::as_mapping);
+            Value::Mapping(
+                properties
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, s)| (name.clone(), schema_example(spec, s, depth + 1)))
+                    .collect(),
+            )
+        }
+        Some("array") => match schema.get("items") {
+            Some(items) => Value::Sequence(vec![schema_example(spec, items, depth + 1)]),
+            None => Value::Sequence(Vec::new()),
+        },
+        Some("string") => Value::String(
+            match str_at(schema, "format") {
+                "date" => "2024-01-01",
+                "date-time" => "2024-01-01T00:00:00Z",
+                "email" => "user@example.com",
+                "uuid" => "00000000-0000-0000-0000-000000000000",
+                "uri" | "url" => "https://example.com",
+                _ => "",
+            }
+            .to_string(),
+        ),
+        Some("integer") | Some("number") => Value::from(0),
+        Some("boolean") => Value::Bool(false),
+        _ => Value::Null,
+    }
+}
+
+/// Follow local `$ref`s, like `#/components/schemas/User`. Other refs are left as they are.
+fn resolve<'a>(spec: &'a Value, v: &'a Value) -> &'a Value {
+    let mut v = v;
+    for _ in 0..MAX_SCHEMA_DEPTH {
+        let target = v
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix('#'))
+            .and_then(|p| pointer(spec, p));
+        match target {
+            Some(t) => v = t,
+            None => break,
+        }
+    }
+    v
+}
+
+/// The value a JSON pointer, like `/components/schemas/User`, points to
+fn pointer<'a>(v: &'a Value, pointer: &str) -> Option<&'a Value> {
+    if pointer.is_empty() {
+        return Some(v);
+    }
+    pointer.strip_prefix('/')?.split('/').try_fold(v, |v, token| {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        match v {
+            Value::Sequence(s) => s.get(token.parse::<usize>().ok()?),
+            v => v.get(token.as_str()),
+        }
+    })
+}
+
+fn first_tag(operation: &Value) -> Option<&str> {
+    operation.get("tags")?.get(0)?.as_str().filter(|t| !t.is_empty())
+}
+
+fn str_at<'a>(v: &'a Value, key: &str) -> &'a str {
+    v.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+/// A value as it'd be typed into a text field
+fn scalar_string(v: &Value) -> String {
+    match v {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        v => serde_json::to_string(v).unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::openapi_import::import_openapi;
+    use serde_json::json;
+
+    const SPEC: &str = r#"
+openapi: 3.0.3
+info:
+  title: Pet Store
+  description: Pets for sale
+servers:
+  - url: https://{region}.pets.test/v1/
+    variables:
+      region:
+        default: eu
+security:
+  - token: []
+paths:
+  /pets/{petId}:
+    parameters:
+      - $ref: '#/components/parameters/PetId'
+    get:
+      summary: Get a pet
+      tags: [pets]
+      parameters:
+        - name: fields
+          in: query
+          schema:
+            type: string
+            example: name,age
+        - name: X-Trace
+          in: header
+          required: true
+          schema:
+            type: string
+      responses:
+        200:
+          description: OK
+  /pets:
+    post:
+      operationId: createPet
+      tags: [pets]
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: '#/components/schemas/Pet'
+      responses:
+        201:
+          description: Created
+  /health:
+    get:
+      security: []
+      responses:
+        200:
+          description: OK
+components:
+  parameters:
+    PetId:
+      name: petId
+      in: path
+      required: true
+      schema:
+        type: integer
+        example: 7
+  schemas:
+    Pet:
+      type: object
+      properties:
+        name:
+          type: string
+          example: Rex
+        born:
+          type: string
+          format: date
+        tags:
+          type: array
+          items:
+            type: string
+            enum: [good]
+  securitySchemes:
+    token:
+      type: http
+      scheme: bearer
+"#;
+
+    #[test]
+    fn import_yaml_spec() {
+        let resources = import_openapi(SPEC).unwrap();
+
+        let workspace = &resources.workspaces[0];
+        assert_eq!(workspace.name, "Pet Store");
+        assert_eq!(workspace.description, "Pets for sale");
+        assert_eq!(workspace.variables[0].name, "base_url");
+        assert_eq!(workspace.variables[0].value, "https://eu.pets.test/v1");
+
+        assert_eq!(resources.folders.len(), 1);
+        assert_eq!(resources.folders[0].name, "pets");
+        let folder_id = Some(resources.folders[0].id.clone());
+
+        // Paths are read in the order the spec lists them
+        let names: Vec<_> = resources.http_requests.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["Get a pet", "createPet", "GET /health"]);
+
+        let health = &resources.http_requests[2];
+        assert_eq!(health.folder_id, None);
+        assert_eq!(health.authentication_type, None);
+
+        let create = &resources.http_requests[1];
+        assert_eq!(create.method, "POST");
+        assert_eq!(create.url, "${[ base_url ]}/pets");
+        assert_eq!(create.folder_id, folder_id);
+        assert_eq!(create.authentication_type.as_deref(), Some("bearer"));
+        assert_eq!(create.body_type.as_deref(), Some("application/json"));
+        // Example bodies list properties in the order the schema does
+        let body = create.body["text"].as_str().unwrap();
+        let body_value: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body_value, json!({"name": "Rex", "born": "2024-01-01", "tags": ["good"]}));
+        assert!(body.find("name") < body.find("born") && body.find("born") < body.find("tags"));
+        assert_eq!(create.headers[0].name, "Content-Type");
+
+        let get = &resources.http_requests[0];
+        assert_eq!(get.url, "${[ base_url ]}/pets/:petId");
+        let params: Vec<_> = get
+            .url_parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.value.as_str(), p.enabled))
+            .collect();
+        assert_eq!(params, [(":petId", "7", true), ("fields", "name,age", false)]);
+        assert_eq!(get.headers[0].name, "X-Trace");
+        assert!(get.headers[0].enabled);
+    }
+
+    #[test]
+    fn import_json_form_spec() {
+        let spec = r#"{
+            "openapi": "3.1.0",
+            "info": {"title": "Forms"},
+            "paths": {"/login": {"post": {
+                "requestBody": {"content": {"application/x-www-form-urlencoded; charset=utf-8": {
+                    "schema": {"properties": {
+                        "user": {"type": ["string", "null"], "format": "email"},
+                        "remember": {"type": "boolean", "default": true}
+                    }}
+                }}}
+            }}}
+        }"#;
+        let resources = import_openapi(spec).unwrap();
+        let request = &resources.http_requests[0];
+        assert_eq!(request.url, "${[ base_url ]}/login");
+        assert_eq!(request.body_type.as_deref(), Some("application/x-www-form-urlencoded"));
+        assert_eq!(
+            request.body["form"],
+            json!([
+                {"enabled": true, "name": "user", "value": "user@example.com"},
+                {"enabled": true, "name": "remember", "value": "true"},
+            ])
+        );
+    }
+
+    #[test]
+    fn import_body_types() {
+        let body_type = |mime: &str| {
+            let spec = json!({
+                "openapi": "3.0.0",
+                "paths": {"/": {"post": {"requestBody": {"content": {mime: {"example": "x"}}}}}},
+            });
+            let resources = import_openapi(&spec.to_string()).unwrap();
+            let request = &resources.http_requests[0];
+            assert_eq!(request.headers[0].value, mime);
+            request.body_type.clone().unwrap()
+        };
+        assert_eq!(body_type("application/json; charset=utf-8"), "application/json");
+        assert_eq!(body_type("application/vnd.api+json"), "application/json");
+        assert_eq!(body_type("application/xml"), "text/xml");
+        assert_eq!(body_type("image/png"), "binary");
+        assert_eq!(body_type("text/plain"), "other");
+    }
+
+    #[test]
+    fn skip_other_formats() {
+        assert!(import_openapi(r#"{"swagger": "2.0", "paths": {}}"#).is_none());
+        assert!(import_openapi("curl https://yaak.test").is_none());
+        assert!(import_openapi(r#"{"info": {"_postman_id": "1"}}"#).is_none());
+    }
+}
@@ -0,0 +1,244 @@
+use std::path::Path;
+
+use http::header::{
+    ACCEPT, ACCEPT_ENCODING, AUTHORIZATION, CONTENT_LENGTH, COOKIE, HOST, PROXY_AUTHORIZATION,
+    RANGE, TRANSFER_ENCODING,
+};
+use http::{HeaderMap, HeaderName, HeaderValue};
+use http_body_util::BodyDataStream;
+use hyper::body::Bytes;
+use log::warn;
+use reqwest::{Body, Request};
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+
+/// What the client asks for, given the decompression it's built with
+const ACCEPT_ENCODING_VALUE: &str = "gzip, br, deflate";
+
+/// Headers whose values are credentials, which are left out of the recording
+const REDACTED_HEADERS: [HeaderName; 3] = [AUTHORIZATION, PROXY_AUTHORIZATION, COOKIE];
+
+/// Record a reconstruction of the request to `path`, as it's written to the connection: the
+/// request line and headers, then the body as the client sends it, including chunk framing.
+/// Streamed bodies are recorded as they're read, so the request is swapped for one that records
+/// its body.
+///
+/// The client doesn't expose the request it serializes, so the headers it adds itself are
+/// filled in here the same way, and may differ from what was actually sent. HTTP/2 sends the
+/// same headers as frames, but they're written the way HTTP/1.1 would send them. The values of
+/// credential headers are redacted, since the file sits next to the response body.
+pub async fn record_raw_request(
+    request: &mut Request,
+    cookie: Option<HeaderValue>,
+    path: &Path,
+) -> std::io::Result<()> {
+    let headers = sent_headers(request, cookie);
+    let chunked = headers
+        .get(TRANSFER_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.to_ascii_lowercase().contains("chunked"));
+
+    let mut file = File::create(path).await?;
+    file.write_all(&raw_request_head(request, &headers)).await?;
+    let buffered = request.body().and_then(|b| b.as_bytes());
+    if let Some(bytes) = buffered {
+        file.write_all(bytes).await?;
+    } else if let Some(body) = request.body_mut().take() {
+        file.flush().await?;
+        *request.body_mut() = Some(recording_body(body, file, chunked));
+        return Ok(());
+    }
+    file.flush().await
+}
+
+/// The request's headers, plus the ones the client adds before sending it
+fn sent_headers(request: &Request, cookie: Option<HeaderValue>) -> HeaderMap {
+    let mut headers = request.headers().clone();
+    let url = request.url();
+    if !headers.contains_key(ACCEPT) {
+        headers.insert(ACCEPT, HeaderValue::from_static("*/*"));
+    }
+    if let Some(cookie) = cookie.filter(|_| !headers.contains_key(COOKIE)) {
+        headers.insert(COOKIE, cookie);
+    }
+    if !headers.contains_key(ACCEPT_ENCODING) && !headers.contains_key(RANGE) {
+        headers.insert(ACCEPT_ENCODING, HeaderValue::from_static(ACCEPT_ENCODING_VALUE));
+    }
+    if !headers.contains_key(HOST) {
+        let host = match url.port() {
+            Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        if let Ok(host) = HeaderValue::from_str(&host) {
+            headers.insert(HOST, host);
+        }
+    }
+    if !headers.contains_key(CONTENT_LENGTH) && !headers.contains_key(TRANSFER_ENCODING) {
+        match request.body().map(|b| b.as_bytes()) {
+            Some(Some(bytes)) if !bytes.is_empty() => {
+                headers.insert(CONTENT_LENGTH, HeaderValue::from(bytes.len()));
+            }
+            Some(None) => {
+                headers.insert(TRANSFER_ENCODING, HeaderValue::from_static("chunked"));
+            }
+            _ => {}
+        }
+    }
+    headers
+}
+
+fn raw_request_head(request: &Request, headers: &HeaderMap) -> Vec<u8> {
+    let url = request.url();
+    let target = match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    };
+    let mut head = format!("{} {target} HTTP/1.1\r\n", request.method()).into_bytes();
+    for (name, value) in headers {
+        head.extend_from_slice(name.as_str().as_bytes());
+        head.extend_from_slice(b": ");
+        match REDACTED_HEADERS.contains(name) {
+            true => head.extend_from_slice(b"[redacted]"),
+            false => head.extend_from_slice(value.as_bytes()),
+        }
+        head.extend_from_slice(b"\r\n");
+    }
+    head.extend_from_slice(b"\r\n");
+    head
+}
+
+/// Wrap a streamed body so each chunk is appended to the file as it's sent. The chunks are
+/// written by a separate task, so sending never waits on the file. Recording stops at the first
+/// failed write, without failing the request.
+fn recording_body(body: Body, mut file: File, chunked: bool) -> Body {
+    let (tx, mut rx) = mpsc::unbounded_channel::<Vec<u8>>();
+    tokio::spawn(async move {
+        while let Some(bytes) = rx.recv().await {
+            if let Err(e) = file.write_all(&bytes).await {
+                warn!("Failed to record raw request body {e:?}");
+                return;
+            }
+        }
+        if let Err(e) = file.flush().await {
+            warn!("Failed to record raw request body {e:?}");
+        }
+    });
+    let record = move |bytes: &[u8]| {
+        // The writer only stops early after a failed write, which it's already logged
+        let _ = tx.send(bytes.to_vec());
+    };
+
+    let record_end = record.clone();
+    let data = BodyDataStream::new(body).map(move |chunk: Result<Bytes, reqwest::Error>| {
+        match &chunk {
+            // Empty chunks aren't sent, since they'd read as the end of a chunked body
+            Ok(bytes) if bytes.is_empty() => {}
+            Ok(bytes) if chunked => record(&chunk_frame(bytes)),
+            Ok(bytes) => record(bytes),
+            Err(_) => {}
+        }
+        chunk
+    });
+    let end = tokio_stream::iter(std::iter::from_fn(move || {
+        if chunked {
+            record_end(b"0\r\n\r\n");
+        }
+        None::<Result<Bytes, reqwest::Error>>
+    }));
+    Body::wrap_stream(data.chain(end))
+}
+
+fn chunk_frame(bytes: &[u8]) -> Vec<u8> {
+    let mut frame = format!("{:X}\r\n", bytes.len()).into_bytes();
+    frame.extend_from_slice(bytes);
+    frame.extend_from_slice(b"\r\n");
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::raw_request::record_raw_request;
+    use http::HeaderValue;
+    use http_body_util::BodyExt;
+    use reqwest::{Body, Client};
+    use std::fs;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn record_buffered_request() {
+        let path = std::env::temp_dir().join("yaak-raw-request-buffered.request");
+        let mut request = Client::new()
+            .post("http://yaak.test:8080/users?page=2")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer secret")
+            .body(r#"{"name":"Yaak"}"#)
+            .build()
+            .unwrap();
+        let cookie = Some(HeaderValue::from_static("a=1"));
+        record_raw_request(&mut request, cookie, &path).await.unwrap();
+
+        // Credentials are redacted
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            [
+                "POST /users?page=2 HTTP/1.1",
+                "content-type: application/json",
+                "authorization: [redacted]",
+                "accept: */*",
+                "cookie: [redacted]",
+                "accept-encoding: gzip, br, deflate",
+                "host: yaak.test:8080",
+                "content-length: 15",
+                "",
+                r#"{"name":"Yaak"}"#,
+            ]
+            .join("\r\n")
+        );
+        assert_eq!(request.body().unwrap().as_bytes(), Some(r#"{"name":"Yaak"}"#.as_bytes()));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn record_streamed_request() {
+        let path = std::env::temp_dir().join("yaak-raw-request-streamed.request");
+        let chunks: Vec<Result<&'static str, std::io::Error>> =
+            vec![Ok("hello "), Ok(""), Ok("world")];
+        let mut request = Client::new()
+            .put("https://yaak.test")
+            .header("accept", "text/plain")
+            .header("range", "bytes=0-")
+            .body(Body::wrap_stream(tokio_stream::iter(chunks)))
+            .build()
+            .unwrap();
+        record_raw_request(&mut request, None, &path).await.unwrap();
+
+        // The body is recorded as it's sent
+        let head = [
+            "PUT / HTTP/1.1",
+            "accept: text/plain",
+            "range: bytes=0-",
+            "host: yaak.test",
+            "transfer-encoding: chunked",
+            "",
+            "",
+        ]
+        .join("\r\n");
+        assert_eq!(fs::read_to_string(&path).unwrap(), head);
+
+        let body = request.body_mut().take().unwrap().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "hello world");
+
+        // The chunks are written by their own task, which finishes once the body is dropped
+        let recorded = format!("{head}6\r\nhello \r\n5\r\nworld\r\n0\r\n\r\n");
+        for _ in 0..100 {
+            if fs::read_to_string(&path).unwrap() == recorded {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), recorded);
+        fs::remove_file(&path).unwrap();
+    }
+}
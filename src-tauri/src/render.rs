@@ -5,7 +5,7 @@ use yaak_models::models::{
     Environment, EnvironmentVariable, GrpcMetadataEntry, GrpcRequest, HttpRequest,
     HttpRequestHeader, HttpUrlParameter, Workspace,
 };
-use yaak_templates::{parse_and_render, TemplateCallback};
+use yaak_templates::{parse_and_render, Parser, TemplateCallback};
 
 pub async fn render_template<T: TemplateCallback>(
     template: &str,
@@ -36,6 +36,18 @@ pub async fn render_grpc_request<T: TemplateCallback>(
     let vars = &make_vars_hashmap(w, e);
 
     let mut metadata = Vec::new();
+
+    // Workspace defaults come first, skipping any the request overrides
+    if r.inherit_metadata {
+        for p in inherited_grpc_metadata(&w.grpc_metadata, &r.metadata) {
+            metadata.push(GrpcMetadataEntry {
+                enabled: p.enabled,
+                name: render(p.name.as_str(), vars, cb).await,
+                value: render(p.value.as_str(), vars, cb).await,
+            })
+        }
+    }
+
     for p in r.metadata.clone() {
         metadata.push(GrpcMetadataEntry {
             enabled: p.enabled,
@@ -59,6 +71,18 @@ pub async fn render_grpc_request<T: TemplateCallback>(
     }
 }
 
+fn inherited_grpc_metadata(
+    defaults: &Vec<GrpcMetadataEntry>,
+    metadata: &Vec<GrpcMetadataEntry>,
+) -> Vec<GrpcMetadataEntry> {
+    defaults
+        .iter()
+        .filter(|d| d.enabled && !d.name.is_empty())
+        .filter(|d| !metadata.iter().any(|m| m.enabled && m.name.eq_ignore_ascii_case(&d.name)))
+        .cloned()
+        .collect()
+}
+
 pub async fn render_http_request(
     r: &HttpRequest,
     w: &Workspace,
@@ -96,12 +120,14 @@ pub async fn render_http_request(
     }
 
     let url = render(r.url.clone().as_str(), vars, cb).await;
+    let setting_server_name = render(r.setting_server_name.as_str(), vars, cb).await;
     let req = HttpRequest {
         url,
         url_parameters,
         headers,
         body,
         authentication,
+        setting_server_name,
         ..r.to_owned()
     };
 
@@ -109,6 +135,10 @@ pub async fn render_http_request(
     apply_path_placeholders(req)
 }
 
+/// Build the variables available to templates. Workspace variables are constants shared by every
+/// environment, and the active environment's variables are layered on top, so an environment
+/// overrides a workspace variable of the same name. Disabled or empty variables are skipped and
+/// never shadow anything.
 pub fn make_vars_hashmap(
     workspace: &Workspace,
     environment: Option<&Environment>,
@@ -123,6 +153,53 @@ pub fn make_vars_hashmap(
     variables
 }
 
+/// Variables referenced by a request's URL, params, headers, or body that aren't defined, sorted
+/// by name. Defined variables are followed into their values, since those are rendered as
+/// templates too.
+pub fn undefined_variables(r: &HttpRequest, vars: &HashMap<String, String>) -> Vec<String> {
+    let mut templates = vec![r.url.clone()];
+    for p in r.url_parameters.iter().filter(|p| p.enabled) {
+        templates.push(p.name.clone());
+        templates.push(p.value.clone());
+    }
+    for h in r.headers.iter().filter(|h| h.enabled) {
+        templates.push(h.name.clone());
+        templates.push(h.value.clone());
+    }
+    for v in r.body.values() {
+        collect_json_strings(v, &mut templates);
+    }
+
+    let mut seen = Vec::new();
+    let mut undefined = Vec::new();
+    while let Some(template) = templates.pop() {
+        for name in Parser::new(&template).parse().variable_names() {
+            if seen.contains(&name) {
+                continue;
+            }
+            match vars.get(&name) {
+                Some(value) => templates.push(value.clone()),
+                None => undefined.push(name.clone()),
+            }
+            seen.push(name);
+        }
+    }
+    undefined.sort();
+    undefined
+}
+
+fn collect_json_strings(v: &Value, strings: &mut Vec<String>) {
+    match v {
+        Value::String(s) => strings.push(s.clone()),
+        Value::Array(a) => a.iter().for_each(|v| collect_json_strings(v, strings)),
+        Value::Object(o) => o.iter().for_each(|(k, v)| {
+            strings.push(k.clone());
+            collect_json_strings(v, strings);
+        }),
+        _ => {}
+    }
+}
+
 pub async fn render<T: TemplateCallback>(
     template: &str,
     vars: &HashMap<String, String>,
@@ -178,7 +255,11 @@ async fn render_json_value_raw<T: TemplateCallback>(
 #[cfg(test)]
 mod render_tests {
     use serde_json::json;
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
+    use yaak_models::models::{
+        Environment, EnvironmentVariable, GrpcMetadataEntry, GrpcRequest, HttpRequest,
+        HttpRequestHeader, Workspace,
+    };
     use yaak_templates::TemplateCallback;
 
     struct EmptyCB {}
@@ -249,6 +330,140 @@ mod render_tests {
             ])
         )
     }
+
+    #[tokio::test]
+    async fn render_grpc_inherited_metadata() {
+        let workspace = Workspace {
+            variables: vec![EnvironmentVariable {
+                name: "token".to_string(),
+                value: "abc".to_string(),
+                enabled: true,
+                ..Default::default()
+            }],
+            grpc_metadata: vec![
+                GrpcMetadataEntry {
+                    name: "authorization".to_string(),
+                    value: "Bearer ${[token]}".to_string(),
+                    enabled: true,
+                },
+                GrpcMetadataEntry {
+                    name: "x-tenant".to_string(),
+                    value: "default".to_string(),
+                    enabled: true,
+                },
+            ],
+            ..Default::default()
+        };
+        let request = GrpcRequest {
+            inherit_metadata: true,
+            metadata: vec![GrpcMetadataEntry {
+                name: "X-Tenant".to_string(),
+                value: "mine".to_string(),
+                enabled: true,
+            }],
+            ..Default::default()
+        };
+
+        let result = super::render_grpc_request(&request, &workspace, None, &EmptyCB {}).await;
+        let metadata: Vec<(String, String)> =
+            result.metadata.into_iter().map(|m| (m.name, m.value)).collect();
+        assert_eq!(
+            metadata,
+            vec![
+                ("authorization".to_string(), "Bearer abc".to_string()),
+                ("X-Tenant".to_string(), "mine".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn render_grpc_without_inherited_metadata() {
+        let workspace = Workspace {
+            grpc_metadata: vec![GrpcMetadataEntry {
+                name: "x-tenant".to_string(),
+                value: "default".to_string(),
+                enabled: true,
+            }],
+            ..Default::default()
+        };
+        let request = GrpcRequest {
+            inherit_metadata: false,
+            ..Default::default()
+        };
+
+        let result = super::render_grpc_request(&request, &workspace, None, &EmptyCB {}).await;
+        assert!(result.metadata.is_empty());
+    }
+
+    fn var(name: &str, value: &str, enabled: bool) -> EnvironmentVariable {
+        EnvironmentVariable {
+            name: name.to_string(),
+            value: value.to_string(),
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn find_undefined_variables() {
+        let request = HttpRequest {
+            url: "${[ base_url ]}/users".to_string(),
+            headers: vec![
+                HttpRequestHeader {
+                    enabled: true,
+                    name: "Authorization".to_string(),
+                    value: "Bearer ${[ hash(value=tokn) ]}".to_string(),
+                },
+                HttpRequestHeader {
+                    enabled: false,
+                    name: "X-Ignored".to_string(),
+                    value: "${[ disabled ]}".to_string(),
+                },
+            ],
+            body: BTreeMap::from([("text".to_string(), json!("{\"host\": \"${[ hosst ]}\"}"))]),
+            ..Default::default()
+        };
+        let vars = HashMap::from([
+            ("base_url".to_string(), "https://${[ host ]}/${[ version ]}".to_string()),
+            ("host".to_string(), "yaak.app".to_string()),
+        ]);
+
+        assert_eq!(super::undefined_variables(&request, &vars), vec!["hosst", "tokn", "version"]);
+    }
+
+    #[test]
+    fn environment_overrides_workspace_variables() {
+        let workspace = Workspace {
+            variables: vec![
+                var("base_url", "https://api.yaak.test", true),
+                var("api_version", "v1", true),
+                var("tenant", "shared", true),
+                var("token", "workspace-token", true),
+            ],
+            ..Default::default()
+        };
+        let environment = Environment {
+            variables: vec![
+                var("base_url", "http://localhost:8080", true),
+                var("tenant", "ignored", false),
+                var("token", "", true),
+                var("debug", "true", true),
+            ],
+            ..Default::default()
+        };
+
+        let vars = super::make_vars_hashmap(&workspace, Some(&environment));
+        assert_eq!(vars["base_url"], "http://localhost:8080");
+        assert_eq!(vars["api_version"], "v1");
+        assert_eq!(vars["tenant"], "shared");
+        assert_eq!(vars["token"], "workspace-token");
+        assert_eq!(vars["debug"], "true");
+        assert_eq!(vars.len(), 5);
+
+        let vars = super::make_vars_hashmap(&workspace, None);
+        assert_eq!(vars["base_url"], "https://api.yaak.test");
+        assert_eq!(vars.len(), 4);
+    }
 }
 
 fn replace_path_placeholder(p: &HttpUrlParameter, url: &str) -> String {
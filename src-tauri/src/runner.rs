@@ -0,0 +1,560 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+
+use log::{debug, warn};
+use serde::Serialize;
+use tauri::{Emitter, Manager, Runtime, WebviewWindow};
+use tokio::sync::{mpsc, Semaphore};
+use tokio_stream::wrappers::ReceiverStream;
+use yaak_grpc::manager::{DynamicMessage, StreamError};
+use yaak_grpc::{deserialize_message, Code};
+use yaak_models::models::{
+    CookieJar, Environment, Folder, GrpcConnection, GrpcConnectionState, GrpcRequest, HttpRequest,
+    HttpResponse, RunResult, RunResultStatus,
+};
+use yaak_models::queries::{
+    create_default_http_response, generate_id, get_cookie_jar, get_key_value_raw, list_folders,
+    list_grpc_requests, list_http_requests, upsert_grpc_connection, upsert_run_result,
+};
+use yaak_plugin_runtime::events::{RenderPurpose, WindowContext};
+
+use crate::grpc::{record_grpc_history, GrpcCall};
+use crate::http_request::send_http_request;
+use crate::render::render_template;
+use crate::template_callback::PluginTemplateCallback;
+use crate::{register_cancel_channel, response_err};
+
+/// A request to send as part of a folder run
+#[derive(Debug, Clone)]
+pub enum RunItem {
+    Http(HttpRequest),
+    Grpc(GrpcRequest),
+}
+
+impl RunItem {
+    fn id(&self) -> &str {
+        match self {
+            RunItem::Http(r) => &r.id,
+            RunItem::Grpc(r) => &r.id,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            RunItem::Http(r) => &r.name,
+            RunItem::Grpc(r) => &r.name,
+        }
+    }
+
+    fn model(&self) -> &'static str {
+        match self {
+            RunItem::Http(_) => "http_request",
+            RunItem::Grpc(_) => "grpc_request",
+        }
+    }
+}
+
+/// Sent to the window after each request of a folder run finishes
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunProgress {
+    pub run_id: String,
+    pub completed: usize,
+    pub total: usize,
+    pub result: RunResult,
+}
+
+/// Sent to the window as each response of a batch send finishes
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendManyProgress {
+    pub completed: usize,
+    pub total: usize,
+    pub response: HttpResponse,
+}
+
+/// Send every request in the folder and its subfolders one after another, saving a result for
+/// each. With `stop_on_failure`, the requests after the first failure are saved as skipped
+/// instead of being sent.
+pub async fn run_folder<R: Runtime>(
+    window: &WebviewWindow<R>,
+    folder: &Folder,
+    environment: Option<Environment>,
+    cookie_jar: Option<CookieJar>,
+    stop_on_failure: bool,
+) -> Result<Vec<RunResult>, String> {
+    let workspace_id = &folder.workspace_id;
+    let folders = list_folders(window, workspace_id).await.map_err(|e| e.to_string())?;
+    let http_requests =
+        list_http_requests(window, workspace_id).await.map_err(|e| e.to_string())?;
+    let grpc_requests =
+        list_grpc_requests(window, workspace_id).await.map_err(|e| e.to_string())?;
+    let items = run_order(&folder.id, &folders, &http_requests, &grpc_requests);
+
+    let run_id = generate_id();
+    let event_name = format!("run_folder_progress_{}", folder.id);
+    debug!("Running {} requests in folder {} as run {run_id}", items.len(), folder.id);
+
+    let mut results = Vec::new();
+    let mut failed = false;
+    for item in &items {
+        let result = RunResult {
+            workspace_id: workspace_id.to_string(),
+            folder_id: folder.id.clone(),
+            run_id: run_id.clone(),
+            request_id: item.id().to_string(),
+            request_model: item.model().to_string(),
+            request_name: item.name().to_string(),
+            status: RunResultStatus::Skipped,
+            ..Default::default()
+        };
+
+        let result = match item {
+            _ if failed && stop_on_failure => result,
+            RunItem::Http(r) => {
+                run_http_request(window, r, environment.clone(), cookie_jar.clone(), result).await
+            }
+            RunItem::Grpc(r) => run_grpc_request(window, r, environment.as_ref(), result).await,
+        };
+        failed |= matches!(result.status, RunResultStatus::Failed);
+
+        let result = upsert_run_result(window, &result).await.map_err(|e| e.to_string())?;
+        results.push(result.clone());
+
+        let progress = RunProgress {
+            run_id: run_id.clone(),
+            completed: results.len(),
+            total: items.len(),
+            result,
+        };
+        if let Err(e) = window.emit_to(window.label(), &event_name, progress) {
+            warn!("Failed to emit run progress {e:?}");
+        }
+    }
+
+    Ok(results)
+}
+
+/// Every request in the folder and its subfolders, in the order the sidebar shows them. A
+/// subfolder's requests run where the subfolder sits among its siblings.
+pub fn run_order(
+    folder_id: &str,
+    folders: &[Folder],
+    http_requests: &[HttpRequest],
+    grpc_requests: &[GrpcRequest],
+) -> Vec<RunItem> {
+    enum Child<'a> {
+        Folder(&'a Folder),
+        Request(RunItem),
+    }
+
+    let in_folder = |id: &Option<String>| id.as_deref() == Some(folder_id);
+    let mut children: Vec<(f32, Child)> = Vec::new();
+    for f in folders.iter().filter(|f| in_folder(&f.folder_id)) {
+        children.push((f.sort_priority, Child::Folder(f)));
+    }
+    for r in http_requests.iter().filter(|r| in_folder(&r.folder_id)) {
+        children.push((r.sort_priority, Child::Request(RunItem::Http(r.clone()))));
+    }
+    for r in grpc_requests.iter().filter(|r| in_folder(&r.folder_id)) {
+        children.push((r.sort_priority, Child::Request(RunItem::Grpc(r.clone()))));
+    }
+    children.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+    let mut items = Vec::new();
+    for (_, child) in children {
+        match child {
+            Child::Request(r) => items.push(r),
+            Child::Folder(f) => {
+                items.extend(run_order(&f.id, folders, http_requests, grpc_requests))
+            }
+        }
+    }
+    items
+}
+
+async fn run_http_request<R: Runtime>(
+    window: &WebviewWindow<R>,
+    request: &HttpRequest,
+    environment: Option<Environment>,
+    cookie_jar: Option<CookieJar>,
+    result: RunResult,
+) -> RunResult {
+    let response = match create_default_http_response(window, &request.id).await {
+        Ok(r) => r,
+        Err(e) => {
+            return RunResult {
+                status: RunResultStatus::Failed,
+                error: Some(e.to_string()),
+                ..result
+            }
+        }
+    };
+
+    let mut cancel_rx = register_cancel_channel(window, &response.id).await;
+    match send_http_request(window, request, &response, environment, cookie_jar, &mut cancel_rx)
+        .await
+    {
+        Ok(r) => RunResult {
+            status: match (&r.error, r.status) {
+                (None, s) if s < 400 => RunResultStatus::Passed,
+                _ => RunResultStatus::Failed,
+            },
+            response_id: Some(r.id),
+            status_code: (r.status > 0).then_some(r.status),
+            elapsed: r.elapsed,
+            error: r.error,
+            ..result
+        },
+        Err(e) => RunResult {
+            status: RunResultStatus::Failed,
+            response_id: Some(response.id),
+            error: Some(e),
+            ..result
+        },
+    }
+}
+
+/// Send the requests at the same time, each with its own workspace's environment, at most
+/// `concurrency` at once, emitting each response to the window as it finishes. The responses are
+/// returned in the order the requests were given. Each send starts from the jar as it is when
+/// the send starts, so it gets the cookies set by the responses that finished before it.
+pub async fn send_many_http_requests<R: Runtime>(
+    window: &WebviewWindow<R>,
+    requests: Vec<(HttpRequest, Option<Environment>)>,
+    cookie_jar: Option<CookieJar>,
+    concurrency: usize,
+) -> Vec<HttpResponse> {
+    let total = requests.len();
+    debug!("Sending {total} requests, {concurrency} at a time");
+
+    let send = |(request, environment): (HttpRequest, Option<Environment>)| {
+        let window = window.clone();
+        let cookie_jar = cookie_jar.clone();
+        async move { send_for_batch(&window, &request, environment, cookie_jar).await }
+    };
+    let on_done = |completed: usize, response: &HttpResponse| {
+        let progress = SendManyProgress {
+            completed,
+            total,
+            response: response.clone(),
+        };
+        if let Err(e) = window.emit_to(window.label(), "send_many_http_requests_progress", progress)
+        {
+            warn!("Failed to emit send progress {e:?}");
+        }
+    };
+    send_concurrently(requests, concurrency, send, on_done).await
+}
+
+/// Run `send` for each item on its own task, at most `concurrency` at once, calling `on_done`
+/// with how many have finished as each one does. The outputs are returned in the order the
+/// items were given.
+async fn send_concurrently<T, O, F>(
+    items: Vec<T>,
+    concurrency: usize,
+    send: impl Fn(T) -> F,
+    mut on_done: impl FnMut(usize, &O),
+) -> Vec<O>
+where
+    T: Send + 'static,
+    O: Send + 'static,
+    F: Future<Output = O> + Send + 'static,
+{
+    let total = items.len();
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let (tx, mut rx) = mpsc::channel(total.max(1));
+    for (i, item) in items.into_iter().enumerate() {
+        let semaphore = Arc::clone(&semaphore);
+        let tx = tx.clone();
+        let output = send(item);
+        tokio::spawn(async move {
+            // Waiters are woken in order, so the items start in the order they were given
+            let _permit = semaphore.acquire().await;
+            let _ = tx.send((i, output.await)).await;
+        });
+    }
+    drop(tx);
+
+    let mut outputs = Vec::with_capacity(total);
+    while let Some((i, output)) = rx.recv().await {
+        on_done(outputs.len() + 1, &output);
+        outputs.push((i, output));
+    }
+
+    outputs.sort_by_key(|(i, _)| *i);
+    outputs.into_iter().map(|(_, o)| o).collect()
+}
+
+/// Send a request of a batch. Failing to send it is saved as an error on its response.
+async fn send_for_batch<R: Runtime>(
+    window: &WebviewWindow<R>,
+    request: &HttpRequest,
+    environment: Option<Environment>,
+    cookie_jar: Option<CookieJar>,
+) -> HttpResponse {
+    // Earlier sends of the batch may have saved cookies since the batch started
+    let cookie_jar = match cookie_jar {
+        Some(jar) => Some(get_cookie_jar(window, &jar.id).await.unwrap_or(jar)),
+        None => None,
+    };
+    let response = match create_default_http_response(window, &request.id).await {
+        Ok(r) => r,
+        Err(e) => {
+            return HttpResponse {
+                request_id: request.id.clone(),
+                workspace_id: request.workspace_id.clone(),
+                error: Some(e.to_string()),
+                ..Default::default()
+            }
+        }
+    };
+
+    let mut cancel_rx = register_cancel_channel(window, &response.id).await;
+    match send_http_request(window, request, &response, environment, cookie_jar, &mut cancel_rx)
+        .await
+    {
+        Ok(r) => r,
+        Err(e) => response_err(&response, e, window).await,
+    }
+}
+
+/// Make a gRPC call for a run. Its outcome is saved on a connection, but the individual
+/// messages aren't recorded as events.
+async fn run_grpc_request<R: Runtime>(
+    window: &WebviewWindow<R>,
+    request: &GrpcRequest,
+    environment: Option<&Environment>,
+    result: RunResult,
+) -> RunResult {
+    let start = Instant::now();
+    let conn = GrpcConnection {
+        workspace_id: request.workspace_id.clone(),
+        request_id: request.id.clone(),
+        status: -1,
+        state: GrpcConnectionState::Initialized,
+        url: request.url.clone(),
+        ..Default::default()
+    };
+    let conn = match upsert_grpc_connection(window, &conn).await {
+        Ok(c) => c,
+        Err(e) => {
+            return RunResult {
+                status: RunResultStatus::Failed,
+                error: Some(e.to_string()),
+                ..result
+            }
+        }
+    };
+
+    let (code, error) = match call_grpc(window, request, environment).await {
+        Ok(()) => (Code::Ok, None),
+        Err((code, message)) => (code, Some(message)),
+    };
+    let elapsed = start.elapsed().as_millis() as i32;
+
+    let conn = GrpcConnection {
+        elapsed,
+        error: error.clone(),
+        status: code as i32,
+        state: GrpcConnectionState::Closed,
+        ..conn
+    };
+    match upsert_grpc_connection(window, &conn).await {
+        Ok(conn) => {
+            let environment_id = environment.map(|e| e.id.clone());
+            record_grpc_history(window, request, &conn, environment_id).await;
+        }
+        Err(e) => warn!("Failed to save gRPC connection for run {e:?}"),
+    }
+
+    RunResult {
+        status: match code {
+            Code::Ok => RunResultStatus::Passed,
+            _ => RunResultStatus::Failed,
+        },
+        response_id: Some(conn.id),
+        status_code: Some(code as i32),
+        elapsed,
+        error,
+        ..result
+    }
+}
+
+/// Call the request's method and wait for it to finish. The request's message is the only one
+/// sent, so a client stream is closed right after it.
+async fn call_grpc<R: Runtime>(
+    window: &WebviewWindow<R>,
+    request: &GrpcRequest,
+    environment: Option<&Environment>,
+) -> Result<(), (Code, String)> {
+    let unknown = |e: String| (Code::Unknown, e);
+    let call = GrpcCall::prepare(window, request, environment).await.map_err(unknown)?;
+    let req = &call.request;
+    let cb = PluginTemplateCallback::new(
+        window.app_handle(),
+        &WindowContext::from_window(window),
+        RenderPurpose::Send,
+    );
+    let msg = if req.message.is_empty() { "{}" } else { req.message.as_str() };
+    let msg = render_template(msg, &call.workspace, environment, &cb).await;
+
+    let proto_files = proto_files(window, &req.id).await;
+    let import_dirs = proto_import_dirs(window, &req.id).await;
+    let connection = call.connect(window, proto_files, import_dirs).await.map_err(unknown)?;
+    let (service, method) = (call.service.as_str(), call.method.as_str());
+    let method_desc = connection.method(service, method).map_err(unknown)?;
+    let metadata = call.metadata.clone();
+
+    let outgoing = || -> Result<ReceiverStream<DynamicMessage>, (Code, String)> {
+        let message = deserialize_message(&msg, method_desc.clone())
+            .map_err(|e| (Code::InvalidArgument, e))?;
+        let (tx, rx) = mpsc::channel(1);
+        tx.try_send(message).map_err(|e| unknown(e.to_string()))?;
+        Ok(ReceiverStream::new(rx))
+    };
+
+    let stream = match (method_desc.is_client_streaming(), method_desc.is_server_streaming()) {
+        (false, false) => {
+            connection.unary(service, method, &msg, metadata).await.map_err(stream_error)?;
+            return Ok(());
+        }
+        (true, false) => {
+            connection
+                .client_streaming(service, method, outgoing()?, metadata)
+                .await
+                .map_err(stream_error)?;
+            return Ok(());
+        }
+        (false, true) => connection.server_streaming(service, method, &msg, metadata).await,
+        (true, true) => connection.streaming(service, method, outgoing()?, metadata).await,
+    };
+
+    let mut stream = stream.map_err(stream_error)?.into_inner();
+    loop {
+        match stream.message().await {
+            Ok(Some(_)) => continue,
+            Ok(None) => return Ok(()),
+            Err(status) => {
+                let status = connection.annotate_status(status);
+                return Err((status.code(), status.message().to_string()));
+            }
+        }
+    }
+}
+
+fn stream_error(e: StreamError) -> (Code, String) {
+    match e.status {
+        Some(s) => (s.code(), s.message().to_string()),
+        None => (Code::Unknown, e.message),
+    }
+}
+
+/// Proto files picked for the request, which the frontend saves as a global key value
+async fn proto_files<R: Runtime>(window: &WebviewWindow<R>, request_id: &str) -> Vec<PathBuf> {
+    paths_key_value(window, &format!("proto_files::{request_id}")).await
+}
+
+/// Import directories for the request's proto files, saved like the files themselves
+async fn proto_import_dirs<R: Runtime>(
+    window: &WebviewWindow<R>,
+    request_id: &str,
+) -> Vec<PathBuf> {
+    paths_key_value(window, &format!("proto_import_dirs::{request_id}")).await
+}
+
+async fn paths_key_value<R: Runtime>(window: &WebviewWindow<R>, key: &str) -> Vec<PathBuf> {
+    let paths: Vec<String> = match get_key_value_raw(window, "global", key).await {
+        None => Vec::new(),
+        Some(kv) => serde_json::from_str(&kv.value).unwrap_or_default(),
+    };
+    paths.into_iter().map(PathBuf::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runner::{run_order, send_concurrently};
+    use std::sync::Arc;
+    use yaak_models::models::{Folder, GrpcRequest, HttpRequest};
+
+    fn folder(id: &str, parent: Option<&str>, sort_priority: f32) -> Folder {
+        Folder {
+            id: id.to_string(),
+            folder_id: parent.map(|p| p.to_string()),
+            sort_priority,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn runs_in_sidebar_order() {
+        let http = |id: &str, parent: &str, sort_priority: f32| HttpRequest {
+            id: id.to_string(),
+            folder_id: Some(parent.to_string()),
+            sort_priority,
+            ..Default::default()
+        };
+        let folders = vec![
+            folder("fl_suite", None, 0.0),
+            folder("fl_nested", Some("fl_suite"), 2000.0),
+            folder("fl_other", None, 1000.0),
+        ];
+        let http_requests = vec![
+            http("rq_last", "fl_suite", 3000.0),
+            http("rq_first", "fl_suite", 0.0),
+            http("rq_nested", "fl_nested", 0.0),
+            http("rq_elsewhere", "fl_other", 0.0),
+        ];
+        let grpc_requests = vec![GrpcRequest {
+            id: "gr_second".to_string(),
+            folder_id: Some("fl_suite".to_string()),
+            sort_priority: 1000.0,
+            ..Default::default()
+        }];
+
+        let ids: Vec<String> = run_order("fl_suite", &folders, &http_requests, &grpc_requests)
+            .iter()
+            .map(|i| i.id().to_string())
+            .collect();
+        assert_eq!(ids, ["rq_first", "gr_second", "rq_nested", "rq_last"]);
+    }
+
+    #[tokio::test]
+    async fn sends_concurrently_in_order() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let most_in_flight = Arc::new(AtomicUsize::new(0));
+        let send = |i: usize| {
+            let in_flight = Arc::clone(&in_flight);
+            let most_in_flight = Arc::clone(&most_in_flight);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                most_in_flight.fetch_max(now, Ordering::SeqCst);
+                // Later items take less time, so they finish out of order
+                for _ in 0..(10 - i) {
+                    tokio::task::yield_now().await;
+                }
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                i * 10
+            }
+        };
+
+        let mut progress = Vec::new();
+        let outputs = send_concurrently((0..6).collect(), 2, send, |completed, output| {
+            progress.push((completed, *output))
+        })
+        .await;
+
+        assert_eq!(outputs, [0, 10, 20, 30, 40, 50]);
+        assert_eq!(most_in_flight.load(Ordering::SeqCst), 2);
+        let completed: Vec<usize> = progress.iter().map(|(c, _)| *c).collect();
+        assert_eq!(completed, [1, 2, 3, 4, 5, 6]);
+        let mut finished: Vec<usize> = progress.iter().map(|(_, o)| *o).collect();
+        assert_ne!(finished, outputs, "expected items to finish out of order");
+        finished.sort();
+        assert_eq!(finished, outputs);
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+
+use crate::render::make_vars_hashmap;
+use log::{info, warn};
+use tauri::{Emitter, Manager, Runtime, WebviewWindow};
+use yaak_models::models::{Environment, EnvironmentVariable, HttpRequest, HttpResponse, Workspace};
+use yaak_models::queries::{upsert_environment, upsert_workspace};
+use yaak_plugin_runtime::events::{Color, Icon, RunScriptRequest, ScriptPhase, ShowToastRequest};
+use yaak_plugin_runtime::manager::PluginManager;
+
+/// Largest response body, in bytes, handed to a post-response script
+const MAX_SCRIPT_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+/// Run the request's pre-request script, if it has one. The script's changes to the request are
+/// applied in place, and the variables it sets are saved to the environment (or the workspace,
+/// when there's no environment) so they're used when rendering.
+pub async fn run_pre_request_script<R: Runtime>(
+    window: &WebviewWindow<R>,
+    request: &mut HttpRequest,
+    workspace: &mut Workspace,
+    environment: &mut Option<Environment>,
+) -> Result<(), String> {
+    let script = match script_to_run(request, &request.pre_request_script)? {
+        None => return Ok(()),
+        Some(s) => s,
+    };
+
+    let req = RunScriptRequest {
+        phase: ScriptPhase::PreRequest,
+        script,
+        http_request: request.clone(),
+        http_response: None,
+        body: None,
+        variables: make_vars_hashmap(workspace, environment.as_ref()),
+    };
+    let plugin_manager = window.state::<PluginManager>();
+    let resp = plugin_manager.run_script(window, req).await.map_err(|e| e.to_string())?;
+    log_script_output(&resp.logs);
+
+    *request = scripted_request(request, resp.http_request);
+    apply_variables(window, workspace, environment, resp.variables).await
+}
+
+/// Run the request's post-response script, if it has one, saving the variables it sets. Errors
+/// are shown to the user rather than returned, since the response has already finished.
+pub async fn run_post_response_script<R: Runtime>(
+    window: &WebviewWindow<R>,
+    request: &HttpRequest,
+    response: &HttpResponse,
+    mut workspace: Workspace,
+    mut environment: Option<Environment>,
+) {
+    let script = match script_to_run(request, &request.post_response_script) {
+        Ok(None) => return,
+        Ok(Some(s)) => s,
+        Err(e) => return show_script_error(window, e),
+    };
+
+    let body = match &response.body_path {
+        Some(p) => match tokio::fs::read(p).await {
+            Ok(b) if b.len() <= MAX_SCRIPT_BODY_BYTES => Some(String::from_utf8_lossy(&b).into()),
+            Ok(b) => {
+                warn!("Not passing response body of {} bytes to script", b.len());
+                None
+            }
+            Err(e) => {
+                warn!("Failed to read response body for script {e:?}");
+                None
+            }
+        },
+        None => None,
+    };
+
+    let req = RunScriptRequest {
+        phase: ScriptPhase::PostResponse,
+        script,
+        http_request: request.clone(),
+        http_response: Some(response.clone()),
+        body,
+        variables: make_vars_hashmap(&workspace, environment.as_ref()),
+    };
+    let plugin_manager = window.state::<PluginManager>();
+    let result = match plugin_manager.run_script(window, req).await {
+        Ok(resp) => {
+            log_script_output(&resp.logs);
+            apply_variables(window, &mut workspace, &mut environment, resp.variables).await
+        }
+        Err(e) => Err(e.to_string()),
+    };
+
+    if let Err(e) = result {
+        show_script_error(window, e);
+    }
+}
+
+/// Write what a script logged to the app's log, alongside everything else
+fn log_script_output(logs: &[String]) {
+    for line in logs {
+        info!("[script] {line}");
+    }
+}
+
+fn show_script_error<R: Runtime>(window: &WebviewWindow<R>, error: String) {
+    warn!("Post-response script failed {error}");
+    let toast = ShowToastRequest {
+        message: format!("Post-response script failed: {error}"),
+        color: Some(Color::Danger),
+        icon: Some(Icon::AlertTriangle),
+    };
+    if let Err(e) = window.emit_to(window.label(), "show_toast", toast) {
+        warn!("Failed to emit show_toast {e:?}");
+    }
+}
+
+/// The script to run, if it isn't blank. Scripts that came in with an import may do anything
+/// Node can, so they're refused until the user trusts them.
+fn script_to_run(request: &HttpRequest, script: &str) -> Result<Option<String>, String> {
+    if script.trim().is_empty() {
+        return Ok(None);
+    }
+    if !request.scripts_trusted {
+        return Err("Scripts from imported requests must be trusted before they run".to_string());
+    }
+    Ok(Some(script.to_string()))
+}
+
+/// The request the script returned. It may only change what's sent, not which request or
+/// workspace it belongs to, or which scripts run.
+fn scripted_request(original: &HttpRequest, scripted: HttpRequest) -> HttpRequest {
+    HttpRequest {
+        model: original.model.clone(),
+        id: original.id.clone(),
+        workspace_id: original.workspace_id.clone(),
+        folder_id: original.folder_id.clone(),
+        created_at: original.created_at,
+        updated_at: original.updated_at,
+        pre_request_script: original.pre_request_script.clone(),
+        post_response_script: original.post_response_script.clone(),
+        scripts_trusted: original.scripts_trusted,
+        ..scripted
+    }
+}
+
+/// Set the variables on the environment, or on the workspace when there's no environment, and
+/// save whichever was changed
+async fn apply_variables<R: Runtime>(
+    window: &WebviewWindow<R>,
+    workspace: &mut Workspace,
+    environment: &mut Option<Environment>,
+    variables: HashMap<String, String>,
+) -> Result<(), String> {
+    if variables.is_empty() {
+        return Ok(());
+    }
+
+    match environment {
+        Some(e) => {
+            set_variables(&mut e.variables, variables);
+            *e = upsert_environment(window, e.clone()).await.map_err(|e| e.to_string())?;
+        }
+        None => {
+            set_variables(&mut workspace.variables, variables);
+            *workspace =
+                upsert_workspace(window, workspace.clone()).await.map_err(|e| e.to_string())?;
+        }
+    };
+
+    Ok(())
+}
+
+/// Update variables that already exist, enabling them if they were disabled, and append the rest
+/// in name order so saving is deterministic
+fn set_variables(existing: &mut Vec<EnvironmentVariable>, variables: HashMap<String, String>) {
+    let mut variables: Vec<(String, String)> = variables.into_iter().collect();
+    variables.sort();
+    for (name, value) in variables {
+        match existing.iter_mut().find(|v| v.name == name) {
+            Some(v) => {
+                v.value = value;
+                v.enabled = true;
+            }
+            None => existing.push(EnvironmentVariable {
+                enabled: true,
+                name,
+                value,
+                secret: false,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scripts::{script_to_run, scripted_request, set_variables};
+    use std::collections::HashMap;
+    use yaak_models::models::{EnvironmentVariable, HttpRequest};
+
+    fn var(name: &str, value: &str, enabled: bool) -> EnvironmentVariable {
+        EnvironmentVariable {
+            enabled,
+            name: name.to_string(),
+            value: value.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn set_variables_updates_and_appends() {
+        let mut existing = vec![var("token", "old", false), var("host", "example.com", true)];
+        let variables = HashMap::from([
+            ("token".to_string(), "new".to_string()),
+            ("user_id".to_string(), "42".to_string()),
+            ("b".to_string(), "2".to_string()),
+        ]);
+        set_variables(&mut existing, variables);
+
+        let got: Vec<(&str, &str, bool)> =
+            existing.iter().map(|v| (v.name.as_str(), v.value.as_str(), v.enabled)).collect();
+        assert_eq!(
+            got,
+            vec![
+                ("token", "new", true),
+                ("host", "example.com", true),
+                ("b", "2", true),
+                ("user_id", "42", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn untrusted_scripts_do_not_run() {
+        let request = HttpRequest {
+            pre_request_script: "yaak.variables.set('a', 1);".to_string(),
+            post_response_script: "  \n".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(script_to_run(&request, &request.post_response_script), Ok(None));
+        assert!(script_to_run(&request, &request.pre_request_script).is_err());
+
+        let request = HttpRequest {
+            scripts_trusted: true,
+            ..request
+        };
+        assert_eq!(
+            script_to_run(&request, &request.pre_request_script),
+            Ok(Some("yaak.variables.set('a', 1);".to_string()))
+        );
+    }
+
+    #[test]
+    fn scripts_cannot_change_what_they_belong_to() {
+        let original = HttpRequest {
+            id: "rq_1".to_string(),
+            workspace_id: "wk_1".to_string(),
+            pre_request_script: "yaak.request.url = 'https://yaak.app';".to_string(),
+            ..Default::default()
+        };
+        let scripted = HttpRequest {
+            id: "rq_2".to_string(),
+            workspace_id: "wk_2".to_string(),
+            url: "https://yaak.app".to_string(),
+            post_response_script: "steal()".to_string(),
+            scripts_trusted: true,
+            ..Default::default()
+        };
+        let request = scripted_request(&original, scripted);
+        assert_eq!(request.id, "rq_1");
+        assert_eq!(request.workspace_id, "wk_1");
+        assert_eq!(request.url, "https://yaak.app");
+        assert_eq!(request.pre_request_script, original.pre_request_script);
+        assert_eq!(request.post_response_script, "");
+        assert!(!request.scripts_trusted);
+    }
+}
@@ -0,0 +1,498 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use chrono::{NaiveDateTime, TimeDelta, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, Runtime, WebviewWindow};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use yaak_models::models::{HttpResponse, HttpResponseState};
+use yaak_models::queries::{
+    delete_http_responses, delete_http_responses_before, get_or_create_settings,
+    list_http_responses_for_workspace, list_response_file_paths, vacuum_database,
+};
+
+#[derive(Default, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageStats {
+    pub responses_bytes: u64,
+    pub database_bytes: u64,
+    pub logs_bytes: u64,
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CleanupStorageOptions {
+    pub older_than_days: u32,
+    pub responses: bool,
+    pub logs: bool,
+}
+
+#[derive(Default, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupStorageResult {
+    pub deleted_responses: usize,
+    pub deleted_files: usize,
+    pub bytes_reclaimed: u64,
+}
+
+#[derive(Default, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneResponsesResult {
+    pub deleted_responses: usize,
+    pub bytes_reclaimed: u64,
+}
+
+#[derive(Default, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VacuumResult {
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+/// Set while the database is being vacuumed, since a second one would only wait on the first
+static VACUUM_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Largest slice of a response body that can be read at once
+const MAX_CHUNK_BYTES: u64 = 10 * 1024 * 1024;
+
+#[derive(Default, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseBodyChunk {
+    pub offset: u64,
+    pub total_size: u64,
+    /// `utf8` when the slice is valid text, otherwise `base64`
+    pub encoding: String,
+    pub data: String,
+}
+
+/// Directory response bodies are written to. A custom location still gets its own `responses`
+/// folder inside it, so cleaning up old bodies never touches anything else there.
+pub fn responses_dir(data_dir: &Path, custom_dir: Option<&str>) -> PathBuf {
+    match custom_dir.map(|d| d.trim()) {
+        Some(d) if !d.is_empty() => Path::new(d).join("responses"),
+        _ => data_dir.join("responses"),
+    }
+}
+
+/// The responses directory for the current settings
+pub async fn current_responses_dir<R: Runtime>(w: &WebviewWindow<R>) -> Result<PathBuf, String> {
+    let data_dir = w.path().app_data_dir().map_err(|e| e.to_string())?;
+    let settings = get_or_create_settings(w).await;
+    Ok(responses_dir(&data_dir, settings.response_body_dir.as_deref()))
+}
+
+/// Copy a response's body next to the other responses, under the id of the example it's saved
+/// as, so pruning or deleting the response doesn't take the example's body with it
+pub async fn freeze_example_body<R: Runtime>(
+    w: &WebviewWindow<R>,
+    response: &HttpResponse,
+    example_id: &str,
+) -> Result<Option<String>, String> {
+    let body_path = match &response.body_path {
+        Some(p) if Path::new(p).exists() => p,
+        _ => return Ok(None),
+    };
+
+    let dir = current_responses_dir(w).await?.join("examples");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let dest = dir.join(example_id);
+    fs::copy(body_path, &dest).map_err(|e| e.to_string())?;
+    Ok(Some(dest.to_string_lossy().to_string()))
+}
+
+/// Check that response bodies can be written to a directory, creating it if needed, before it's
+/// accepted as the storage location
+pub fn ensure_writable_dir(dir: &Path) -> Result<(), String> {
+    let probe = dir.join(".yaak-write-test");
+    fs::create_dir_all(dir)
+        .and_then(|_| fs::write(&probe, b""))
+        .and_then(|_| fs::remove_file(&probe))
+        .map_err(|e| format!("Can't store responses in {}: {e}", dir.display()))
+}
+
+pub async fn get_storage_stats<R: Runtime>(w: &WebviewWindow<R>) -> Result<StorageStats, String> {
+    let data_dir = w.path().app_data_dir().map_err(|e| e.to_string())?;
+    let log_dir = w.path().app_log_dir().map_err(|e| e.to_string())?;
+    let responses_dir = current_responses_dir(w).await?;
+
+    // Walking the responses directory can take a while, so keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || StorageStats {
+        responses_bytes: dir_size(&responses_dir),
+        database_bytes: database_size(&data_dir),
+        logs_bytes: dir_size(&log_dir),
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Compact the database to reclaim space from deleted rows, returning its size before and after
+pub async fn vacuum<R: Runtime>(w: &WebviewWindow<R>) -> Result<VacuumResult, String> {
+    let data_dir = w.path().app_data_dir().map_err(|e| e.to_string())?;
+    if VACUUM_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("The database is already being compacted".to_string());
+    }
+    let _running = VacuumRunning;
+
+    let bytes_before = database_size(&data_dir);
+    vacuum_database(w).await.map_err(|e| e.to_string())?;
+
+    Ok(VacuumResult {
+        bytes_before,
+        bytes_after: database_size(&data_dir),
+    })
+}
+
+/// Clears [`VACUUM_RUNNING`] when dropped, so it's cleared however the vacuum ends, including
+/// when the command's future is dropped part way
+struct VacuumRunning;
+
+impl Drop for VacuumRunning {
+    fn drop(&mut self) {
+        VACUUM_RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Size of the database, including the -wal and -shm files that sit next to it
+fn database_size(data_dir: &Path) -> u64 {
+    fs::read_dir(data_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_name().to_string_lossy().starts_with("db.sqlite"))
+                .filter_map(|e| e.metadata().ok())
+                .map(|m| m.len())
+                .sum()
+        })
+        .unwrap_or_default()
+}
+
+pub async fn cleanup_storage<R: Runtime>(
+    w: &WebviewWindow<R>,
+    options: CleanupStorageOptions,
+) -> Result<CleanupStorageResult, String> {
+    if options.older_than_days == 0 {
+        return Err("Cleanup threshold must be at least one day".to_string());
+    }
+
+    let log_dir = w.path().app_log_dir().map_err(|e| e.to_string())?;
+    let max_age = Duration::from_secs(options.older_than_days as u64 * 24 * 60 * 60);
+    let mut result = CleanupStorageResult::default();
+
+    if options.responses {
+        let responses_dir = current_responses_dir(w).await?;
+        let size_before = dir_size(&responses_dir);
+
+        let cutoff = Utc::now().naive_utc() - TimeDelta::days(options.older_than_days as i64);
+        result.deleted_responses =
+            delete_http_responses_before(w, cutoff).await.map_err(|e| e.to_string())?;
+
+        // Sweep up body files that no longer belong to a response. A recent response can still
+        // point to an old file, so anything that's referenced is kept regardless of its age.
+        let cutoff = SystemTime::now() - max_age;
+        let referenced: HashSet<PathBuf> = list_response_file_paths(w)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+        let (count, responses_dir) = tauri::async_runtime::spawn_blocking(move || {
+            (remove_files_older_than(&responses_dir, cutoff, &referenced).0, responses_dir)
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        result.deleted_files += count;
+        result.bytes_reclaimed += size_before.saturating_sub(dir_size(&responses_dir));
+    }
+
+    if options.logs {
+        // The active log file is always recent, so it won't be touched here
+        let cutoff = SystemTime::now() - max_age;
+        let (count, bytes) = tauri::async_runtime::spawn_blocking(move || {
+            remove_files_older_than(&log_dir, cutoff, &HashSet::new())
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        result.deleted_files += count;
+        result.bytes_reclaimed += bytes;
+    }
+
+    Ok(result)
+}
+
+pub async fn prune_responses<R: Runtime>(
+    w: &WebviewWindow<R>,
+    workspace_id: &str,
+    older_than_days: u32,
+    keep_per_request: usize,
+) -> Result<PruneResponsesResult, String> {
+    let cutoff = Utc::now().naive_utc() - TimeDelta::days(older_than_days as i64);
+    let responses = list_http_responses_for_workspace(w, workspace_id, None)
+        .await
+        .map_err(|e| e.to_string())?;
+    let to_delete = responses_to_prune(responses, cutoff, keep_per_request);
+    let bytes_reclaimed = delete_http_responses(w, &to_delete).await.map_err(|e| e.to_string())?;
+
+    Ok(PruneResponsesResult {
+        deleted_responses: to_delete.len(),
+        bytes_reclaimed,
+    })
+}
+
+/// Read `length` bytes of a body file starting at `offset`. Reading at the very end of the
+/// file returns an empty chunk, while starting past it is an error.
+pub async fn read_body_chunk(
+    path: &Path,
+    offset: u64,
+    length: u64,
+) -> Result<ResponseBodyChunk, String> {
+    if length > MAX_CHUNK_BYTES {
+        return Err(format!("Chunk length {length} exceeds the limit of {MAX_CHUNK_BYTES} bytes"));
+    }
+
+    let mut f = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| format!("Unable to read file {}: {e}", path.display()))?;
+    let total_size = f.metadata().await.map_err(|e| e.to_string())?.len();
+    if offset > total_size {
+        return Err(format!("Offset {offset} is past the end of the {total_size} byte body"));
+    }
+
+    f.seek(SeekFrom::Start(offset)).await.map_err(|e| e.to_string())?;
+    let mut buf = Vec::new();
+    f.take(length).read_to_end(&mut buf).await.map_err(|e| e.to_string())?;
+
+    let (encoding, data) = match String::from_utf8(buf) {
+        Ok(text) => ("utf8", text),
+        Err(e) => ("base64", BASE64_STANDARD.encode(e.into_bytes())),
+    };
+    Ok(ResponseBodyChunk {
+        offset,
+        total_size,
+        encoding: encoding.to_string(),
+        data,
+    })
+}
+
+/// Pick the closed responses created before `cutoff`, always keeping the newest
+/// `keep_per_request` responses of each request. Expects responses sorted newest first.
+fn responses_to_prune(
+    responses: Vec<HttpResponse>,
+    cutoff: NaiveDateTime,
+    keep_per_request: usize,
+) -> Vec<HttpResponse> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    responses
+        .into_iter()
+        .filter(|r| {
+            let count = counts.entry(r.request_id.clone()).or_default();
+            *count += 1;
+            *count > keep_per_request
+                && r.created_at < cutoff
+                && matches!(r.state, HttpResponseState::Closed)
+        })
+        .collect()
+}
+
+/// Total size of all files under `dir`, or 0 if it doesn't exist
+pub fn dir_size(dir: &Path) -> u64 {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+
+    entries
+        .flatten()
+        .map(|e| match e.metadata() {
+            Ok(m) if m.is_dir() => dir_size(&e.path()),
+            Ok(m) => m.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Delete files directly inside `dir` that were last modified before `cutoff`, except those in
+/// `keep`, returning how many were deleted and their total size
+pub fn remove_files_older_than(
+    dir: &Path,
+    cutoff: SystemTime,
+    keep: &HashSet<PathBuf>,
+) -> (usize, u64) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return (0, 0),
+    };
+
+    let mut count = 0;
+    let mut bytes = 0;
+    for entry in entries.flatten() {
+        if keep.contains(&entry.path()) {
+            continue;
+        }
+        let metadata = match entry.metadata() {
+            Ok(m) if m.is_file() => m,
+            _ => continue,
+        };
+        match metadata.modified() {
+            Ok(modified) if modified < cutoff => {}
+            _ => continue,
+        }
+        if let Err(e) = fs::remove_file(entry.path()) {
+            warn!("Failed to remove {:?} {e:?}", entry.path());
+            continue;
+        }
+        count += 1;
+        bytes += metadata.len();
+    }
+
+    (count, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::{
+        dir_size, ensure_writable_dir, read_body_chunk, remove_files_older_than, responses_dir,
+        responses_to_prune,
+    };
+    use chrono::{NaiveDateTime, TimeDelta};
+    use std::collections::HashSet;
+    use std::fs;
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+    use yaak_models::models::{HttpResponse, HttpResponseState};
+
+    #[test]
+    fn custom_responses_dir() {
+        let data_dir = Path::new("/data");
+        assert_eq!(responses_dir(data_dir, None), Path::new("/data/responses"));
+        assert_eq!(responses_dir(data_dir, Some("  ")), Path::new("/data/responses"));
+        assert_eq!(responses_dir(data_dir, Some("/mnt/big")), Path::new("/mnt/big/responses"));
+    }
+
+    #[test]
+    fn writable_responses_dir() {
+        let dir = std::env::temp_dir().join("yaak-writable-dir").join("responses");
+        ensure_writable_dir(&dir).unwrap();
+        assert!(dir.is_dir());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+
+        let file = std::env::temp_dir().join("yaak-not-a-dir");
+        fs::write(&file, "").unwrap();
+        assert!(ensure_writable_dir(&file.join("responses")).is_err());
+        fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn dir_size_missing_dir() {
+        assert_eq!(dir_size(&std::env::temp_dir().join("yaak-missing-storage-dir")), 0);
+    }
+
+    #[test]
+    fn dir_size_nested() {
+        let dir = std::env::temp_dir().join("yaak-test-dir-size");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("a"), "hello").unwrap();
+        fs::write(dir.join("nested").join("b"), "world!").unwrap();
+
+        assert_eq!(dir_size(&dir), 11);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn remove_only_old_files() {
+        let dir = std::env::temp_dir().join("yaak-test-remove-old-files");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a"), "hello").unwrap();
+
+        fs::write(dir.join("b"), "world!").unwrap();
+
+        let past = SystemTime::now() - Duration::from_secs(60);
+        assert_eq!(remove_files_older_than(&dir, past, &HashSet::new()), (0, 0));
+
+        // Files that are still referenced are kept, however old they are
+        let future = SystemTime::now() + Duration::from_secs(60);
+        let keep = HashSet::from([dir.join("b")]);
+        assert_eq!(remove_files_older_than(&dir, future, &keep), (1, 5));
+        assert!(!dir.join("a").exists());
+        assert!(dir.join("b").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn response(
+        id: &str,
+        request_id: &str,
+        days_ago: i64,
+        state: HttpResponseState,
+    ) -> HttpResponse {
+        HttpResponse {
+            id: id.to_string(),
+            request_id: request_id.to_string(),
+            created_at: NaiveDateTime::default() - TimeDelta::days(days_ago),
+            state,
+            ..Default::default()
+        }
+    }
+
+    fn ids(responses: Vec<HttpResponse>) -> Vec<String> {
+        responses.into_iter().map(|r| r.id).collect()
+    }
+
+    #[test]
+    fn prune_keeps_newest_per_request() {
+        let responses = vec![
+            response("a1", "a", 1, HttpResponseState::Closed),
+            response("b1", "b", 2, HttpResponseState::Closed),
+            response("a2", "a", 3, HttpResponseState::Closed),
+            response("a3", "a", 4, HttpResponseState::Closed),
+        ];
+        assert_eq!(
+            ids(responses_to_prune(responses.clone(), NaiveDateTime::default(), 1)),
+            vec!["a2", "a3"]
+        );
+        assert_eq!(
+            ids(responses_to_prune(responses, NaiveDateTime::default(), 0)),
+            vec!["a1", "b1", "a2", "a3"]
+        );
+    }
+
+    #[test]
+    fn prune_only_old_closed_responses() {
+        let responses = vec![
+            response("new", "a", 1, HttpResponseState::Closed),
+            response("active", "a", 10, HttpResponseState::Connected),
+            response("old", "a", 10, HttpResponseState::Closed),
+        ];
+        let cutoff = NaiveDateTime::default() - TimeDelta::days(5);
+        assert_eq!(ids(responses_to_prune(responses, cutoff, 0)), vec!["old"]);
+    }
+
+    #[tokio::test]
+    async fn read_chunks() {
+        let dir = std::env::temp_dir().join("yaak-test-read-chunks");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("text"), "hello world").unwrap();
+        fs::write(dir.join("binary"), [0xff, 0x00, 0xfe]).unwrap();
+
+        let chunk = read_body_chunk(&dir.join("text"), 6, 100).await.unwrap();
+        assert_eq!((chunk.encoding.as_str(), chunk.data.as_str()), ("utf8", "world"));
+        assert_eq!(chunk.total_size, 11);
+
+        let chunk = read_body_chunk(&dir.join("binary"), 0, 2).await.unwrap();
+        assert_eq!((chunk.encoding.as_str(), chunk.data.as_str()), ("base64", "/wA="));
+
+        assert_eq!(read_body_chunk(&dir.join("text"), 11, 10).await.unwrap().data, "");
+        assert!(read_body_chunk(&dir.join("text"), 12, 10).await.is_err());
+        assert!(read_body_chunk(&dir.join("text"), 0, u64::MAX).await.is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
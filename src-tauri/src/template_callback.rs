@@ -1,4 +1,6 @@
+use serde::Serialize;
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 use tauri::{AppHandle, Manager, Runtime};
 use yaak_plugin_runtime::events::{RenderPurpose, TemplateFunctionArg, WindowContext};
 use yaak_plugin_runtime::manager::PluginManager;
@@ -26,8 +28,33 @@ impl PluginTemplateCallback {
     }
 }
 
-impl TemplateCallback for PluginTemplateCallback {
-    async fn run(&self, fn_name: &str, args: HashMap<String, String>) -> Result<String, String> {
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TemplateFunctionError {
+    /// No plugin provides a function with this name
+    UnknownFunction { name: String },
+    /// The function, or the plugins providing it, returned an error
+    Failed { message: String },
+}
+
+impl Display for TemplateFunctionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateFunctionError::UnknownFunction { name } => {
+                write!(f, "Unknown template function \"{name}\"")
+            }
+            TemplateFunctionError::Failed { message } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl PluginTemplateCallback {
+    /// Call a template function, filling in defaults for any args that weren't given
+    pub async fn call(
+        &self,
+        fn_name: &str,
+        args: HashMap<String, String>,
+    ) -> Result<String, TemplateFunctionError> {
         let window_context = self.window_context.to_owned();
         // The beta named the function `Response` but was changed in stable.
         // Keep this here for a while because there's no easy way to migrate
@@ -41,11 +68,15 @@ impl TemplateCallback for PluginTemplateCallback {
             .plugin_manager
             .get_template_functions_with_context(window_context.to_owned())
             .await
-            .map_err(|e| e.to_string())?
+            .map_err(|e| TemplateFunctionError::Failed {
+                message: e.to_string(),
+            })?
             .iter()
             .flat_map(|f| f.functions.clone())
             .find(|f| f.name == fn_name)
-            .ok_or("")?;
+            .ok_or(TemplateFunctionError::UnknownFunction {
+                name: fn_name.to_string(),
+            })?;
 
         let mut args_with_defaults = args.clone();
 
@@ -72,7 +103,15 @@ impl TemplateCallback for PluginTemplateCallback {
                 self.render_purpose.to_owned(),
             )
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| TemplateFunctionError::Failed {
+                message: e.to_string(),
+            })?;
         Ok(resp.unwrap_or_default())
     }
 }
+
+impl TemplateCallback for PluginTemplateCallback {
+    async fn run(&self, fn_name: &str, args: HashMap<String, String>) -> Result<String, String> {
+        self.call(fn_name, args).await.map_err(|e| e.to_string())
+    }
+}
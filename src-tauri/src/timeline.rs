@@ -0,0 +1,303 @@
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use http::HeaderMap;
+use log::warn;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use tauri::{Runtime, WebviewWindow};
+use yaak_models::models::{
+    HttpResponse, HttpResponseHeader, HttpTimelineEvent, HttpTimelineEventType,
+};
+use yaak_models::queries::upsert_http_timeline_event;
+
+/// A host name lookup made while sending a request
+#[derive(Debug, Clone)]
+pub struct DnsLookup {
+    pub host: String,
+    pub started: Instant,
+    pub duration: Duration,
+    pub result: Result<Vec<SocketAddr>, String>,
+}
+
+/// Resolves host names with the system resolver, like the client does by default, while
+/// recording how long each lookup took
+#[derive(Debug, Clone, Default)]
+pub struct TimingResolver {
+    lookups: Arc<Mutex<Vec<DnsLookup>>>,
+}
+
+impl TimingResolver {
+    /// The lookups made so far, oldest first
+    pub fn lookups(&self) -> Vec<DnsLookup> {
+        self.lookups.lock().unwrap().clone()
+    }
+}
+
+impl Resolve for TimingResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let lookups = Arc::clone(&self.lookups);
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let started = Instant::now();
+            // The client connects on the URL's port, whatever port is resolved here
+            let result = tokio::net::lookup_host((host.as_str(), 0))
+                .await
+                .map(|addrs| addrs.collect::<Vec<_>>());
+            lookups.lock().unwrap().push(DnsLookup {
+                host,
+                started,
+                duration: started.elapsed(),
+                result: result.as_ref().map(Clone::clone).map_err(|e| e.to_string()),
+            });
+            let addrs: Addrs = Box::new(result?.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+/// The request line and headers, as they're handed to the client. The client adds a few of its
+/// own, like `Host`, `Cookie`, and `Accept-Encoding`, which aren't included.
+pub fn request_headers_event(request: &reqwest::Request) -> HttpTimelineEvent {
+    HttpTimelineEvent {
+        event_type: HttpTimelineEventType::RequestHeaders,
+        content: format!("{} {}", request.method(), request.url()),
+        headers: header_list(request.headers()),
+        elapsed: 0,
+        ..Default::default()
+    }
+}
+
+/// The timeline up to the response headers arriving, or up to the request failing when there's
+/// no response. The client doesn't report when it connected or finished the TLS handshake, so
+/// everything between resolving the host and the first byte of the response is one phase.
+pub fn headers_timeline(
+    request: HttpTimelineEvent,
+    lookups: &[DnsLookup],
+    start: Instant,
+    response: Option<&HttpResponse>,
+) -> Vec<HttpTimelineEvent> {
+    let mut events = vec![request];
+    events.extend(lookups.iter().map(|l| dns_lookup_event(l, start)));
+
+    let response = match response {
+        Some(r) => r,
+        None => return events,
+    };
+
+    let resolved = lookups
+        .iter()
+        .map(|l| millis(l.started.saturating_duration_since(start) + l.duration))
+        .max()
+        .unwrap_or_default()
+        .min(response.elapsed_headers);
+    events.push(connection_event(response, resolved));
+
+    let status_line = [
+        response.version.clone().unwrap_or_default(),
+        response.status.to_string(),
+        response.status_reason.clone().unwrap_or_default(),
+    ];
+    events.push(HttpTimelineEvent {
+        event_type: HttpTimelineEventType::ResponseHeaders,
+        content: status_line
+            .iter()
+            .filter(|s| !s.is_empty())
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" "),
+        headers: response.headers.clone(),
+        elapsed: response.elapsed_headers,
+        ..Default::default()
+    });
+    events
+}
+
+/// Downloading the body, from the response headers arriving until the last byte did
+pub fn download_event(bytes: u64, elapsed_headers: i32, elapsed: i32) -> HttpTimelineEvent {
+    HttpTimelineEvent {
+        event_type: HttpTimelineEventType::Download,
+        content: format!("Downloaded {bytes} bytes"),
+        elapsed: elapsed_headers,
+        duration: Some(elapsed.saturating_sub(elapsed_headers).max(0)),
+        ..Default::default()
+    }
+}
+
+/// Store events on a response's timeline, unless the response is ephemeral
+pub async fn save_timeline_events<R: Runtime>(
+    window: &WebviewWindow<R>,
+    response: &HttpResponse,
+    events: Vec<HttpTimelineEvent>,
+) {
+    if response.id.is_empty() {
+        return;
+    }
+    for event in events {
+        let event = HttpTimelineEvent {
+            workspace_id: response.workspace_id.clone(),
+            response_id: response.id.clone(),
+            ..event
+        };
+        if let Err(e) = upsert_http_timeline_event(window, &event).await {
+            warn!("Failed to save timeline event {e:?}");
+        }
+    }
+}
+
+fn dns_lookup_event(lookup: &DnsLookup, start: Instant) -> HttpTimelineEvent {
+    let content = match &lookup.result {
+        Ok(addrs) => {
+            let mut ips: Vec<String> = Vec::new();
+            for ip in addrs.iter().map(|a| a.ip().to_string()) {
+                if !ips.contains(&ip) {
+                    ips.push(ip);
+                }
+            }
+            format!("Resolved {} to {}", lookup.host, ips.join(", "))
+        }
+        Err(e) => format!("Failed to resolve {}: {e}", lookup.host),
+    };
+    HttpTimelineEvent {
+        event_type: HttpTimelineEventType::DnsLookup,
+        content,
+        elapsed: millis(lookup.started.saturating_duration_since(start)),
+        duration: Some(millis(lookup.duration)),
+        ..Default::default()
+    }
+}
+
+/// Connecting, the TLS handshake, sending the request, and waiting for the server to answer.
+/// reqwest has no hook between the TCP connect and the TLS handshake, so the event notes that
+/// they aren't timed on their own.
+fn connection_event(response: &HttpResponse, elapsed: i32) -> HttpTimelineEvent {
+    let mut content = match &response.remote_addr {
+        Some(addr) => format!("Connected to {addr}"),
+        None => "Connected".to_string(),
+    };
+    if let Some(version) = &response.version {
+        content.push_str(&format!(" using {version}"));
+    }
+    if let Some(cert) = &response.tls_certificate {
+        content
+            .push_str(&format!("\nTLS certificate for {} issued by {}", cert.subject, cert.issuer));
+    }
+    content.push_str(
+        "\nIncludes connecting, the TLS handshake, and waiting for the server, which aren't timed \
+         separately",
+    );
+    HttpTimelineEvent {
+        event_type: HttpTimelineEventType::Connection,
+        content,
+        elapsed,
+        duration: Some(response.elapsed_headers - elapsed),
+        ..Default::default()
+    }
+}
+
+fn header_list(headers: &HeaderMap) -> Vec<HttpResponseHeader> {
+    headers
+        .iter()
+        .map(|(name, value)| HttpResponseHeader {
+            name: name.to_string(),
+            value: value.to_str().unwrap_or_default().to_string(),
+        })
+        .collect()
+}
+
+fn millis(duration: Duration) -> i32 {
+    duration.as_millis().min(i32::MAX as u128) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::timeline::{
+        download_event, headers_timeline, request_headers_event, DnsLookup, TimingResolver,
+    };
+    use reqwest::dns::{Name, Resolve};
+    use std::str::FromStr;
+    use std::time::{Duration, Instant};
+    use yaak_models::models::{HttpResponse, HttpResponseHeader, HttpTimelineEventType};
+
+    #[tokio::test]
+    async fn resolver_records_lookups() {
+        let resolver = TimingResolver::default();
+        let addrs: Vec<_> =
+            resolver.resolve(Name::from_str("localhost").unwrap()).await.unwrap().collect();
+        assert!(addrs.iter().all(|a| a.ip().is_loopback()), "{addrs:?}");
+
+        let lookups = resolver.lookups();
+        assert_eq!(lookups.len(), 1);
+        assert_eq!(lookups[0].host, "localhost");
+        assert_eq!(lookups[0].result.as_ref().unwrap(), &addrs);
+    }
+
+    #[test]
+    fn timeline_until_headers() {
+        let request = reqwest::Client::new()
+            .get("https://yaak.test/users")
+            .header("Accept", "*/*")
+            .build()
+            .unwrap();
+        let start = Instant::now();
+        let lookups = [DnsLookup {
+            host: "yaak.test".to_string(),
+            started: start + Duration::from_millis(2),
+            duration: Duration::from_millis(5),
+            result: Ok(vec![
+                "127.0.0.1:0".parse().unwrap(),
+                "127.0.0.1:0".parse().unwrap(),
+                "[::1]:0".parse().unwrap(),
+            ]),
+        }];
+        let response = HttpResponse {
+            elapsed_headers: 30,
+            status: 404,
+            status_reason: Some("Not Found".to_string()),
+            version: Some("HTTP/1.1".to_string()),
+            remote_addr: Some("127.0.0.1:443".to_string()),
+            headers: vec![HttpResponseHeader {
+                name: "content-length".to_string(),
+                value: "0".to_string(),
+            }],
+            ..Default::default()
+        };
+
+        let events = headers_timeline(request_headers_event(&request), &lookups, start, None);
+        assert_eq!(events.len(), 2);
+
+        let events =
+            headers_timeline(request_headers_event(&request), &lookups, start, Some(&response));
+        let summary: Vec<_> = events
+            .iter()
+            .map(|e| (e.event_type.clone(), e.content.as_str(), e.elapsed, e.duration))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (HttpTimelineEventType::RequestHeaders, "GET https://yaak.test/users", 0, None),
+                (
+                    HttpTimelineEventType::DnsLookup,
+                    "Resolved yaak.test to 127.0.0.1, ::1",
+                    2,
+                    Some(5)
+                ),
+                (
+                    HttpTimelineEventType::Connection,
+                    "Connected to 127.0.0.1:443 using HTTP/1.1",
+                    7,
+                    Some(23)
+                ),
+                (HttpTimelineEventType::ResponseHeaders, "HTTP/1.1 404 Not Found", 30, None),
+            ]
+        );
+        assert_eq!(events[0].headers[0].value, "*/*");
+        assert_eq!(events[3].headers[0].name, "content-length");
+
+        let download = download_event(1024, 30, 45);
+        assert_eq!(
+            (download.content.as_str(), download.elapsed, download.duration),
+            ("Downloaded 1024 bytes", 30, Some(15))
+        );
+    }
+}
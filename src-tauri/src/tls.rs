@@ -0,0 +1,70 @@
+use chrono::{DateTime, NaiveDateTime};
+use x509_parser::prelude::{FromDer, GeneralName, X509Certificate};
+use yaak_models::models::HttpResponseTlsCertificate;
+
+/// Summarize a DER-encoded peer certificate for display, or None if it can't be parsed
+pub fn summarize_certificate(der: &[u8]) -> Option<HttpResponseTlsCertificate> {
+    let (_, cert) = X509Certificate::from_der(der).ok()?;
+
+    let subject_alt_names = match cert.subject_alternative_name() {
+        Ok(Some(ext)) => ext
+            .value
+            .general_names
+            .iter()
+            .filter_map(|n| match n {
+                GeneralName::DNSName(name) => Some(name.to_string()),
+                GeneralName::IPAddress(ip) => ip_to_string(ip),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    Some(HttpResponseTlsCertificate {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        serial_number: cert.raw_serial_as_string(),
+        not_before: timestamp_to_datetime(cert.validity().not_before.timestamp()),
+        not_after: timestamp_to_datetime(cert.validity().not_after.timestamp()),
+        subject_alt_names,
+    })
+}
+
+fn timestamp_to_datetime(ts: i64) -> NaiveDateTime {
+    DateTime::from_timestamp(ts, 0).unwrap_or_default().naive_utc()
+}
+
+fn ip_to_string(ip: &[u8]) -> Option<String> {
+    match ip.len() {
+        4 => Some(std::net::Ipv4Addr::from(<[u8; 4]>::try_from(ip).ok()?).to_string()),
+        16 => Some(std::net::Ipv6Addr::from(<[u8; 16]>::try_from(ip).ok()?).to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tls::summarize_certificate;
+    use base64::prelude::BASE64_STANDARD;
+    use base64::Engine;
+
+    // Self-signed certificate for yaak.test with DNS and IP alternative names
+    const CERT: &str = "MIIBsjCCAVigAwIBAgICEjQwCgYIKoZIzj0EAwIwIzESMBAGA1UEAwwJeWFhay50ZXN0MQ0wCwYDVQQKDARZYWFrMB4XDTI2MTAxNjE1MDExN1oXDTI3MTAxNjE1MDExN1owIzESMBAGA1UEAwwJeWFhay50ZXN0MQ0wCwYDVQQKDARZYWFrMFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAExRN2tIFsHfx2phkzcI9lYlWLzHcvA+NIxA40tbPDp1C2O2K5VSApclbfj9ODtwWZIKr1B3/Cj6F7XSMG66ml46N8MHowHQYDVR0OBBYEFKEukbehJdsH0VPY/e2j/OJekzDRMB8GA1UdIwQYMBaAFKEukbehJdsH0VPY/e2j/OJekzDRMA8GA1UdEwEB/wQFMAMBAf8wJwYDVR0RBCAwHoIJeWFhay50ZXN0ggsqLnlhYWsudGVzdIcEfwAAATAKBggqhkjOPQQDAgNIADBFAiEA2n4ieDQxInre51jwqLx/VUPAXIlxrPSMaF16VdmHX8gCIDwFZdRug5y+jTt6qW4MaKtK0ZPGBZzL8tCnY0FjnUsD";
+
+    #[test]
+    fn summarize_self_signed() {
+        let der = BASE64_STANDARD.decode(CERT).unwrap();
+        let cert = summarize_certificate(&der).unwrap();
+        assert_eq!(cert.subject, "CN=yaak.test, O=Yaak");
+        assert_eq!(cert.issuer, "CN=yaak.test, O=Yaak");
+        assert_eq!(cert.serial_number, "12:34");
+        assert_eq!(cert.not_before.to_string(), "2026-10-16 15:01:17");
+        assert_eq!(cert.not_after.to_string(), "2027-10-16 15:01:17");
+        assert_eq!(cert.subject_alt_names, vec!["yaak.test", "*.yaak.test", "127.0.0.1"]);
+    }
+
+    #[test]
+    fn summarize_invalid() {
+        assert!(summarize_certificate(b"not a certificate").is_none());
+    }
+}
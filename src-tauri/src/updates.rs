@@ -1,11 +1,18 @@
 use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::PathBuf;
 use std::time::SystemTime;
 
-use log::info;
-use tauri::{AppHandle, Manager};
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use log::{info, warn};
+use minisign_verify::{PublicKey, Signature};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
 use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
 use tauri_plugin_updater::UpdaterExt;
 use tokio::task::block_in_place;
+use yaak_models::queries::{get_key_value_raw, set_key_value_raw};
 use yaak_plugin_runtime::manager::PluginManager;
 
 use crate::is_dev;
@@ -14,6 +21,10 @@ const MAX_UPDATE_CHECK_HOURS_STABLE: u64 = 12;
 const MAX_UPDATE_CHECK_HOURS_BETA: u64 = 3;
 const MAX_UPDATE_CHECK_HOURS_ALPHA: u64 = 1;
 
+const KV_NAMESPACE: &str = "updater";
+const KV_ROLLBACK_KEY: &str = "last_known_good";
+const KV_CHANGELOG_NAMESPACE: &str = "changelog";
+
 // Create updater struct
 pub struct YaakUpdater {
     last_update_check: SystemTime,
@@ -36,6 +47,25 @@ impl Display for UpdateMode {
     }
 }
 
+/// The version that was running before the most recent update, along with the cached
+/// installer artifact that can be used to reinstall it.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RollbackArtifact {
+    pub version: String,
+    pub path: String,
+}
+
+/// Release notes (markdown) for a single version. `available` is false when the notes
+/// couldn't be fetched, like when offline.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Changelog {
+    pub version: String,
+    pub available: bool,
+    pub notes: String,
+}
+
 impl UpdateMode {
     pub fn new(mode: &str) -> UpdateMode {
         match mode {
@@ -102,7 +132,21 @@ impl YaakUpdater {
                             return;
                         }
                         tauri::async_runtime::spawn(async move {
-                            match update.download_and_install(|_, _| {}, || {}).await {
+                            let result = match update.download(|_, _| {}, || {}).await {
+                                Ok(bytes) => {
+                                    // Cache the artifact and remember the current version before
+                                    // installing, since install exits the app on Windows.
+                                    if let Err(e) =
+                                        cache_update_artifact(&h, &update.version, &bytes)
+                                    {
+                                        warn!("Failed to cache update artifact {e:?}");
+                                    }
+                                    record_last_known_good(&h).await;
+                                    update.install(bytes)
+                                }
+                                Err(e) => Err(e),
+                            };
+                            match result {
                                 Ok(_) => {
                                     if h.dialog()
                                         .message("Would you like to restart the app?")
@@ -152,4 +196,172 @@ impl YaakUpdater {
 
         self.force_check(app_handle, mode).await
     }
+
+    pub async fn rollback(
+        &mut self,
+        app_handle: &AppHandle,
+        mode: UpdateMode,
+    ) -> Result<String, String> {
+        let artifact = get_last_known_good(app_handle)
+            .await
+            .ok_or("No previous version is available to roll back to".to_string())?;
+        let artifact_path = PathBuf::from(&artifact.path);
+        if artifact.path.is_empty() || !artifact_path.exists() {
+            return Err(format!(
+                "No rollback artifact is available for version {}",
+                artifact.version
+            ));
+        }
+
+        info!("Rolling back to version={} mode={}", artifact.version, mode);
+
+        // Ask the update server for the previous release so we get its signature, and allow
+        // it to be installed even though it's older than the running version.
+        let target_version = artifact.version.clone();
+        let update = app_handle
+            .updater_builder()
+            .header("X-Update-Mode", mode.to_string())
+            .map_err(|e| e.to_string())?
+            .header("X-Update-Version", artifact.version.clone())
+            .map_err(|e| e.to_string())?
+            .version_comparator(move |_, remote| remote.version.to_string() == target_version)
+            .build()
+            .map_err(|e| e.to_string())?
+            .check()
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or(format!("Version {} is no longer available", artifact.version))?;
+
+        // Only downloads are verified by the updater, so check the cached artifact ourselves
+        // before installing it
+        let bytes = fs::read(artifact_path).map_err(|e| e.to_string())?;
+        let pubkey = updater_pubkey(app_handle).ok_or("The updater has no public key")?;
+        verify_artifact(&bytes, &update.signature, &pubkey)?;
+        update.install(bytes).map_err(|e| e.to_string())?;
+        self.last_update_check = SystemTime::now();
+
+        Ok(artifact.version)
+    }
+}
+
+pub async fn get_changelog<R: Runtime>(w: &WebviewWindow<R>, version: &str) -> Changelog {
+    if let Some(kv) = get_key_value_raw(w, KV_CHANGELOG_NAMESPACE, version).await {
+        if let Ok(changelog) = serde_json::from_str::<Changelog>(&kv.value) {
+            return changelog;
+        }
+    }
+
+    let changelog = match fetch_changelog(version).await {
+        Ok(c) => c,
+        Err(e) => {
+            warn!("Failed to fetch changelog for {version}: {e}");
+            return Changelog {
+                version: version.to_string(),
+                available: false,
+                notes: "".to_string(),
+            };
+        }
+    };
+
+    let value = serde_json::to_string(&changelog).unwrap();
+    set_key_value_raw(w, KV_CHANGELOG_NAMESPACE, version, &value).await;
+    changelog
+}
+
+async fn fetch_changelog(version: &str) -> Result<Changelog, String> {
+    let resp = reqwest::Client::default()
+        .get(format!("https://update.yaak.app/changelog/{version}"))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("Unexpected status {}", resp.status()));
+    }
+
+    #[derive(Deserialize)]
+    struct ChangelogResponse {
+        notes: String,
+    }
+
+    let body = resp.json::<ChangelogResponse>().await.map_err(|e| e.to_string())?;
+    Ok(Changelog {
+        version: version.to_string(),
+        available: true,
+        notes: body.notes,
+    })
+}
+
+fn updater_pubkey(app_handle: &AppHandle) -> Option<String> {
+    let updater = app_handle.config().plugins.0.get("updater")?;
+    Some(updater.get("pubkey")?.as_str()?.to_string())
+}
+
+/// Check an installer against its release's minisign signature. Both the signature and the
+/// public key are base64 encoded, the way the updater serves and configures them.
+fn verify_artifact(bytes: &[u8], signature: &str, pubkey: &str) -> Result<(), String> {
+    let decode = |s: &str| {
+        let decoded = BASE64_STANDARD.decode(s).map_err(|e| e.to_string())?;
+        String::from_utf8(decoded).map_err(|e| e.to_string())
+    };
+    let public_key = PublicKey::decode(&decode(pubkey)?).map_err(|e| e.to_string())?;
+    let signature = Signature::decode(&decode(signature)?).map_err(|e| e.to_string())?;
+    public_key
+        .verify(bytes, &signature, true)
+        .map_err(|e| format!("The rollback artifact failed signature verification: {e}"))
+}
+
+fn update_artifact_path(app_handle: &AppHandle, version: &str) -> PathBuf {
+    let dir = app_handle.path().app_data_dir().unwrap();
+    dir.join("updates").join(version)
+}
+
+fn cache_update_artifact(
+    app_handle: &AppHandle,
+    version: &str,
+    bytes: &[u8],
+) -> std::io::Result<()> {
+    let path = update_artifact_path(app_handle, version);
+    fs::create_dir_all(path.parent().unwrap())?;
+    fs::write(path, bytes)
+}
+
+async fn record_last_known_good(app_handle: &AppHandle) {
+    let version = app_handle.package_info().version.to_string();
+    let path = update_artifact_path(app_handle, &version);
+    let artifact = RollbackArtifact {
+        path: match path.exists() {
+            true => path.to_string_lossy().to_string(),
+            false => "".to_string(),
+        },
+        version,
+    };
+
+    let windows = app_handle.webview_windows();
+    let w = match windows.values().next() {
+        Some(w) => w,
+        None => return,
+    };
+    let value = serde_json::to_string(&artifact).unwrap();
+    set_key_value_raw(w, KV_NAMESPACE, KV_ROLLBACK_KEY, &value).await;
+}
+
+async fn get_last_known_good(app_handle: &AppHandle) -> Option<RollbackArtifact> {
+    let kv = get_key_value_raw(app_handle, KV_NAMESPACE, KV_ROLLBACK_KEY).await?;
+    serde_json::from_str(&kv.value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::updates::verify_artifact;
+
+    // A throwaway key pair, and its signature of "yaak installer"
+    const PUBKEY: &str = "dW50cnVzdGVkIGNvbW1lbnQ6IG1pbmlzaWduIHB1YmxpYyBrZXkKUldRQkFnTUVCUVlIQ0FPaEI3L3p6aEMrSFhEZEdPZEx3SmxuNU5Zd202VU5YeDNjaG1RU1ZURzQK";
+    const SIGNATURE: &str = "dW50cnVzdGVkIGNvbW1lbnQ6IHNpZ25hdHVyZQpSVVFCQWdNRUJRWUhDTHV5a2ZwVXZwT0pUcVNnQVUvd0JmU3hsKzFCczlMblJhSFl1dG1uR0lTMko4YmRyZjE5U1NTYktLbHhJQWZhdWU0Z2lVRGNmRDBza1FkQU0vK211UTA9CnRydXN0ZWQgY29tbWVudDogdGltZXN0YW1wOjAJZmlsZTp5YWFrCjUwQnprZmNoSDh2RGwwblh1bHpuSkIxbmF5bXdScjA3UVBHVDhwQlJwZDgwVFRKaWtUSXhmUm1XZ01KaXZ3Unp0M1plRXBFeFRNNDk2eGtBSFZSQUNBPT0K";
+
+    #[test]
+    fn tampered_artifact_is_rejected() {
+        assert!(verify_artifact(b"yaak installer", SIGNATURE, PUBKEY).is_ok());
+        assert!(verify_artifact(b"yaak installer!", SIGNATURE, PUBKEY).is_err());
+        assert!(verify_artifact(b"yaak installer", "not a signature", PUBKEY).is_err());
+    }
 }
@@ -0,0 +1,137 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use rustls::client::{ServerCertVerified, ServerCertVerifier};
+use rustls::{Certificate, ClientConfig, PrivateKey, RootCertStore, ServerName};
+use rustls_pemfile::Item;
+
+use crate::proxy::GrpcProxy;
+
+/// How a gRPC channel connects to the server
+#[derive(Clone, Debug, Default)]
+pub struct GrpcChannelOptions {
+    /// Skip verifying the server's TLS certificate
+    pub accept_invalid_certs: bool,
+    /// PEM file of CA certificates to trust, on top of the system's
+    pub ca_certificate: Option<PathBuf>,
+    /// PEM files of the client certificate (chain) and its private key, for mutual TLS
+    pub client_certificate: Option<(PathBuf, PathBuf)>,
+    /// Interval between HTTP/2 keepalive pings, which are also sent while idle
+    pub keepalive_interval: Option<Duration>,
+    /// Deadline for each call, which also bounds connecting
+    pub timeout: Option<Duration>,
+    pub proxy: Option<GrpcProxy>,
+}
+
+/// TLS configuration for the channel's HTTPS connector. ALPN is left to the connector.
+pub(crate) fn tls_config(options: &GrpcChannelOptions) -> Result<ClientConfig, String> {
+    let mut roots = RootCertStore::empty();
+    let native = rustls_native_certs::load_native_certs()
+        .map_err(|e| format!("Failed to load system certificates: {e}"))?;
+    roots.add_parsable_certificates(&native.into_iter().map(|c| c.0).collect::<Vec<_>>());
+    if let Some(path) = &options.ca_certificate {
+        for cert in read_certs(path)? {
+            roots
+                .add(&cert)
+                .map_err(|e| format!("Invalid CA certificate {}: {e}", path.display()))?;
+        }
+    }
+
+    let builder = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots);
+    let mut config = match &options.client_certificate {
+        Some((cert_path, key_path)) => builder
+            .with_client_auth_cert(read_certs(cert_path)?, read_key(key_path)?)
+            .map_err(|e| format!("Invalid client certificate: {e}"))?,
+        None => builder.with_no_client_auth(),
+    };
+
+    if options.accept_invalid_certs {
+        config
+            .dangerous()
+            .set_certificate_verifier(Arc::new(AcceptAnyCertificate));
+    }
+
+    Ok(config)
+}
+
+fn read_certs(path: &Path) -> Result<Vec<Certificate>, String> {
+    let certs = rustls_pemfile::certs(&mut open_pem(path)?)
+        .map_err(|e| format!("Failed to read certificates from {}: {e}", path.display()))?;
+    if certs.is_empty() {
+        return Err(format!("No certificates found in {}", path.display()));
+    }
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn read_key(path: &Path) -> Result<PrivateKey, String> {
+    let items = rustls_pemfile::read_all(&mut open_pem(path)?)
+        .map_err(|e| format!("Failed to read private key from {}: {e}", path.display()))?;
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            Item::RSAKey(k) | Item::PKCS8Key(k) | Item::ECKey(k) => Some(PrivateKey(k)),
+            _ => None,
+        })
+        .ok_or(format!("No private key found in {}", path.display()))
+}
+
+fn open_pem(path: &Path) -> Result<BufReader<File>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    Ok(BufReader::new(file))
+}
+
+/// Verifier for when the user turned off certificate validation
+struct AcceptAnyCertificate;
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::channel::{tls_config, GrpcChannelOptions};
+    use std::path::PathBuf;
+
+    #[test]
+    fn missing_ca_certificate() {
+        let options = GrpcChannelOptions {
+            ca_certificate: Some(PathBuf::from("/does/not/exist.pem")),
+            ..Default::default()
+        };
+        let err = tls_config(&options).unwrap_err();
+        assert!(
+            err.starts_with("Failed to open /does/not/exist.pem"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn empty_client_certificate() {
+        let dir = std::env::temp_dir().join("yaak-grpc-channel-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let cert = dir.join("empty.pem");
+        std::fs::write(&cert, "").unwrap();
+
+        let options = GrpcChannelOptions {
+            client_certificate: Some((cert.clone(), cert.clone())),
+            ..Default::default()
+        };
+        let err = tls_config(&options).unwrap_err();
+        assert_eq!(err, format!("No certificates found in {}", cert.display()));
+    }
+}
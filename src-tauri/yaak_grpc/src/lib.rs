@@ -1,12 +1,17 @@
+use prost::Message;
 use prost_reflect::{DynamicMessage, MethodDescriptor, SerializeOptions};
 use serde::{Deserialize, Serialize};
 use serde_json::Deserializer;
 
+mod channel;
 mod codec;
 mod json_schema;
 pub mod manager;
 mod proto;
+mod proxy;
 
+pub use channel::GrpcChannelOptions;
+pub use proxy::GrpcProxy;
 pub use tonic::metadata::*;
 pub use tonic::Code;
 
@@ -43,6 +48,11 @@ pub fn serialize_message(msg: &DynamicMessage) -> Result<String, String> {
     Ok(s)
 }
 
+/// The protobuf wire encoding of a message, exactly as it's sent or received
+pub fn encode_message(msg: &DynamicMessage) -> Vec<u8> {
+    msg.encode_to_vec()
+}
+
 pub fn deserialize_message(msg: &str, method: MethodDescriptor) -> Result<DynamicMessage, String> {
     let mut deserializer = Deserializer::from_str(&msg);
     let req_message = DynamicMessage::deserialize(method.input(), &mut deserializer)
@@ -1,10 +1,8 @@
 use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
-use hyper::client::HttpConnector;
-use hyper::Client;
-use hyper_rustls::HttpsConnector;
 pub use prost_reflect::DynamicMessage;
 use prost_reflect::{DescriptorPool, MethodDescriptor, ServiceDescriptor};
 use serde_json::Deserializer;
@@ -13,19 +11,28 @@ use tokio_stream::wrappers::ReceiverStream;
 use tonic::body::BoxBody;
 use tonic::metadata::{MetadataKey, MetadataValue};
 use tonic::transport::Uri;
-use tonic::{IntoRequest, IntoStreamingRequest, Request, Response, Status, Streaming};
+use tonic::{Code, IntoRequest, IntoStreamingRequest, Request, Response, Status, Streaming};
 
+use crate::channel::GrpcChannelOptions;
 use crate::codec::DynamicCodec;
 use crate::proto::{
-    fill_pool_from_files, fill_pool_from_reflection, get_transport, method_desc_to_path,
+    fill_pool_from_files, fill_pool_from_reflection, get_transport, method_desc_to_path, Transport,
 };
 use crate::{json_schema, MethodDefinition, ServiceDefinition};
 
+/// Used when the workspace doesn't configure a limit. Tonic's own default is only 4MB for
+/// received messages, which is easy to hit.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 32 * 1024 * 1024;
+
 #[derive(Clone)]
 pub struct GrpcConnection {
     pool: DescriptorPool,
-    conn: Client<HttpsConnector<HttpConnector>, BoxBody>,
+    conn: Transport,
     pub uri: Uri,
+    pub max_send_message_size: usize,
+    pub max_receive_message_size: usize,
+    /// Deadline sent with each call
+    pub timeout: Option<Duration>,
 }
 
 #[derive(Default, Debug)]
@@ -53,6 +60,28 @@ impl From<Status> for StreamError {
 }
 
 impl GrpcConnection {
+    fn client(&self) -> tonic::client::Grpc<Transport> {
+        tonic::client::Grpc::with_origin(self.conn.clone(), self.uri.clone())
+            .max_encoding_message_size(self.max_send_message_size)
+            .max_decoding_message_size(self.max_receive_message_size)
+    }
+
+    /// Tonic reports oversized messages with a generic "message length too large" status, so
+    /// rewrite it to say which configured limit was hit.
+    pub fn annotate_status(&self, s: Status) -> Status {
+        if s.code() != Code::OutOfRange || !s.message().contains("message length too large") {
+            return s;
+        }
+
+        let message = format!(
+            "Message too large (send limit {} bytes, receive limit {} bytes): {}",
+            self.max_send_message_size,
+            self.max_receive_message_size,
+            s.message()
+        );
+        Status::with_metadata(s.code(), message, s.metadata().clone())
+    }
+
     pub fn service(&self, service: &str) -> Result<ServiceDescriptor, String> {
         let service = self
             .pool
@@ -85,16 +114,19 @@ impl GrpcConnection {
             .map_err(|e| e.to_string())?;
         deserializer.end().unwrap();
 
-        let mut client = tonic::client::Grpc::with_origin(self.conn.clone(), self.uri.clone());
+        let mut client = self.client();
 
         let mut req = req_message.into_request();
-        decorate_req(metadata, &mut req).map_err(|e| e.to_string())?;
+        decorate_req(metadata, self.timeout, &mut req).map_err(|e| e.to_string())?;
 
         let path = method_desc_to_path(method);
         let codec = DynamicCodec::new(method.clone());
         client.ready().await.unwrap();
 
-        Ok(client.unary(req, path, codec).await?)
+        client
+            .unary(req, path, codec)
+            .await
+            .map_err(|e| self.annotate_status(e).into())
     }
 
     pub async fn streaming(
@@ -105,16 +137,19 @@ impl GrpcConnection {
         metadata: BTreeMap<String, String>,
     ) -> Result<Response<Streaming<DynamicMessage>>, StreamError> {
         let method = &self.method(&service, &method)?;
-        let mut client = tonic::client::Grpc::with_origin(self.conn.clone(), self.uri.clone());
+        let mut client = self.client();
 
         let mut req = stream.into_streaming_request();
 
-        decorate_req(metadata, &mut req).map_err(|e| e.to_string())?;
+        decorate_req(metadata, self.timeout, &mut req).map_err(|e| e.to_string())?;
 
         let path = method_desc_to_path(method);
         let codec = DynamicCodec::new(method.clone());
         client.ready().await.map_err(|e| e.to_string())?;
-        Ok(client.streaming(req, path, codec).await?)
+        client
+            .streaming(req, path, codec)
+            .await
+            .map_err(|e| self.annotate_status(e).into())
     }
 
     pub async fn client_streaming(
@@ -125,9 +160,9 @@ impl GrpcConnection {
         metadata: BTreeMap<String, String>,
     ) -> Result<Response<DynamicMessage>, StreamError> {
         let method = &self.method(&service, &method)?;
-        let mut client = tonic::client::Grpc::with_origin(self.conn.clone(), self.uri.clone());
+        let mut client = self.client();
         let mut req = stream.into_streaming_request();
-        decorate_req(metadata, &mut req).map_err(|e| e.to_string())?;
+        decorate_req(metadata, self.timeout, &mut req).map_err(|e| e.to_string())?;
 
         let path = method_desc_to_path(method);
         let codec = DynamicCodec::new(method.clone());
@@ -135,10 +170,7 @@ impl GrpcConnection {
         client
             .client_streaming(req, path, codec)
             .await
-            .map_err(|e| StreamError {
-                message: e.message().to_string(),
-                status: Some(e),
-            })
+            .map_err(|e| self.annotate_status(e).into())
     }
 
     pub async fn server_streaming(
@@ -156,15 +188,18 @@ impl GrpcConnection {
             .map_err(|e| e.to_string())?;
         deserializer.end().unwrap();
 
-        let mut client = tonic::client::Grpc::with_origin(self.conn.clone(), self.uri.clone());
+        let mut client = self.client();
 
         let mut req = req_message.into_request();
-        decorate_req(metadata, &mut req).map_err(|e| e.to_string())?;
+        decorate_req(metadata, self.timeout, &mut req).map_err(|e| e.to_string())?;
 
         let path = method_desc_to_path(method);
         let codec = DynamicCodec::new(method.clone());
         client.ready().await.map_err(|e| e.to_string())?;
-        Ok(client.server_streaming(req, path, codec).await?)
+        client
+            .server_streaming(req, path, codec)
+            .await
+            .map_err(|e| self.annotate_status(e).into())
     }
 }
 
@@ -189,16 +224,20 @@ impl GrpcHandle {
         id: &str,
         uri: &str,
         proto_files: &Vec<PathBuf>,
+        import_dirs: &Vec<PathBuf>,
+        options: &GrpcChannelOptions,
     ) -> Result<(), String> {
         let pool = if proto_files.is_empty() {
             let full_uri = uri_from_str(uri)?;
-            fill_pool_from_reflection(&full_uri).await
+            fill_pool_from_reflection(&full_uri, options).await
         } else {
-            fill_pool_from_files(&self.app_handle, proto_files).await
+            fill_pool_from_files(&self.app_handle, proto_files, import_dirs).await
         }?;
 
-        self.pools
-            .insert(make_pool_key(id, uri, proto_files), pool.clone());
+        self.pools.insert(
+            make_pool_key(id, uri, proto_files, import_dirs),
+            pool.clone(),
+        );
         Ok(())
     }
 
@@ -207,12 +246,15 @@ impl GrpcHandle {
         id: &str,
         uri: &str,
         proto_files: &Vec<PathBuf>,
+        import_dirs: &Vec<PathBuf>,
+        options: &GrpcChannelOptions,
     ) -> Result<Vec<ServiceDefinition>, String> {
         // Ensure reflection is up-to-date
-        self.reflect(id, uri, proto_files).await?;
+        self.reflect(id, uri, proto_files, import_dirs, options)
+            .await?;
 
         let pool = self
-            .get_pool(id, uri, proto_files)
+            .get_pool(id, uri, proto_files, import_dirs)
             .ok_or("Failed to get pool".to_string())?;
         Ok(self.services_from_pool(&pool))
     }
@@ -247,28 +289,50 @@ impl GrpcHandle {
         id: &str,
         uri: &str,
         proto_files: &Vec<PathBuf>,
+        import_dirs: &Vec<PathBuf>,
+        max_send_message_size: usize,
+        max_receive_message_size: usize,
+        options: &GrpcChannelOptions,
     ) -> Result<GrpcConnection, String> {
-        self.reflect(id, uri, proto_files).await?;
+        self.reflect(id, uri, proto_files, import_dirs, options)
+            .await?;
         let pool = self
-            .get_pool(id, uri, proto_files)
+            .get_pool(id, uri, proto_files, import_dirs)
             .ok_or("Failed to get pool")?;
 
         let uri = uri_from_str(uri)?;
-        let conn = get_transport();
+        let conn = get_transport(options)?;
         let connection = GrpcConnection {
             pool: pool.clone(),
             conn,
             uri,
+            max_send_message_size,
+            max_receive_message_size,
+            timeout: options.timeout,
         };
         Ok(connection)
     }
 
-    fn get_pool(&self, id: &str, uri: &str, proto_files: &Vec<PathBuf>) -> Option<&DescriptorPool> {
-        self.pools.get(make_pool_key(id, uri, proto_files).as_str())
+    fn get_pool(
+        &self,
+        id: &str,
+        uri: &str,
+        proto_files: &Vec<PathBuf>,
+        import_dirs: &Vec<PathBuf>,
+    ) -> Option<&DescriptorPool> {
+        self.pools
+            .get(make_pool_key(id, uri, proto_files, import_dirs).as_str())
     }
 }
 
-fn decorate_req<T>(metadata: BTreeMap<String, String>, req: &mut Request<T>) -> Result<(), String> {
+fn decorate_req<T>(
+    metadata: BTreeMap<String, String>,
+    timeout: Option<Duration>,
+    req: &mut Request<T>,
+) -> Result<(), String> {
+    if let Some(timeout) = timeout {
+        req.set_timeout(timeout);
+    }
     for (k, v) in metadata {
         req.metadata_mut().insert(
             MetadataKey::from_str(k.as_str()).map_err(|e| e.to_string())?,
@@ -288,16 +352,25 @@ fn uri_from_str(uri_str: &str) -> Result<Uri, String> {
     }
 }
 
-fn make_pool_key(id: &str, uri: &str, proto_files: &Vec<PathBuf>) -> String {
-    let pool_key = format!(
-        "{}::{}::{}",
-        id,
-        uri,
-        proto_files
+fn make_pool_key(
+    id: &str,
+    uri: &str,
+    proto_files: &Vec<PathBuf>,
+    import_dirs: &Vec<PathBuf>,
+) -> String {
+    let join = |paths: &Vec<PathBuf>| {
+        paths
             .iter()
             .map(|p| p.to_string_lossy().to_string())
             .collect::<Vec<String>>()
             .join(":")
+    };
+    let pool_key = format!(
+        "{}::{}::{}::{}",
+        id,
+        uri,
+        join(proto_files),
+        join(import_dirs)
     );
 
     format!("{:x}", md5::compute(pool_key))
@@ -1,11 +1,10 @@
 use std::env::temp_dir;
 use std::ops::Deref;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use anyhow::anyhow;
 use async_recursion::async_recursion;
-use hyper::client::HttpConnector;
 use hyper::Client;
 use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
 use log::{debug, warn};
@@ -26,49 +25,28 @@ use tonic_reflection::pb::server_reflection_request::MessageRequest;
 use tonic_reflection::pb::server_reflection_response::MessageResponse;
 use tonic_reflection::pb::ServerReflectionRequest;
 
+use crate::channel::{tls_config, GrpcChannelOptions};
+use crate::proxy::ProxyConnector;
+
 pub async fn fill_pool_from_files(
     app_handle: &AppHandle,
     paths: &Vec<PathBuf>,
+    import_dirs: &Vec<PathBuf>,
 ) -> Result<DescriptorPool, String> {
     let mut pool = DescriptorPool::new();
     let random_file_name = format!("{}.desc", uuid::Uuid::new_v4());
     let desc_path = temp_dir().join(random_file_name);
+    // The google/protobuf/*.proto well-known types that ship with protoc
     let global_import_dir = app_handle
         .path()
-        .resolve("vendored/protoc/protoc-include", BaseDirectory::Resource)
-        .expect("failed to resolve protoc include directory");
+        .resolve("vendored/protoc/include", BaseDirectory::Resource)
+        .map_err(|e| format!("Failed to resolve protoc include directory: {e}"))?;
 
     // HACK: Remove UNC prefix for Windows paths
-    let global_import_dir =
-        dunce::simplified(global_import_dir.as_path()).to_string_lossy().to_string();
+    let global_import_dir = dunce::simplified(global_import_dir.as_path());
     let desc_path = dunce::simplified(desc_path.as_path());
 
-    let mut args = vec![
-        "--include_imports".to_string(),
-        "--include_source_info".to_string(),
-        "-I".to_string(),
-        global_import_dir,
-        "-o".to_string(),
-        desc_path.to_string_lossy().to_string(),
-    ];
-
-    for p in paths {
-        if p.as_path().exists() {
-            args.push(p.to_string_lossy().to_string());
-        } else {
-            continue;
-        }
-
-        let parent = p.as_path().parent();
-        if let Some(parent_path) = parent {
-            args.push("-I".to_string());
-            args.push(parent_path.to_string_lossy().to_string());
-            args.push("-I".to_string());
-            args.push(parent_path.parent().unwrap().to_string_lossy().to_string());
-        } else {
-            debug!("ignoring {:?} since it does not exist.", parent)
-        }
-    }
+    let args = protoc_args(paths, import_dirs, global_import_dir, desc_path);
 
     let out = app_handle
         .shell()
@@ -96,9 +74,61 @@ pub async fn fill_pool_from_files(
     Ok(pool)
 }
 
-pub async fn fill_pool_from_reflection(uri: &Uri) -> Result<DescriptorPool, String> {
-    let mut pool = DescriptorPool::new();
-    let mut client = ServerReflectionClient::with_origin(get_transport(), uri.clone());
+/// Arguments to compile the files into a descriptor set at `desc_path`. protoc names each file
+/// after the first import directory containing it, so the user's directories go before the
+/// directories of the files themselves, which are only there for files outside any of them.
+fn protoc_args(
+    paths: &[PathBuf],
+    import_dirs: &[PathBuf],
+    global_import_dir: &Path,
+    desc_path: &Path,
+) -> Vec<String> {
+    let mut args = vec![
+        "--include_imports".to_string(),
+        "--include_source_info".to_string(),
+        "-o".to_string(),
+        desc_path.to_string_lossy().to_string(),
+    ];
+
+    let mut include = |dir: &Path| {
+        let dir = dir.to_string_lossy().to_string();
+        if !args.windows(2).any(|w| w[0] == "-I" && w[1] == dir) {
+            args.push("-I".to_string());
+            args.push(dir);
+        }
+    };
+
+    for dir in import_dirs {
+        if dir.is_dir() {
+            include(dir);
+        } else {
+            debug!("ignoring import directory {dir:?} since it does not exist.")
+        }
+    }
+
+    let paths: Vec<&PathBuf> = paths.iter().filter(|p| p.exists()).collect();
+    for p in &paths {
+        if let Some(parent_path) = p.parent() {
+            include(parent_path);
+            if let Some(grandparent_path) = parent_path.parent() {
+                include(grandparent_path);
+            }
+        }
+    }
+
+    // Last, so the user's own copies of the well-known types take precedence
+    include(global_import_dir);
+
+    args.extend(paths.iter().map(|p| p.to_string_lossy().to_string()));
+    args
+}
+
+pub async fn fill_pool_from_reflection(
+    uri: &Uri,
+    options: &GrpcChannelOptions,
+) -> Result<DescriptorPool, String> {
+    let mut pool = well_known_types_pool();
+    let mut client = ServerReflectionClient::with_origin(get_transport(options)?, uri.clone());
 
     for service in list_services(&mut client).await? {
         if service == "grpc.reflection.v1alpha.ServerReflection" {
@@ -108,24 +138,38 @@ pub async fn fill_pool_from_reflection(uri: &Uri) -> Result<DescriptorPool, Stri
             // TODO: update reflection client to use v1
             continue;
         }
-        file_descriptor_set_from_service_name(&service, &mut pool, &mut client).await;
+        file_descriptor_set_from_service_name(&service, &mut pool, &mut client).await?;
     }
 
     Ok(pool)
 }
 
-pub fn get_transport() -> Client<HttpsConnector<HttpConnector>, BoxBody> {
-    let connector = HttpsConnectorBuilder::new().with_native_roots();
-    let connector = connector.https_or_http().enable_http2().wrap_connector({
-        let mut http_connector = HttpConnector::new();
-        http_connector.enforce_http(false);
-        http_connector
-    });
-    Client::builder().pool_max_idle_per_host(0).http2_only(true).build(connector)
+/// A pool that already holds the well-known types (google/protobuf/*.proto), since servers often
+/// don't return them through reflection. The global pool only contains these by default.
+fn well_known_types_pool() -> DescriptorPool {
+    DescriptorPool::global()
+}
+
+/// HTTP/2 client that gRPC requests and reflection are sent over
+pub type Transport = Client<HttpsConnector<ProxyConnector>, BoxBody>;
+
+pub fn get_transport(options: &GrpcChannelOptions) -> Result<Transport, String> {
+    let connector = HttpsConnectorBuilder::new().with_tls_config(tls_config(options)?);
+    let connector = connector
+        .https_or_http()
+        .enable_http2()
+        .wrap_connector(ProxyConnector::new(options.proxy.clone(), options.timeout));
+
+    let mut builder = Client::builder();
+    builder.pool_max_idle_per_host(0).http2_only(true);
+    if let Some(interval) = options.keepalive_interval {
+        builder.http2_keep_alive_interval(interval).http2_keep_alive_while_idle(true);
+    }
+    Ok(builder.build(connector))
 }
 
 async fn list_services(
-    reflect_client: &mut ServerReflectionClient<Client<HttpsConnector<HttpConnector>, BoxBody>>,
+    reflect_client: &mut ServerReflectionClient<Transport>,
 ) -> Result<Vec<String>, String> {
     let response =
         send_reflection_request(reflect_client, MessageRequest::ListServices("".into())).await?;
@@ -141,8 +185,8 @@ async fn list_services(
 async fn file_descriptor_set_from_service_name(
     service_name: &str,
     pool: &mut DescriptorPool,
-    client: &mut ServerReflectionClient<Client<HttpsConnector<HttpConnector>, BoxBody>>,
-) {
+    client: &mut ServerReflectionClient<Transport>,
+) -> Result<(), String> {
     let response = match send_reflection_request(
         client,
         MessageRequest::FileContainingSymbol(service_name.into()),
@@ -152,7 +196,7 @@ async fn file_descriptor_set_from_service_name(
         Ok(resp) => resp,
         Err(e) => {
             warn!("Error fetching file descriptor for service {}: {}", service_name, e);
-            return;
+            return Ok(());
         }
     };
 
@@ -161,16 +205,15 @@ async fn file_descriptor_set_from_service_name(
         _ => panic!("Expected a FileDescriptorResponse variant"),
     };
 
-    add_file_descriptors_to_pool(file_descriptor_response.file_descriptor_proto, pool, client)
-        .await;
+    add_file_descriptors_to_pool(file_descriptor_response.file_descriptor_proto, pool, client).await
 }
 
 #[async_recursion]
 async fn add_file_descriptors_to_pool(
     fds: Vec<Vec<u8>>,
     pool: &mut DescriptorPool,
-    client: &mut ServerReflectionClient<Client<HttpsConnector<HttpConnector>, BoxBody>>,
-) {
+    client: &mut ServerReflectionClient<Transport>,
+) -> Result<(), String> {
     let mut topo_sort = topology::SimpleTopoSort::new();
     let mut fd_mapping = std::collections::HashMap::with_capacity(fds.len());
 
@@ -185,24 +228,42 @@ async fn add_file_descriptors_to_pool(
         match node {
             Ok(node) => {
                 if let Some(fdp) = fd_mapping.remove(&node) {
-                    pool.add_file_descriptor_proto(fdp).expect("add file descriptor proto");
+                    add_file_descriptor(pool, fdp)?;
                 } else {
-                    file_descriptor_set_by_filename(node.as_str(), pool, client).await;
+                    file_descriptor_set_by_filename(node.as_str(), pool, client).await?;
                 }
             }
             Err(_) => panic!("proto file got cycle!"),
         }
     }
+
+    Ok(())
+}
+
+/// Add a file to the pool unless it's already there, with a readable error when one of its
+/// imports couldn't be resolved
+fn add_file_descriptor(pool: &mut DescriptorPool, fdp: FileDescriptorProto) -> Result<(), String> {
+    if pool.get_file_by_name(fdp.name()).is_some() {
+        return Ok(());
+    }
+    if let Some(missing) = fdp.dependency.iter().find(|d| pool.get_file_by_name(d).is_none()) {
+        return Err(format!(
+            "{} imports {}, which the server did not provide through reflection",
+            fdp.name(),
+            missing
+        ));
+    }
+    pool.add_file_descriptor_proto(fdp).map_err(|e| format!("Invalid descriptor: {e}"))
 }
 
 async fn file_descriptor_set_by_filename(
     filename: &str,
     pool: &mut DescriptorPool,
-    client: &mut ServerReflectionClient<Client<HttpsConnector<HttpConnector>, BoxBody>>,
-) {
+    client: &mut ServerReflectionClient<Transport>,
+) -> Result<(), String> {
     // We already fetched this file
     if let Some(_) = pool.get_file_by_name(filename) {
-        return;
+        return Ok(());
     }
 
     let response =
@@ -213,17 +274,17 @@ async fn file_descriptor_set_by_filename(
             panic!("Expected a FileDescriptorResponse variant")
         }
         Err(e) => {
+            // Files that import this one will fail to resolve with a clearer error
             warn!("Error fetching file descriptor for {}: {}", filename, e);
-            return;
+            return Ok(());
         }
     };
 
-    add_file_descriptors_to_pool(file_descriptor_response.file_descriptor_proto, pool, client)
-        .await;
+    add_file_descriptors_to_pool(file_descriptor_response.file_descriptor_proto, pool, client).await
 }
 
 async fn send_reflection_request(
-    client: &mut ServerReflectionClient<Client<HttpsConnector<HttpConnector>, BoxBody>>,
+    client: &mut ServerReflectionClient<Transport>,
     message: MessageRequest,
 ) -> Result<MessageResponse, String> {
     let reflection_request = ServerReflectionRequest {
@@ -392,3 +453,90 @@ mod topology {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::proto::{add_file_descriptor, protoc_args, well_known_types_pool};
+    use prost_types::field_descriptor_proto::{Label, Type};
+    use prost_types::{DescriptorProto, FieldDescriptorProto, FileDescriptorProto};
+    use std::path::{Path, PathBuf};
+
+    fn file_importing(name: &str, import: &str, type_name: &str) -> FileDescriptorProto {
+        FileDescriptorProto {
+            name: Some(name.to_string()),
+            package: Some("yaak.test".to_string()),
+            dependency: vec![import.to_string()],
+            syntax: Some("proto3".to_string()),
+            message_type: vec![DescriptorProto {
+                name: Some("Event".to_string()),
+                field: vec![FieldDescriptorProto {
+                    name: Some("at".to_string()),
+                    number: Some(1),
+                    label: Some(Label::Optional as i32),
+                    r#type: Some(Type::Message as i32),
+                    type_name: Some(type_name.to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resolve_well_known_imports() {
+        let mut pool = well_known_types_pool();
+        let fdp = file_importing(
+            "events.proto",
+            "google/protobuf/timestamp.proto",
+            ".google.protobuf.Timestamp",
+        );
+        add_file_descriptor(&mut pool, fdp.clone()).unwrap();
+        assert!(pool.get_message_by_name("yaak.test.Event").is_some());
+
+        // Adding a file that's already in the pool is a no-op
+        add_file_descriptor(&mut pool, fdp).unwrap();
+    }
+
+    #[test]
+    fn missing_custom_import() {
+        let mut pool = well_known_types_pool();
+        let fdp = file_importing("orders.proto", "acme/money.proto", ".acme.Money");
+        assert_eq!(
+            add_file_descriptor(&mut pool, fdp).unwrap_err(),
+            "orders.proto imports acme/money.proto, which the server did not provide through reflection"
+        );
+    }
+
+    #[test]
+    fn protoc_args_put_import_dirs_first() {
+        let root = std::env::temp_dir().join("yaak-protoc-args-test");
+        let file = root.join("acme").join("orders.proto");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, "").unwrap();
+
+        let args = protoc_args(
+            &[file.clone(), root.join("missing.proto")],
+            &[root.clone(), PathBuf::from("/does/not/exist")],
+            Path::new("/protoc/include"),
+            Path::new("/tmp/out.desc"),
+        );
+        let s = |p: &Path| p.to_string_lossy().to_string();
+        assert_eq!(
+            args,
+            vec![
+                "--include_imports".to_string(),
+                "--include_source_info".to_string(),
+                "-o".to_string(),
+                "/tmp/out.desc".to_string(),
+                "-I".to_string(),
+                s(&root),
+                "-I".to_string(),
+                s(&root.join("acme")),
+                "-I".to_string(),
+                "/protoc/include".to_string(),
+                s(&file),
+            ]
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use hyper::client::HttpConnector;
+use hyper::service::Service;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_socks::tcp::Socks5Stream;
+use tonic::transport::Uri;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Longest proxy response to a CONNECT request we'll read before giving up
+const MAX_CONNECT_RESPONSE_BYTES: usize = 16 * 1024;
+
+/// Proxy to tunnel gRPC connections through
+#[derive(Clone, Debug, PartialEq)]
+pub enum GrpcProxy {
+    /// An HTTP proxy, tunnelled through with a CONNECT request
+    Http {
+        addr: String,
+        auth: Option<(String, String)>,
+    },
+    /// A SOCKS5 proxy, which resolves the target's hostname itself
+    Socks5 {
+        addr: String,
+        auth: Option<(String, String)>,
+    },
+}
+
+/// Connector that opens TCP connections directly, or through a proxy when one is configured.
+/// TLS is layered on top by the HTTPS connector, so it's end-to-end with the server.
+#[derive(Clone)]
+pub struct ProxyConnector {
+    http: HttpConnector,
+    proxy: Option<GrpcProxy>,
+    connect_timeout: Option<Duration>,
+}
+
+impl ProxyConnector {
+    pub fn new(proxy: Option<GrpcProxy>, connect_timeout: Option<Duration>) -> Self {
+        let mut http = HttpConnector::new();
+        http.enforce_http(false);
+        http.set_connect_timeout(connect_timeout);
+        Self {
+            http,
+            proxy,
+            connect_timeout,
+        }
+    }
+}
+
+impl Service<Uri> for ProxyConnector {
+    type Response = TcpStream;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<TcpStream, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.http.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        match self.proxy.clone() {
+            None => {
+                let connecting = self.http.call(uri);
+                Box::pin(async move { connecting.await.map_err(Into::into) })
+            }
+            Some(proxy) => {
+                let connect_timeout = self.connect_timeout;
+                Box::pin(async move {
+                    let connecting = connect_via_proxy(&proxy, &uri);
+                    let stream = match connect_timeout {
+                        Some(t) => tokio::time::timeout(t, connecting)
+                            .await
+                            .map_err(|_| "Timed out connecting to proxy")??,
+                        None => connecting.await?,
+                    };
+                    stream.set_nodelay(true)?;
+                    Ok(stream)
+                })
+            }
+        }
+    }
+}
+
+async fn connect_via_proxy(proxy: &GrpcProxy, uri: &Uri) -> Result<TcpStream, BoxError> {
+    let host = uri.host().ok_or("Missing host in gRPC URL")?;
+    let port = uri.port_u16().unwrap_or(match uri.scheme_str() {
+        Some("https") => 443,
+        _ => 80,
+    });
+
+    match proxy {
+        GrpcProxy::Http { addr, auth } => Ok(http_connect(addr, auth.as_ref(), host, port).await?),
+        GrpcProxy::Socks5 { addr, auth } => {
+            // SOCKS takes IPv6 addresses without the brackets a URI wraps them in
+            let target = (host.trim_start_matches('[').trim_end_matches(']'), port);
+            let stream = match auth {
+                Some((user, password)) => {
+                    Socks5Stream::connect_with_password(addr.as_str(), target, user, password)
+                        .await?
+                }
+                None => Socks5Stream::connect(addr.as_str(), target).await?,
+            };
+            Ok(stream.into_inner())
+        }
+    }
+}
+
+/// Open a tunnel to `host:port` through an HTTP proxy
+async fn http_connect(
+    proxy_addr: &str,
+    auth: Option<&(String, String)>,
+    host: &str,
+    port: u16,
+) -> io::Result<TcpStream> {
+    let mut stream = TcpStream::connect(proxy_addr).await?;
+
+    let target = format!("{host}:{port}");
+    let mut req = format!("CONNECT {target} HTTP/1.1\r\nHost: {target}\r\n");
+    if let Some((user, password)) = auth {
+        let credentials = BASE64_STANDARD.encode(format!("{user}:{password}"));
+        req.push_str(&format!("Proxy-Authorization: Basic {credentials}\r\n"));
+    }
+    req.push_str("\r\n");
+    stream.write_all(req.as_bytes()).await?;
+
+    // Read a byte at a time so nothing past the proxy's response is consumed, as that belongs
+    // to the tunnelled connection
+    let mut resp = Vec::new();
+    while !resp.ends_with(b"\r\n\r\n") {
+        if resp.len() >= MAX_CONNECT_RESPONSE_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Proxy response too large",
+            ));
+        }
+        let mut byte = [0u8; 1];
+        if stream.read(&mut byte).await? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Proxy closed the connection",
+            ));
+        }
+        resp.push(byte[0]);
+    }
+
+    let resp = String::from_utf8_lossy(&resp);
+    let status_line = resp.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some(status) if status.starts_with('2') => Ok(stream),
+        _ => Err(io::Error::other(format!(
+            "Proxy refused to connect to {target}: {status_line}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::proxy::http_connect;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Accept one CONNECT request, reply with `status_line`, and return the request's head
+    async fn fake_proxy(status_line: &'static str) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let handle = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut socket = tokio::io::BufReader::new(socket);
+            let mut head = String::new();
+            loop {
+                let mut line = String::new();
+                socket.read_line(&mut line).await.unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                head.push_str(&line);
+            }
+            socket
+                .write_all(format!("{status_line}\r\n\r\n").as_bytes())
+                .await
+                .unwrap();
+            head
+        });
+        (addr, handle)
+    }
+
+    #[tokio::test]
+    async fn http_connect_tunnels() {
+        let (addr, proxy) = fake_proxy("HTTP/1.1 200 Connection established").await;
+        let auth = ("user".to_string(), "pass".to_string());
+        http_connect(&addr, Some(&auth), "example.com", 443)
+            .await
+            .unwrap();
+
+        let head = proxy.await.unwrap();
+        assert!(head.starts_with("CONNECT example.com:443 HTTP/1.1\r\n"));
+        assert!(head.contains("Proxy-Authorization: Basic dXNlcjpwYXNz\r\n"));
+    }
+
+    #[tokio::test]
+    async fn http_connect_refused() {
+        let (addr, _proxy) = fake_proxy("HTTP/1.1 407 Proxy Authentication Required").await;
+        let err = http_connect(&addr, None, "example.com", 443)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Proxy refused to connect to example.com:443: HTTP/1.1 407 Proxy Authentication Required"
+        );
+    }
+}
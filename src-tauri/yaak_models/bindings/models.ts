@@ -1,6 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type AnyModel = CookieJar | Environment | Folder | GrpcConnection | GrpcEvent | GrpcRequest | HttpRequest | HttpResponse | Plugin | Settings | KeyValue | Workspace;
+export type AnyModel = CookieJar | Environment | Folder | GrpcConnection | GrpcEvent | GrpcRequest | HttpExample | HttpRequest | HttpResponse | HttpResponseEvent | HttpTimelineEvent | LoadTest | Plugin | RequestHistory | RunResult | Settings | KeyValue | Workspace;
 
 export type Cookie = { raw_cookie: string, domain: CookieDomain, expires: CookieExpires, path: [string, boolean], };
 
@@ -10,9 +10,17 @@ export type CookieExpires = { "AtUtc": string } | "SessionEnd";
 
 export type CookieJar = { model: "cookie_jar", id: string, createdAt: string, updatedAt: string, workspaceId: string, cookies: Array<Cookie>, name: string, };
 
-export type Environment = { model: "environment", id: string, workspaceId: string, createdAt: string, updatedAt: string, name: string, variables: Array<EnvironmentVariable>, };
+export type Environment = { model: "environment", id: string, workspaceId: string, createdAt: string, updatedAt: string, color: string | null, 
+/**
+ * Sending to a production environment requires explicit confirmation
+ */
+isProduction: boolean, name: string, variables: Array<EnvironmentVariable>, };
 
-export type EnvironmentVariable = { enabled?: boolean, name: string, value: string, };
+export type EnvironmentVariable = { enabled?: boolean, name: string, value: string, 
+/**
+ * Secret values are kept in the OS keychain instead of the database, and left out of exports
+ */
+secret?: boolean, };
 
 export type Folder = { model: "folder", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, name: string, sortPriority: number, };
 
@@ -20,34 +28,314 @@ export type GrpcConnection = { model: "grpc_connection", id: string, createdAt:
 
 export type GrpcConnectionState = "initialized" | "connected" | "closed";
 
-export type GrpcEvent = { model: "grpc_event", id: string, createdAt: string, updatedAt: string, workspaceId: string, requestId: string, connectionId: string, content: string, error: string | null, eventType: GrpcEventType, metadata: { [key in string]?: string }, status: number | null, };
+export type GrpcEvent = { model: "grpc_event", id: string, createdAt: string, updatedAt: string, workspaceId: string, requestId: string, connectionId: string, content: string, 
+/**
+ * Base64 of the message's protobuf bytes, if the workspace captures them
+ */
+contentRaw: string | null, error: string | null, eventType: GrpcEventType, metadata: { [key in string]?: string }, status: number | null, };
 
 export type GrpcEventType = "info" | "error" | "client_message" | "server_message" | "connection_start" | "connection_end";
 
 export type GrpcMetadataEntry = { enabled?: boolean, name: string, value: string, };
 
-export type GrpcRequest = { model: "grpc_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authenticationType: string | null, authentication: Record<string, any>, message: string, metadata: Array<GrpcMetadataEntry>, method: string | null, name: string, service: string | null, sortPriority: number, url: string, };
+export type GrpcRequest = { model: "grpc_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authenticationType: string | null, authentication: Record<string, any>, inheritMetadata: boolean, message: string, metadata: Array<GrpcMetadataEntry>, method: string | null, name: string, service: string | null, sortPriority: number, url: string, 
+/**
+ * Whether to verify the server's TLS certificate. When None, the workspace setting applies.
+ */
+settingValidateCertificates: boolean | null, 
+/**
+ * Path to a PEM file of CA certificates to trust, on top of the system's
+ */
+settingCaCertificate: string | null, 
+/**
+ * Path to a PEM file of the client certificate, for mutual TLS
+ */
+settingClientCertificate: string | null, 
+/**
+ * Path to a PEM file of the client certificate's private key
+ */
+settingClientKey: string | null, 
+/**
+ * Milliseconds between HTTP/2 keepalive pings. Zero disables keepalive.
+ */
+settingKeepaliveInterval: number, 
+/**
+ * Deadline for each call, in milliseconds. Zero means no deadline.
+ */
+settingTimeout: number, };
 
-export type HttpRequest = { model: "http_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, body: Record<string, any>, bodyType: string | null, headers: Array<HttpRequestHeader>, method: string, name: string, sortPriority: number, url: string, urlParameters: Array<HttpUrlParameter>, };
+export type HttpExample = { model: "http_example", id: string, createdAt: string, updatedAt: string, workspaceId: string, requestId: string, bodyPath: string | null, contentLength: number | null, headers: Array<HttpResponseHeader>, name: string, status: number, };
+
+export type HttpRequest = { model: "http_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, body: Record<string, any>, bodyType: string | null, headers: Array<HttpRequestHeader>, method: string, name: string, sortPriority: number, url: string, urlParameters: Array<HttpUrlParameter>, 
+/**
+ * JavaScript run before the request is rendered and sent. It can change the request and set
+ * variables.
+ */
+preRequestScript: string, 
+/**
+ * JavaScript run after the response finishes. It can extract values into variables.
+ */
+postResponseScript: string, 
+/**
+ * Whether the user wrote or approved the scripts. Imported scripts don't run until they are.
+ */
+scriptsTrusted: boolean, settingIgnoreCookies: boolean, 
+/**
+ * Proxy to use for this request only. When set, it takes precedence over the global proxy
+ * setting. When None, the global setting applies.
+ */
+settingProxy: ProxySetting | null, settingServerName: string, 
+/**
+ * HTTP version for this request only. When None, the workspace setting applies.
+ */
+settingHttpVersion: HttpVersionSetting | null, 
+/**
+ * Whether this request follows redirects. When None, the workspace setting applies.
+ */
+settingFollowRedirects: boolean | null, 
+/**
+ * Milliseconds to wait for each attempt. When None, the workspace setting applies.
+ */
+settingRequestTimeout: number | null, 
+/**
+ * How many times to retry this request. When None, the workspace setting applies.
+ */
+settingRetryCount: number | null, 
+/**
+ * Milliseconds to wait before the first retry. When None, the workspace setting applies.
+ */
+settingRetryBackoff: number | null, 
+/**
+ * Whether to retry methods that aren't idempotent, like POST, which may then take effect
+ * more than once
+ */
+settingRetryNonIdempotent: boolean, };
 
 export type HttpRequestHeader = { enabled?: boolean, name: string, value: string, };
 
-export type HttpResponse = { model: "http_response", id: string, createdAt: string, updatedAt: string, workspaceId: string, requestId: string, bodyPath: string | null, contentLength: number | null, elapsed: number, elapsedHeaders: number, error: string | null, headers: Array<HttpResponseHeader>, remoteAddr: string | null, status: number, statusReason: string | null, state: HttpResponseState, url: string, version: string | null, };
+export type HttpResponse = { model: "http_response", id: string, createdAt: string, updatedAt: string, workspaceId: string, requestId: string, 
+/**
+ * How many times the request was sent, counting retries
+ */
+attempts: number, bodyPath: string | null, contentLength: number | null, elapsed: number, elapsedHeaders: number, error: string | null, headers: Array<HttpResponseHeader>, 
+/**
+ * File with a reconstruction of the request as it was written to the connection, next to the
+ * body. Headers the client adds itself are filled in as it would add them, credentials are
+ * redacted, and HTTP/2 requests are written the way HTTP/1.1 would send them.
+ */
+rawRequestPath: string | null, remoteAddr: string | null, 
+/**
+ * Size of the request body that was sent, in bytes. None when it was streamed without a
+ * known length.
+ */
+requestContentLength: number | null, 
+/**
+ * The rendered request that produced this response, for replaying it exactly. The values of
+ * secret variables are replaced with references to them.
+ */
+requestSnapshot: HttpRequest | null, 
+/**
+ * Whether the response took longer than the workspace's slow response threshold
+ */
+slow: boolean, source: HttpResponseSource, status: number, statusReason: string | null, state: HttpResponseState, 
+/**
+ * The server's TLS certificate. The negotiated protocol version and cipher aren't
+ * included because the native TLS backend doesn't expose them.
+ */
+tlsCertificate: HttpResponseTlsCertificate | null, url: string, version: string | null, };
+
+export type HttpResponseEvent = { model: "http_response_event", id: string, createdAt: string, updatedAt: string, workspaceId: string, responseId: string, eventType: string, data: string, 
+/**
+ * The event's `id` field, if the server sent one
+ */
+eventId: string | null, retry: number | null, };
 
 export type HttpResponseHeader = { name: string, value: string, };
 
+export type HttpResponseSource = "network" | "not_modified" | "cache";
+
 export type HttpResponseState = "initialized" | "connected" | "closed";
 
+export type HttpResponseTlsCertificate = { subject: string, issuer: string, serialNumber: string, notBefore: string, notAfter: string, subjectAltNames: Array<string>, };
+
+export type HttpTimelineEvent = { model: "http_timeline_event", id: string, createdAt: string, updatedAt: string, workspaceId: string, responseId: string, eventType: HttpTimelineEventType, content: string, headers: Array<HttpResponseHeader>, 
+/**
+ * Milliseconds from starting the send until the phase started
+ */
+elapsed: number, 
+/**
+ * How long the phase took in milliseconds, for phases that aren't a single moment
+ */
+duration: number | null, };
+
+export type HttpTimelineEventType = "request_headers" | "dns_lookup" | "connection" | "response_headers" | "download";
+
 export type HttpUrlParameter = { enabled?: boolean, name: string, value: string, };
 
+export type HttpVersionSetting = "auto" | "http1" | "http2" | "http2-prior-knowledge";
+
 export type KeyValue = { model: "key_value", createdAt: string, updatedAt: string, key: string, namespace: string, value: string, };
 
+export type LoadTest = { model: "load_test", id: string, createdAt: string, updatedAt: string, workspaceId: string, requestId: string, 
+/**
+ * How long to keep sending, in milliseconds
+ */
+duration: number, 
+/**
+ * How many requests are in flight at once
+ */
+concurrency: number, state: LoadTestState, 
+/**
+ * Milliseconds since the test started
+ */
+elapsed: number, 
+/**
+ * Requests that got a response, whatever its status
+ */
+requests: number, 
+/**
+ * Requests that failed without a response, like timeouts and refused connections
+ */
+errors: number, 
+/**
+ * How many responses had each status code
+ */
+statusCounts: Record<number, number>, 
+/**
+ * Milliseconds from sending a request until its body was read, over every response
+ */
+latencyMin: number, latencyMean: number, latencyP50: number, latencyP90: number, latencyP95: number, latencyP99: number, latencyMax: number, };
+
+export type LoadTestState = "running" | "finished" | "cancelled";
+
 export type Plugin = { model: "plugin", id: string, createdAt: string, updatedAt: string, checkedAt: string | null, directory: string, enabled: boolean, url: string | null, };
 
-export type ProxySetting = { "type": "enabled", http: string, https: string, auth: ProxySettingAuth | null, } | { "type": "disabled" };
+export type ProxySetting = { "type": "enabled", http: string, https: string, 
+/**
+ * SOCKS5 proxy for whatever the HTTP and HTTPS proxies don't cover
+ */
+socks?: string, auth: ProxySettingAuth | null, } | { "type": "disabled" } | { "type": "system" };
 
 export type ProxySettingAuth = { user: string, password: string, };
 
-export type Settings = { model: "settings", id: string, createdAt: string, updatedAt: string, appearance: string, editorFontSize: number, editorSoftWrap: boolean, interfaceFontSize: number, interfaceScale: number, openWorkspaceNewWindow: boolean | null, telemetry: boolean, theme: string, themeDark: string, themeLight: string, updateChannel: string, proxy: ProxySetting | null, };
+export type RequestHistory = { model: "request_history", id: string, createdAt: string, updatedAt: string, workspaceId: string, 
+/**
+ * Empty for ephemeral sends, like GraphQL introspection, which aren't of a saved request
+ */
+requestId: string, 
+/**
+ * The response, or the connection of a gRPC call, unless the send was ephemeral. It may
+ * have been deleted since.
+ */
+responseId: string | null, environmentId: string | null, 
+/**
+ * Name of the OS user that sent the request
+ */
+sentBy: string, 
+/**
+ * The HTTP method, or the service and method of a gRPC call
+ */
+method: string, 
+/**
+ * The URL that was finally requested, after rendering and any redirects, without credentials
+ */
+url: string, 
+/**
+ * The HTTP request before rendering, so the secrets it used aren't kept
+ */
+requestSnapshot: HttpRequest | null, status: number, elapsed: number, error: string | null, };
+
+export type RequestSummary = { model: "http_request" | "grpc_request", id: string, workspaceId: string, name: string, method: string | null, 
+/**
+ * Names of the folders containing the request, outermost first
+ */
+folderPath: Array<string>, };
+
+export type RunResult = { model: "run_result", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string, 
+/**
+ * Shared by every result from the same run of the folder
+ */
+runId: string, requestId: string, requestModel: "http_request" | "grpc_request", requestName: string, status: RunResultStatus, 
+/**
+ * The HTTP response or gRPC connection the request produced
+ */
+responseId: string | null, 
+/**
+ * HTTP status, or the gRPC status code
+ */
+statusCode: number | null, elapsed: number, error: string | null, };
+
+export type RunResultStatus = "passed" | "failed" | "skipped";
+
+export type SearchMatch = { model: "http_request" | "grpc_request" | "http_response", id: string, 
+/**
+ * The matching request, or the request the matching response belongs to
+ */
+requestId: string, requestName: string, url: string, 
+/**
+ * Text around the match, from whichever field matched best
+ */
+snippet: string, };
+
+export type Settings = { model: "settings", id: string, createdAt: string, updatedAt: string, appearance: string, editorFontSize: number, editorSoftWrap: boolean, interfaceFontSize: number, interfaceScale: number, openWorkspaceNewWindow: boolean | null, restoreLastLocation: boolean, telemetry: boolean, theme: string, themeDark: string, themeLight: string, updateChannel: string, proxy: ProxySetting | null, 
+/**
+ * Directory to store response bodies in, instead of the app data directory. Bodies saved
+ * before it changed are left where they are.
+ */
+responseBodyDir: string | null, 
+/**
+ * Milliseconds to wait for plugins to answer a call before giving up. Zero waits forever.
+ */
+pluginTimeout: number, };
+
+export type TlsVersionSetting = "1.2" | "1.3";
 
-export type Workspace = { model: "workspace", id: string, createdAt: string, updatedAt: string, name: string, description: string, variables: Array<EnvironmentVariable>, settingValidateCertificates: boolean, settingFollowRedirects: boolean, settingRequestTimeout: number, };
+export type Workspace = { model: "workspace", id: string, createdAt: string, updatedAt: string, name: string, description: string, variables: Array<EnvironmentVariable>, grpcMetadata: Array<GrpcMetadataEntry>, settingValidateCertificates: boolean, settingFollowRedirects: boolean, settingRequestTimeout: number, settingGrpcMaxSendMessageSize: number, settingGrpcMaxReceiveMessageSize: number, settingUserAgent: string, settingSendDefaultAccept: boolean, settingHttpVersion: HttpVersionSetting, 
+/**
+ * Body type given to new requests that are created without one
+ */
+settingDefaultBodyType: string | null, 
+/**
+ * Method given to new requests that are created without a body type. Empty means GET.
+ */
+settingDefaultMethod: string, 
+/**
+ * Responses taking longer than this many milliseconds are flagged as slow. Zero disables it.
+ */
+settingSlowResponseThreshold: number, 
+/**
+ * Store the raw protobuf bytes of every gRPC message, not just its JSON
+ */
+settingGrpcCaptureRawMessages: boolean, 
+/**
+ * Lowest TLS version requests may negotiate. None uses the TLS library's default.
+ */
+settingTlsMinVersion: TlsVersionSetting | null, 
+/**
+ * Highest TLS version requests may negotiate. None uses the TLS library's default.
+ */
+settingTlsMaxVersion: TlsVersionSetting | null, 
+/**
+ * Proxy for the workspace's requests. None uses the global proxy setting.
+ */
+settingProxy: ProxySetting | null, 
+/**
+ * Cache GET responses, serving them while fresh and revalidating them with the server once
+ * they're stale
+ */
+settingHttpCache: boolean, 
+/**
+ * How many times a request is retried after a connection error, a timeout, or a 429, 502,
+ * 503, or 504 response
+ */
+settingRetryCount: number, 
+/**
+ * Milliseconds to wait before the first retry, doubling for each one after it. A
+ * `Retry-After` header on the response takes precedence.
+ */
+settingRetryBackoff: number, 
+/**
+ * When a request in the workspace was last sent or a model in it changed. Only bumped
+ * once a minute, so it's approximate.
+ */
+lastActiveAt: string | null, };
@@ -6,10 +6,30 @@ pub enum Error {
     SqlError(#[from] rusqlite::Error),
     #[error("JSON error: {0}")]
     JsonError(#[from] serde_json::Error),
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
     #[error("Model not found {0}")]
     ModelNotFound(String),
+    #[error("More than one model matches {0}")]
+    AmbiguousModel(String),
+    #[error("Invalid database backup: {0}")]
+    InvalidBackup(String),
+    #[error("Invalid color {0}")]
+    InvalidColor(String),
+    #[error("Secret variable {0} refers to a keychain entry it doesn't own")]
+    ForeignSecretRef(String),
     #[error("unknown error")]
     Unknown,
 }
 
+impl Error {
+    /// Whether the error means the model doesn't exist, including a lookup that returned no rows
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            Error::ModelNotFound(_) | Error::SqlError(rusqlite::Error::QueryReturnedNoRows)
+        )
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
@@ -1,5 +1,5 @@
 pub mod models;
 pub mod queries;
-mod error;
+pub mod error;
 
 pub mod plugin;
\ No newline at end of file
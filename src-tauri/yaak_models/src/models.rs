@@ -1,6 +1,8 @@
 use chrono::NaiveDateTime;
+use rusqlite::types::Type;
 use rusqlite::Row;
 use sea_query::Iden;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::BTreeMap;
@@ -13,9 +15,15 @@ pub enum ProxySetting {
     Enabled {
         http: String,
         https: String,
+        /// SOCKS5 proxy for whatever the HTTP and HTTPS proxies don't cover
+        #[serde(default)]
+        #[ts(optional, as = "Option<String>")]
+        socks: String,
         auth: Option<ProxySettingAuth>,
     },
     Disabled,
+    /// Use the proxy configured for the system, or in the `HTTP(S)_PROXY` environment variables
+    System,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -26,6 +34,57 @@ pub struct ProxySettingAuth {
     pub password: String,
 }
 
+/// HTTP version to use when sending a request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "kebab-case")]
+#[ts(export, export_to = "models.ts")]
+pub enum HttpVersionSetting {
+    /// Negotiate the version with the server (ALPN for HTTPS, HTTP/1.1 otherwise)
+    Auto,
+    /// Only ever use HTTP/1.1
+    Http1,
+    /// Require HTTP/2, failing if the server doesn't negotiate it
+    Http2,
+    /// Speak HTTP/2 from the start without negotiating, which also works for plain HTTP
+    Http2PriorKnowledge,
+}
+
+impl Default for HttpVersionSetting {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+impl HttpVersionSetting {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpVersionSetting::Auto => "auto",
+            HttpVersionSetting::Http1 => "http1",
+            HttpVersionSetting::Http2 => "http2",
+            HttpVersionSetting::Http2PriorKnowledge => "http2-prior-knowledge",
+        }
+    }
+}
+
+/// TLS protocol version, for bounding which versions a request may negotiate
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "models.ts")]
+pub enum TlsVersionSetting {
+    #[serde(rename = "1.2")]
+    Tls1_2,
+    #[serde(rename = "1.3")]
+    Tls1_3,
+}
+
+impl TlsVersionSetting {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TlsVersionSetting::Tls1_2 => "1.2",
+            TlsVersionSetting::Tls1_3 => "1.3",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
 #[serde(default, rename_all = "camelCase")]
 #[ts(export, export_to = "models.ts")]
@@ -42,12 +101,19 @@ pub struct Settings {
     pub interface_font_size: i32,
     pub interface_scale: f32,
     pub open_workspace_new_window: Option<bool>,
+    #[serde(default = "default_true")]
+    pub restore_last_location: bool,
     pub telemetry: bool,
     pub theme: String,
     pub theme_dark: String,
     pub theme_light: String,
     pub update_channel: String,
     pub proxy: Option<ProxySetting>,
+    /// Directory to store response bodies in, instead of the app data directory. Bodies saved
+    /// before it changed are left where they are.
+    pub response_body_dir: Option<String>,
+    /// Milliseconds to wait for plugins to answer a call before giving up. Zero waits forever.
+    pub plugin_timeout: i32,
 }
 
 #[derive(Iden)]
@@ -65,7 +131,10 @@ pub enum SettingsIden {
     InterfaceFontSize,
     InterfaceScale,
     OpenWorkspaceNewWindow,
+    PluginTimeout,
     Proxy,
+    ResponseBodyDir,
+    RestoreLastLocation,
     Telemetry,
     Theme,
     ThemeDark,
@@ -89,7 +158,10 @@ impl<'s> TryFrom<&Row<'s>> for Settings {
             interface_font_size: r.get("interface_font_size")?,
             interface_scale: r.get("interface_scale")?,
             open_workspace_new_window: r.get("open_workspace_new_window")?,
-            proxy: proxy.map(|p| -> ProxySetting { serde_json::from_str(p.as_str()).unwrap() }),
+            plugin_timeout: r.get("plugin_timeout")?,
+            proxy: proxy.and_then(|p| serde_json::from_str(p.as_str()).ok()),
+            response_body_dir: r.get("response_body_dir")?,
+            restore_last_location: r.get("restore_last_location")?,
             telemetry: r.get("telemetry")?,
             theme: r.get("theme")?,
             theme_dark: r.get("theme_dark")?,
@@ -111,6 +183,7 @@ pub struct Workspace {
     pub name: String,
     pub description: String,
     pub variables: Vec<EnvironmentVariable>,
+    pub grpc_metadata: Vec<GrpcMetadataEntry>,
 
     // Settings
     #[serde(default = "default_true")]
@@ -118,6 +191,39 @@ pub struct Workspace {
     #[serde(default = "default_true")]
     pub setting_follow_redirects: bool,
     pub setting_request_timeout: i32,
+    pub setting_grpc_max_send_message_size: i32,
+    pub setting_grpc_max_receive_message_size: i32,
+    pub setting_user_agent: String,
+    #[serde(default = "default_true")]
+    pub setting_send_default_accept: bool,
+    pub setting_http_version: HttpVersionSetting,
+    /// Body type given to new requests that are created without one
+    pub setting_default_body_type: Option<String>,
+    /// Method given to new requests that are created without a body type. Empty means GET.
+    pub setting_default_method: String,
+    /// Responses taking longer than this many milliseconds are flagged as slow. Zero disables it.
+    pub setting_slow_response_threshold: i32,
+    /// Store the raw protobuf bytes of every gRPC message, not just its JSON
+    pub setting_grpc_capture_raw_messages: bool,
+    /// Lowest TLS version requests may negotiate. None uses the TLS library's default.
+    pub setting_tls_min_version: Option<TlsVersionSetting>,
+    /// Highest TLS version requests may negotiate. None uses the TLS library's default.
+    pub setting_tls_max_version: Option<TlsVersionSetting>,
+    /// Proxy for the workspace's requests. None uses the global proxy setting.
+    pub setting_proxy: Option<ProxySetting>,
+    /// Cache GET responses, serving them while fresh and revalidating them with the server once
+    /// they're stale
+    pub setting_http_cache: bool,
+    /// How many times a request is retried after a connection error, a timeout, or a 429, 502,
+    /// 503, or 504 response
+    pub setting_retry_count: i32,
+    /// Milliseconds to wait before the first retry, doubling for each one after it. A
+    /// `Retry-After` header on the response takes precedence.
+    pub setting_retry_backoff: i32,
+
+    /// When a request in the workspace was last sent or a model in it changed. Only bumped
+    /// once a minute, so it's approximate.
+    pub last_active_at: Option<NaiveDateTime>,
 }
 
 #[derive(Iden)]
@@ -130,9 +236,26 @@ pub enum WorkspaceIden {
     UpdatedAt,
 
     Description,
+    GrpcMetadata,
+    LastActiveAt,
     Name,
+    SettingDefaultBodyType,
+    SettingDefaultMethod,
     SettingFollowRedirects,
+    SettingGrpcCaptureRawMessages,
+    SettingGrpcMaxReceiveMessageSize,
+    SettingGrpcMaxSendMessageSize,
+    SettingHttpCache,
+    SettingHttpVersion,
+    SettingProxy,
     SettingRequestTimeout,
+    SettingRetryBackoff,
+    SettingRetryCount,
+    SettingSendDefaultAccept,
+    SettingSlowResponseThreshold,
+    SettingTlsMaxVersion,
+    SettingTlsMinVersion,
+    SettingUserAgent,
     SettingValidateCertificates,
     Variables,
 }
@@ -142,6 +265,8 @@ impl<'s> TryFrom<&Row<'s>> for Workspace {
 
     fn try_from(r: &Row<'s>) -> Result<Self, Self::Error> {
         let variables: String = r.get("variables")?;
+        let grpc_metadata: String = r.get("grpc_metadata")?;
+        let setting_proxy: Option<String> = r.get("setting_proxy")?;
         Ok(Workspace {
             id: r.get("id")?,
             model: r.get("model")?,
@@ -150,9 +275,27 @@ impl<'s> TryFrom<&Row<'s>> for Workspace {
             name: r.get("name")?,
             description: r.get("description")?,
             variables: serde_json::from_str(variables.as_str()).unwrap_or_default(),
+            grpc_metadata: serde_json::from_str(grpc_metadata.as_str()).unwrap_or_default(),
             setting_validate_certificates: r.get("setting_validate_certificates")?,
             setting_follow_redirects: r.get("setting_follow_redirects")?,
             setting_request_timeout: r.get("setting_request_timeout")?,
+            setting_grpc_max_send_message_size: r.get("setting_grpc_max_send_message_size")?,
+            setting_grpc_max_receive_message_size: r
+                .get("setting_grpc_max_receive_message_size")?,
+            setting_user_agent: r.get("setting_user_agent")?,
+            setting_send_default_accept: r.get("setting_send_default_accept")?,
+            setting_http_version: enum_column(r, "setting_http_version").unwrap_or_default(),
+            setting_default_body_type: r.get("setting_default_body_type")?,
+            setting_default_method: r.get("setting_default_method")?,
+            setting_slow_response_threshold: r.get("setting_slow_response_threshold")?,
+            setting_grpc_capture_raw_messages: r.get("setting_grpc_capture_raw_messages")?,
+            setting_tls_min_version: enum_column(r, "setting_tls_min_version").ok(),
+            setting_tls_max_version: enum_column(r, "setting_tls_max_version").ok(),
+            setting_proxy: setting_proxy.and_then(|p| serde_json::from_str(p.as_str()).ok()),
+            setting_http_cache: r.get("setting_http_cache")?,
+            setting_retry_count: r.get("setting_retry_count")?,
+            setting_retry_backoff: r.get("setting_retry_backoff")?,
+            last_active_at: r.get("last_active_at")?,
         })
     }
 }
@@ -164,6 +307,7 @@ impl Workspace {
             model: "workspace".to_string(),
             setting_validate_certificates: true,
             setting_follow_redirects: true,
+            setting_send_default_accept: true,
             ..Default::default()
         }
     }
@@ -251,6 +395,9 @@ pub struct Environment {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 
+    pub color: Option<String>,
+    /// Sending to a production environment requires explicit confirmation
+    pub is_production: bool,
     pub name: String,
     pub variables: Vec<EnvironmentVariable>,
 }
@@ -265,6 +412,8 @@ pub enum EnvironmentIden {
     UpdatedAt,
     WorkspaceId,
 
+    Color,
+    IsProduction,
     Name,
     Variables,
 }
@@ -280,6 +429,8 @@ impl<'s> TryFrom<&Row<'s>> for Environment {
             workspace_id: r.get("workspace_id")?,
             created_at: r.get("created_at")?,
             updated_at: r.get("updated_at")?,
+            color: r.get("color")?,
+            is_production: r.get("is_production")?,
             name: r.get("name")?,
             variables: serde_json::from_str(variables.as_str()).unwrap_or_default(),
         })
@@ -295,6 +446,9 @@ pub struct EnvironmentVariable {
     pub enabled: bool,
     pub name: String,
     pub value: String,
+    /// Secret values are kept in the OS keychain instead of the database, and left out of exports
+    #[ts(optional, as = "Option<bool>")]
+    pub secret: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
@@ -392,6 +546,34 @@ pub struct HttpRequest {
     pub sort_priority: f32,
     pub url: String,
     pub url_parameters: Vec<HttpUrlParameter>,
+
+    /// JavaScript run before the request is rendered and sent. It can change the request and set
+    /// variables.
+    pub pre_request_script: String,
+    /// JavaScript run after the response finishes. It can extract values into variables.
+    pub post_response_script: String,
+    /// Whether the user wrote or approved the scripts. Imported scripts don't run until they are.
+    pub scripts_trusted: bool,
+
+    // Settings
+    pub setting_ignore_cookies: bool,
+    /// Proxy to use for this request only. When set, it takes precedence over the global proxy
+    /// setting. When None, the global setting applies.
+    pub setting_proxy: Option<ProxySetting>,
+    pub setting_server_name: String,
+    /// HTTP version for this request only. When None, the workspace setting applies.
+    pub setting_http_version: Option<HttpVersionSetting>,
+    /// Whether this request follows redirects. When None, the workspace setting applies.
+    pub setting_follow_redirects: Option<bool>,
+    /// Milliseconds to wait for each attempt. When None, the workspace setting applies.
+    pub setting_request_timeout: Option<i32>,
+    /// How many times to retry this request. When None, the workspace setting applies.
+    pub setting_retry_count: Option<i32>,
+    /// Milliseconds to wait before the first retry. When None, the workspace setting applies.
+    pub setting_retry_backoff: Option<i32>,
+    /// Whether to retry methods that aren't idempotent, like POST, which may then take effect
+    /// more than once
+    pub setting_retry_non_idempotent: bool,
 }
 
 #[derive(Iden)]
@@ -412,6 +594,18 @@ pub enum HttpRequestIden {
     Headers,
     Method,
     Name,
+    PostResponseScript,
+    PreRequestScript,
+    ScriptsTrusted,
+    SettingFollowRedirects,
+    SettingHttpVersion,
+    SettingIgnoreCookies,
+    SettingProxy,
+    SettingRequestTimeout,
+    SettingRetryBackoff,
+    SettingRetryCount,
+    SettingRetryNonIdempotent,
+    SettingServerName,
     SortPriority,
     Url,
     UrlParameters,
@@ -425,6 +619,7 @@ impl<'s> TryFrom<&Row<'s>> for HttpRequest {
         let body: String = r.get("body")?;
         let authentication: String = r.get("authentication")?;
         let headers: String = r.get("headers")?;
+        let setting_proxy: Option<String> = r.get("setting_proxy")?;
         Ok(HttpRequest {
             id: r.get("id")?,
             model: r.get("model")?,
@@ -442,6 +637,80 @@ impl<'s> TryFrom<&Row<'s>> for HttpRequest {
             headers: serde_json::from_str(headers.as_str()).unwrap_or_default(),
             folder_id: r.get("folder_id")?,
             name: r.get("name")?,
+            pre_request_script: r.get("pre_request_script")?,
+            post_response_script: r.get("post_response_script")?,
+            scripts_trusted: r.get("scripts_trusted")?,
+            setting_ignore_cookies: r.get("setting_ignore_cookies")?,
+            setting_proxy: setting_proxy.and_then(|p| serde_json::from_str(p.as_str()).ok()),
+            setting_server_name: r.get("setting_server_name")?,
+            setting_http_version: enum_column(r, "setting_http_version").ok(),
+            setting_follow_redirects: r.get("setting_follow_redirects")?,
+            setting_request_timeout: r.get("setting_request_timeout")?,
+            setting_retry_count: r.get("setting_retry_count")?,
+            setting_retry_backoff: r.get("setting_retry_backoff")?,
+            setting_retry_non_idempotent: r.get("setting_retry_non_idempotent")?,
+        })
+    }
+}
+
+/// Just enough of an HTTP or gRPC request to find and open it, without its body or headers
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "models.ts")]
+pub struct RequestSummary {
+    #[ts(type = "\"http_request\" | \"grpc_request\"")]
+    pub model: String,
+    pub id: String,
+    pub workspace_id: String,
+    pub name: String,
+    pub method: Option<String>,
+    /// Names of the folders containing the request, outermost first
+    pub folder_path: Vec<String>,
+}
+
+impl<'s> TryFrom<&Row<'s>> for RequestSummary {
+    type Error = rusqlite::Error;
+
+    fn try_from(r: &Row<'s>) -> Result<Self, Self::Error> {
+        let folder_path: String = r.get("folder_path")?;
+        Ok(RequestSummary {
+            model: r.get("model")?,
+            id: r.get("id")?,
+            workspace_id: r.get("workspace_id")?,
+            name: r.get("name")?,
+            method: r.get("method")?,
+            folder_path: serde_json::from_str(folder_path.as_str()).unwrap_or_default(),
+        })
+    }
+}
+
+/// A request, or a response to one, whose text matched a workspace search
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "models.ts")]
+pub struct SearchMatch {
+    #[ts(type = "\"http_request\" | \"grpc_request\" | \"http_response\"")]
+    pub model: String,
+    pub id: String,
+    /// The matching request, or the request the matching response belongs to
+    pub request_id: String,
+    pub request_name: String,
+    pub url: String,
+    /// Text around the match, from whichever field matched best
+    pub snippet: String,
+}
+
+impl<'s> TryFrom<&Row<'s>> for SearchMatch {
+    type Error = rusqlite::Error;
+
+    fn try_from(r: &Row<'s>) -> Result<Self, Self::Error> {
+        Ok(SearchMatch {
+            model: r.get("model")?,
+            id: r.get("id")?,
+            request_id: r.get("request_id")?,
+            request_name: r.get("request_name")?,
+            url: r.get("url")?,
+            snippet: r.get("snippet")?,
         })
     }
 }
@@ -469,6 +738,25 @@ impl Default for HttpResponseState {
     }
 }
 
+/// Where the body of a response came from
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "models.ts")]
+pub enum HttpResponseSource {
+    /// Received from the server
+    Network,
+    /// The server replied 304 Not Modified, so the body of an earlier response was reused
+    NotModified,
+    /// Served from a local cache without contacting the server
+    Cache,
+}
+
+impl Default for HttpResponseSource {
+    fn default() -> Self {
+        Self::Network
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
 #[serde(default, rename_all = "camelCase")]
 #[ts(export, export_to = "models.ts")]
@@ -481,20 +769,51 @@ pub struct HttpResponse {
     pub workspace_id: String,
     pub request_id: String,
 
+    /// How many times the request was sent, counting retries
+    pub attempts: i32,
     pub body_path: Option<String>,
-    pub content_length: Option<i32>,
+    #[ts(type = "number | null")]
+    pub content_length: Option<i64>,
     pub elapsed: i32,
     pub elapsed_headers: i32,
     pub error: Option<String>,
     pub headers: Vec<HttpResponseHeader>,
+    /// File with a reconstruction of the request as it was written to the connection, next to the
+    /// body. Headers the client adds itself are filled in as it would add them, credentials are
+    /// redacted, and HTTP/2 requests are written the way HTTP/1.1 would send them.
+    pub raw_request_path: Option<String>,
     pub remote_addr: Option<String>,
+    /// Size of the request body that was sent, in bytes. None when it was streamed without a
+    /// known length.
+    pub request_content_length: Option<i32>,
+    /// The rendered request that produced this response, for replaying it exactly. The values of
+    /// secret variables are replaced with references to them.
+    pub request_snapshot: Option<HttpRequest>,
+    /// Whether the response took longer than the workspace's slow response threshold
+    pub slow: bool,
+    pub source: HttpResponseSource,
     pub status: i32,
     pub status_reason: Option<String>,
     pub state: HttpResponseState,
+    /// The server's TLS certificate. The negotiated protocol version and cipher aren't
+    /// included because the native TLS backend doesn't expose them.
+    pub tls_certificate: Option<HttpResponseTlsCertificate>,
     pub url: String,
     pub version: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "models.ts")]
+pub struct HttpResponseTlsCertificate {
+    pub subject: String,
+    pub issuer: String,
+    pub serial_number: String,
+    pub not_before: NaiveDateTime,
+    pub not_after: NaiveDateTime,
+    pub subject_alt_names: Vec<String>,
+}
+
 #[derive(Iden)]
 pub enum HttpResponseIden {
     #[iden = "http_responses"]
@@ -506,16 +825,23 @@ pub enum HttpResponseIden {
     WorkspaceId,
     RequestId,
 
+    Attempts,
     BodyPath,
     ContentLength,
     Elapsed,
     ElapsedHeaders,
     Error,
     Headers,
+    RawRequestPath,
     RemoteAddr,
+    RequestContentLength,
+    RequestSnapshot,
+    Slow,
+    Source,
     Status,
     StatusReason,
     State,
+    TlsCertificate,
     Url,
     Version,
 }
@@ -525,12 +851,15 @@ impl<'s> TryFrom<&Row<'s>> for HttpResponse {
 
     fn try_from(r: &Row<'s>) -> Result<Self, Self::Error> {
         let headers: String = r.get("headers")?;
-        let state: String = r.get("state")?;
+        let source: String = r.get("source")?;
+        let tls_certificate: Option<String> = r.get("tls_certificate")?;
+        let request_snapshot: Option<String> = r.get("request_snapshot")?;
         Ok(HttpResponse {
             id: r.get("id")?,
             model: r.get("model")?,
             workspace_id: r.get("workspace_id")?,
             request_id: r.get("request_id")?,
+            attempts: r.get("attempts")?,
             created_at: r.get("created_at")?,
             updated_at: r.get("updated_at")?,
             error: r.get("error")?,
@@ -539,12 +868,18 @@ impl<'s> TryFrom<&Row<'s>> for HttpResponse {
             version: r.get("version")?,
             elapsed: r.get("elapsed")?,
             elapsed_headers: r.get("elapsed_headers")?,
+            raw_request_path: r.get("raw_request_path")?,
             remote_addr: r.get("remote_addr")?,
+            request_content_length: r.get("request_content_length")?,
+            request_snapshot: request_snapshot.and_then(|s| serde_json::from_str(&s).ok()),
+            slow: r.get("slow")?,
+            source: serde_json::from_str(format!(r#""{source}""#).as_str()).unwrap_or_default(),
             status: r.get("status")?,
             status_reason: r.get("status_reason")?,
-            state: serde_json::from_str(format!(r#""{state}""#).as_str()).unwrap(),
+            state: enum_column(r, "state")?,
             body_path: r.get("body_path")?,
             headers: serde_json::from_str(headers.as_str()).unwrap_or_default(),
+            tls_certificate: tls_certificate.and_then(|c| serde_json::from_str(&c).ok()),
         })
     }
 }
@@ -558,6 +893,474 @@ impl HttpResponse {
     }
 }
 
+/// A response saved under a name for documenting and mocking its request. The body is a frozen
+/// copy, so it outlives the response it came from.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "models.ts")]
+pub struct HttpExample {
+    #[ts(type = "\"http_example\"")]
+    pub model: String,
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub workspace_id: String,
+    pub request_id: String,
+
+    pub body_path: Option<String>,
+    #[ts(type = "number | null")]
+    pub content_length: Option<i64>,
+    pub headers: Vec<HttpResponseHeader>,
+    pub name: String,
+    pub status: i32,
+}
+
+#[derive(Iden)]
+pub enum HttpExampleIden {
+    #[iden = "http_examples"]
+    Table,
+    Model,
+    Id,
+    CreatedAt,
+    UpdatedAt,
+    WorkspaceId,
+    RequestId,
+
+    BodyPath,
+    ContentLength,
+    Headers,
+    Name,
+    Status,
+}
+
+impl<'s> TryFrom<&Row<'s>> for HttpExample {
+    type Error = rusqlite::Error;
+
+    fn try_from(r: &Row<'s>) -> Result<Self, Self::Error> {
+        let headers: String = r.get("headers")?;
+        Ok(HttpExample {
+            id: r.get("id")?,
+            model: r.get("model")?,
+            workspace_id: r.get("workspace_id")?,
+            request_id: r.get("request_id")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            body_path: r.get("body_path")?,
+            content_length: r.get("content_length")?,
+            headers: serde_json::from_str(headers.as_str()).unwrap_or_default(),
+            name: r.get("name")?,
+            status: r.get("status")?,
+        })
+    }
+}
+
+/// A server-sent event received on an HTTP response, stored as it arrives so the stream can be
+/// watched live
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "models.ts")]
+pub struct HttpResponseEvent {
+    #[ts(type = "\"http_response_event\"")]
+    pub model: String,
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub workspace_id: String,
+    pub response_id: String,
+
+    pub event_type: String,
+    pub data: String,
+    /// The event's `id` field, if the server sent one
+    pub event_id: Option<String>,
+    pub retry: Option<i32>,
+}
+
+#[derive(Iden)]
+pub enum HttpResponseEventIden {
+    #[iden = "http_response_events"]
+    Table,
+    Model,
+    Id,
+    CreatedAt,
+    UpdatedAt,
+    WorkspaceId,
+    ResponseId,
+
+    Data,
+    EventId,
+    EventType,
+    Retry,
+}
+
+impl<'s> TryFrom<&Row<'s>> for HttpResponseEvent {
+    type Error = rusqlite::Error;
+
+    fn try_from(r: &Row<'s>) -> Result<Self, Self::Error> {
+        Ok(HttpResponseEvent {
+            id: r.get("id")?,
+            model: r.get("model")?,
+            workspace_id: r.get("workspace_id")?,
+            response_id: r.get("response_id")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            event_type: r.get("event_type")?,
+            data: r.get("data")?,
+            event_id: r.get("event_id")?,
+            retry: r.get("retry")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Default, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "models.ts")]
+pub enum HttpTimelineEventType {
+    /// The request line and headers handed to the HTTP client
+    #[default]
+    RequestHeaders,
+    DnsLookup,
+    /// Which address the client connected to, and the TLS certificate it was given. Its duration
+    /// covers connecting, the TLS handshake, and waiting for the server, which the client can't
+    /// time separately.
+    Connection,
+    /// The status line and headers, once the first byte of the response arrived
+    ResponseHeaders,
+    Download,
+}
+
+/// One phase of sending an HTTP request, for showing the response's timeline
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "models.ts")]
+pub struct HttpTimelineEvent {
+    #[ts(type = "\"http_timeline_event\"")]
+    pub model: String,
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub workspace_id: String,
+    pub response_id: String,
+
+    pub event_type: HttpTimelineEventType,
+    pub content: String,
+    pub headers: Vec<HttpResponseHeader>,
+    /// Milliseconds from starting the send until the phase started
+    pub elapsed: i32,
+    /// How long the phase took in milliseconds, for phases that aren't a single moment
+    pub duration: Option<i32>,
+}
+
+#[derive(Iden)]
+pub enum HttpTimelineEventIden {
+    #[iden = "http_timeline_events"]
+    Table,
+    Model,
+    Id,
+    CreatedAt,
+    UpdatedAt,
+    WorkspaceId,
+    ResponseId,
+
+    Content,
+    Duration,
+    Elapsed,
+    EventType,
+    Headers,
+}
+
+impl<'s> TryFrom<&Row<'s>> for HttpTimelineEvent {
+    type Error = rusqlite::Error;
+
+    fn try_from(r: &Row<'s>) -> Result<Self, Self::Error> {
+        let headers: String = r.get("headers")?;
+        Ok(HttpTimelineEvent {
+            id: r.get("id")?,
+            model: r.get("model")?,
+            workspace_id: r.get("workspace_id")?,
+            response_id: r.get("response_id")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            event_type: enum_column(r, "event_type")?,
+            content: r.get("content")?,
+            headers: serde_json::from_str(headers.as_str()).unwrap_or_default(),
+            elapsed: r.get("elapsed")?,
+            duration: r.get("duration")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Default, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "models.ts")]
+pub enum LoadTestState {
+    #[default]
+    Running,
+    Finished,
+    Cancelled,
+}
+
+/// Metrics from sending a request over and over for a while, from several tasks at once
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "models.ts")]
+pub struct LoadTest {
+    #[ts(type = "\"load_test\"")]
+    pub model: String,
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub workspace_id: String,
+    pub request_id: String,
+
+    /// How long to keep sending, in milliseconds
+    pub duration: i32,
+    /// How many requests are in flight at once
+    pub concurrency: i32,
+    pub state: LoadTestState,
+    /// Milliseconds since the test started
+    pub elapsed: i32,
+    /// Requests that got a response, whatever its status
+    pub requests: i32,
+    /// Requests that failed without a response, like timeouts and refused connections
+    pub errors: i32,
+    /// How many responses had each status code
+    pub status_counts: BTreeMap<u16, i32>,
+    /// Milliseconds from sending a request until its body was read, over every response
+    pub latency_min: i32,
+    pub latency_mean: i32,
+    pub latency_p50: i32,
+    pub latency_p90: i32,
+    pub latency_p95: i32,
+    pub latency_p99: i32,
+    pub latency_max: i32,
+}
+
+#[derive(Iden)]
+pub enum LoadTestIden {
+    #[iden = "load_tests"]
+    Table,
+    Model,
+    Id,
+    CreatedAt,
+    UpdatedAt,
+    WorkspaceId,
+    RequestId,
+
+    Concurrency,
+    Duration,
+    Elapsed,
+    Errors,
+    LatencyMax,
+    LatencyMean,
+    LatencyMin,
+    LatencyP50,
+    LatencyP90,
+    LatencyP95,
+    LatencyP99,
+    Requests,
+    State,
+    StatusCounts,
+}
+
+impl<'s> TryFrom<&Row<'s>> for LoadTest {
+    type Error = rusqlite::Error;
+
+    fn try_from(r: &Row<'s>) -> Result<Self, Self::Error> {
+        let status_counts: String = r.get("status_counts")?;
+        Ok(LoadTest {
+            id: r.get("id")?,
+            model: r.get("model")?,
+            workspace_id: r.get("workspace_id")?,
+            request_id: r.get("request_id")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            duration: r.get("duration")?,
+            concurrency: r.get("concurrency")?,
+            state: enum_column(r, "state")?,
+            elapsed: r.get("elapsed")?,
+            requests: r.get("requests")?,
+            errors: r.get("errors")?,
+            status_counts: serde_json::from_str(status_counts.as_str()).unwrap_or_default(),
+            latency_min: r.get("latency_min")?,
+            latency_mean: r.get("latency_mean")?,
+            latency_p50: r.get("latency_p50")?,
+            latency_p90: r.get("latency_p90")?,
+            latency_p95: r.get("latency_p95")?,
+            latency_p99: r.get("latency_p99")?,
+            latency_max: r.get("latency_max")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "models.ts")]
+pub enum RunResultStatus {
+    Passed,
+    Failed,
+    /// Not sent, because an earlier request failed and the run stopped
+    Skipped,
+}
+
+impl Default for RunResultStatus {
+    fn default() -> Self {
+        Self::Skipped
+    }
+}
+
+/// The outcome of one request in a folder run
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "models.ts")]
+pub struct RunResult {
+    #[ts(type = "\"run_result\"")]
+    pub model: String,
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub workspace_id: String,
+    pub folder_id: String,
+    /// Shared by every result from the same run of the folder
+    pub run_id: String,
+    pub request_id: String,
+    #[ts(type = "\"http_request\" | \"grpc_request\"")]
+    pub request_model: String,
+    pub request_name: String,
+
+    pub status: RunResultStatus,
+    /// The HTTP response or gRPC connection the request produced
+    pub response_id: Option<String>,
+    /// HTTP status, or the gRPC status code
+    pub status_code: Option<i32>,
+    pub elapsed: i32,
+    pub error: Option<String>,
+}
+
+#[derive(Iden)]
+pub enum RunResultIden {
+    #[iden = "run_results"]
+    Table,
+    Model,
+    Id,
+    CreatedAt,
+    UpdatedAt,
+    WorkspaceId,
+    FolderId,
+    RunId,
+    RequestId,
+    RequestModel,
+    RequestName,
+
+    Elapsed,
+    Error,
+    ResponseId,
+    Status,
+    StatusCode,
+}
+
+impl<'s> TryFrom<&Row<'s>> for RunResult {
+    type Error = rusqlite::Error;
+
+    fn try_from(r: &Row<'s>) -> Result<Self, Self::Error> {
+        Ok(RunResult {
+            id: r.get("id")?,
+            model: r.get("model")?,
+            workspace_id: r.get("workspace_id")?,
+            folder_id: r.get("folder_id")?,
+            run_id: r.get("run_id")?,
+            request_id: r.get("request_id")?,
+            request_model: r.get("request_model")?,
+            request_name: r.get("request_name")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            status: enum_column(r, "status")?,
+            response_id: r.get("response_id")?,
+            status_code: r.get("status_code")?,
+            elapsed: r.get("elapsed")?,
+            error: r.get("error")?,
+        })
+    }
+}
+
+/// A record of an HTTP request being sent, as it was actually sent. It outlives the response,
+/// so it's an audit trail of everything sent from the workspace.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "models.ts")]
+pub struct RequestHistory {
+    #[ts(type = "\"request_history\"")]
+    pub model: String,
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub workspace_id: String,
+    /// Empty for ephemeral sends, like GraphQL introspection, which aren't of a saved request
+    pub request_id: String,
+    /// The response, or the connection of a gRPC call, unless the send was ephemeral. It may
+    /// have been deleted since.
+    pub response_id: Option<String>,
+    pub environment_id: Option<String>,
+    /// Name of the OS user that sent the request
+    pub sent_by: String,
+
+    /// The HTTP method, or the service and method of a gRPC call
+    pub method: String,
+    /// The URL that was finally requested, after rendering and any redirects, without credentials
+    pub url: String,
+    /// The HTTP request before rendering, so the secrets it used aren't kept
+    pub request_snapshot: Option<HttpRequest>,
+    pub status: i32,
+    pub elapsed: i32,
+    pub error: Option<String>,
+}
+
+#[derive(Iden)]
+pub enum RequestHistoryIden {
+    #[iden = "request_history"]
+    Table,
+    Model,
+    Id,
+    CreatedAt,
+    UpdatedAt,
+    WorkspaceId,
+    RequestId,
+    ResponseId,
+    EnvironmentId,
+    SentBy,
+
+    Elapsed,
+    Error,
+    Method,
+    RequestSnapshot,
+    Status,
+    Url,
+}
+
+impl<'s> TryFrom<&Row<'s>> for RequestHistory {
+    type Error = rusqlite::Error;
+
+    fn try_from(r: &Row<'s>) -> Result<Self, Self::Error> {
+        let request_snapshot: Option<String> = r.get("request_snapshot")?;
+        Ok(RequestHistory {
+            id: r.get("id")?,
+            model: r.get("model")?,
+            workspace_id: r.get("workspace_id")?,
+            request_id: r.get("request_id")?,
+            response_id: r.get("response_id")?,
+            environment_id: r.get("environment_id")?,
+            sent_by: r.get("sent_by")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            method: r.get("method")?,
+            url: r.get("url")?,
+            request_snapshot: request_snapshot.and_then(|s| serde_json::from_str(&s).ok()),
+            status: r.get("status")?,
+            elapsed: r.get("elapsed")?,
+            error: r.get("error")?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
 #[serde(default, rename_all = "camelCase")]
 #[ts(export, export_to = "models.ts")]
@@ -584,6 +1387,8 @@ pub struct GrpcRequest {
     pub authentication_type: Option<String>,
     #[ts(type = "Record<string, any>")]
     pub authentication: BTreeMap<String, Value>,
+    #[serde(default = "default_true")]
+    pub inherit_metadata: bool,
     pub message: String,
     pub metadata: Vec<GrpcMetadataEntry>,
     pub method: Option<String>,
@@ -591,6 +1396,20 @@ pub struct GrpcRequest {
     pub service: Option<String>,
     pub sort_priority: f32,
     pub url: String,
+
+    // Settings
+    /// Whether to verify the server's TLS certificate. When None, the workspace setting applies.
+    pub setting_validate_certificates: Option<bool>,
+    /// Path to a PEM file of CA certificates to trust, on top of the system's
+    pub setting_ca_certificate: Option<String>,
+    /// Path to a PEM file of the client certificate, for mutual TLS
+    pub setting_client_certificate: Option<String>,
+    /// Path to a PEM file of the client certificate's private key
+    pub setting_client_key: Option<String>,
+    /// Milliseconds between HTTP/2 keepalive pings. Zero disables keepalive.
+    pub setting_keepalive_interval: i32,
+    /// Deadline for each call, in milliseconds. Zero means no deadline.
+    pub setting_timeout: i32,
 }
 
 #[derive(Iden)]
@@ -606,11 +1425,18 @@ pub enum GrpcRequestIden {
 
     Authentication,
     AuthenticationType,
+    InheritMetadata,
     Message,
     Metadata,
     Method,
     Name,
     Service,
+    SettingCaCertificate,
+    SettingClientCertificate,
+    SettingClientKey,
+    SettingKeepaliveInterval,
+    SettingTimeout,
+    SettingValidateCertificates,
     SortPriority,
     Url,
 }
@@ -636,7 +1462,14 @@ impl<'s> TryFrom<&Row<'s>> for GrpcRequest {
             authentication: serde_json::from_str(authentication.as_str()).unwrap_or_default(),
             url: r.get("url")?,
             sort_priority: r.get("sort_priority")?,
+            inherit_metadata: r.get("inherit_metadata")?,
             metadata: serde_json::from_str(metadata.as_str()).unwrap_or_default(),
+            setting_validate_certificates: r.get("setting_validate_certificates")?,
+            setting_ca_certificate: r.get("setting_ca_certificate")?,
+            setting_client_certificate: r.get("setting_client_certificate")?,
+            setting_client_key: r.get("setting_client_key")?,
+            setting_keepalive_interval: r.get("setting_keepalive_interval")?,
+            setting_timeout: r.get("setting_timeout")?,
         })
     }
 }
@@ -704,7 +1537,6 @@ impl<'s> TryFrom<&Row<'s>> for GrpcConnection {
 
     fn try_from(r: &Row<'s>) -> Result<Self, Self::Error> {
         let trailers: String = r.get("trailers")?;
-        let state: String = r.get("state")?;
         Ok(GrpcConnection {
             id: r.get("id")?,
             model: r.get("model")?,
@@ -715,7 +1547,7 @@ impl<'s> TryFrom<&Row<'s>> for GrpcConnection {
             service: r.get("service")?,
             method: r.get("method")?,
             elapsed: r.get("elapsed")?,
-            state: serde_json::from_str(format!(r#""{state}""#).as_str()).unwrap(),
+            state: enum_column(r, "state")?,
             status: r.get("status")?,
             url: r.get("url")?,
             error: r.get("error")?,
@@ -756,6 +1588,8 @@ pub struct GrpcEvent {
     pub connection_id: String,
 
     pub content: String,
+    /// Base64 of the message's protobuf bytes, if the workspace captures them
+    pub content_raw: Option<String>,
     pub error: Option<String>,
     pub event_type: GrpcEventType,
     pub metadata: BTreeMap<String, String>,
@@ -775,6 +1609,7 @@ pub enum GrpcEventIden {
     ConnectionId,
 
     Content,
+    ContentRaw,
     Error,
     EventType,
     Metadata,
@@ -796,6 +1631,7 @@ impl<'s> TryFrom<&Row<'s>> for GrpcEvent {
             created_at: r.get("created_at")?,
             updated_at: r.get("updated_at")?,
             content: r.get("content")?,
+            content_raw: r.get("content_raw")?,
             event_type: serde_json::from_str(event_type.as_str()).unwrap_or_default(),
             metadata: serde_json::from_str(metadata.as_str()).unwrap_or_default(),
             status: r.get("status")?,
@@ -894,6 +1730,14 @@ impl<'s> TryFrom<&Row<'s>> for KeyValue {
     }
 }
 
+/// Whether the string is a hex color like `#f00`, `#ff0000`, or `#ff000080`
+pub fn is_hex_color(s: &str) -> bool {
+    match s.strip_prefix('#') {
+        Some(hex) => matches!(hex.len(), 3 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        None => false,
+    }
+}
+
 fn default_true() -> bool {
     true
 }
@@ -909,9 +1753,15 @@ pub enum ModelType {
     TypeGrpcConnection,
     TypeGrpcEvent,
     TypeGrpcRequest,
+    TypeHttpExample,
     TypeHttpRequest,
     TypeHttpResponse,
+    TypeHttpResponseEvent,
+    TypeHttpTimelineEvent,
+    TypeLoadTest,
     TypePlugin,
+    TypeRequestHistory,
+    TypeRunResult,
     TypeWorkspace,
 }
 
@@ -924,9 +1774,15 @@ impl ModelType {
             ModelType::TypeGrpcConnection => "gc",
             ModelType::TypeGrpcEvent => "ge",
             ModelType::TypeGrpcRequest => "gr",
+            ModelType::TypeHttpExample => "ex",
             ModelType::TypeHttpRequest => "rq",
             ModelType::TypeHttpResponse => "rs",
+            ModelType::TypeHttpResponseEvent => "se",
+            ModelType::TypeHttpTimelineEvent => "te",
+            ModelType::TypeLoadTest => "lt",
             ModelType::TypePlugin => "pg",
+            ModelType::TypeRequestHistory => "rh",
+            ModelType::TypeRunResult => "rr",
             ModelType::TypeWorkspace => "wk",
         }
         .to_string()
@@ -943,10 +1799,24 @@ pub enum AnyModel {
     GrpcConnection(GrpcConnection),
     GrpcEvent(GrpcEvent),
     GrpcRequest(GrpcRequest),
+    HttpExample(HttpExample),
     HttpRequest(HttpRequest),
     HttpResponse(HttpResponse),
+    HttpResponseEvent(HttpResponseEvent),
+    HttpTimelineEvent(HttpTimelineEvent),
+    LoadTest(LoadTest),
     Plugin(Plugin),
+    RequestHistory(RequestHistory),
+    RunResult(RunResult),
     Settings(Settings),
     KeyValue(KeyValue),
     Workspace(Workspace),
 }
+
+/// Read an enum stored as text, failing the row instead of panicking on an unknown value
+fn enum_column<T: DeserializeOwned>(r: &Row, col: &str) -> rusqlite::Result<T> {
+    let idx = r.as_ref().column_index(col)?;
+    let value: String = r.get(idx)?;
+    serde_json::from_str(format!(r#""{value}""#).as_str())
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, Box::new(e)))
+}
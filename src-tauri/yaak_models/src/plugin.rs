@@ -1,20 +1,24 @@
-use log::info;
+use crate::queries::migrate_secret_variables;
+use log::{info, warn};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 use serde::Deserialize;
 use sqlx::migrate::Migrator;
 use sqlx::sqlite::SqliteConnectOptions;
 use sqlx::SqlitePool;
-use std::fs::{create_dir_all, File};
+use std::fs::{create_dir_all, remove_file, rename, File};
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
-use tauri::async_runtime::Mutex;
 use tauri::path::BaseDirectory;
 use tauri::plugin::TauriPlugin;
 use tauri::{plugin, AppHandle, Manager, Runtime};
 
-pub struct SqliteConnection(pub Mutex<Pool<SqliteConnectionManager>>);
+pub struct SqliteConnection(pub Pool<SqliteConnectionManager>);
+
+/// A restored backup is staged here and swapped in on the next launch, since the live
+/// database can't be replaced while the connection pool has it open.
+pub const RESTORE_DB_FILE_NAME: &str = "db.sqlite.restore";
 
 #[derive(Default, Deserialize)]
 pub struct PluginConfig {
@@ -40,6 +44,18 @@ impl Builder {
 
                 let db_file_path = app_path.join("db.sqlite");
 
+                let restore_file_path = app_path.join(RESTORE_DB_FILE_NAME);
+                if restore_file_path.exists() {
+                    info!("Restoring database from {:?}", restore_file_path);
+                    // A leftover WAL belongs to the old database and must not be replayed
+                    // onto the restored one
+                    for suffix in ["-wal", "-shm"] {
+                        let _ = remove_file(app_path.join(format!("db.sqlite{suffix}")));
+                    }
+                    rename(restore_file_path, db_file_path.clone())
+                        .expect("Failed to restore database");
+                }
+
                 {
                     let db_file_path = db_file_path.clone();
                     tauri::async_runtime::block_on(async move {
@@ -47,14 +63,26 @@ impl Builder {
                     });
                 };
 
-                let manager = SqliteConnectionManager::file(db_file_path);
+                // WAL lets reads proceed while a write is in progress. SQLite still allows
+                // only one writer at a time, so the busy timeout makes concurrent writers wait
+                // for their turn instead of failing with SQLITE_BUSY.
+                let manager = SqliteConnectionManager::file(db_file_path).with_init(|c| {
+                    c.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
+                    c.busy_timeout(Duration::from_secs(10))
+                });
                 let pool = Pool::builder()
                     .max_size(100) // Up from 10 (just in case)
                     .connection_timeout(Duration::from_secs(10)) // Down from 30
                     .build(manager)
                     .unwrap();
 
-                app.manage(SqliteConnection(Mutex::new(pool)));
+                if let Err(e) =
+                    migrate_secret_variables(&pool.get().unwrap(), &app.config().identifier)
+                {
+                    warn!("Failed to move secret variables to the keychain {e:?}");
+                }
+
+                app.manage(SqliteConnection(pool));
 
                 Ok(())
             })
@@ -1,27 +1,39 @@
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
+use std::path::Path;
 
-use crate::error::Error::ModelNotFound;
+use crate::error::Error::{
+    AmbiguousModel, ForeignSecretRef, InvalidBackup, InvalidColor, ModelNotFound, Unknown,
+};
 use crate::error::Result;
 use crate::models::{
-    CookieJar, CookieJarIden, Environment, EnvironmentIden, Folder, FolderIden, GrpcConnection,
-    GrpcConnectionIden, GrpcConnectionState, GrpcEvent, GrpcEventIden, GrpcRequest,
-    GrpcRequestIden, HttpRequest, HttpRequestIden, HttpResponse, HttpResponseHeader,
-    HttpResponseIden, HttpResponseState, KeyValue, KeyValueIden, ModelType, Plugin, PluginIden,
-    Settings, SettingsIden, Workspace, WorkspaceIden,
+    is_hex_color, CookieJar, CookieJarIden, Environment, EnvironmentIden, EnvironmentVariable,
+    Folder, FolderIden, GrpcConnection, GrpcConnectionIden, GrpcConnectionState, GrpcEvent,
+    GrpcEventIden, GrpcRequest, GrpcRequestIden, HttpExample, HttpExampleIden, HttpRequest,
+    HttpRequestIden, HttpResponse, HttpResponseEvent, HttpResponseEventIden, HttpResponseHeader,
+    HttpResponseIden, HttpResponseSource, HttpResponseState, HttpTimelineEvent,
+    HttpTimelineEventIden, KeyValue, KeyValueIden, LoadTest, LoadTestIden, ModelType, Plugin,
+    PluginIden, RequestHistory, RequestHistoryIden, RequestSummary, RunResult, RunResultIden,
+    SearchMatch, Settings, SettingsIden, Workspace, WorkspaceIden,
 };
-use crate::plugin::SqliteConnection;
-use log::{debug, error};
+use crate::plugin::{SqliteConnection, RESTORE_DB_FILE_NAME};
+use chrono::NaiveDateTime;
+use log::{debug, error, warn};
 use rand::distributions::{Alphanumeric, DistString};
-use rusqlite::OptionalExtension;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
 use sea_query::ColumnRef::Asterisk;
 use sea_query::Keyword::CurrentTimestamp;
-use sea_query::{Cond, Expr, OnConflict, Order, Query, SqliteQueryBuilder};
+use sea_query::{Alias, Cond, Expr, Func, Iden, OnConflict, Order, Query, SqliteQueryBuilder};
 use sea_query_rusqlite::RusqliteBinder;
 use serde::Serialize;
 use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindow};
+use yaak_secrets::{
+    delete_secret, is_keychain_ref, read_secret, store_secret, KEYCHAIN_REF_PREFIX,
+};
 
 const MAX_GRPC_CONNECTIONS_PER_REQUEST: usize = 20;
 const MAX_HTTP_RESPONSES_PER_REQUEST: usize = MAX_GRPC_CONNECTIONS_PER_REQUEST;
+const WORKSPACE_ACTIVITY_DEBOUNCE_SECS: u32 = 60;
 
 pub async fn set_key_value_string<R: Runtime>(
     mgr: &WebviewWindow<R>,
@@ -94,7 +106,7 @@ pub async fn set_key_value_raw<R: Runtime>(
     let existing = get_key_value_raw(w, namespace, key).await;
 
     let dbm = &*w.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::insert()
         .into_table(KeyValueIden::Table)
         .columns([
@@ -132,7 +144,7 @@ pub async fn get_key_value_raw<R: Runtime>(
     key: &str,
 ) -> Option<KeyValue> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::select()
         .from(KeyValueIden::Table)
         .column(Asterisk)
@@ -146,21 +158,422 @@ pub async fn get_key_value_raw<R: Runtime>(
     db.query_row(sql.as_str(), &*params.as_params(), |row| row.try_into()).ok()
 }
 
+/// Store a sensitive value in the OS keychain, keeping only a reference to it in the database.
+/// Where there's no usable keychain, the value is saved to the database like any other.
+pub async fn set_secure_key_value<R: Runtime>(
+    w: &WebviewWindow<R>,
+    namespace: &str,
+    key: &str,
+    value: &str,
+) -> (KeyValue, bool) {
+    let account = format!("{namespace}::{key}");
+    let stored = match store_secret(&w.config().identifier, &account, value) {
+        Ok(reference) => reference,
+        Err(e) => {
+            warn!("Keychain unavailable, storing {account} in the database instead: {e}");
+            value.to_string()
+        }
+    };
+    set_key_value_raw(w, namespace, key, &stored).await
+}
+
+/// Read a value stored with `set_secure_key_value`, from the keychain if that's where it is
+pub async fn get_secure_key_value<R: Runtime>(
+    mgr: &impl Manager<R>,
+    namespace: &str,
+    key: &str,
+) -> Option<String> {
+    let kv = get_key_value_raw(mgr, namespace, key).await?;
+    match read_secret(&mgr.config().identifier, &kv.value) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!("Failed to read {namespace}::{key} from the keychain: {e}");
+            None
+        }
+    }
+}
+
+/// Keychain accounts holding the values of a workspace or environment's variables, in order.
+/// Later variables that share a name get a numbered account, so each has its own.
+fn secret_variable_accounts(model_id: &str, variables: &[EnvironmentVariable]) -> Vec<String> {
+    let mut seen: BTreeMap<&str, usize> = BTreeMap::new();
+    variables
+        .iter()
+        .map(|v| {
+            let count = seen.entry(v.name.as_str()).or_default();
+            *count += 1;
+            match *count {
+                1 => format!("variables::{model_id}::{}", v.name),
+                n => format!("variables#{n}::{model_id}::{}", v.name),
+            }
+        })
+        .collect()
+}
+
+/// The reference a variable holds when its secret is in the keychain under `account`
+fn secret_ref(account: &str) -> String {
+    format!("{KEYCHAIN_REF_PREFIX}{account}")
+}
+
+/// Indexes of the secret variables that refer to their own keychain entry. Any other
+/// reference, like one copied from another model's variables, is never read or removed.
+fn owned_secret_refs(model_id: &str, variables: &[EnvironmentVariable]) -> Vec<usize> {
+    let accounts = secret_variable_accounts(model_id, variables);
+    variables
+        .iter()
+        .zip(accounts)
+        .enumerate()
+        .filter(|(_, (v, account))| v.secret && v.value == secret_ref(account))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Move the values of secret variables into the keychain, returning the variables to save with
+/// references in their place. Where there's no usable keychain, values are saved as they are.
+/// Secrets that the previously saved variables referred to and these no longer do are removed.
+/// A secret variable referring to any keychain entry but its own is refused.
+fn store_secret_variables(
+    service: &str,
+    model_id: &str,
+    variables: &[EnvironmentVariable],
+    previous: &[EnvironmentVariable],
+) -> Result<Vec<EnvironmentVariable>> {
+    let accounts = secret_variable_accounts(model_id, variables);
+    let mut stored = Vec::new();
+    for (v, account) in variables.iter().zip(accounts) {
+        if !v.secret {
+            stored.push(v.clone());
+            continue;
+        }
+        // A reference means the secret couldn't be read, so it's kept as is
+        if is_keychain_ref(&v.value) {
+            if v.value != secret_ref(&account) {
+                return Err(ForeignSecretRef(v.name.clone()));
+            }
+            stored.push(v.clone());
+            continue;
+        }
+        match store_secret(service, &account, &v.value) {
+            Ok(value) => stored.push(EnvironmentVariable { value, ..v.clone() }),
+            Err(e) => {
+                warn!("Keychain unavailable, storing {account} in the database instead: {e}");
+                stored.push(v.clone());
+            }
+        }
+    }
+    forget_secret_variables(service, model_id, previous, &stored);
+    Ok(stored)
+}
+
+/// Remove the secrets owned by the old variables that the new ones don't refer to
+fn forget_secret_variables(
+    service: &str,
+    model_id: &str,
+    old: &[EnvironmentVariable],
+    new: &[EnvironmentVariable],
+) {
+    for v in owned_secret_refs(model_id, old).into_iter().map(|i| &old[i]) {
+        if new.iter().any(|n| n.value == v.value) {
+            continue;
+        }
+        if let Err(e) = delete_secret(service, &v.value) {
+            warn!("Failed to remove secret variable {} from the keychain: {e}", v.name);
+        }
+    }
+}
+
+/// Replace references to secrets with their values from the keychain. A secret that can't be
+/// read keeps its reference, so saving the variables again doesn't lose it.
+fn resolve_secret_variables(service: &str, model_id: &str, variables: &mut [EnvironmentVariable]) {
+    for i in owned_secret_refs(model_id, variables) {
+        let v = &mut variables[i];
+        match read_secret(service, &v.value) {
+            Ok(value) => v.value = value,
+            Err(e) => warn!("Failed to read secret variable {} from the keychain: {e}", v.name),
+        }
+    }
+}
+
+/// Move secret variable values that were saved to the database, because the keychain wasn't
+/// available at the time, into the keychain. Run once the database is open.
+pub fn migrate_secret_variables(db: &Connection, service: &str) -> Result<()> {
+    let needs_migrating =
+        |vars: &[EnvironmentVariable]| vars.iter().any(|v| v.secret && !is_keychain_ref(&v.value));
+
+    let workspaces: Vec<Workspace> = {
+        let (sql, params) = Query::select()
+            .from(WorkspaceIden::Table)
+            .column(Asterisk)
+            .build_rusqlite(SqliteQueryBuilder);
+        let mut stmt = db.prepare(sql.as_str())?;
+        let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
+        collect_rows(items)
+    };
+    for w in workspaces.into_iter().filter(|w| needs_migrating(&w.variables)) {
+        let variables = match store_secret_variables(service, &w.id, &w.variables, &[]) {
+            Ok(v) => v,
+            Err(err) => {
+                warn!("Failed to migrate secret variables of {}: {err}", w.id);
+                continue;
+            }
+        };
+        let (sql, params) = Query::update()
+            .table(WorkspaceIden::Table)
+            .value(WorkspaceIden::Variables, serde_json::to_string(&variables)?)
+            .cond_where(Expr::col(WorkspaceIden::Id).eq(w.id.as_str()))
+            .build_rusqlite(SqliteQueryBuilder);
+        db.execute(sql.as_str(), &*params.as_params())?;
+    }
+
+    let environments: Vec<Environment> = {
+        let (sql, params) = Query::select()
+            .from(EnvironmentIden::Table)
+            .column(Asterisk)
+            .build_rusqlite(SqliteQueryBuilder);
+        let mut stmt = db.prepare(sql.as_str())?;
+        let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
+        collect_rows(items)
+    };
+    for e in environments.into_iter().filter(|e| needs_migrating(&e.variables)) {
+        let variables = match store_secret_variables(service, &e.id, &e.variables, &[]) {
+            Ok(v) => v,
+            Err(err) => {
+                warn!("Failed to migrate secret variables of {}: {err}", e.id);
+                continue;
+            }
+        };
+        let (sql, params) = Query::update()
+            .table(EnvironmentIden::Table)
+            .value(EnvironmentIden::Variables, serde_json::to_string(&variables)?)
+            .cond_where(Expr::col(EnvironmentIden::Id).eq(e.id.as_str()))
+            .build_rusqlite(SqliteQueryBuilder);
+        db.execute(sql.as_str(), &*params.as_params())?;
+    }
+
+    Ok(())
+}
+
+/// Every workspace, most recently active first
 pub async fn list_workspaces<R: Runtime>(mgr: &impl Manager<R>) -> Result<Vec<Workspace>> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::select()
         .from(WorkspaceIden::Table)
         .column(Asterisk)
+        .order_by(WorkspaceIden::LastActiveAt, Order::Desc)
+        .order_by(WorkspaceIden::Name, Order::Asc)
         .build_rusqlite(SqliteQueryBuilder);
     let mut stmt = db.prepare(sql.as_str())?;
     let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
-    Ok(items.map(|v| v.unwrap()).collect())
+    let mut workspaces: Vec<Workspace> = collect_rows(items);
+    for w in workspaces.iter_mut() {
+        resolve_secret_variables(&mgr.config().identifier, &w.id, &mut w.variables);
+    }
+    Ok(workspaces)
+}
+
+/// Summaries of every HTTP and gRPC request in every workspace, with the path of folders each
+/// one is nested in, built by walking the folder tree in a recursive query
+const LIST_ALL_REQUESTS_SQL: &str = r#"
+    WITH RECURSIVE folder_paths (id, path) AS (
+        SELECT id, json_array(name) FROM folders WHERE folder_id IS NULL
+        UNION ALL
+        SELECT f.id, json_insert(p.path, '$[#]', f.name)
+        FROM folders f
+        JOIN folder_paths p ON f.folder_id = p.id
+    )
+    SELECT r.model, r.id, r.workspace_id, r.name, r.method,
+           COALESCE(p.path, '[]') AS folder_path
+    FROM (
+        SELECT model, id, workspace_id, folder_id, name, method, sort_priority
+        FROM http_requests
+        UNION ALL
+        SELECT model, id, workspace_id, folder_id, name, method, sort_priority
+        FROM grpc_requests
+    ) r
+    LEFT JOIN folder_paths p ON p.id = r.folder_id
+    ORDER BY r.workspace_id, r.sort_priority
+"#;
+
+pub async fn list_all_requests<R: Runtime>(mgr: &impl Manager<R>) -> Result<Vec<RequestSummary>> {
+    let dbm = &*mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    query_request_summaries(&db)
+}
+
+fn query_request_summaries(db: &Connection) -> Result<Vec<RequestSummary>> {
+    let mut stmt = db.prepare(LIST_ALL_REQUESTS_SQL)?;
+    let items = stmt.query_map([], |row| row.try_into())?;
+    Ok(collect_rows(items))
+}
+
+/// The most recent workspace, and its most recent environment, cookie jar, and request, as
+/// tracked by the frontend in the "recent_*" key values. Only IDs that still exist are returned,
+/// and nothing at all when restoring the last location is turned off.
+const LAST_LOCATION_SQL: &str = r#"
+    WITH recent (key, id) AS (
+        SELECT key, CASE WHEN json_valid(value) THEN json_extract(value, '$[0]') END
+        FROM key_values
+        WHERE namespace = 'global' AND key LIKE 'recent_%'
+    )
+    SELECT w.id,
+           (SELECT e.id FROM recent r JOIN environments e ON e.id = r.id
+            WHERE r.key = 'recent_environments::' || w.id),
+           (SELECT c.id FROM recent r JOIN cookie_jars c ON c.id = r.id
+            WHERE r.key = 'recent_cookie_jars::' || w.id),
+           (SELECT r.id FROM recent r
+            WHERE r.key = 'recent_requests::' || w.id
+              AND (r.id IN (SELECT id FROM http_requests) OR r.id IN (SELECT id FROM grpc_requests)))
+    FROM recent r
+    JOIN workspaces w ON w.id = r.id
+    WHERE r.key = 'recent_workspaces'
+      AND COALESCE((SELECT restore_last_location FROM settings), TRUE)
+"#;
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct LastLocation {
+    pub workspace_id: String,
+    pub environment_id: Option<String>,
+    pub cookie_jar_id: Option<String>,
+    pub request_id: Option<String>,
+}
+
+/// Where the app was last, in a single query so it's cheap enough to run before the first window
+pub async fn get_last_location<R: Runtime>(mgr: &impl Manager<R>) -> Option<LastLocation> {
+    let dbm = &*mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    query_last_location(&db)
+}
+
+fn query_last_location(db: &Connection) -> Option<LastLocation> {
+    let location = db.query_row(LAST_LOCATION_SQL, [], |r| {
+        Ok(LastLocation {
+            workspace_id: r.get(0)?,
+            environment_id: r.get(1)?,
+            cookie_jar_id: r.get(2)?,
+            request_id: r.get(3)?,
+        })
+    });
+    match location.optional() {
+        Ok(location) => location,
+        Err(e) => {
+            warn!("Failed to get last location {e:?}");
+            None
+        }
+    }
+}
+
+/// Requests and responses in a workspace matching a full-text query, best matches first. Each
+/// index is searched separately, since FTS5 only ranks and snippets within one table. BM25
+/// scores from different tables can't be compared, so results are ranked within their own
+/// table and then interleaved, requests before responses.
+const SEARCH_WORKSPACE_SQL: &str = r#"
+    SELECT model, id, request_id, request_name, url, snippet
+    FROM (
+        SELECT *, ROW_NUMBER() OVER (ORDER BY rank) AS position, 0 AS source
+        FROM (
+            SELECT r.model, r.id, r.id AS request_id, r.name AS request_name, r.url,
+                   snippet(http_requests_fts, -1, '', '', '…', 12) AS snippet,
+                   bm25(http_requests_fts) AS rank
+            FROM http_requests_fts
+            JOIN http_requests r ON r.rowid = http_requests_fts.rowid
+            WHERE http_requests_fts MATCH ?1 AND r.workspace_id = ?2
+        )
+        UNION ALL
+        SELECT *, ROW_NUMBER() OVER (ORDER BY rank), 1
+        FROM (
+            SELECT r.model, r.id, r.id, r.name, r.url,
+                   snippet(grpc_requests_fts, -1, '', '', '…', 12),
+                   bm25(grpc_requests_fts) AS rank
+            FROM grpc_requests_fts
+            JOIN grpc_requests r ON r.rowid = grpc_requests_fts.rowid
+            WHERE grpc_requests_fts MATCH ?1 AND r.workspace_id = ?2
+        )
+        UNION ALL
+        SELECT *, ROW_NUMBER() OVER (ORDER BY rank), 2
+        FROM (
+            SELECT s.model, s.id, s.request_id, r.name, s.url,
+                   snippet(http_responses_fts, -1, '', '', '…', 12),
+                   bm25(http_responses_fts) AS rank
+            FROM http_responses_fts
+            JOIN http_responses s ON s.rowid = http_responses_fts.rowid
+            JOIN http_requests r ON r.id = s.request_id
+            WHERE http_responses_fts MATCH ?1 AND s.workspace_id = ?2
+        )
+    )
+    ORDER BY position, source
+    LIMIT ?3
+"#;
+
+pub async fn search_workspace<R: Runtime>(
+    mgr: &impl Manager<R>,
+    workspace_id: &str,
+    query: &str,
+    limit: Option<i64>,
+) -> Result<Vec<SearchMatch>> {
+    let dbm = &*mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    query_search(&db, workspace_id, query, limit)
+}
+
+fn query_search(
+    db: &Connection,
+    workspace_id: &str,
+    query: &str,
+    limit: Option<i64>,
+) -> Result<Vec<SearchMatch>> {
+    let query = match fts_query(query) {
+        Some(q) => q,
+        None => return Ok(Vec::new()),
+    };
+    let mut stmt = db.prepare(SEARCH_WORKSPACE_SQL)?;
+    let items = stmt
+        .query_map(params![query, workspace_id, limit.unwrap_or(i64::MAX)], |row| row.try_into())?;
+    Ok(collect_rows(items))
+}
+
+/// Turn what the user typed into an FTS5 query that matches every word, each as a prefix. Words
+/// are quoted so that FTS5 syntax, like `AND` or `-`, is searched for literally.
+fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> =
+        input.split_whitespace().map(|w| format!("\"{}\"*", w.replace('"', "\"\""))).collect();
+    match terms.is_empty() {
+        true => None,
+        false => Some(terms.join(" ")),
+    }
+}
+
+/// Make a finished response's body searchable. Bodies aren't stored in the database, so unlike
+/// the rest of the index, this isn't kept up to date by triggers.
+pub async fn index_http_response_body<R: Runtime>(
+    mgr: &impl Manager<R>,
+    response_id: &str,
+    body: &str,
+) -> Result<()> {
+    let dbm = &*mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    write_http_response_body_index(&db, response_id, body)
+}
+
+fn write_http_response_body_index(db: &Connection, response_id: &str, body: &str) -> Result<()> {
+    db.execute(
+        "UPDATE http_responses_fts SET body = ?1
+         WHERE rowid = (SELECT rowid FROM http_responses WHERE id = ?2)",
+        params![body, response_id],
+    )?;
+    Ok(())
 }
 
 pub async fn get_workspace<R: Runtime>(mgr: &impl Manager<R>, id: &str) -> Result<Workspace> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
+    let mut workspace = query_workspace(&db, id)?;
+    resolve_secret_variables(&mgr.config().identifier, id, &mut workspace.variables);
+    Ok(workspace)
+}
+
+/// A workspace as it's saved, with its secret variables still referring to the keychain
+fn query_workspace(db: &Connection, id: &str) -> Result<Workspace> {
     let (sql, params) = Query::select()
         .from(WorkspaceIden::Table)
         .column(Asterisk)
@@ -181,8 +594,25 @@ pub async fn upsert_workspace<R: Runtime>(
     let trimmed_name = workspace.name.trim();
 
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
+
+    let service = &window.config().identifier;
+    let previous = query_workspace(&db, &id).map(|w| w.variables).unwrap_or_default();
+    let variables = store_secret_variables(service, &id, &workspace.variables, &previous)?;
+    let workspace = Workspace {
+        id,
+        name: trimmed_name.to_string(),
+        variables,
+        ..workspace
+    };
+
+    let mut m = write_workspace(&db, workspace)?;
+    resolve_secret_variables(service, &m.id, &mut m.variables);
+    Ok(emit_upserted_model(window, m))
+}
 
+/// Insert or update a workspace, keeping its original `created_at`
+fn write_workspace(db: &Connection, workspace: Workspace) -> Result<Workspace> {
     let (sql, params) = Query::insert()
         .into_table(WorkspaceIden::Table)
         .columns([
@@ -192,31 +622,82 @@ pub async fn upsert_workspace<R: Runtime>(
             WorkspaceIden::Name,
             WorkspaceIden::Description,
             WorkspaceIden::Variables,
+            WorkspaceIden::GrpcMetadata,
             WorkspaceIden::SettingRequestTimeout,
             WorkspaceIden::SettingFollowRedirects,
             WorkspaceIden::SettingValidateCertificates,
+            WorkspaceIden::SettingGrpcMaxSendMessageSize,
+            WorkspaceIden::SettingGrpcMaxReceiveMessageSize,
+            WorkspaceIden::SettingUserAgent,
+            WorkspaceIden::SettingSendDefaultAccept,
+            WorkspaceIden::SettingHttpVersion,
+            WorkspaceIden::SettingDefaultBodyType,
+            WorkspaceIden::SettingDefaultMethod,
+            WorkspaceIden::SettingSlowResponseThreshold,
+            WorkspaceIden::SettingGrpcCaptureRawMessages,
+            WorkspaceIden::SettingTlsMinVersion,
+            WorkspaceIden::SettingTlsMaxVersion,
+            WorkspaceIden::SettingProxy,
+            WorkspaceIden::SettingHttpCache,
+            WorkspaceIden::SettingRetryCount,
+            WorkspaceIden::SettingRetryBackoff,
+            WorkspaceIden::LastActiveAt,
         ])
         .values_panic([
-            id.as_str().into(),
+            workspace.id.as_str().into(),
             CurrentTimestamp.into(),
             CurrentTimestamp.into(),
-            trimmed_name.into(),
+            workspace.name.as_str().into(),
             workspace.description.into(),
             serde_json::to_string(&workspace.variables)?.into(),
+            serde_json::to_string(&workspace.grpc_metadata)?.into(),
             workspace.setting_request_timeout.into(),
             workspace.setting_follow_redirects.into(),
             workspace.setting_validate_certificates.into(),
+            workspace.setting_grpc_max_send_message_size.into(),
+            workspace.setting_grpc_max_receive_message_size.into(),
+            workspace.setting_user_agent.into(),
+            workspace.setting_send_default_accept.into(),
+            workspace.setting_http_version.as_str().into(),
+            workspace.setting_default_body_type.as_ref().map(|s| s.as_str()).into(),
+            workspace.setting_default_method.as_str().into(),
+            workspace.setting_slow_response_threshold.into(),
+            workspace.setting_grpc_capture_raw_messages.into(),
+            workspace.setting_tls_min_version.as_ref().map(|v| v.as_str()).into(),
+            workspace.setting_tls_max_version.as_ref().map(|v| v.as_str()).into(),
+            workspace.setting_proxy.as_ref().map(serde_json::to_string).transpose()?.into(),
+            workspace.setting_http_cache.into(),
+            workspace.setting_retry_count.into(),
+            workspace.setting_retry_backoff.into(),
+            CurrentTimestamp.into(),
         ])
         .on_conflict(
-            OnConflict::column(GrpcRequestIden::Id)
+            OnConflict::column(WorkspaceIden::Id)
                 .update_columns([
                     WorkspaceIden::UpdatedAt,
                     WorkspaceIden::Name,
                     WorkspaceIden::Description,
                     WorkspaceIden::Variables,
+                    WorkspaceIden::GrpcMetadata,
                     WorkspaceIden::SettingRequestTimeout,
                     WorkspaceIden::SettingFollowRedirects,
                     WorkspaceIden::SettingValidateCertificates,
+                    WorkspaceIden::SettingGrpcMaxSendMessageSize,
+                    WorkspaceIden::SettingGrpcMaxReceiveMessageSize,
+                    WorkspaceIden::SettingUserAgent,
+                    WorkspaceIden::SettingSendDefaultAccept,
+                    WorkspaceIden::SettingHttpVersion,
+                    WorkspaceIden::SettingDefaultBodyType,
+                    WorkspaceIden::SettingDefaultMethod,
+                    WorkspaceIden::SettingSlowResponseThreshold,
+                    WorkspaceIden::SettingGrpcCaptureRawMessages,
+                    WorkspaceIden::SettingTlsMinVersion,
+                    WorkspaceIden::SettingTlsMaxVersion,
+                    WorkspaceIden::SettingProxy,
+                    WorkspaceIden::SettingHttpCache,
+                    WorkspaceIden::SettingRetryCount,
+                    WorkspaceIden::SettingRetryBackoff,
+                    WorkspaceIden::LastActiveAt,
                 ])
                 .to_owned(),
         )
@@ -224,8 +705,40 @@ pub async fn upsert_workspace<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
 
     let mut stmt = db.prepare(sql.as_str())?;
-    let m = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
-    Ok(emit_upserted_model(window, m))
+    Ok(stmt.query_row(&*params.as_params(), |row| row.try_into())?)
+}
+
+/// Record activity in a workspace, emitting the workspace if its `last_active_at` moved
+fn touch_workspace<R: Runtime>(window: &WebviewWindow<R>, db: &Connection, workspace_id: &str) {
+    match mark_workspace_active(db, workspace_id) {
+        Ok(Some(mut w)) => {
+            resolve_secret_variables(&window.config().identifier, &w.id, &mut w.variables);
+            emit_upserted_model(window, w);
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Failed to mark workspace {workspace_id} active {e:?}"),
+    }
+}
+
+/// Bump a workspace's `last_active_at`, unless it was already bumped in the last
+/// `WORKSPACE_ACTIVITY_DEBOUNCE_SECS`, so a burst of edits only writes it once. Returns the
+/// workspace if it was updated.
+fn mark_workspace_active(db: &Connection, workspace_id: &str) -> Result<Option<Workspace>> {
+    let debounced = format!("DATETIME('now', '-{WORKSPACE_ACTIVITY_DEBOUNCE_SECS} seconds')");
+    let (sql, params) = Query::update()
+        .table(WorkspaceIden::Table)
+        .value(WorkspaceIden::LastActiveAt, CurrentTimestamp)
+        .cond_where(
+            Cond::all().add(Expr::col(WorkspaceIden::Id).eq(workspace_id)).add(
+                Cond::any()
+                    .add(Expr::col(WorkspaceIden::LastActiveAt).is_null())
+                    .add(Expr::col(WorkspaceIden::LastActiveAt).lt(Expr::cust(debounced))),
+            ),
+        )
+        .returning_all()
+        .build_rusqlite(SqliteQueryBuilder);
+    let mut stmt = db.prepare(sql.as_str())?;
+    Ok(stmt.query_row(&*params.as_params(), |row| row.try_into()).optional()?)
 }
 
 pub async fn delete_workspace<R: Runtime>(
@@ -235,13 +748,22 @@ pub async fn delete_workspace<R: Runtime>(
     let workspace = get_workspace(window, id).await?;
 
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
+
+    // Environments are deleted along with the workspace, so their secrets go too
+    let mut secrets = vec![(id.to_string(), query_workspace(&db, id)?.variables)];
+    for e in query_environments(&db, id)? {
+        secrets.push((e.id, e.variables));
+    }
 
     let (sql, params) = Query::delete()
         .from_table(WorkspaceIden::Table)
         .cond_where(Expr::col(WorkspaceIden::Id).eq(id))
         .build_rusqlite(SqliteQueryBuilder);
     db.execute(sql.as_str(), &*params.as_params())?;
+    for (model_id, variables) in secrets {
+        forget_secret_variables(&window.config().identifier, &model_id, &variables, &[]);
+    }
 
     for r in list_responses_by_workspace_id(window, id).await? {
         delete_http_response(window, &r.id).await?;
@@ -252,7 +774,7 @@ pub async fn delete_workspace<R: Runtime>(
 
 pub async fn get_cookie_jar<R: Runtime>(mgr: &impl Manager<R>, id: &str) -> Result<CookieJar> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::select()
         .from(CookieJarIden::Table)
@@ -268,7 +790,7 @@ pub async fn list_cookie_jars<R: Runtime>(
     workspace_id: &str,
 ) -> Result<Vec<CookieJar>> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::select()
         .from(CookieJarIden::Table)
         .column(Asterisk)
@@ -276,7 +798,7 @@ pub async fn list_cookie_jars<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
     let mut stmt = db.prepare(sql.as_str())?;
     let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
-    Ok(items.map(|v| v.unwrap()).collect())
+    Ok(collect_rows(items))
 }
 
 pub async fn delete_cookie_jar<R: Runtime>(
@@ -285,7 +807,7 @@ pub async fn delete_cookie_jar<R: Runtime>(
 ) -> Result<CookieJar> {
     let cookie_jar = get_cookie_jar(window, id).await?;
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::delete()
         .from_table(CookieJarIden::Table)
@@ -293,6 +815,7 @@ pub async fn delete_cookie_jar<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
     db.execute(sql.as_str(), &*params.as_params())?;
 
+    touch_workspace(window, &db, &cookie_jar.workspace_id);
     emit_deleted_model(window, cookie_jar)
 }
 
@@ -322,13 +845,14 @@ pub async fn delete_grpc_request<R: Runtime>(
     };
 
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::delete()
         .from_table(GrpcRequestIden::Table)
         .cond_where(Expr::col(GrpcRequestIden::Id).eq(id))
         .build_rusqlite(SqliteQueryBuilder);
     db.execute(sql.as_str(), &*params.as_params())?;
 
+    touch_workspace(window, &db, &req.workspace_id);
     emit_deleted_model(window, req)
 }
 
@@ -343,7 +867,7 @@ pub async fn upsert_grpc_request<R: Runtime>(
     let trimmed_name = request.name.trim();
 
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::insert()
         .into_table(GrpcRequestIden::Table)
         .columns([
@@ -361,6 +885,13 @@ pub async fn upsert_grpc_request<R: Runtime>(
             GrpcRequestIden::AuthenticationType,
             GrpcRequestIden::Authentication,
             GrpcRequestIden::Metadata,
+            GrpcRequestIden::InheritMetadata,
+            GrpcRequestIden::SettingValidateCertificates,
+            GrpcRequestIden::SettingCaCertificate,
+            GrpcRequestIden::SettingClientCertificate,
+            GrpcRequestIden::SettingClientKey,
+            GrpcRequestIden::SettingKeepaliveInterval,
+            GrpcRequestIden::SettingTimeout,
         ])
         .values_panic([
             id.as_str().into(),
@@ -377,6 +908,13 @@ pub async fn upsert_grpc_request<R: Runtime>(
             request.authentication_type.as_ref().map(|s| s.as_str()).into(),
             serde_json::to_string(&request.authentication)?.into(),
             serde_json::to_string(&request.metadata)?.into(),
+            request.inherit_metadata.into(),
+            request.setting_validate_certificates.into(),
+            request.setting_ca_certificate.as_ref().map(|s| s.as_str()).into(),
+            request.setting_client_certificate.as_ref().map(|s| s.as_str()).into(),
+            request.setting_client_key.as_ref().map(|s| s.as_str()).into(),
+            request.setting_keepalive_interval.into(),
+            request.setting_timeout.into(),
         ])
         .on_conflict(
             OnConflict::column(GrpcRequestIden::Id)
@@ -393,6 +931,13 @@ pub async fn upsert_grpc_request<R: Runtime>(
                     GrpcRequestIden::AuthenticationType,
                     GrpcRequestIden::Authentication,
                     GrpcRequestIden::Metadata,
+                    GrpcRequestIden::InheritMetadata,
+                    GrpcRequestIden::SettingValidateCertificates,
+                    GrpcRequestIden::SettingCaCertificate,
+                    GrpcRequestIden::SettingClientCertificate,
+                    GrpcRequestIden::SettingClientKey,
+                    GrpcRequestIden::SettingKeepaliveInterval,
+                    GrpcRequestIden::SettingTimeout,
                 ])
                 .to_owned(),
         )
@@ -400,7 +945,8 @@ pub async fn upsert_grpc_request<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
 
     let mut stmt = db.prepare(sql.as_str())?;
-    let m = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
+    let m: GrpcRequest = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
+    touch_workspace(window, &db, &m.workspace_id);
     Ok(emit_upserted_model(window, m))
 }
 
@@ -409,7 +955,7 @@ pub async fn get_grpc_request<R: Runtime>(
     id: &str,
 ) -> Result<Option<GrpcRequest>> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::select()
         .from(GrpcRequestIden::Table)
@@ -425,7 +971,7 @@ pub async fn list_grpc_requests<R: Runtime>(
     workspace_id: &str,
 ) -> Result<Vec<GrpcRequest>> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::select()
         .from(GrpcRequestIden::Table)
         .cond_where(Expr::col(GrpcRequestIden::WorkspaceId).eq(workspace_id))
@@ -433,7 +979,7 @@ pub async fn list_grpc_requests<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
     let mut stmt = db.prepare(sql.as_str())?;
     let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
-    Ok(items.map(|v| v.unwrap()).collect())
+    Ok(collect_rows(items))
 }
 
 pub async fn upsert_grpc_connection<R: Runtime>(
@@ -452,7 +998,7 @@ pub async fn upsert_grpc_connection<R: Runtime>(
         _ => connection.id.to_string(),
     };
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::insert()
         .into_table(GrpcConnectionIden::Table)
         .columns([
@@ -504,7 +1050,8 @@ pub async fn upsert_grpc_connection<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
 
     let mut stmt = db.prepare(sql.as_str())?;
-    let m = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
+    let m: GrpcConnection = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
+    touch_workspace(window, &db, &m.workspace_id);
     Ok(emit_upserted_model(window, m))
 }
 
@@ -513,7 +1060,7 @@ pub async fn get_grpc_connection<R: Runtime>(
     id: &str,
 ) -> Result<GrpcConnection> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::select()
         .from(GrpcConnectionIden::Table)
         .column(Asterisk)
@@ -528,7 +1075,7 @@ pub async fn list_grpc_connections_for_workspace<R: Runtime>(
     workspace_id: &str,
 ) -> Result<Vec<GrpcConnection>> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::select()
         .from(GrpcConnectionIden::Table)
@@ -538,7 +1085,7 @@ pub async fn list_grpc_connections_for_workspace<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
     let mut stmt = db.prepare(sql.as_str())?;
     let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
-    Ok(items.map(|v| v.unwrap()).collect())
+    Ok(collect_rows(items))
 }
 
 pub async fn list_grpc_connections_for_request<R: Runtime>(
@@ -546,7 +1093,7 @@ pub async fn list_grpc_connections_for_request<R: Runtime>(
     request_id: &str,
 ) -> Result<Vec<GrpcConnection>> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::select()
         .from(GrpcConnectionIden::Table)
@@ -556,7 +1103,7 @@ pub async fn list_grpc_connections_for_request<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
     let mut stmt = db.prepare(sql.as_str())?;
     let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
-    Ok(items.map(|v| v.unwrap()).collect())
+    Ok(collect_rows(items))
 }
 
 pub async fn delete_grpc_connection<R: Runtime>(
@@ -566,7 +1113,7 @@ pub async fn delete_grpc_connection<R: Runtime>(
     let resp = get_grpc_connection(window, id).await?;
 
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::delete()
         .from_table(GrpcConnectionIden::Table)
@@ -607,7 +1154,7 @@ pub async fn upsert_grpc_event<R: Runtime>(
     };
 
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::insert()
         .into_table(GrpcEventIden::Table)
         .columns([
@@ -618,6 +1165,7 @@ pub async fn upsert_grpc_event<R: Runtime>(
             GrpcEventIden::RequestId,
             GrpcEventIden::ConnectionId,
             GrpcEventIden::Content,
+            GrpcEventIden::ContentRaw,
             GrpcEventIden::EventType,
             GrpcEventIden::Metadata,
             GrpcEventIden::Status,
@@ -631,6 +1179,7 @@ pub async fn upsert_grpc_event<R: Runtime>(
             event.request_id.as_str().into(),
             event.connection_id.as_str().into(),
             event.content.as_str().into(),
+            event.content_raw.as_ref().map(|s| s.as_str()).into(),
             serde_json::to_string(&event.event_type)?.into(),
             serde_json::to_string(&event.metadata)?.into(),
             event.status.into(),
@@ -641,6 +1190,7 @@ pub async fn upsert_grpc_event<R: Runtime>(
                 .update_columns([
                     GrpcEventIden::UpdatedAt,
                     GrpcEventIden::Content,
+                    GrpcEventIden::ContentRaw,
                     GrpcEventIden::EventType,
                     GrpcEventIden::Metadata,
                     GrpcEventIden::Status,
@@ -658,7 +1208,7 @@ pub async fn upsert_grpc_event<R: Runtime>(
 
 pub async fn get_grpc_event<R: Runtime>(mgr: &impl Manager<R>, id: &str) -> Result<GrpcEvent> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::select()
         .from(GrpcEventIden::Table)
         .column(Asterisk)
@@ -673,7 +1223,7 @@ pub async fn list_grpc_events<R: Runtime>(
     connection_id: &str,
 ) -> Result<Vec<GrpcEvent>> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::select()
         .from(GrpcEventIden::Table)
@@ -683,7 +1233,7 @@ pub async fn list_grpc_events<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
     let mut stmt = db.prepare(sql.as_str())?;
     let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
-    Ok(items.map(|v| v.unwrap()).collect())
+    Ok(collect_rows(items))
 }
 
 pub async fn upsert_cookie_jar<R: Runtime>(
@@ -697,7 +1247,7 @@ pub async fn upsert_cookie_jar<R: Runtime>(
     let trimmed_name = cookie_jar.name.trim();
 
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::insert()
         .into_table(CookieJarIden::Table)
@@ -730,7 +1280,8 @@ pub async fn upsert_cookie_jar<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
 
     let mut stmt = db.prepare(sql.as_str())?;
-    let m = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
+    let m: CookieJar = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
+    touch_workspace(window, &db, &m.workspace_id);
     Ok(emit_upserted_model(window, m))
 }
 
@@ -739,8 +1290,15 @@ pub async fn list_environments<R: Runtime>(
     workspace_id: &str,
 ) -> Result<Vec<Environment>> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
+    let mut environments = query_environments(&db, workspace_id)?;
+    for e in environments.iter_mut() {
+        resolve_secret_variables(&mgr.config().identifier, &e.id, &mut e.variables);
+    }
+    Ok(environments)
+}
 
+fn query_environments(db: &Connection, workspace_id: &str) -> Result<Vec<Environment>> {
     let (sql, params) = Query::select()
         .from(EnvironmentIden::Table)
         .cond_where(Expr::col(EnvironmentIden::WorkspaceId).eq(workspace_id))
@@ -749,7 +1307,7 @@ pub async fn list_environments<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
     let mut stmt = db.prepare(sql.as_str())?;
     let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
-    Ok(items.map(|v| v.unwrap()).collect())
+    Ok(collect_rows(items))
 }
 
 pub async fn delete_environment<R: Runtime>(
@@ -759,7 +1317,8 @@ pub async fn delete_environment<R: Runtime>(
     let env = get_environment(window, id).await?;
 
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
+    let secrets = query_environment(&db, id)?.variables;
 
     let (sql, params) = Query::delete()
         .from_table(EnvironmentIden::Table)
@@ -767,6 +1326,8 @@ pub async fn delete_environment<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
 
     db.execute(sql.as_str(), &*params.as_params())?;
+    forget_secret_variables(&window.config().identifier, id, &secrets, &[]);
+    touch_workspace(window, &db, &env.workspace_id);
     emit_deleted_model(window, env)
 }
 
@@ -774,7 +1335,7 @@ const SETTINGS_ID: &str = "default";
 
 async fn get_settings<R: Runtime>(mgr: &impl Manager<R>) -> Result<Option<Settings>> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::select()
         .from(SettingsIden::Table)
@@ -793,7 +1354,7 @@ pub async fn get_or_create_settings<R: Runtime>(mgr: &impl Manager<R>) -> Settin
     };
 
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::insert()
         .into_table(SettingsIden::Table)
@@ -811,14 +1372,18 @@ pub async fn update_settings<R: Runtime>(
     settings: Settings,
 ) -> Result<Settings> {
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
+    let m = write_settings(&db, settings)?;
+    Ok(emit_upserted_model(window, m))
+}
 
+fn write_settings(db: &Connection, settings: Settings) -> Result<Settings> {
     let (sql, params) = Query::update()
         .table(SettingsIden::Table)
         .cond_where(Expr::col(SettingsIden::Id).eq("default"))
         .values([
             (SettingsIden::Id, "default".into()),
-            (SettingsIden::CreatedAt, CurrentTimestamp.into()),
+            (SettingsIden::UpdatedAt, CurrentTimestamp.into()),
             (SettingsIden::Appearance, settings.appearance.as_str().into()),
             (SettingsIden::ThemeDark, settings.theme_dark.as_str().into()),
             (SettingsIden::ThemeLight, settings.theme_light.as_str().into()),
@@ -828,6 +1393,7 @@ pub async fn update_settings<R: Runtime>(
             (SettingsIden::EditorFontSize, settings.editor_font_size.into()),
             (SettingsIden::EditorSoftWrap, settings.editor_soft_wrap.into()),
             (SettingsIden::Telemetry, settings.telemetry.into()),
+            (SettingsIden::RestoreLastLocation, settings.restore_last_location.into()),
             (SettingsIden::OpenWorkspaceNewWindow, settings.open_workspace_new_window.into()),
             (
                 SettingsIden::Proxy,
@@ -837,13 +1403,14 @@ pub async fn update_settings<R: Runtime>(
                 })
                 .into(),
             ),
+            (SettingsIden::ResponseBodyDir, settings.response_body_dir.into()),
+            (SettingsIden::PluginTimeout, settings.plugin_timeout.into()),
         ])
         .returning_all()
         .build_rusqlite(SqliteQueryBuilder);
 
     let mut stmt = db.prepare(sql.as_str())?;
-    let m = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
-    Ok(emit_upserted_model(window, m))
+    Ok(stmt.query_row(&*params.as_params(), |row| row.try_into())?)
 }
 
 pub async fn upsert_environment<R: Runtime>(
@@ -856,8 +1423,20 @@ pub async fn upsert_environment<R: Runtime>(
     };
     let trimmed_name = environment.name.trim();
 
+    // Treat a blank color as no color
+    let color = environment.color.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    if let Some(c) = color {
+        if !is_hex_color(c) {
+            return Err(InvalidColor(c.to_string()));
+        }
+    }
+
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
+
+    let service = &window.config().identifier;
+    let previous = query_environment(&db, &id).map(|e| e.variables).unwrap_or_default();
+    let variables = store_secret_variables(service, &id, &environment.variables, &previous)?;
 
     let (sql, params) = Query::insert()
         .into_table(EnvironmentIden::Table)
@@ -866,6 +1445,8 @@ pub async fn upsert_environment<R: Runtime>(
             EnvironmentIden::CreatedAt,
             EnvironmentIden::UpdatedAt,
             EnvironmentIden::WorkspaceId,
+            EnvironmentIden::Color,
+            EnvironmentIden::IsProduction,
             EnvironmentIden::Name,
             EnvironmentIden::Variables,
         ])
@@ -874,13 +1455,17 @@ pub async fn upsert_environment<R: Runtime>(
             CurrentTimestamp.into(),
             CurrentTimestamp.into(),
             environment.workspace_id.as_str().into(),
+            color.into(),
+            environment.is_production.into(),
             trimmed_name.into(),
-            serde_json::to_string(&environment.variables)?.into(),
+            serde_json::to_string(&variables)?.into(),
         ])
         .on_conflict(
             OnConflict::column(EnvironmentIden::Id)
                 .update_columns([
                     EnvironmentIden::UpdatedAt,
+                    EnvironmentIden::Color,
+                    EnvironmentIden::IsProduction,
                     EnvironmentIden::Name,
                     EnvironmentIden::Variables,
                 ])
@@ -890,14 +1475,22 @@ pub async fn upsert_environment<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
 
     let mut stmt = db.prepare(sql.as_str())?;
-    let m = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
+    let mut m: Environment = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
+    resolve_secret_variables(service, &m.id, &mut m.variables);
+    touch_workspace(window, &db, &m.workspace_id);
     Ok(emit_upserted_model(window, m))
 }
 
 pub async fn get_environment<R: Runtime>(mgr: &impl Manager<R>, id: &str) -> Result<Environment> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
+    let mut environment = query_environment(&db, id)?;
+    resolve_secret_variables(&mgr.config().identifier, &environment.id, &mut environment.variables);
+    Ok(environment)
+}
 
+/// An environment as it's saved, with its secret variables still referring to the keychain
+fn query_environment(db: &Connection, id: &str) -> Result<Environment> {
     let (sql, params) = Query::select()
         .from(EnvironmentIden::Table)
         .column(Asterisk)
@@ -907,9 +1500,58 @@ pub async fn get_environment<R: Runtime>(mgr: &impl Manager<R>, id: &str) -> Res
     Ok(stmt.query_row(&*params.as_params(), |row| row.try_into())?)
 }
 
+pub async fn duplicate_environment<R: Runtime>(
+    window: &WebviewWindow<R>,
+    id: &str,
+) -> Result<Environment> {
+    let mut environment = get_environment(window, id).await?;
+    environment.id = "".to_string();
+    environment.name = format!("{} (Copy)", environment.name);
+    upsert_environment(window, environment).await
+}
+
+/// Find a workspace's environment by its exact name, for callers that don't know the id. It's
+/// an error for the name to match more than one environment, since names aren't unique.
+pub async fn get_environment_by_name<R: Runtime>(
+    mgr: &impl Manager<R>,
+    workspace_id: &str,
+    name: &str,
+) -> Result<Environment> {
+    let dbm = &*mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    let mut environment = query_environment_by_name(&db, workspace_id, name)?;
+    resolve_secret_variables(&mgr.config().identifier, &environment.id, &mut environment.variables);
+    Ok(environment)
+}
+
+fn query_environment_by_name(
+    db: &Connection,
+    workspace_id: &str,
+    name: &str,
+) -> Result<Environment> {
+    let (sql, params) = Query::select()
+        .from(EnvironmentIden::Table)
+        .column(Asterisk)
+        .cond_where(
+            Cond::all()
+                .add(Expr::col(EnvironmentIden::WorkspaceId).eq(workspace_id))
+                .add(Expr::col(EnvironmentIden::Name).eq(name)),
+        )
+        .limit(2)
+        .build_rusqlite(SqliteQueryBuilder);
+    let mut stmt = db.prepare(sql.as_str())?;
+    let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
+    let mut environments: Vec<Environment> = collect_rows(items);
+    match environments.len() {
+        0 => Err(ModelNotFound(format!("environment named {name:?}"))),
+        1 => Ok(environments.remove(0)),
+        _ => Err(AmbiguousModel(format!("environment named {name:?}"))),
+    }
+}
+
 pub async fn get_plugin<R: Runtime>(mgr: &impl Manager<R>, id: &str) -> Result<Plugin> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::select()
         .from(PluginIden::Table)
@@ -922,7 +1564,7 @@ pub async fn get_plugin<R: Runtime>(mgr: &impl Manager<R>, id: &str) -> Result<P
 
 pub async fn list_plugins<R: Runtime>(mgr: &impl Manager<R>) -> Result<Vec<Plugin>> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::select()
         .from(PluginIden::Table)
@@ -931,7 +1573,7 @@ pub async fn list_plugins<R: Runtime>(mgr: &impl Manager<R>) -> Result<Vec<Plugi
         .build_rusqlite(SqliteQueryBuilder);
     let mut stmt = db.prepare(sql.as_str())?;
     let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
-    Ok(items.map(|v| v.unwrap()).collect())
+    Ok(collect_rows(items))
 }
 
 pub async fn upsert_plugin<R: Runtime>(
@@ -943,7 +1585,7 @@ pub async fn upsert_plugin<R: Runtime>(
         _ => plugin.id.to_string(),
     };
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::insert()
         .into_table(PluginIden::Table)
@@ -988,7 +1630,7 @@ pub async fn delete_plugin<R: Runtime>(window: &WebviewWindow<R>, id: &str) -> R
     let plugin = get_plugin(window, id).await?;
 
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::delete()
         .from_table(PluginIden::Table)
@@ -1001,7 +1643,7 @@ pub async fn delete_plugin<R: Runtime>(window: &WebviewWindow<R>, id: &str) -> R
 
 pub async fn get_folder<R: Runtime>(mgr: &impl Manager<R>, id: &str) -> Result<Folder> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::select()
         .from(FolderIden::Table)
@@ -1017,7 +1659,7 @@ pub async fn list_folders<R: Runtime>(
     workspace_id: &str,
 ) -> Result<Vec<Folder>> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::select()
         .from(FolderIden::Table)
@@ -1027,14 +1669,14 @@ pub async fn list_folders<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
     let mut stmt = db.prepare(sql.as_str())?;
     let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
-    Ok(items.map(|v| v.unwrap()).collect())
+    Ok(collect_rows(items))
 }
 
 pub async fn delete_folder<R: Runtime>(window: &WebviewWindow<R>, id: &str) -> Result<Folder> {
     let folder = get_folder(window, id).await?;
 
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::delete()
         .from_table(FolderIden::Table)
@@ -1042,6 +1684,7 @@ pub async fn delete_folder<R: Runtime>(window: &WebviewWindow<R>, id: &str) -> R
         .build_rusqlite(SqliteQueryBuilder);
     db.execute(sql.as_str(), &*params.as_params())?;
 
+    touch_workspace(window, &db, &folder.workspace_id);
     emit_deleted_model(window, folder)
 }
 
@@ -1053,7 +1696,7 @@ pub async fn upsert_folder<R: Runtime>(window: &WebviewWindow<R>, r: Folder) ->
     let trimmed_name = r.name.trim();
 
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::insert()
         .into_table(FolderIden::Table)
@@ -1089,20 +1732,127 @@ pub async fn upsert_folder<R: Runtime>(window: &WebviewWindow<R>, r: Folder) ->
         .build_rusqlite(SqliteQueryBuilder);
 
     let mut stmt = db.prepare(sql.as_str())?;
-    let m = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
+    let m: Folder = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
+    touch_workspace(window, &db, &m.workspace_id);
     Ok(emit_upserted_model(window, m))
 }
 
+/// Duplicate a request, along with up to `response_count` of its most recent finished responses
 pub async fn duplicate_http_request<R: Runtime>(
     window: &WebviewWindow<R>,
     id: &str,
+    response_count: usize,
 ) -> Result<HttpRequest> {
     let mut request = match get_http_request(window, id).await? {
         None => return Err(ModelNotFound(id.to_string())),
         Some(r) => r,
     };
     request.id = "".to_string();
-    upsert_http_request(window, request).await
+    let request = upsert_http_request(window, request).await?;
+    if response_count == 0 {
+        return Ok(request);
+    }
+
+    let limit = response_count.min(MAX_HTTP_RESPONSES_PER_REQUEST) as i64;
+    let responses = list_http_responses_for_request(window, id, Some(limit)).await?;
+    let dbm = &*window.app_handle().state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    // Copy oldest first, although the copies keep their original created_at anyway
+    for response in responses.iter().rev() {
+        if !matches!(response.state, HttpResponseState::Closed) {
+            continue;
+        }
+        let m = copy_http_response(&db, response, &request.id)?;
+        emit_upserted_model(window, m);
+    }
+
+    Ok(request)
+}
+
+/// Insert a copy of a response under another request. The body file is copied next to the
+/// original, named after the new response id.
+fn copy_http_response(
+    db: &Connection,
+    response: &HttpResponse,
+    request_id: &str,
+) -> Result<HttpResponse> {
+    let id = generate_model_id(ModelType::TypeHttpResponse);
+    let body_path = match &response.body_path {
+        Some(p) if Path::new(p).is_file() => {
+            let new_path = Path::new(p).with_file_name(&id);
+            fs::copy(p, &new_path)?;
+            Some(new_path.to_string_lossy().to_string())
+        }
+        _ => None,
+    };
+    let raw_request_path = match &response.raw_request_path {
+        Some(p) if Path::new(p).is_file() => {
+            let new_path = Path::new(p).with_file_name(format!("{id}.request"));
+            fs::copy(p, &new_path)?;
+            Some(new_path.to_string_lossy().to_string())
+        }
+        _ => None,
+    };
+
+    let (sql, params) = Query::insert()
+        .into_table(HttpResponseIden::Table)
+        .columns([
+            HttpResponseIden::Id,
+            HttpResponseIden::CreatedAt,
+            HttpResponseIden::UpdatedAt,
+            HttpResponseIden::RequestId,
+            HttpResponseIden::WorkspaceId,
+            HttpResponseIden::Elapsed,
+            HttpResponseIden::ElapsedHeaders,
+            HttpResponseIden::Url,
+            HttpResponseIden::State,
+            HttpResponseIden::Status,
+            HttpResponseIden::StatusReason,
+            HttpResponseIden::ContentLength,
+            HttpResponseIden::BodyPath,
+            HttpResponseIden::Error,
+            HttpResponseIden::Headers,
+            HttpResponseIden::Version,
+            HttpResponseIden::RemoteAddr,
+            HttpResponseIden::Source,
+            HttpResponseIden::TlsCertificate,
+            HttpResponseIden::RequestSnapshot,
+            HttpResponseIden::Slow,
+            HttpResponseIden::RequestContentLength,
+            HttpResponseIden::RawRequestPath,
+            HttpResponseIden::Attempts,
+        ])
+        .values_panic([
+            id.as_str().into(),
+            response.created_at.into(),
+            CurrentTimestamp.into(),
+            request_id.into(),
+            response.workspace_id.as_str().into(),
+            response.elapsed.into(),
+            response.elapsed_headers.into(),
+            response.url.as_str().into(),
+            serde_json::to_value(&response.state)?.as_str().into(),
+            response.status.into(),
+            response.status_reason.as_ref().map(|s| s.as_str()).into(),
+            response.content_length.into(),
+            body_path.into(),
+            response.error.as_ref().map(|s| s.as_str()).into(),
+            serde_json::to_string(&response.headers)?.into(),
+            response.version.as_ref().map(|s| s.as_str()).into(),
+            response.remote_addr.as_ref().map(|s| s.as_str()).into(),
+            serde_json::to_value(&response.source)?.as_str().into(),
+            response.tls_certificate.as_ref().map(serde_json::to_string).transpose()?.into(),
+            response.request_snapshot.as_ref().map(serde_json::to_string).transpose()?.into(),
+            response.slow.into(),
+            response.request_content_length.into(),
+            raw_request_path.into(),
+            response.attempts.into(),
+        ])
+        .returning_all()
+        .build_rusqlite(SqliteQueryBuilder);
+
+    let mut stmt = db.prepare(sql.as_str())?;
+    Ok(stmt.query_row(&*params.as_params(), |row| row.try_into())?)
 }
 
 pub async fn upsert_http_request<R: Runtime>(
@@ -1116,7 +1866,7 @@ pub async fn upsert_http_request<R: Runtime>(
     let trimmed_name = r.name.trim();
 
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::insert()
         .into_table(HttpRequestIden::Table)
@@ -1136,6 +1886,18 @@ pub async fn upsert_http_request<R: Runtime>(
             HttpRequestIden::AuthenticationType,
             HttpRequestIden::Headers,
             HttpRequestIden::SortPriority,
+            HttpRequestIden::PreRequestScript,
+            HttpRequestIden::PostResponseScript,
+            HttpRequestIden::ScriptsTrusted,
+            HttpRequestIden::SettingIgnoreCookies,
+            HttpRequestIden::SettingProxy,
+            HttpRequestIden::SettingServerName,
+            HttpRequestIden::SettingHttpVersion,
+            HttpRequestIden::SettingFollowRedirects,
+            HttpRequestIden::SettingRequestTimeout,
+            HttpRequestIden::SettingRetryCount,
+            HttpRequestIden::SettingRetryBackoff,
+            HttpRequestIden::SettingRetryNonIdempotent,
         ])
         .values_panic([
             id.as_str().into(),
@@ -1153,6 +1915,18 @@ pub async fn upsert_http_request<R: Runtime>(
             r.authentication_type.as_ref().map(|s| s.as_str()).into(),
             serde_json::to_string(&r.headers)?.into(),
             r.sort_priority.into(),
+            r.pre_request_script.as_str().into(),
+            r.post_response_script.as_str().into(),
+            r.scripts_trusted.into(),
+            r.setting_ignore_cookies.into(),
+            r.setting_proxy.as_ref().map(serde_json::to_string).transpose()?.into(),
+            r.setting_server_name.as_str().into(),
+            r.setting_http_version.as_ref().map(|v| v.as_str()).into(),
+            r.setting_follow_redirects.into(),
+            r.setting_request_timeout.into(),
+            r.setting_retry_count.into(),
+            r.setting_retry_backoff.into(),
+            r.setting_retry_non_idempotent.into(),
         ])
         .on_conflict(
             OnConflict::column(GrpcEventIden::Id)
@@ -1170,6 +1944,18 @@ pub async fn upsert_http_request<R: Runtime>(
                     HttpRequestIden::Url,
                     HttpRequestIden::UrlParameters,
                     HttpRequestIden::SortPriority,
+                    HttpRequestIden::PreRequestScript,
+                    HttpRequestIden::PostResponseScript,
+                    HttpRequestIden::ScriptsTrusted,
+                    HttpRequestIden::SettingIgnoreCookies,
+                    HttpRequestIden::SettingProxy,
+                    HttpRequestIden::SettingServerName,
+                    HttpRequestIden::SettingHttpVersion,
+                    HttpRequestIden::SettingFollowRedirects,
+                    HttpRequestIden::SettingRequestTimeout,
+                    HttpRequestIden::SettingRetryCount,
+                    HttpRequestIden::SettingRetryBackoff,
+                    HttpRequestIden::SettingRetryNonIdempotent,
                 ])
                 .to_owned(),
         )
@@ -1177,7 +1963,8 @@ pub async fn upsert_http_request<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
 
     let mut stmt = db.prepare(sql.as_str())?;
-    let m = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
+    let m: HttpRequest = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
+    touch_workspace(window, &db, &m.workspace_id);
     Ok(emit_upserted_model(window, m))
 }
 
@@ -1186,7 +1973,7 @@ pub async fn list_http_requests<R: Runtime>(
     workspace_id: &str,
 ) -> Result<Vec<HttpRequest>> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::select()
         .from(HttpRequestIden::Table)
         .cond_where(Expr::col(HttpRequestIden::WorkspaceId).eq(workspace_id))
@@ -1195,7 +1982,7 @@ pub async fn list_http_requests<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
     let mut stmt = db.prepare(sql.as_str())?;
     let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
-    Ok(items.map(|v| v.unwrap()).collect())
+    Ok(collect_rows(items))
 }
 
 pub async fn get_http_request<R: Runtime>(
@@ -1203,7 +1990,7 @@ pub async fn get_http_request<R: Runtime>(
     id: &str,
 ) -> Result<Option<HttpRequest>> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::select()
         .from(HttpRequestIden::Table)
@@ -1227,13 +2014,14 @@ pub async fn delete_http_request<R: Runtime>(
     delete_all_http_responses_for_request(window, id).await?;
 
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::delete()
         .from_table(HttpRequestIden::Table)
         .cond_where(Expr::col(HttpRequestIden::Id).eq(id))
         .build_rusqlite(SqliteQueryBuilder);
     db.execute(sql.as_str(), &*params.as_params())?;
 
+    touch_workspace(window, &db, &req.workspace_id);
     emit_deleted_model(window, req)
 }
 
@@ -1255,6 +2043,7 @@ pub async fn create_default_http_response<R: Runtime>(
         vec![],
         None,
         None,
+        HttpResponseSource::Network,
     )
     .await
 }
@@ -1274,6 +2063,7 @@ pub async fn create_http_response<R: Runtime>(
     headers: Vec<HttpResponseHeader>,
     version: Option<&str>,
     remote_addr: Option<&str>,
+    source: HttpResponseSource,
 ) -> Result<HttpResponse> {
     let responses = list_http_responses_for_request(window, request_id, None).await?;
     for response in responses.iter().skip(MAX_HTTP_RESPONSES_PER_REQUEST - 1) {
@@ -1287,7 +2077,7 @@ pub async fn create_http_response<R: Runtime>(
     };
     let id = generate_model_id(ModelType::TypeHttpResponse);
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::insert()
         .into_table(HttpResponseIden::Table)
@@ -1308,6 +2098,7 @@ pub async fn create_http_response<R: Runtime>(
             HttpResponseIden::Headers,
             HttpResponseIden::Version,
             HttpResponseIden::RemoteAddr,
+            HttpResponseIden::Source,
         ])
         .values_panic([
             id.as_str().into(),
@@ -1326,23 +2117,28 @@ pub async fn create_http_response<R: Runtime>(
             serde_json::to_string(&headers)?.into(),
             version.into(),
             remote_addr.into(),
+            serde_json::to_value(source)?.as_str().unwrap_or_default().into(),
         ])
         .returning_all()
         .build_rusqlite(SqliteQueryBuilder);
 
     let mut stmt = db.prepare(sql.as_str())?;
-    let m = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
+    let m: HttpResponse = stmt.query_row(&*params.as_params(), |row| row.try_into())?;
+    touch_workspace(window, &db, &m.workspace_id);
     Ok(emit_upserted_model(window, m))
 }
 
 pub async fn cancel_pending_grpc_connections(app: &AppHandle) -> Result<()> {
     let dbm = &*app.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let closed = serde_json::to_value(&GrpcConnectionState::Closed)?;
     let (sql, params) = Query::update()
         .table(GrpcConnectionIden::Table)
-        .values([(GrpcConnectionIden::State, closed.as_str().into())])
+        .values([
+            (GrpcConnectionIden::UpdatedAt, CurrentTimestamp.into()),
+            (GrpcConnectionIden::State, closed.as_str().into()),
+        ])
         .cond_where(Expr::col(GrpcConnectionIden::State).ne(closed.as_str()))
         .build_rusqlite(SqliteQueryBuilder);
 
@@ -1352,12 +2148,16 @@ pub async fn cancel_pending_grpc_connections(app: &AppHandle) -> Result<()> {
 
 pub async fn cancel_pending_responses(app: &AppHandle) -> Result<()> {
     let dbm = &*app.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
+    close_pending_responses(&db)
+}
 
+fn close_pending_responses(db: &Connection) -> Result<()> {
     let closed = serde_json::to_value(&GrpcConnectionState::Closed)?;
     let (sql, params) = Query::update()
         .table(HttpResponseIden::Table)
         .values([
+            (HttpResponseIden::UpdatedAt, CurrentTimestamp.into()),
             (HttpResponseIden::State, closed.as_str().into()),
             (HttpResponseIden::StatusReason, "Cancelled".into()),
         ])
@@ -1384,7 +2184,7 @@ pub async fn update_http_response<R: Runtime>(
     response: &HttpResponse,
 ) -> Result<HttpResponse> {
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
 
     let (sql, params) = Query::update()
         .table(HttpResponseIden::Table)
@@ -1411,6 +2211,22 @@ pub async fn update_http_response<R: Runtime>(
                 HttpResponseIden::RemoteAddr,
                 response.remote_addr.as_ref().map(|s| s.as_str()).into(),
             ),
+            (HttpResponseIden::Source, serde_json::to_value(&response.source)?.as_str().into()),
+            (
+                HttpResponseIden::TlsCertificate,
+                response.tls_certificate.as_ref().map(serde_json::to_string).transpose()?.into(),
+            ),
+            (
+                HttpResponseIden::RequestSnapshot,
+                response.request_snapshot.as_ref().map(serde_json::to_string).transpose()?.into(),
+            ),
+            (HttpResponseIden::Slow, response.slow.into()),
+            (HttpResponseIden::RequestContentLength, response.request_content_length.into()),
+            (
+                HttpResponseIden::RawRequestPath,
+                response.raw_request_path.as_ref().map(|s| s.as_str()).into(),
+            ),
+            (HttpResponseIden::Attempts, response.attempts.into()),
         ])
         .returning_all()
         .build_rusqlite(SqliteQueryBuilder);
@@ -1425,7 +2241,7 @@ pub async fn get_http_response<R: Runtime>(
     id: &str,
 ) -> Result<HttpResponse> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::select()
         .from(HttpResponseIden::Table)
         .column(Asterisk)
@@ -1447,9 +2263,14 @@ pub async fn delete_http_response<R: Runtime>(
             error!("Failed to delete body file: {}", e);
         };
     }
+    if let Some(p) = resp.raw_request_path.clone() {
+        if let Err(e) = fs::remove_file(p) {
+            error!("Failed to delete raw request file: {}", e);
+        };
+    }
 
     let dbm = &*window.app_handle().state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::delete()
         .from_table(HttpResponseIden::Table)
         .cond_where(Expr::col(HttpResponseIden::Id).eq(id))
@@ -1469,44 +2290,730 @@ pub async fn delete_all_http_responses_for_request<R: Runtime>(
     Ok(())
 }
 
-pub async fn delete_all_http_responses_for_workspace<R: Runtime>(
+pub async fn upsert_http_example<R: Runtime>(
     window: &WebviewWindow<R>,
-    workspace_id: &str,
-) -> Result<()> {
-    for r in list_http_responses_for_workspace(window, workspace_id, None).await? {
-        delete_http_response(window, &r.id).await?;
-    }
-    Ok(())
+    example: &HttpExample,
+) -> Result<HttpExample> {
+    let id = match example.id.as_str() {
+        "" => generate_model_id(ModelType::TypeHttpExample),
+        _ => example.id.to_string(),
+    };
+
+    let dbm = &*window.app_handle().state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    let m = write_http_example(&db, &id, example)?;
+    touch_workspace(window, &db, &m.workspace_id);
+    Ok(emit_upserted_model(window, m))
 }
 
-pub async fn list_http_responses_for_workspace<R: Runtime>(
-    mgr: &impl Manager<R>,
-    workspace_id: &str,
-    limit: Option<i64>,
-) -> Result<Vec<HttpResponse>> {
-    let limit_unwrapped = limit.unwrap_or_else(|| i64::MAX);
-    let dbm = mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
-    let (sql, params) = Query::select()
-        .from(HttpResponseIden::Table)
-        .cond_where(Expr::col(HttpResponseIden::WorkspaceId).eq(workspace_id))
-        .column(Asterisk)
-        .order_by(HttpResponseIden::CreatedAt, Order::Desc)
-        .limit(limit_unwrapped as u64)
+fn write_http_example(db: &Connection, id: &str, example: &HttpExample) -> Result<HttpExample> {
+    let (sql, params) = Query::insert()
+        .into_table(HttpExampleIden::Table)
+        .columns([
+            HttpExampleIden::Id,
+            HttpExampleIden::CreatedAt,
+            HttpExampleIden::UpdatedAt,
+            HttpExampleIden::WorkspaceId,
+            HttpExampleIden::RequestId,
+            HttpExampleIden::BodyPath,
+            HttpExampleIden::ContentLength,
+            HttpExampleIden::Headers,
+            HttpExampleIden::Name,
+            HttpExampleIden::Status,
+        ])
+        .values_panic([
+            id.into(),
+            CurrentTimestamp.into(),
+            CurrentTimestamp.into(),
+            example.workspace_id.as_str().into(),
+            example.request_id.as_str().into(),
+            example.body_path.as_ref().map(|s| s.as_str()).into(),
+            example.content_length.into(),
+            serde_json::to_string(&example.headers)?.into(),
+            example.name.trim().into(),
+            example.status.into(),
+        ])
+        .on_conflict(
+            OnConflict::column(HttpExampleIden::Id)
+                .update_columns([HttpExampleIden::UpdatedAt, HttpExampleIden::Name])
+                .to_owned(),
+        )
+        .returning_all()
         .build_rusqlite(SqliteQueryBuilder);
+
     let mut stmt = db.prepare(sql.as_str())?;
-    let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
-    Ok(items.map(|v| v.unwrap()).collect())
+    Ok(stmt.query_row(&*params.as_params(), |row| row.try_into())?)
 }
 
-pub async fn list_http_responses_for_request<R: Runtime>(
+pub async fn list_http_examples<R: Runtime>(
+    mgr: &impl Manager<R>,
+    request_id: &str,
+) -> Result<Vec<HttpExample>> {
+    let dbm = &*mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    query_http_examples(&db, request_id)
+}
+
+fn query_http_examples(db: &Connection, request_id: &str) -> Result<Vec<HttpExample>> {
+    let (sql, params) = Query::select()
+        .from(HttpExampleIden::Table)
+        .cond_where(Expr::col(HttpExampleIden::RequestId).eq(request_id))
+        .column(Asterisk)
+        .order_by(HttpExampleIden::CreatedAt, Order::Asc)
+        .build_rusqlite(SqliteQueryBuilder);
+    let mut stmt = db.prepare(sql.as_str())?;
+    let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
+    Ok(collect_rows(items))
+}
+
+pub async fn upsert_http_response_event<R: Runtime>(
+    window: &WebviewWindow<R>,
+    event: &HttpResponseEvent,
+) -> Result<HttpResponseEvent> {
+    let id = match event.id.as_str() {
+        "" => generate_model_id(ModelType::TypeHttpResponseEvent),
+        _ => event.id.to_string(),
+    };
+
+    let dbm = &*window.app_handle().state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    let m = write_http_response_event(&db, &id, event)?;
+    Ok(emit_upserted_model(window, m))
+}
+
+fn write_http_response_event(
+    db: &Connection,
+    id: &str,
+    event: &HttpResponseEvent,
+) -> Result<HttpResponseEvent> {
+    let (sql, params) = Query::insert()
+        .into_table(HttpResponseEventIden::Table)
+        .columns([
+            HttpResponseEventIden::Id,
+            HttpResponseEventIden::CreatedAt,
+            HttpResponseEventIden::UpdatedAt,
+            HttpResponseEventIden::WorkspaceId,
+            HttpResponseEventIden::ResponseId,
+            HttpResponseEventIden::EventType,
+            HttpResponseEventIden::Data,
+            HttpResponseEventIden::EventId,
+            HttpResponseEventIden::Retry,
+        ])
+        .values_panic([
+            id.into(),
+            CurrentTimestamp.into(),
+            CurrentTimestamp.into(),
+            event.workspace_id.as_str().into(),
+            event.response_id.as_str().into(),
+            event.event_type.as_str().into(),
+            event.data.as_str().into(),
+            event.event_id.as_ref().map(|s| s.as_str()).into(),
+            event.retry.into(),
+        ])
+        .on_conflict(
+            OnConflict::column(HttpResponseEventIden::Id)
+                .update_columns([
+                    HttpResponseEventIden::UpdatedAt,
+                    HttpResponseEventIden::EventType,
+                    HttpResponseEventIden::Data,
+                    HttpResponseEventIden::EventId,
+                    HttpResponseEventIden::Retry,
+                ])
+                .to_owned(),
+        )
+        .returning_all()
+        .build_rusqlite(SqliteQueryBuilder);
+
+    let mut stmt = db.prepare(sql.as_str())?;
+    Ok(stmt.query_row(&*params.as_params(), |row| row.try_into())?)
+}
+
+pub async fn list_http_response_events<R: Runtime>(
+    mgr: &impl Manager<R>,
+    response_id: &str,
+) -> Result<Vec<HttpResponseEvent>> {
+    let dbm = &*mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    query_http_response_events(&db, response_id)
+}
+
+fn query_http_response_events(
+    db: &Connection,
+    response_id: &str,
+) -> Result<Vec<HttpResponseEvent>> {
+    // Events can arrive within the same millisecond, and rowid keeps them in arrival order
+    let (sql, params) = Query::select()
+        .from(HttpResponseEventIden::Table)
+        .cond_where(Expr::col(HttpResponseEventIden::ResponseId).eq(response_id))
+        .column(Asterisk)
+        .order_by(Alias::new("rowid"), Order::Asc)
+        .build_rusqlite(SqliteQueryBuilder);
+    let mut stmt = db.prepare(sql.as_str())?;
+    let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
+    Ok(collect_rows(items))
+}
+
+pub async fn upsert_http_timeline_event<R: Runtime>(
+    window: &WebviewWindow<R>,
+    event: &HttpTimelineEvent,
+) -> Result<HttpTimelineEvent> {
+    let id = match event.id.as_str() {
+        "" => generate_model_id(ModelType::TypeHttpTimelineEvent),
+        _ => event.id.to_string(),
+    };
+
+    let dbm = &*window.app_handle().state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    let m = write_http_timeline_event(&db, &id, event)?;
+    Ok(emit_upserted_model(window, m))
+}
+
+fn write_http_timeline_event(
+    db: &Connection,
+    id: &str,
+    event: &HttpTimelineEvent,
+) -> Result<HttpTimelineEvent> {
+    let (sql, params) = Query::insert()
+        .into_table(HttpTimelineEventIden::Table)
+        .columns([
+            HttpTimelineEventIden::Id,
+            HttpTimelineEventIden::CreatedAt,
+            HttpTimelineEventIden::UpdatedAt,
+            HttpTimelineEventIden::WorkspaceId,
+            HttpTimelineEventIden::ResponseId,
+            HttpTimelineEventIden::EventType,
+            HttpTimelineEventIden::Content,
+            HttpTimelineEventIden::Headers,
+            HttpTimelineEventIden::Elapsed,
+            HttpTimelineEventIden::Duration,
+        ])
+        .values_panic([
+            id.into(),
+            CurrentTimestamp.into(),
+            CurrentTimestamp.into(),
+            event.workspace_id.as_str().into(),
+            event.response_id.as_str().into(),
+            serde_json::to_value(&event.event_type)?.as_str().into(),
+            event.content.as_str().into(),
+            serde_json::to_string(&event.headers)?.into(),
+            event.elapsed.into(),
+            event.duration.into(),
+        ])
+        .on_conflict(
+            OnConflict::column(HttpTimelineEventIden::Id)
+                .update_columns([
+                    HttpTimelineEventIden::UpdatedAt,
+                    HttpTimelineEventIden::EventType,
+                    HttpTimelineEventIden::Content,
+                    HttpTimelineEventIden::Headers,
+                    HttpTimelineEventIden::Elapsed,
+                    HttpTimelineEventIden::Duration,
+                ])
+                .to_owned(),
+        )
+        .returning_all()
+        .build_rusqlite(SqliteQueryBuilder);
+
+    let mut stmt = db.prepare(sql.as_str())?;
+    Ok(stmt.query_row(&*params.as_params(), |row| row.try_into())?)
+}
+
+pub async fn list_http_timeline_events<R: Runtime>(
+    mgr: &impl Manager<R>,
+    response_id: &str,
+) -> Result<Vec<HttpTimelineEvent>> {
+    let dbm = &*mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    query_http_timeline_events(&db, response_id)
+}
+
+fn query_http_timeline_events(
+    db: &Connection,
+    response_id: &str,
+) -> Result<Vec<HttpTimelineEvent>> {
+    let (sql, params) = Query::select()
+        .from(HttpTimelineEventIden::Table)
+        .cond_where(Expr::col(HttpTimelineEventIden::ResponseId).eq(response_id))
+        .column(Asterisk)
+        .order_by(HttpTimelineEventIden::Elapsed, Order::Asc)
+        .order_by(Alias::new("rowid"), Order::Asc)
+        .build_rusqlite(SqliteQueryBuilder);
+    let mut stmt = db.prepare(sql.as_str())?;
+    let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
+    Ok(collect_rows(items))
+}
+
+pub async fn upsert_load_test<R: Runtime>(
+    window: &WebviewWindow<R>,
+    test: &LoadTest,
+) -> Result<LoadTest> {
+    let id = match test.id.as_str() {
+        "" => generate_model_id(ModelType::TypeLoadTest),
+        _ => test.id.to_string(),
+    };
+
+    let dbm = &*window.app_handle().state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    let m = write_load_test(&db, &id, test)?;
+    Ok(emit_upserted_model(window, m))
+}
+
+fn write_load_test(db: &Connection, id: &str, test: &LoadTest) -> Result<LoadTest> {
+    let (sql, params) = Query::insert()
+        .into_table(LoadTestIden::Table)
+        .columns([
+            LoadTestIden::Id,
+            LoadTestIden::CreatedAt,
+            LoadTestIden::UpdatedAt,
+            LoadTestIden::WorkspaceId,
+            LoadTestIden::RequestId,
+            LoadTestIden::Duration,
+            LoadTestIden::Concurrency,
+            LoadTestIden::State,
+            LoadTestIden::Elapsed,
+            LoadTestIden::Requests,
+            LoadTestIden::Errors,
+            LoadTestIden::StatusCounts,
+            LoadTestIden::LatencyMin,
+            LoadTestIden::LatencyMean,
+            LoadTestIden::LatencyP50,
+            LoadTestIden::LatencyP90,
+            LoadTestIden::LatencyP95,
+            LoadTestIden::LatencyP99,
+            LoadTestIden::LatencyMax,
+        ])
+        .values_panic([
+            id.into(),
+            CurrentTimestamp.into(),
+            CurrentTimestamp.into(),
+            test.workspace_id.as_str().into(),
+            test.request_id.as_str().into(),
+            test.duration.into(),
+            test.concurrency.into(),
+            serde_json::to_value(&test.state)?.as_str().into(),
+            test.elapsed.into(),
+            test.requests.into(),
+            test.errors.into(),
+            serde_json::to_string(&test.status_counts)?.into(),
+            test.latency_min.into(),
+            test.latency_mean.into(),
+            test.latency_p50.into(),
+            test.latency_p90.into(),
+            test.latency_p95.into(),
+            test.latency_p99.into(),
+            test.latency_max.into(),
+        ])
+        .on_conflict(
+            OnConflict::column(LoadTestIden::Id)
+                .update_columns([
+                    LoadTestIden::UpdatedAt,
+                    LoadTestIden::State,
+                    LoadTestIden::Elapsed,
+                    LoadTestIden::Requests,
+                    LoadTestIden::Errors,
+                    LoadTestIden::StatusCounts,
+                    LoadTestIden::LatencyMin,
+                    LoadTestIden::LatencyMean,
+                    LoadTestIden::LatencyP50,
+                    LoadTestIden::LatencyP90,
+                    LoadTestIden::LatencyP95,
+                    LoadTestIden::LatencyP99,
+                    LoadTestIden::LatencyMax,
+                ])
+                .to_owned(),
+        )
+        .returning_all()
+        .build_rusqlite(SqliteQueryBuilder);
+
+    let mut stmt = db.prepare(sql.as_str())?;
+    Ok(stmt.query_row(&*params.as_params(), |row| row.try_into())?)
+}
+
+/// Load tests of a request, newest first
+pub async fn list_load_tests<R: Runtime>(
+    mgr: &impl Manager<R>,
+    request_id: &str,
+) -> Result<Vec<LoadTest>> {
+    let dbm = &*mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    query_load_tests(&db, request_id)
+}
+
+fn query_load_tests(db: &Connection, request_id: &str) -> Result<Vec<LoadTest>> {
+    let (sql, params) = Query::select()
+        .from(LoadTestIden::Table)
+        .cond_where(Expr::col(LoadTestIden::RequestId).eq(request_id))
+        .column(Asterisk)
+        .order_by(Alias::new("rowid"), Order::Desc)
+        .build_rusqlite(SqliteQueryBuilder);
+    let mut stmt = db.prepare(sql.as_str())?;
+    let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
+    Ok(collect_rows(items))
+}
+
+pub async fn upsert_run_result<R: Runtime>(
+    window: &WebviewWindow<R>,
+    result: &RunResult,
+) -> Result<RunResult> {
+    let id = match result.id.as_str() {
+        "" => generate_model_id(ModelType::TypeRunResult),
+        _ => result.id.to_string(),
+    };
+
+    let dbm = &*window.app_handle().state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    let m = write_run_result(&db, &id, result)?;
+    Ok(emit_upserted_model(window, m))
+}
+
+fn write_run_result(db: &Connection, id: &str, result: &RunResult) -> Result<RunResult> {
+    let (sql, params) = Query::insert()
+        .into_table(RunResultIden::Table)
+        .columns([
+            RunResultIden::Id,
+            RunResultIden::CreatedAt,
+            RunResultIden::UpdatedAt,
+            RunResultIden::WorkspaceId,
+            RunResultIden::FolderId,
+            RunResultIden::RunId,
+            RunResultIden::RequestId,
+            RunResultIden::RequestModel,
+            RunResultIden::RequestName,
+            RunResultIden::Status,
+            RunResultIden::ResponseId,
+            RunResultIden::StatusCode,
+            RunResultIden::Elapsed,
+            RunResultIden::Error,
+        ])
+        .values_panic([
+            id.into(),
+            CurrentTimestamp.into(),
+            CurrentTimestamp.into(),
+            result.workspace_id.as_str().into(),
+            result.folder_id.as_str().into(),
+            result.run_id.as_str().into(),
+            result.request_id.as_str().into(),
+            result.request_model.as_str().into(),
+            result.request_name.as_str().into(),
+            serde_json::to_value(&result.status)?.as_str().into(),
+            result.response_id.as_ref().map(|s| s.as_str()).into(),
+            result.status_code.into(),
+            result.elapsed.into(),
+            result.error.as_ref().map(|s| s.as_str()).into(),
+        ])
+        .on_conflict(
+            OnConflict::column(RunResultIden::Id)
+                .update_columns([
+                    RunResultIden::UpdatedAt,
+                    RunResultIden::Status,
+                    RunResultIden::ResponseId,
+                    RunResultIden::StatusCode,
+                    RunResultIden::Elapsed,
+                    RunResultIden::Error,
+                ])
+                .to_owned(),
+        )
+        .returning_all()
+        .build_rusqlite(SqliteQueryBuilder);
+
+    let mut stmt = db.prepare(sql.as_str())?;
+    Ok(stmt.query_row(&*params.as_params(), |row| row.try_into())?)
+}
+
+/// Results of every run of a folder, oldest run first and in run order within each
+pub async fn list_run_results<R: Runtime>(
+    mgr: &impl Manager<R>,
+    folder_id: &str,
+) -> Result<Vec<RunResult>> {
+    let dbm = &*mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    query_run_results(&db, folder_id)
+}
+
+fn query_run_results(db: &Connection, folder_id: &str) -> Result<Vec<RunResult>> {
+    // Results of a fast run can share a timestamp, and rowid keeps them in run order
+    let (sql, params) = Query::select()
+        .from(RunResultIden::Table)
+        .cond_where(Expr::col(RunResultIden::FolderId).eq(folder_id))
+        .column(Asterisk)
+        .order_by(Alias::new("rowid"), Order::Asc)
+        .build_rusqlite(SqliteQueryBuilder);
+    let mut stmt = db.prepare(sql.as_str())?;
+    let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
+    Ok(collect_rows(items))
+}
+
+pub async fn insert_request_history<R: Runtime>(
+    window: &WebviewWindow<R>,
+    history: &RequestHistory,
+) -> Result<RequestHistory> {
+    let dbm = &*window.app_handle().state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    let id = generate_model_id(ModelType::TypeRequestHistory);
+    let m = write_request_history(&db, &id, history)?;
+    Ok(emit_upserted_model(window, m))
+}
+
+fn write_request_history(
+    db: &Connection,
+    id: &str,
+    history: &RequestHistory,
+) -> Result<RequestHistory> {
+    let (sql, params) = Query::insert()
+        .into_table(RequestHistoryIden::Table)
+        .columns([
+            RequestHistoryIden::Id,
+            RequestHistoryIden::CreatedAt,
+            RequestHistoryIden::UpdatedAt,
+            RequestHistoryIden::WorkspaceId,
+            RequestHistoryIden::RequestId,
+            RequestHistoryIden::ResponseId,
+            RequestHistoryIden::EnvironmentId,
+            RequestHistoryIden::SentBy,
+            RequestHistoryIden::Method,
+            RequestHistoryIden::Url,
+            RequestHistoryIden::RequestSnapshot,
+            RequestHistoryIden::Status,
+            RequestHistoryIden::Elapsed,
+            RequestHistoryIden::Error,
+        ])
+        .values_panic([
+            id.into(),
+            CurrentTimestamp.into(),
+            CurrentTimestamp.into(),
+            history.workspace_id.as_str().into(),
+            history.request_id.as_str().into(),
+            history.response_id.as_ref().map(|s| s.as_str()).into(),
+            history.environment_id.as_ref().map(|s| s.as_str()).into(),
+            history.sent_by.as_str().into(),
+            history.method.as_str().into(),
+            history.url.as_str().into(),
+            history.request_snapshot.as_ref().map(serde_json::to_string).transpose()?.into(),
+            history.status.into(),
+            history.elapsed.into(),
+            history.error.as_ref().map(|s| s.as_str()).into(),
+        ])
+        .returning_all()
+        .build_rusqlite(SqliteQueryBuilder);
+
+    let mut stmt = db.prepare(sql.as_str())?;
+    Ok(stmt.query_row(&*params.as_params(), |row| row.try_into())?)
+}
+
+/// Sends from the workspace, newest first, optionally only those of one request
+pub async fn list_request_history<R: Runtime>(
+    mgr: &impl Manager<R>,
+    workspace_id: &str,
+    request_id: Option<&str>,
+    limit: Option<i64>,
+) -> Result<Vec<RequestHistory>> {
+    let dbm = &*mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    query_request_history(&db, workspace_id, request_id, limit)
+}
+
+fn query_request_history(
+    db: &Connection,
+    workspace_id: &str,
+    request_id: Option<&str>,
+    limit: Option<i64>,
+) -> Result<Vec<RequestHistory>> {
+    let mut cond = Cond::all().add(Expr::col(RequestHistoryIden::WorkspaceId).eq(workspace_id));
+    if let Some(request_id) = request_id {
+        cond = cond.add(Expr::col(RequestHistoryIden::RequestId).eq(request_id));
+    }
+    // Sends can finish within the same millisecond, and rowid keeps them in order
+    let (sql, params) = Query::select()
+        .from(RequestHistoryIden::Table)
+        .cond_where(cond)
+        .column(Asterisk)
+        .order_by(Alias::new("rowid"), Order::Desc)
+        .limit(limit.unwrap_or(i64::MAX) as u64)
+        .build_rusqlite(SqliteQueryBuilder);
+    let mut stmt = db.prepare(sql.as_str())?;
+    let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
+    Ok(collect_rows(items))
+}
+
+/// Delete the workspace's history from before the given time, returning how many entries were
+/// deleted
+pub async fn delete_request_history_before<R: Runtime>(
+    mgr: &impl Manager<R>,
+    workspace_id: &str,
+    before: NaiveDateTime,
+) -> Result<usize> {
+    let dbm = &*mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    prune_request_history(&db, workspace_id, before)
+}
+
+fn prune_request_history(
+    db: &Connection,
+    workspace_id: &str,
+    before: NaiveDateTime,
+) -> Result<usize> {
+    let (sql, params) = Query::delete()
+        .from_table(RequestHistoryIden::Table)
+        .cond_where(
+            Cond::all()
+                .add(Expr::col(RequestHistoryIden::WorkspaceId).eq(workspace_id))
+                .add(Expr::col(RequestHistoryIden::CreatedAt).lt(before)),
+        )
+        .build_rusqlite(SqliteQueryBuilder);
+    Ok(db.execute(sql.as_str(), &*params.as_params())?)
+}
+
+pub async fn delete_all_http_responses_for_workspace<R: Runtime>(
+    window: &WebviewWindow<R>,
+    workspace_id: &str,
+) -> Result<()> {
+    for r in list_http_responses_for_workspace(window, workspace_id, None).await? {
+        delete_http_response(window, &r.id).await?;
+    }
+    Ok(())
+}
+
+/// Delete every response (and its body file) created before the given time, returning how
+/// many were deleted
+/// Delete every response created before `before` in a single transaction, then remove their
+/// files. Returns how many were deleted.
+pub async fn delete_http_responses_before<R: Runtime>(
+    window: &WebviewWindow<R>,
+    before: NaiveDateTime,
+) -> Result<usize> {
+    let responses: Vec<HttpResponse> = {
+        let dbm = &*window.app_handle().state::<SqliteConnection>();
+        let mut db = dbm.0.get().unwrap();
+        let tx = db.transaction()?;
+        let (sql, params) = Query::select()
+            .from(HttpResponseIden::Table)
+            .cond_where(Expr::col(HttpResponseIden::CreatedAt).lt(before))
+            .column(Asterisk)
+            .build_rusqlite(SqliteQueryBuilder);
+        let responses = {
+            let mut stmt = tx.prepare(sql.as_str())?;
+            let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
+            collect_rows(items)
+        };
+        let (sql, params) = Query::delete()
+            .from_table(HttpResponseIden::Table)
+            .cond_where(Expr::col(HttpResponseIden::CreatedAt).lt(before))
+            .build_rusqlite(SqliteQueryBuilder);
+        tx.execute(sql.as_str(), &*params.as_params())?;
+        tx.commit()?;
+        responses
+    };
+
+    remove_response_files(window, &responses)?;
+    Ok(responses.len())
+}
+
+/// Delete the given responses in a single transaction, then remove their body files. Returns
+/// the number of bytes freed by the removed body files.
+pub async fn delete_http_responses<R: Runtime>(
+    window: &WebviewWindow<R>,
+    responses: &[HttpResponse],
+) -> Result<u64> {
+    {
+        let dbm = &*window.app_handle().state::<SqliteConnection>();
+        let mut db = dbm.0.get().unwrap();
+        let tx = db.transaction()?;
+        // Chunk the ids to stay well under SQLite's bound parameter limit
+        for chunk in responses.chunks(500) {
+            let (sql, params) = Query::delete()
+                .from_table(HttpResponseIden::Table)
+                .cond_where(
+                    Expr::col(HttpResponseIden::Id).is_in(chunk.iter().map(|r| r.id.as_str())),
+                )
+                .build_rusqlite(SqliteQueryBuilder);
+            tx.execute(sql.as_str(), &*params.as_params())?;
+        }
+        tx.commit()?;
+    }
+
+    remove_response_files(window, responses)
+}
+
+/// Remove the body and raw request files of responses that were deleted, returning the bytes
+/// freed
+fn remove_response_files<R: Runtime>(
+    window: &WebviewWindow<R>,
+    responses: &[HttpResponse],
+) -> Result<u64> {
+    let mut bytes = 0;
+    for r in responses {
+        if let Some(p) = r.body_path.as_ref() {
+            bytes += fs::metadata(p).map(|m| m.len()).unwrap_or_default();
+            if let Err(e) = fs::remove_file(p) {
+                error!("Failed to delete body file: {}", e);
+            };
+        }
+        if let Some(p) = r.raw_request_path.as_ref() {
+            bytes += fs::metadata(p).map(|m| m.len()).unwrap_or_default();
+            if let Err(e) = fs::remove_file(p) {
+                error!("Failed to delete raw request file: {}", e);
+            };
+        }
+        emit_deleted_model(window, r.to_owned())?;
+    }
+    Ok(bytes)
+}
+
+/// Paths of every file that a response or example still points to
+pub async fn list_response_file_paths<R: Runtime>(
+    mgr: &impl Manager<R>,
+) -> Result<HashSet<String>> {
+    let dbm = mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    let selects = [
+        Query::select().column(HttpResponseIden::BodyPath).from(HttpResponseIden::Table).to_owned(),
+        Query::select()
+            .column(HttpResponseIden::RawRequestPath)
+            .from(HttpResponseIden::Table)
+            .to_owned(),
+        Query::select().column(HttpExampleIden::BodyPath).from(HttpExampleIden::Table).to_owned(),
+    ];
+
+    let mut paths = HashSet::new();
+    for select in selects {
+        let (sql, params) = select.build_rusqlite(SqliteQueryBuilder);
+        let mut stmt = db.prepare(sql.as_str())?;
+        let rows = stmt.query_map(&*params.as_params(), |row| row.get::<_, Option<String>>(0))?;
+        for path in rows {
+            paths.extend(path?);
+        }
+    }
+    Ok(paths)
+}
+
+pub async fn list_http_responses_for_workspace<R: Runtime>(
+    mgr: &impl Manager<R>,
+    workspace_id: &str,
+    limit: Option<i64>,
+) -> Result<Vec<HttpResponse>> {
+    let limit_unwrapped = limit.unwrap_or_else(|| i64::MAX);
+    let dbm = mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    let (sql, params) = Query::select()
+        .from(HttpResponseIden::Table)
+        .cond_where(Expr::col(HttpResponseIden::WorkspaceId).eq(workspace_id))
+        .column(Asterisk)
+        .order_by(HttpResponseIden::CreatedAt, Order::Desc)
+        .limit(limit_unwrapped as u64)
+        .build_rusqlite(SqliteQueryBuilder);
+    let mut stmt = db.prepare(sql.as_str())?;
+    let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
+    Ok(collect_rows(items))
+}
+
+pub async fn list_http_responses_for_request<R: Runtime>(
     mgr: &impl Manager<R>,
     request_id: &str,
     limit: Option<i64>,
 ) -> Result<Vec<HttpResponse>> {
     let limit_unwrapped = limit.unwrap_or_else(|| i64::MAX);
     let dbm = mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::select()
         .from(HttpResponseIden::Table)
         .cond_where(Expr::col(HttpResponseIden::RequestId).eq(request_id))
@@ -1516,7 +3023,81 @@ pub async fn list_http_responses_for_request<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
     let mut stmt = db.prepare(sql.as_str())?;
     let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
-    Ok(items.map(|v| v.unwrap()).collect())
+    Ok(collect_rows(items))
+}
+
+#[derive(Default, Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceStats {
+    pub folders: i64,
+    pub http_requests: i64,
+    pub grpc_requests: i64,
+    pub environments: i64,
+    pub cookie_jars: i64,
+    pub http_responses: i64,
+    pub http_response_bytes: i64,
+}
+
+/// Count the models in a workspace without loading them
+pub async fn get_workspace_stats<R: Runtime>(
+    mgr: &impl Manager<R>,
+    workspace_id: &str,
+) -> Result<WorkspaceStats> {
+    let dbm = &*mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+
+    let (sql, params) = Query::select()
+        .expr(Expr::cust("COUNT(*)"))
+        .expr(Func::sum(Expr::col(HttpResponseIden::ContentLength)))
+        .from(HttpResponseIden::Table)
+        .cond_where(Expr::col(HttpResponseIden::WorkspaceId).eq(workspace_id))
+        .build_rusqlite(SqliteQueryBuilder);
+    let (http_responses, http_response_bytes): (i64, Option<i64>) =
+        db.query_row(sql.as_str(), &*params.as_params(), |r| Ok((r.get(0)?, r.get(1)?)))?;
+
+    Ok(WorkspaceStats {
+        folders: count_rows(&db, FolderIden::Table, FolderIden::WorkspaceId, workspace_id)?,
+        http_requests: count_rows(
+            &db,
+            HttpRequestIden::Table,
+            HttpRequestIden::WorkspaceId,
+            workspace_id,
+        )?,
+        grpc_requests: count_rows(
+            &db,
+            GrpcRequestIden::Table,
+            GrpcRequestIden::WorkspaceId,
+            workspace_id,
+        )?,
+        environments: count_rows(
+            &db,
+            EnvironmentIden::Table,
+            EnvironmentIden::WorkspaceId,
+            workspace_id,
+        )?,
+        cookie_jars: count_rows(
+            &db,
+            CookieJarIden::Table,
+            CookieJarIden::WorkspaceId,
+            workspace_id,
+        )?,
+        http_responses,
+        http_response_bytes: http_response_bytes.unwrap_or_default(),
+    })
+}
+
+fn count_rows<T: Iden + 'static>(
+    db: &Connection,
+    table: T,
+    workspace_col: T,
+    workspace_id: &str,
+) -> Result<i64> {
+    let (sql, params) = Query::select()
+        .expr(Expr::cust("COUNT(*)"))
+        .from(table)
+        .cond_where(Expr::col(workspace_col).eq(workspace_id))
+        .build_rusqlite(SqliteQueryBuilder);
+    Ok(db.query_row(sql.as_str(), &*params.as_params(), |r| r.get(0))?)
 }
 
 pub async fn list_responses_by_workspace_id<R: Runtime>(
@@ -1524,7 +3105,7 @@ pub async fn list_responses_by_workspace_id<R: Runtime>(
     workspace_id: &str,
 ) -> Result<Vec<HttpResponse>> {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await.get().unwrap();
+    let db = dbm.0.get().unwrap();
     let (sql, params) = Query::select()
         .from(HttpResponseIden::Table)
         .cond_where(Expr::col(HttpResponseIden::WorkspaceId).eq(workspace_id))
@@ -1533,13 +3114,113 @@ pub async fn list_responses_by_workspace_id<R: Runtime>(
         .build_rusqlite(SqliteQueryBuilder);
     let mut stmt = db.prepare(sql.as_str())?;
     let items = stmt.query_map(&*params.as_params(), |row| row.try_into())?;
-    Ok(items.map(|v| v.unwrap()).collect())
+    Ok(collect_rows(items))
+}
+
+/// Write a consistent copy of the database to the given path. Note that response bodies
+/// live in the separate `responses` directory and are not part of the backup.
+pub async fn backup_database<R: Runtime>(mgr: &impl Manager<R>, path: &Path) -> Result<()> {
+    let dbm = &*mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    let path = path.to_path_buf();
+    tauri::async_runtime::spawn_blocking(move || vacuum_into(&db, &path))
+        .await
+        .map_err(|_| Unknown)?
+}
+
+/// VACUUM INTO refuses to overwrite, so write to a file next to the target and move it into
+/// place. Whatever the user chose to replace is left alone if the backup fails.
+fn vacuum_into(db: &Connection, path: &Path) -> Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    if tmp_path.exists() {
+        fs::remove_file(&tmp_path)?;
+    }
+
+    if let Err(e) = db.execute("VACUUM INTO ?1", [tmp_path.to_string_lossy()]) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Rebuild the database to reclaim the pages left behind by deleted rows. Other writers wait on
+/// the busy timeout while it runs.
+pub async fn vacuum_database<R: Runtime>(mgr: &impl Manager<R>) -> Result<()> {
+    let dbm = &*mgr.state::<SqliteConnection>();
+    let db = dbm.0.get().unwrap();
+    tauri::async_runtime::spawn_blocking(move || vacuum(&db)).await.map_err(|_| Unknown)?
+}
+
+fn vacuum(db: &Connection) -> Result<()> {
+    // Checkpoint first so the WAL file is emptied too, not just the main database file
+    db.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    db.execute("VACUUM", [])?;
+    Ok(())
+}
+
+/// Validate a backup and stage it to replace the current database on next launch
+pub async fn restore_database<R: Runtime>(mgr: &impl Manager<R>, path: &Path) -> Result<()> {
+    let path = path.to_path_buf();
+    let restore_path = mgr.path().app_data_dir().unwrap().join(RESTORE_DB_FILE_NAME);
+    tauri::async_runtime::spawn_blocking(move || stage_restore(&path, &restore_path))
+        .await
+        .map_err(|_| Unknown)?
+}
+
+/// Copy a backup into place, then check the copy. FTS5 can't check its indexes in a read-only
+/// database, so it's the copy that's opened for writing rather than the user's file.
+fn stage_restore(path: &Path, restore_path: &Path) -> Result<()> {
+    fs::copy(path, restore_path)?;
+    if let Err(e) = validate_backup(restore_path) {
+        let _ = fs::remove_file(restore_path);
+        return Err(e);
+    }
+    Ok(())
+}
+
+fn validate_backup(path: &Path) -> Result<()> {
+    let db = rusqlite::Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE)
+        .map_err(|e| InvalidBackup(e.to_string()))?;
+
+    let integrity: String = db
+        .query_row("PRAGMA integrity_check", [], |r| r.get(0))
+        .map_err(|e| InvalidBackup(e.to_string()))?;
+    if integrity != "ok" {
+        return Err(InvalidBackup(integrity));
+    }
+
+    let num_tables: i32 = db.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name IN ('settings', 'workspaces')",
+        [],
+        |r| r.get(0),
+    )?;
+    if num_tables != 2 {
+        return Err(InvalidBackup("Not a Yaak database".to_string()));
+    }
+
+    Ok(())
 }
 
 pub async fn debug_pool<R: Runtime>(mgr: &impl Manager<R>) {
     let dbm = &*mgr.state::<SqliteConnection>();
-    let db = dbm.0.lock().await;
-    debug!("Debug database state: {:?}", db.state());
+    debug!("Debug database state: {:?}", dbm.0.state());
+}
+
+/// Collect the rows of a list query, logging and skipping any that fail to load so a single
+/// corrupt row doesn't take the whole list down with it
+fn collect_rows<T>(items: impl Iterator<Item = rusqlite::Result<T>>) -> Vec<T> {
+    items
+        .filter_map(|item| match item {
+            Ok(v) => Some(v),
+            Err(e) => {
+                warn!("Skipping row that failed to load: {e}");
+                None
+            }
+        })
+        .collect()
 }
 
 pub fn generate_model_id(model: ModelType) -> String {
@@ -1579,3 +3260,813 @@ fn emit_deleted_model<M: Serialize + Clone, R: Runtime>(
     window.emit("deleted_model", payload).unwrap();
     Ok(model)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error::{AmbiguousModel, ForeignSecretRef, InvalidBackup, ModelNotFound};
+    use crate::models::{
+        EnvironmentVariable, HttpExample, HttpRequest, HttpResponse, HttpResponseEvent,
+        HttpResponseHeader, HttpResponseIden, HttpResponseState, HttpTimelineEvent,
+        HttpTimelineEventType, LoadTest, LoadTestState, RequestHistory, RunResult, RunResultStatus,
+        Settings, Workspace, WorkspaceIden,
+    };
+    use crate::queries::{
+        close_pending_responses, collect_rows, copy_http_response, fts_query,
+        mark_workspace_active, owned_secret_refs, prune_request_history, query_environment_by_name,
+        query_http_examples, query_http_response_events, query_http_timeline_events,
+        query_last_location, query_load_tests, query_request_history, query_request_summaries,
+        query_run_results, query_search, secret_variable_accounts, stage_restore,
+        store_secret_variables, vacuum, vacuum_into, write_http_example,
+        write_http_response_body_index, write_http_response_event, write_http_timeline_event,
+        write_load_test, write_request_history, write_run_result, write_settings, write_workspace,
+        LastLocation,
+    };
+    use chrono::NaiveDate;
+    use rusqlite::Connection;
+    use sea_query::ColumnRef::Asterisk;
+    use sea_query::{Iden, Order, Query, SqliteQueryBuilder};
+    use sea_query_rusqlite::RusqliteBinder;
+    use std::fs;
+
+    fn migrated_db() -> Connection {
+        let db = Connection::open_in_memory().unwrap();
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../migrations");
+        let mut migrations: Vec<_> =
+            fs::read_dir(dir).unwrap().map(|e| e.unwrap().path()).collect();
+        migrations.sort();
+        for m in migrations {
+            db.execute_batch(&fs::read_to_string(m).unwrap()).unwrap();
+        }
+        db
+    }
+
+    fn list_all<T, I>(db: &Connection, table: I, id: I) -> Vec<T>
+    where
+        T: for<'r, 's> TryFrom<&'r rusqlite::Row<'s>, Error = rusqlite::Error>,
+        I: Iden + 'static,
+    {
+        let (sql, params) = Query::select()
+            .from(table)
+            .column(Asterisk)
+            .order_by(id, Order::Asc)
+            .build_rusqlite(SqliteQueryBuilder);
+        let mut stmt = db.prepare(sql.as_str()).unwrap();
+        let items = stmt.query_map(&*params.as_params(), |row| T::try_from(row)).unwrap();
+        collect_rows(items)
+    }
+
+    #[test]
+    fn skip_corrupt_rows() {
+        let db = migrated_db();
+        db.execute_batch(
+            r#"
+            INSERT INTO workspaces (id, name, description)
+            VALUES ('wk_1', 'One', ''), ('wk_2', 'Two', ''), ('wk_3', 'Three', '');
+            UPDATE workspaces SET created_at = 'not a date' WHERE id = 'wk_2';
+
+            INSERT INTO http_responses
+                (id, request_id, workspace_id, elapsed, status, url, headers, state)
+            VALUES
+                ('rs_1', 'rq_1', 'wk_1', 0, 200, '', '[]', 'closed'),
+                ('rs_2', 'rq_1', 'wk_1', 0, 200, '', '[]', 'exploded');
+            "#,
+        )
+        .unwrap();
+
+        let workspaces: Vec<Workspace> = list_all(&db, WorkspaceIden::Table, WorkspaceIden::Id);
+        let ids: Vec<&str> = workspaces.iter().map(|w| w.id.as_str()).collect();
+        assert_eq!(ids, vec!["wk_1", "wk_3"]);
+
+        let responses: Vec<HttpResponse> =
+            list_all(&db, HttpResponseIden::Table, HttpResponseIden::Id);
+        let ids: Vec<&str> = responses.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["rs_1"]);
+    }
+
+    const LONG_AGO: &str = "2020-01-01 00:00:00";
+
+    /// Backdate a row so a later write is guaranteed a newer (second-resolution) timestamp
+    fn backdate(db: &Connection, table: &str, id: &str) {
+        db.execute(
+            &format!("UPDATE {table} SET created_at = ?1, updated_at = ?1 WHERE id = ?2"),
+            [LONG_AGO, id],
+        )
+        .unwrap();
+    }
+
+    fn timestamps(db: &Connection, table: &str, id: &str) -> (String, String) {
+        db.query_row(
+            &format!("SELECT created_at, updated_at FROM {table} WHERE id = ?1"),
+            [id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn upsert_workspace_keeps_created_at() {
+        let db = migrated_db();
+        let workspace = Workspace {
+            id: "wk_1".to_string(),
+            name: "Before".to_string(),
+            ..Default::default()
+        };
+        let created = write_workspace(&db, workspace.clone()).unwrap();
+        assert_eq!(created.created_at, created.updated_at);
+        backdate(&db, "workspaces", "wk_1");
+
+        let updated = write_workspace(
+            &db,
+            Workspace {
+                name: "After".to_string(),
+                ..workspace
+            },
+        )
+        .unwrap();
+        assert_eq!(updated.name, "After");
+        assert_eq!(updated.created_at.to_string(), LONG_AGO);
+        assert!(updated.updated_at > updated.created_at);
+    }
+
+    #[test]
+    fn workspace_activity_is_debounced() {
+        let db = migrated_db();
+        let workspace = Workspace {
+            id: "wk_1".to_string(),
+            ..Default::default()
+        };
+        let created = write_workspace(&db, workspace).unwrap();
+        assert!(created.last_active_at.is_some());
+
+        // Just written, so a burst of activity right after doesn't write again
+        assert!(mark_workspace_active(&db, "wk_1").unwrap().is_none());
+
+        db.execute("UPDATE workspaces SET last_active_at = ?1", [LONG_AGO]).unwrap();
+        let touched = mark_workspace_active(&db, "wk_1").unwrap().unwrap();
+        assert!(touched.last_active_at.unwrap().to_string().as_str() > LONG_AGO);
+        assert!(mark_workspace_active(&db, "wk_missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn update_settings_keeps_created_at() {
+        let db = migrated_db();
+        db.execute_batch("INSERT INTO settings (id) VALUES ('default')").unwrap();
+        backdate(&db, "settings", "default");
+
+        let settings = Settings {
+            theme: "dark".to_string(),
+            ..Default::default()
+        };
+        let updated = write_settings(&db, settings).unwrap();
+        assert_eq!(updated.created_at.to_string(), LONG_AGO);
+        assert!(updated.updated_at > updated.created_at);
+    }
+
+    #[test]
+    fn cancel_pending_responses_touches_updated_at() {
+        let db = migrated_db();
+        db.execute_batch(
+            r#"
+            INSERT INTO workspaces (id, name, description) VALUES ('wk_1', 'One', '');
+            INSERT INTO http_requests (id, workspace_id, name, url, method, headers)
+            VALUES ('rq_1', 'wk_1', '', '', 'GET', '[]');
+            INSERT INTO http_responses
+                (id, request_id, workspace_id, elapsed, status, url, headers, state)
+            VALUES
+                ('rs_1', 'rq_1', 'wk_1', 0, 0, '', '[]', 'initialized'),
+                ('rs_2', 'rq_1', 'wk_1', 0, 200, '', '[]', 'closed');
+            "#,
+        )
+        .unwrap();
+        backdate(&db, "http_responses", "rs_1");
+        backdate(&db, "http_responses", "rs_2");
+
+        close_pending_responses(&db).unwrap();
+
+        let responses: Vec<HttpResponse> =
+            list_all(&db, HttpResponseIden::Table, HttpResponseIden::Id);
+        assert!(responses.iter().all(|r| matches!(r.state, HttpResponseState::Closed)));
+        let (created_at, updated_at) = timestamps(&db, "http_responses", "rs_1");
+        assert_eq!(created_at, LONG_AGO);
+        assert!(updated_at.as_str() > LONG_AGO);
+        assert_eq!(timestamps(&db, "http_responses", "rs_2").1, LONG_AGO);
+    }
+
+    #[test]
+    fn list_requests_across_workspaces() {
+        let db = migrated_db();
+        db.execute_batch(
+            r#"
+            INSERT INTO workspaces (id, name, description) VALUES ('wk_1', 'One', ''), ('wk_2', 'Two', '');
+            INSERT INTO folders (id, workspace_id, folder_id, name)
+            VALUES ('fl_1', 'wk_1', NULL, 'Users'), ('fl_2', 'wk_1', 'fl_1', 'Admin');
+            INSERT INTO http_requests (id, workspace_id, folder_id, name, url, method, headers, body)
+            VALUES ('rq_1', 'wk_1', 'fl_2', 'Delete User', '', 'DELETE', '[]', '{}'),
+                   ('rq_2', 'wk_2', NULL, 'Health', '', 'GET', '[]', '{}');
+            INSERT INTO grpc_requests (id, workspace_id, folder_id, name, url, sort_priority, message)
+            VALUES ('gr_1', 'wk_1', 'fl_1', 'Stream', '', 1, '');
+            "#,
+        )
+        .unwrap();
+
+        let summaries = query_request_summaries(&db).unwrap();
+        let summaries: Vec<_> = summaries
+            .iter()
+            .map(|r| {
+                (
+                    r.model.as_str(),
+                    r.id.as_str(),
+                    r.workspace_id.as_str(),
+                    r.method.as_deref(),
+                    r.folder_path.join("/"),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summaries,
+            vec![
+                ("http_request", "rq_1", "wk_1", Some("DELETE"), "Users/Admin".to_string()),
+                ("grpc_request", "gr_1", "wk_1", None, "Users".to_string()),
+                ("http_request", "rq_2", "wk_2", Some("GET"), "".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn copy_response_with_body() {
+        let db = migrated_db();
+        let dir = std::env::temp_dir().join("yaak-copy-response");
+        fs::create_dir_all(&dir).unwrap();
+        let body_path = dir.join("rs_1");
+        fs::write(&body_path, "hello").unwrap();
+        let raw_request_path = dir.join("rs_1.request");
+        fs::write(&raw_request_path, "GET / HTTP/1.1\r\n\r\n").unwrap();
+        db.execute_batch(
+            r#"
+            INSERT INTO workspaces (id, name, description) VALUES ('wk_1', 'One', '');
+            INSERT INTO http_requests (id, workspace_id, name, url, method, headers)
+            VALUES ('rq_1', 'wk_1', '', '', 'GET', '[]'), ('rq_2', 'wk_1', '', '', 'GET', '[]');
+            "#,
+        )
+        .unwrap();
+        db.execute(
+            r#"
+            INSERT INTO http_responses
+                (id, created_at, request_id, workspace_id, elapsed, status, url, headers, body_path,
+                 raw_request_path)
+            VALUES
+                ('rs_1', '2020-01-01 00:00:00', 'rq_1', 'wk_1', 12, 200, 'https://yaak.app',
+                 '[{"name":"x-test","value":"1"}]', ?1, ?2)
+            "#,
+            [
+                body_path.to_str().unwrap(),
+                raw_request_path.to_str().unwrap(),
+            ],
+        )
+        .unwrap();
+        let original: Vec<HttpResponse> =
+            list_all(&db, HttpResponseIden::Table, HttpResponseIden::Id);
+
+        let copy = copy_http_response(&db, &original[0], "rq_2").unwrap();
+        assert_ne!(copy.id, "rs_1");
+        assert_eq!(copy.request_id, "rq_2");
+        assert_eq!(copy.created_at.to_string(), "2020-01-01 00:00:00");
+        assert_eq!((copy.status, copy.elapsed), (200, 12));
+        assert_eq!(copy.headers[0].name, "x-test");
+        let copy_path = copy.body_path.unwrap();
+        assert_eq!(copy_path, dir.join(&copy.id).to_str().unwrap());
+        assert_eq!(fs::read_to_string(&copy_path).unwrap(), "hello");
+        assert_eq!(fs::read_to_string(&body_path).unwrap(), "hello");
+        let copy_raw_path = copy.raw_request_path.unwrap();
+        assert_eq!(copy_raw_path, dir.join(format!("{}.request", copy.id)).to_str().unwrap());
+        assert_eq!(fs::read_to_string(&copy_raw_path).unwrap(), "GET / HTTP/1.1\r\n\r\n");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn vacuum_reclaims_deleted_pages() {
+        let db = migrated_db();
+        db.execute("INSERT INTO workspaces (id, name, description) VALUES ('wk_1', 'One', '')", [])
+            .unwrap();
+        for i in 0..500 {
+            db.execute(
+                "INSERT INTO http_requests (id, workspace_id, name, url, method, headers, body)
+                 VALUES (?1, 'wk_1', '', '', 'GET', '[]', ?2)",
+                [format!("rq_{i}"), "x".repeat(1000)],
+            )
+            .unwrap();
+        }
+        db.execute("DELETE FROM http_requests", []).unwrap();
+        let page_count = || -> i64 { db.query_row("PRAGMA page_count", [], |r| r.get(0)).unwrap() };
+        let before = page_count();
+
+        vacuum(&db).unwrap();
+        assert!(page_count() < before / 2);
+    }
+
+    #[test]
+    fn backup_replaces_existing_file() {
+        let db = migrated_db();
+        db.execute("INSERT INTO workspaces (id, name, description) VALUES ('wk_1', 'One', '')", [])
+            .unwrap();
+        let dir = std::env::temp_dir().join("yaak-backup");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("yaak.db");
+        fs::write(&path, "an older backup").unwrap();
+
+        vacuum_into(&db, &path).unwrap();
+        let backup = Connection::open(&path).unwrap();
+        let name: String =
+            backup.query_row("SELECT name FROM workspaces", [], |r| r.get(0)).unwrap();
+        assert_eq!(name, "One");
+        assert!(!dir.join("yaak.db.tmp").exists());
+
+        // A failed backup leaves the file it would have replaced
+        fs::write(&path, "an older backup").unwrap();
+        db.execute_batch("BEGIN").unwrap();
+        assert!(vacuum_into(&db, &path).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "an older backup");
+        assert!(!dir.join("yaak.db.tmp").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn restore_validates_backup() {
+        let db = migrated_db();
+        let dir = std::env::temp_dir().join("yaak-restore");
+        fs::create_dir_all(&dir).unwrap();
+        let (backup_path, restore_path) = (dir.join("backup.db"), dir.join("restore.db"));
+
+        vacuum_into(&db, &backup_path).unwrap();
+        stage_restore(&backup_path, &restore_path).unwrap();
+        assert!(restore_path.exists());
+
+        fs::write(&backup_path, "not a database").unwrap();
+        assert!(matches!(stage_restore(&backup_path, &restore_path), Err(InvalidBackup(_))));
+        assert!(!restore_path.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn environment_by_name() {
+        let db = migrated_db();
+        db.execute_batch(
+            r#"
+            INSERT INTO workspaces (id, name, description) VALUES ('wk_1', 'One', ''), ('wk_2', 'Two', '');
+            INSERT INTO environments (id, workspace_id, name)
+            VALUES ('ev_1', 'wk_1', 'Production'), ('ev_2', 'wk_1', 'Staging'),
+                   ('ev_3', 'wk_1', 'Staging'), ('ev_4', 'wk_2', 'Production');
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(query_environment_by_name(&db, "wk_1", "Production").unwrap().id, "ev_1");
+        assert_eq!(query_environment_by_name(&db, "wk_2", "Production").unwrap().id, "ev_4");
+        assert!(matches!(
+            query_environment_by_name(&db, "wk_1", "Staging"),
+            Err(AmbiguousModel(_))
+        ));
+        assert!(matches!(query_environment_by_name(&db, "wk_2", "Staging"), Err(ModelNotFound(_))));
+    }
+
+    #[test]
+    fn save_and_rename_examples() {
+        let db = migrated_db();
+        db.execute_batch(
+            r#"
+            INSERT INTO workspaces (id, name, description) VALUES ('wk_1', 'One', '');
+            INSERT INTO http_requests (id, workspace_id, name, url, method, headers)
+            VALUES ('rq_1', 'wk_1', '', '', 'GET', '[]'), ('rq_2', 'wk_1', '', '', 'GET', '[]');
+            "#,
+        )
+        .unwrap();
+
+        let example = HttpExample {
+            workspace_id: "wk_1".to_string(),
+            request_id: "rq_1".to_string(),
+            name: " Not Found ".to_string(),
+            status: 404,
+            headers: vec![HttpResponseHeader {
+                name: "content-type".to_string(),
+                value: "application/json".to_string(),
+            }],
+            body_path: Some("/tmp/ex_1".to_string()),
+            content_length: Some(2),
+            ..Default::default()
+        };
+        let saved = write_http_example(&db, "ex_1", &example).unwrap();
+        assert_eq!(saved.model, "http_example");
+        assert_eq!(saved.name, "Not Found");
+        assert_eq!(saved.headers[0].value, "application/json");
+        backdate(&db, "http_examples", "ex_1");
+        write_http_example(
+            &db,
+            "ex_2",
+            &HttpExample {
+                status: 200,
+                ..example.clone()
+            },
+        )
+        .unwrap();
+
+        // Saving again only renames it, since the response it froze can't change
+        let renamed = HttpExample {
+            name: "Missing User".to_string(),
+            status: 500,
+            ..saved
+        };
+        let renamed = write_http_example(&db, "ex_1", &renamed).unwrap();
+        assert_eq!((renamed.name.as_str(), renamed.status), ("Missing User", 404));
+
+        let examples = query_http_examples(&db, "rq_1").unwrap();
+        assert_eq!(examples.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(), ["ex_1", "ex_2"]);
+        assert!(query_http_examples(&db, "rq_2").unwrap().is_empty());
+    }
+
+    #[test]
+    fn response_events_in_arrival_order() {
+        let db = migrated_db();
+        db.execute_batch(
+            r#"
+            INSERT INTO workspaces (id, name, description) VALUES ('wk_1', 'One', '');
+            INSERT INTO http_requests (id, workspace_id, name, url, method, headers)
+            VALUES ('rq_1', 'wk_1', '', '', 'GET', '[]');
+            INSERT INTO http_responses
+                (id, request_id, workspace_id, elapsed, status, url, headers, state)
+            VALUES ('rs_1', 'rq_1', 'wk_1', 0, 200, '', '[]', 'connected');
+            "#,
+        )
+        .unwrap();
+
+        // Ids sort in the opposite order to arrival, so only rowid can keep them in order
+        for (id, data) in [("se_3", "first"), ("se_2", "second"), ("se_1", "third")] {
+            let event = HttpResponseEvent {
+                workspace_id: "wk_1".to_string(),
+                response_id: "rs_1".to_string(),
+                event_type: "message".to_string(),
+                data: data.to_string(),
+                ..Default::default()
+            };
+            let saved = write_http_response_event(&db, id, &event).unwrap();
+            assert_eq!(saved.model, "http_response_event");
+        }
+
+        let events = query_http_response_events(&db, "rs_1").unwrap();
+        assert_eq!(
+            events.iter().map(|e| e.data.as_str()).collect::<Vec<_>>(),
+            ["first", "second", "third"]
+        );
+
+        db.execute_batch("PRAGMA foreign_keys = ON; DELETE FROM http_responses;").unwrap();
+        assert!(query_http_response_events(&db, "rs_1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn timeline_events_by_elapsed() {
+        let db = migrated_db();
+        db.execute_batch(
+            r#"
+            INSERT INTO workspaces (id, name, description) VALUES ('wk_1', 'One', '');
+            INSERT INTO http_requests (id, workspace_id, name, url, method, headers)
+            VALUES ('rq_1', 'wk_1', '', '', 'GET', '[]');
+            INSERT INTO http_responses
+                (id, request_id, workspace_id, elapsed, status, url, headers, state)
+            VALUES ('rs_1', 'rq_1', 'wk_1', 0, 200, '', '[]', 'closed');
+            "#,
+        )
+        .unwrap();
+
+        // Sorted by when each phase started, then by when it was saved
+        let events = [
+            ("te_1", HttpTimelineEventType::RequestHeaders, 0, None),
+            ("te_2", HttpTimelineEventType::DnsLookup, 0, Some(4)),
+            ("te_3", HttpTimelineEventType::ResponseHeaders, 4, Some(20)),
+            ("te_4", HttpTimelineEventType::Download, 24, Some(3)),
+            ("te_5", HttpTimelineEventType::Connection, 24, None),
+        ];
+        for (id, event_type, elapsed, duration) in events {
+            let event = HttpTimelineEvent {
+                workspace_id: "wk_1".to_string(),
+                response_id: "rs_1".to_string(),
+                event_type,
+                headers: vec![HttpResponseHeader {
+                    name: "Accept".to_string(),
+                    value: "*/*".to_string(),
+                }],
+                elapsed,
+                duration,
+                ..Default::default()
+            };
+            let saved = write_http_timeline_event(&db, id, &event).unwrap();
+            assert_eq!(saved.model, "http_timeline_event");
+        }
+
+        let events = query_http_timeline_events(&db, "rs_1").unwrap();
+        assert_eq!(
+            events.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(),
+            ["te_1", "te_2", "te_3", "te_4", "te_5"]
+        );
+        assert_eq!(events[1].event_type, HttpTimelineEventType::DnsLookup);
+        assert_eq!(events[3].duration, Some(3));
+        assert_eq!(events[4].headers[0].value, "*/*");
+
+        db.execute_batch("PRAGMA foreign_keys = ON; DELETE FROM http_responses;").unwrap();
+        assert!(query_http_timeline_events(&db, "rs_1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn load_test_progress_and_history() {
+        let db = migrated_db();
+        db.execute_batch(
+            r#"
+            INSERT INTO workspaces (id, name, description) VALUES ('wk_1', 'One', '');
+            INSERT INTO http_requests (id, workspace_id, name, url, method, headers)
+            VALUES ('rq_1', 'wk_1', '', '', 'GET', '[]');
+            "#,
+        )
+        .unwrap();
+
+        let test = LoadTest {
+            workspace_id: "wk_1".to_string(),
+            request_id: "rq_1".to_string(),
+            duration: 10_000,
+            concurrency: 4,
+            ..Default::default()
+        };
+        write_load_test(&db, "lt_1", &test).unwrap();
+        let running = write_load_test(&db, "lt_2", &test).unwrap();
+        assert_eq!(running.model, "load_test");
+        assert_eq!(running.state, LoadTestState::Running);
+
+        // Progress updates the counts, but not what the test was started with
+        let finished = LoadTest {
+            duration: 1,
+            state: LoadTestState::Finished,
+            requests: 120,
+            errors: 3,
+            status_counts: [(200, 100), (503, 20)].into(),
+            latency_p99: 250,
+            ..running
+        };
+        write_load_test(&db, "lt_2", &finished).unwrap();
+
+        let tests = query_load_tests(&db, "rq_1").unwrap();
+        assert_eq!(tests.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), ["lt_2", "lt_1"]);
+        assert_eq!(tests[0].duration, 10_000);
+        assert_eq!((tests[0].requests, tests[0].errors, tests[0].latency_p99), (120, 3, 250));
+        assert_eq!(tests[0].status_counts.get(&503), Some(&20));
+
+        db.execute_batch("PRAGMA foreign_keys = ON; DELETE FROM http_requests;").unwrap();
+        assert!(query_load_tests(&db, "rq_1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn run_results_by_folder() {
+        let db = migrated_db();
+        db.execute_batch(
+            r#"
+            INSERT INTO workspaces (id, name, description) VALUES ('wk_1', 'One', '');
+            INSERT INTO folders (id, workspace_id, name) VALUES ('fl_1', 'wk_1', 'Suite');
+            INSERT INTO folders (id, workspace_id, name) VALUES ('fl_2', 'wk_1', 'Other');
+            "#,
+        )
+        .unwrap();
+
+        let result = |folder_id: &str, request_id: &str, status: RunResultStatus| RunResult {
+            workspace_id: "wk_1".to_string(),
+            folder_id: folder_id.to_string(),
+            run_id: "run_1".to_string(),
+            request_id: request_id.to_string(),
+            request_model: "http_request".to_string(),
+            status,
+            ..Default::default()
+        };
+        write_run_result(&db, "rr_2", &result("fl_1", "rq_1", RunResultStatus::Passed)).unwrap();
+        write_run_result(&db, "rr_1", &result("fl_1", "rq_2", RunResultStatus::Skipped)).unwrap();
+        write_run_result(&db, "rr_3", &result("fl_2", "rq_3", RunResultStatus::Passed)).unwrap();
+
+        let failed = RunResult {
+            status_code: Some(500),
+            ..result("fl_1", "rq_2", RunResultStatus::Failed)
+        };
+        let saved = write_run_result(&db, "rr_1", &failed).unwrap();
+        assert_eq!(saved.model, "run_result");
+        assert!(matches!(saved.status, RunResultStatus::Failed));
+
+        let results = query_run_results(&db, "fl_1").unwrap();
+        assert_eq!(
+            results.iter().map(|r| r.request_id.as_str()).collect::<Vec<_>>(),
+            ["rq_1", "rq_2"]
+        );
+        assert_eq!(results[1].status_code, Some(500));
+
+        db.execute_batch("PRAGMA foreign_keys = ON; DELETE FROM folders WHERE id = 'fl_1';")
+            .unwrap();
+        assert!(query_run_results(&db, "fl_1").unwrap().is_empty());
+        assert_eq!(query_run_results(&db, "fl_2").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn request_history_list_and_prune() {
+        let db = migrated_db();
+        db.execute_batch(
+            "INSERT INTO workspaces (id, name, description) VALUES ('wk_1', 'One', '');",
+        )
+        .unwrap();
+
+        let history = |request_id: &str, status: i32| RequestHistory {
+            workspace_id: "wk_1".to_string(),
+            request_id: request_id.to_string(),
+            method: "GET".to_string(),
+            url: "https://example.com".to_string(),
+            status,
+            ..Default::default()
+        };
+        write_request_history(&db, "rh_1", &history("rq_1", 200)).unwrap();
+        write_request_history(&db, "rh_2", &history("", 200)).unwrap();
+        let snapshot = HttpRequest {
+            url: "https://example.com/rendered".to_string(),
+            ..Default::default()
+        };
+        let saved = write_request_history(
+            &db,
+            "rh_3",
+            &RequestHistory {
+                request_snapshot: Some(snapshot),
+                ..history("rq_1", 500)
+            },
+        )
+        .unwrap();
+        assert_eq!(saved.model, "request_history");
+        assert_eq!(
+            saved.request_snapshot.map(|r| r.url),
+            Some("https://example.com/rendered".to_string())
+        );
+
+        let all = query_request_history(&db, "wk_1", None, None).unwrap();
+        assert_eq!(all.iter().map(|h| h.id.as_str()).collect::<Vec<_>>(), ["rh_3", "rh_2", "rh_1"]);
+        let latest = query_request_history(&db, "wk_1", Some("rq_1"), Some(1)).unwrap();
+        assert_eq!(latest.iter().map(|h| h.status).collect::<Vec<_>>(), [500]);
+
+        db.execute("UPDATE request_history SET created_at = '2020-01-01' WHERE id = 'rh_1'", [])
+            .unwrap();
+        let cutoff = NaiveDate::from_ymd_opt(2021, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        assert_eq!(prune_request_history(&db, "wk_1", cutoff).unwrap(), 1);
+        assert_eq!(query_request_history(&db, "wk_1", None, None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn search_workspace_index() {
+        let db = migrated_db();
+        db.execute_batch(
+            r#"
+            INSERT INTO workspaces (id, name, description)
+            VALUES ('wk_1', 'One', ''), ('wk_2', 'Two', '');
+            INSERT INTO http_requests (id, workspace_id, name, url, method, headers, body)
+            VALUES
+                ('rq_1', 'wk_1', 'List Users', 'https://example.com/users', 'GET',
+                 '[{"enabled": true, "name": "X-Trace", "value": "abc"}]', '{"text": "hello"}'),
+                ('rq_2', 'wk_2', 'Users', 'https://example.com/users', 'GET', '[]', '{}');
+            INSERT INTO grpc_requests (id, workspace_id, name, url, sort_priority, message)
+            VALUES ('gr_1', 'wk_1', 'Stream', 'localhost:50051', 1, '{"widget": 1}');
+            INSERT INTO http_responses
+                (id, request_id, workspace_id, elapsed, status, url, headers, state)
+            VALUES ('rs_1', 'rq_1', 'wk_1', 0, 200, 'https://example.com/users', '[]', 'closed');
+            "#,
+        )
+        .unwrap();
+        let search = |query: &str| -> Vec<(String, String)> {
+            query_search(&db, "wk_1", query, None)
+                .unwrap()
+                .into_iter()
+                .map(|m| (m.model, m.id))
+                .collect()
+        };
+        let pair = |model: &str, id: &str| (model.to_string(), id.to_string());
+
+        assert_eq!(search("list use"), [pair("http_request", "rq_1")]);
+        assert_eq!(search("WIDGET"), [pair("grpc_request", "gr_1")]);
+        assert_eq!(search("trace abc hello"), [pair("http_request", "rq_1")]);
+        assert_eq!(search("enabled"), []);
+        assert_eq!(search("text"), []);
+        assert_eq!(search("users"), [pair("http_request", "rq_1"), pair("http_response", "rs_1")]);
+        assert_eq!(search("needle"), []);
+        assert_eq!(search("  "), []);
+        assert_eq!(search("\"AND -(*"), []);
+
+        write_http_response_body_index(&db, "rs_1", "a needle in a haystack").unwrap();
+        let found = query_search(&db, "wk_1", "needle", None).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            (found[0].model.as_str(), found[0].request_id.as_str()),
+            ("http_response", "rq_1")
+        );
+        assert_eq!(found[0].request_name, "List Users");
+        assert_eq!(found[0].snippet, "a needle in a haystack");
+
+        db.execute_batch(
+            r#"
+            UPDATE http_requests SET name = 'All Accounts' WHERE id = 'rq_1';
+            DELETE FROM grpc_requests WHERE id = 'gr_1';
+            "#,
+        )
+        .unwrap();
+        assert_eq!(search("list"), []);
+        assert_eq!(search("accounts"), [pair("http_request", "rq_1")]);
+        assert_eq!(search("widget"), []);
+
+        // Deleting the request cascades to its responses, which leave the index with them
+        db.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        db.execute("DELETE FROM http_requests WHERE id = 'rq_1'", []).unwrap();
+        assert_eq!(search("needle"), []);
+        let indexed: i64 =
+            db.query_row("SELECT COUNT(*) FROM http_responses_fts", [], |r| r.get(0)).unwrap();
+        assert_eq!(indexed, 0);
+    }
+
+    #[test]
+    fn last_location_skips_deleted() {
+        let db = migrated_db();
+        assert_eq!(query_last_location(&db), None);
+
+        db.execute_batch(
+            r#"
+            INSERT INTO workspaces (id, name, description) VALUES ('wk_1', 'One', '');
+            INSERT INTO environments (id, workspace_id, name, variables)
+            VALUES ('ev_1', 'wk_1', 'Prod', '[]');
+            INSERT INTO grpc_requests (id, workspace_id, name, url, sort_priority, message)
+            VALUES ('gr_1', 'wk_1', 'Stream', 'localhost:50051', 1, '{}');
+            INSERT INTO key_values (namespace, key, value)
+            VALUES
+                ('global', 'recent_workspaces', '["wk_1", "wk_2"]'),
+                ('global', 'recent_environments::wk_1', '["ev_1"]'),
+                ('global', 'recent_cookie_jars::wk_1', '["cj_deleted"]'),
+                ('global', 'recent_requests::wk_1', 'not json');
+            "#,
+        )
+        .unwrap();
+        let location = LastLocation {
+            workspace_id: "wk_1".to_string(),
+            environment_id: Some("ev_1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(query_last_location(&db), Some(location.clone()));
+
+        let sql = r#"UPDATE key_values SET value = '["gr_1"]' WHERE key = 'recent_requests::wk_1'"#;
+        db.execute(sql, []).unwrap();
+        let with_request = LastLocation {
+            request_id: Some("gr_1".to_string()),
+            ..location
+        };
+        assert_eq!(query_last_location(&db), Some(with_request));
+
+        db.execute(
+            "INSERT INTO settings (id, restore_last_location) VALUES ('default', FALSE)",
+            [],
+        )
+        .unwrap();
+        assert_eq!(query_last_location(&db), None);
+    }
+
+    #[test]
+    fn fts_query_quotes_terms() {
+        assert_eq!(fts_query(" list  users "), Some(r#""list"* "users"*"#.to_string()));
+        assert_eq!(fts_query(r#"say "hi""#), Some(r#""say"* """hi"""*"#.to_string()));
+        assert_eq!(fts_query("\t\n"), None);
+    }
+
+    #[test]
+    fn secret_refs_belong_to_their_variable() {
+        let var = |name: &str, value: &str, secret: bool| EnvironmentVariable {
+            enabled: true,
+            name: name.to_string(),
+            value: value.to_string(),
+            secret,
+        };
+        let tokens = [var("token", "a", true), var("token", "b", true)];
+        let accounts = secret_variable_accounts("ev_1", &tokens);
+        assert_eq!(accounts, ["variables::ev_1::token", "variables#2::ev_1::token"]);
+
+        let variables = [
+            var("token", "keychain:variables::ev_1::token", true),
+            var("stolen", "keychain:variables::ev_other::token", true),
+            var("plain", "keychain:variables::ev_1::plain", false),
+            var("token", "keychain:variables#2::ev_1::token", true),
+        ];
+        assert_eq!(owned_secret_refs("ev_1", &variables), [0, 3]);
+        assert_eq!(owned_secret_refs("ev_2", &variables), [] as [usize; 0]);
+
+        // An unreadable secret keeps its own reference, but another model's is refused
+        let own = [variables[0].clone()];
+        let kept = store_secret_variables("yaak.test", "ev_1", &own, &[]).unwrap();
+        assert_eq!(kept[0].value, own[0].value);
+        assert!(matches!(
+            store_secret_variables("yaak.test", "ev_1", &variables[1..2], &[]),
+            Err(ForeignSecretRef(name)) if name == "stolen"
+        ));
+        // A variable that isn't secret is never resolved, so its value is saved as is
+        let plain = store_secret_variables("yaak.test", "ev_1", &variables[2..3], &[]).unwrap();
+        assert_eq!(plain[0].value, variables[2].value);
+    }
+}
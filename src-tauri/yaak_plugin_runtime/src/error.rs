@@ -28,6 +28,12 @@ pub enum Error {
     
     #[error("Plugin error: {0}")]
     PluginErr(String),
+
+    #[error("Plugin timed out: {0}")]
+    PluginTimeoutErr(String),
+
+    #[error("Failed to import with {plugin}: {error}")]
+    PluginImportErr { plugin: String, error: String },
     
     #[error("Client not initialized error")]
     ClientNotInitializedErr,
@@ -36,9 +42,9 @@ pub enum Error {
     UnknownEventErr,
 }
 
-impl Into<String> for Error {
-    fn into(self) -> String {
-        todo!()
+impl From<Error> for String {
+    fn from(e: Error) -> Self {
+        e.to_string()
     }
 }
 
@@ -47,6 +47,8 @@ pub enum InternalEventPayload {
 
     ImportRequest(ImportRequest),
     ImportResponse(ImportResponse),
+    GetImporterRequest,
+    GetImporterResponse(Importer),
 
     FilterRequest(FilterRequest),
     FilterResponse(FilterResponse),
@@ -85,6 +87,12 @@ pub enum InternalEventPayload {
     FindHttpResponsesRequest(FindHttpResponsesRequest),
     FindHttpResponsesResponse(FindHttpResponsesResponse),
 
+    RunScriptRequest(RunScriptRequest),
+    RunScriptResponse(RunScriptResponse),
+
+    /// Returned when a plugin hook throws, so the error can be shown to the user
+    ErrorResponse(ErrorResponse),
+
     /// Returned when a plugin doesn't get run, just so the server
     /// has something to listen for
     EmptyResponse,
@@ -107,6 +115,18 @@ pub struct BootResponse {
     pub capabilities: Vec<String>,
 }
 
+/// Whether a plugin booted in the runtime, and the error if it didn't
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "events.ts")]
+pub struct PluginStatus {
+    pub dir: String,
+    pub name: String,
+    pub version: String,
+    pub loaded: bool,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
 #[serde(default, rename_all = "camelCase")]
 #[ts(export, export_to = "events.ts")]
@@ -121,6 +141,27 @@ pub struct ImportResponse {
     pub resources: ImportResources,
 }
 
+/// An import plugin and the formats it reads, so the app can pick importers for a file
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "events.ts")]
+pub struct Importer {
+    pub plugin_ref_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// File extensions the importer reads, without the leading dot
+    pub extensions: Vec<String>,
+    /// MIME types the importer reads, matched against the type guessed from the file name
+    pub mime_types: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "events.ts")]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
 #[serde(default, rename_all = "camelCase")]
 #[ts(export, export_to = "events.ts")]
@@ -136,6 +177,43 @@ pub struct FilterResponse {
     pub content: String,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "events.ts")]
+pub struct RunScriptRequest {
+    pub phase: ScriptPhase,
+    pub script: String,
+    pub http_request: HttpRequest,
+    /// The response, for post-response scripts
+    #[ts(optional)]
+    pub http_response: Option<HttpResponse>,
+    /// The response body as text, for post-response scripts
+    #[ts(optional)]
+    pub body: Option<String>,
+    pub variables: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "events.ts")]
+pub struct RunScriptResponse {
+    /// The request after the script changed it. Only used before sending.
+    pub http_request: HttpRequest,
+    /// Variables the script set, to be saved to the active environment
+    pub variables: HashMap<String, String>,
+    /// Lines the script wrote to its console
+    pub logs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "events.ts")]
+pub enum ScriptPhase {
+    #[default]
+    PreRequest,
+    PostResponse,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
 #[serde(default, rename_all = "camelCase")]
 #[ts(export, export_to = "events.ts")]
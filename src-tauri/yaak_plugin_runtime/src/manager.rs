@@ -1,20 +1,23 @@
-use crate::error::Error::{ClientNotInitializedErr, PluginErr, PluginNotFoundErr, UnknownEventErr};
+use crate::error::Error::{
+    ClientNotInitializedErr, PluginErr, PluginImportErr, PluginNotFoundErr, PluginTimeoutErr,
+    UnknownEventErr,
+};
 use crate::error::Result;
 use crate::events::{
     BootRequest, CallHttpRequestActionRequest, CallTemplateFunctionArgs,
     CallTemplateFunctionRequest, CallTemplateFunctionResponse, FilterRequest, FilterResponse,
     GetHttpRequestActionsRequest, GetHttpRequestActionsResponse, GetTemplateFunctionsResponse,
-    ImportRequest, ImportResponse, InternalEvent, InternalEventPayload, RenderPurpose,
-    WindowContext,
+    ImportRequest, ImportResponse, Importer, InternalEvent, InternalEventPayload, PluginStatus,
+    RenderPurpose, RunScriptRequest, RunScriptResponse, WindowContext,
 };
 use crate::nodejs::start_nodejs_plugin_runtime;
 use crate::plugin_handle::PluginHandle;
 use crate::server::plugin_runtime::plugin_runtime_server::PluginRuntimeServer;
 use crate::server::PluginRuntimeServerImpl;
-use log::{info, warn};
+use log::{debug, info, warn};
 use std::collections::HashMap;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tauri::path::BaseDirectory;
@@ -24,12 +27,16 @@ use tokio::net::TcpListener;
 use tokio::sync::{mpsc, Mutex};
 use tonic::codegen::tokio_stream;
 use tonic::transport::Server;
-use yaak_models::queries::{generate_id, list_plugins};
+use yaak_models::queries::{generate_id, get_or_create_settings, list_plugins};
 
 #[derive(Clone)]
 pub struct PluginManager {
     subscribers: Arc<Mutex<HashMap<String, mpsc::Sender<InternalEvent>>>>,
     plugins: Arc<Mutex<Vec<PluginHandle>>>,
+    /// Why each plugin that failed to boot didn't load, by directory
+    boot_errors: Arc<Mutex<HashMap<String, String>>>,
+    /// How long to wait for plugins to reply before giving up on them. None waits forever.
+    timeout: Arc<Mutex<Option<Duration>>>,
     kill_tx: tokio::sync::watch::Sender<bool>,
     server: Arc<PluginRuntimeServerImpl>,
 }
@@ -52,6 +59,8 @@ impl PluginManager {
 
         let plugin_manager = PluginManager {
             plugins: Arc::new(Mutex::new(Vec::new())),
+            boot_errors: Arc::new(Mutex::new(HashMap::new())),
+            timeout: Arc::new(Mutex::new(None)),
             subscribers: Arc::new(Mutex::new(HashMap::new())),
             server: Arc::new(server.clone()),
             kill_tx: kill_server_tx,
@@ -98,6 +107,8 @@ impl PluginManager {
                 match client_connect_rx.changed().await {
                     Ok(_) => {
                         info!("Plugin runtime client connected!");
+                        let settings = get_or_create_settings(&app_handle).await;
+                        plugin_manager.set_timeout(settings.plugin_timeout).await;
                         plugin_manager
                             .initialize_all_plugins(&app_handle, WindowContext::None)
                             .await
@@ -194,6 +205,7 @@ impl PluginManager {
         if let Some(pos) = pos {
             plugins.remove(pos);
         }
+        self.boot_errors.lock().await.remove(&plugin.dir);
 
         Ok(())
     }
@@ -205,12 +217,26 @@ impl PluginManager {
         watch: bool,
     ) -> Result<()> {
         info!("Adding plugin by dir {dir}");
-        let maybe_tx = self.server.app_to_plugin_events_tx.lock().await;
-        let tx = match &*maybe_tx {
+        let result = self.boot_plugin(window_context, dir, watch).await;
+        let mut boot_errors = self.boot_errors.lock().await;
+        match &result {
+            Ok(()) => boot_errors.remove(dir),
+            Err(e) => boot_errors.insert(dir.to_string(), e.to_string()),
+        };
+        result
+    }
+
+    async fn boot_plugin(
+        &self,
+        window_context: WindowContext,
+        dir: &str,
+        watch: bool,
+    ) -> Result<()> {
+        let tx = match &*self.server.app_to_plugin_events_tx.lock().await {
             None => return Err(ClientNotInitializedErr),
-            Some(tx) => tx,
+            Some(tx) => tx.clone(),
         };
-        let plugin_handle = PluginHandle::new(dir, tx.clone());
+        let plugin_handle = PluginHandle::new(dir, tx);
 
         // Add the new plugin
         self.plugins.lock().await.push(plugin_handle.clone());
@@ -218,7 +244,7 @@ impl PluginManager {
         // Boot the plugin
         let event = self
             .send_to_plugin_and_wait(
-                window_context,
+                window_context.to_owned(),
                 &plugin_handle,
                 &InternalEventPayload::BootRequest(BootRequest {
                     dir: dir.to_string(),
@@ -229,7 +255,16 @@ impl PluginManager {
 
         let resp = match event.payload {
             InternalEventPayload::BootResponse(resp) => resp,
-            _ => return Err(UnknownEventErr),
+            payload => {
+                // A plugin that didn't boot can't answer anything else, so don't keep it
+                if let Err(e) = self.remove_plugin(window_context, &plugin_handle).await {
+                    warn!("Failed to remove plugin {dir} after it failed to boot {e:?}");
+                }
+                return match payload {
+                    InternalEventPayload::ErrorResponse(resp) => Err(PluginErr(resp.error)),
+                    _ => Err(UnknownEventErr),
+                };
+            }
         };
 
         // Set the boot response
@@ -238,6 +273,53 @@ impl PluginManager {
         Ok(())
     }
 
+    /// Reload a plugin's code and hooks. If the new code fails to load, the previous version
+    /// stays running and the error is returned.
+    pub async fn reload_plugin(
+        &self,
+        window_context: WindowContext,
+        dir: &str,
+    ) -> Result<BootResponse> {
+        let plugin = match self.get_plugin_by_dir(dir).await {
+            Some(plugin) => plugin,
+            None => {
+                // Plugins that failed to boot aren't kept, so boot it again instead
+                self.add_plugin_by_dir(window_context, dir, true).await?;
+                let plugin = self
+                    .get_plugin_by_dir(dir)
+                    .await
+                    .ok_or(PluginNotFoundErr(dir.to_string()))?;
+                return Ok(plugin.info().await);
+            }
+        };
+
+        let event = self
+            .send_to_plugin_and_wait(
+                window_context,
+                &plugin,
+                &InternalEventPayload::ReloadRequest,
+            )
+            .await?;
+
+        match event.payload {
+            InternalEventPayload::BootResponse(resp) => {
+                plugin.set_boot_response(&resp).await;
+                Ok(resp)
+            }
+            InternalEventPayload::ErrorResponse(resp) => Err(PluginErr(resp.error)),
+            _ => Err(UnknownEventErr),
+        }
+    }
+
+    /// Set how many milliseconds to wait for plugins to reply. Zero waits forever.
+    pub async fn set_timeout(&self, millis: i32) {
+        let timeout = match millis {
+            m if m > 0 => Some(Duration::from_millis(m as u64)),
+            _ => None,
+        };
+        *self.timeout.lock().await = timeout;
+    }
+
     pub async fn initialize_all_plugins<R: Runtime>(
         &self,
         app_handle: &AppHandle<R>,
@@ -273,6 +355,36 @@ impl PluginManager {
         Ok(())
     }
 
+    /// Load state of every plugin, including ones that failed to boot and were dropped
+    pub async fn plugin_statuses(&self) -> Vec<PluginStatus> {
+        let boot_errors = self.boot_errors.lock().await.clone();
+        let mut statuses = Vec::new();
+        for plugin in self.plugins.lock().await.iter() {
+            let info = plugin.info().await;
+            let error = boot_errors.get(&plugin.dir).cloned();
+            statuses.push(PluginStatus {
+                dir: plugin.dir.to_owned(),
+                loaded: error.is_none() && !info.name.is_empty(),
+                name: info.name,
+                version: info.version,
+                error,
+            });
+        }
+
+        for (dir, error) in boot_errors {
+            if statuses.iter().any(|s| s.dir == dir) {
+                continue;
+            }
+            statuses.push(PluginStatus {
+                dir,
+                error: Some(error),
+                ..Default::default()
+            });
+        }
+
+        statuses
+    }
+
     pub async fn subscribe(&self, label: &str) -> (String, mpsc::Receiver<InternalEvent>) {
         let (tx, rx) = mpsc::channel(128);
         let rx_id = format!("{label}_{}", generate_id());
@@ -374,42 +486,44 @@ impl PluginManager {
         // 2. Spawn thread to subscribe to incoming events and check reply ids
         let send_events_fut = {
             let events_to_send = events_to_send.clone();
-
-            tokio::spawn(async move {
-                let mut found_events = Vec::new();
-
-                while let Some(event) = rx.recv().await {
-                    if events_to_send
-                        .iter()
-                        .find(|e| Some(e.id.to_owned()) == event.reply_id)
-                        .is_some()
-                    {
-                        found_events.push(event.clone());
-                    };
-                    if found_events.len() == events_to_send.len() {
-                        break;
-                    }
-                }
-
-                found_events
-            })
+            let timeout = *self.timeout.lock().await;
+            tokio::spawn(async move { collect_replies(&mut rx, &events_to_send, timeout).await })
         };
 
         // 3. Send the events
-        for event in events_to_send {
+        for event in &events_to_send {
             let plugin = plugins
                 .iter()
                 .find(|p| p.ref_id == event.plugin_ref_id)
-                .expect("Didn't find plugin in list");
-            plugin.send(&event).await?
+                .ok_or(PluginNotFoundErr(event.plugin_ref_id.to_owned()))?;
+            plugin.send(event).await?
         }
 
         // 4. Join on the spawned thread
-        let events = send_events_fut.await.expect("Thread didn't succeed");
+        let events = send_events_fut
+            .await
+            .map_err(|e| PluginErr(format!("Failed to wait for plugin replies: {e}")))?;
 
         // 5. Unsubscribe
         self.unsubscribe(rx_id.as_str()).await;
 
+        // 6. Name the plugins that never replied, if any
+        if events.len() < events_to_send.len() {
+            let mut names = Vec::new();
+            for p in plugins
+                .iter()
+                .filter(|p| !events.iter().any(|e| e.plugin_ref_id == p.ref_id))
+            {
+                let name = p.info().await.name;
+                names.push(if name.is_empty() {
+                    p.dir.to_owned()
+                } else {
+                    name
+                });
+            }
+            return Err(PluginTimeoutErr(names.join(", ")));
+        }
+
         Ok(events)
     }
 
@@ -516,40 +630,81 @@ impl PluginManager {
         Ok(value)
     }
 
+    pub async fn list_importers(&self, window_context: WindowContext) -> Result<Vec<Importer>> {
+        let reply_events = self
+            .send_and_wait(window_context, &InternalEventPayload::GetImporterRequest)
+            .await?;
+
+        let importers = reply_events
+            .into_iter()
+            .filter_map(|e| match e.payload {
+                InternalEventPayload::GetImporterResponse(importer) => Some(importer),
+                _ => None,
+            })
+            .collect();
+
+        Ok(importers)
+    }
+
+    /// Import the content with the importers that read its format. With no file path, every
+    /// importer is tried.
     pub async fn import_data<R: Runtime>(
         &self,
         window: &WebviewWindow<R>,
+        file_path: Option<&str>,
         content: &str,
     ) -> Result<(ImportResponse, String)> {
-        let reply_events = self
-            .send_and_wait(
-                WindowContext::from_window(window),
+        let window_context = WindowContext::from_window(window);
+        let mut importers = self.list_importers(window_context.to_owned()).await?;
+        if let Some(file_path) = file_path {
+            importers = importers_for_file(importers, file_path);
+            if importers.is_empty() {
+                let format = match Path::new(file_path).extension() {
+                    Some(ext) => format!(".{} files", ext.to_string_lossy()),
+                    None => "this file".to_string(),
+                };
+                return Err(PluginErr(format!("No importer for {format}")));
+            }
+        }
+
+        let mut plugins = Vec::new();
+        for importer in &importers {
+            if let Some(plugin) = self.get_plugin_by_ref_id(&importer.plugin_ref_id).await {
+                plugins.push(plugin);
+            }
+        }
+
+        let mut reply_events = self
+            .send_to_plugins_and_wait(
+                window_context,
                 &InternalEventPayload::ImportRequest(ImportRequest {
                     content: content.to_string(),
                 }),
+                plugins.to_owned(),
             )
             .await?;
 
-        // TODO: Don't just return the first valid response
-        let result = reply_events.into_iter().find_map(|e| match e.payload {
-            InternalEventPayload::ImportResponse(resp) => Some((resp, e.plugin_ref_id)),
-            _ => None,
-        });
+        // Replies arrive in whatever order plugins finish, so put them back in order of preference
+        reply_events.sort_by_key(|e| plugins.iter().position(|p| p.ref_id == e.plugin_ref_id));
 
-        match result {
-            None => Err(PluginErr(
-                "No importers found for file contents".to_string(),
-            )),
-            Some((resp, ref_id)) => {
-                let plugin = self
-                    .get_plugin_by_ref_id(ref_id.as_str())
-                    .await
-                    .ok_or(PluginNotFoundErr(ref_id))?;
-                Ok((resp, plugin.info().await.name))
-            }
+        match pick_import_response(reply_events) {
+            Ok((resp, ref_id)) => Ok((resp, self.plugin_name(ref_id).await?)),
+            Err(PluginImportErr { plugin, error }) => Err(PluginImportErr {
+                plugin: self.plugin_name(plugin).await?,
+                error,
+            }),
+            Err(e) => Err(e),
         }
     }
 
+    async fn plugin_name(&self, ref_id: String) -> Result<String> {
+        let plugin = self
+            .get_plugin_by_ref_id(ref_id.as_str())
+            .await
+            .ok_or(PluginNotFoundErr(ref_id))?;
+        Ok(plugin.info().await.name)
+    }
+
     pub async fn filter_data<R: Runtime>(
         &self,
         window: &WebviewWindow<R>,
@@ -587,6 +742,136 @@ impl PluginManager {
             e => Err(PluginErr(format!("Export returned invalid event {:?}", e))),
         }
     }
+
+    /// Run a request's pre-request or post-response script. Scripts aren't tied to a plugin, so
+    /// the runtime evaluates them in a dedicated worker with no access to Node or the plugins.
+    pub async fn run_script<R: Runtime>(
+        &self,
+        window: &WebviewWindow<R>,
+        req: RunScriptRequest,
+    ) -> Result<RunScriptResponse> {
+        let runner = match &*self.server.app_to_plugin_events_tx.lock().await {
+            None => return Err(ClientNotInitializedErr),
+            Some(tx) => PluginHandle::script_runner(tx.clone()),
+        };
+
+        let event = self
+            .send_to_plugin_and_wait(
+                WindowContext::from_window(window),
+                &runner,
+                &InternalEventPayload::RunScriptRequest(req),
+            )
+            .await?;
+
+        match event.payload {
+            InternalEventPayload::RunScriptResponse(resp) => Ok(resp),
+            InternalEventPayload::ErrorResponse(resp) => Err(PluginErr(resp.error)),
+            e => Err(PluginErr(format!("Script returned invalid event {:?}", e))),
+        }
+    }
+}
+
+/// Collect the replies to the sent events, giving up on the rest once the timeout passes. A
+/// plugin that prompts the user is waiting on them rather than hanging, so once one of the
+/// plugins still being waited on shows a prompt, the timeout no longer applies.
+async fn collect_replies(
+    rx: &mut mpsc::Receiver<InternalEvent>,
+    events_to_send: &[InternalEvent],
+    timeout: Option<Duration>,
+) -> Vec<InternalEvent> {
+    let mut found_events: Vec<InternalEvent> = Vec::new();
+    let mut deadline = timeout.map(|t| tokio::time::Instant::now() + t);
+    while found_events.len() < events_to_send.len() {
+        let event = match deadline {
+            Some(d) => match tokio::time::timeout_at(d, rx.recv()).await {
+                Ok(event) => event,
+                Err(_) => {
+                    let timeout = timeout.unwrap_or_default();
+                    warn!("Timed out waiting for plugin replies after {timeout:?}");
+                    break;
+                }
+            },
+            None => rx.recv().await,
+        };
+        let event = match event {
+            Some(e) => e,
+            None => break,
+        };
+
+        if events_to_send
+            .iter()
+            .any(|e| Some(e.id.to_owned()) == event.reply_id)
+        {
+            found_events.push(event);
+        } else if let InternalEventPayload::PromptTextRequest(_) = event.payload {
+            let waiting_on = events_to_send.iter().any(|e| {
+                e.plugin_ref_id == event.plugin_ref_id
+                    && !found_events
+                        .iter()
+                        .any(|f| f.reply_id.as_ref() == Some(&e.id))
+            });
+            if waiting_on && deadline.is_some() {
+                debug!(
+                    "Plugin {} is prompting, so its reply won't time out",
+                    event.plugin_ref_id
+                );
+                deadline = None;
+            }
+        }
+    }
+
+    found_events
+}
+
+/// The importers that read a file, judging by its name, with the most specific ones first.
+/// Importers that don't declare any formats inspect the content themselves, so they're kept for
+/// every file.
+fn importers_for_file(importers: Vec<Importer>, file_path: &str) -> Vec<Importer> {
+    let ext = Path::new(file_path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_string());
+    let mime = mime_guess::from_path(file_path).first_raw();
+
+    let mut matched = importers
+        .into_iter()
+        .filter(|i| {
+            let reads_any = i.extensions.is_empty() && i.mime_types.is_empty();
+            let reads_ext = ext.as_ref().is_some_and(|ext| {
+                i.extensions
+                    .iter()
+                    .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(ext))
+            });
+            let reads_mime =
+                mime.is_some_and(|m| i.mime_types.iter().any(|t| t.eq_ignore_ascii_case(m)));
+            reads_any || reads_ext || reads_mime
+        })
+        .collect::<Vec<Importer>>();
+
+    matched.sort_by_key(|i| i.extensions.is_empty() && i.mime_types.is_empty());
+    matched
+}
+
+/// Pick the first successful import along with the ref id of the plugin that produced it. If no
+/// importer succeeded, report the first one that threw (the file looked like its format but
+/// couldn't be parsed) before falling back to a generic error.
+fn pick_import_response(events: Vec<InternalEvent>) -> Result<(ImportResponse, String)> {
+    let mut first_error = None;
+    for e in events {
+        match e.payload {
+            InternalEventPayload::ImportResponse(resp) => return Ok((resp, e.plugin_ref_id)),
+            InternalEventPayload::ErrorResponse(resp) if first_error.is_none() => {
+                first_error = Some(PluginImportErr {
+                    plugin: e.plugin_ref_id,
+                    error: resp.error,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Err(first_error.unwrap_or(PluginErr(
+        "No importers found for file contents".to_string(),
+    )))
 }
 
 async fn read_plugins_dir(dir: &PathBuf) -> Result<Vec<String>> {
@@ -624,3 +909,173 @@ fn fix_windows_paths(p: &PathBuf) -> String {
 
     safe_path
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error::{PluginErr, PluginImportErr};
+    use crate::events::{
+        ErrorResponse, ImportResponse, Importer, InternalEvent, InternalEventPayload,
+        PromptTextRequest, WindowContext,
+    };
+    use crate::manager::{collect_replies, importers_for_file, pick_import_response};
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    fn event(plugin_ref_id: &str, payload: InternalEventPayload) -> InternalEvent {
+        InternalEvent {
+            id: "ev_1".to_string(),
+            plugin_ref_id: plugin_ref_id.to_string(),
+            reply_id: None,
+            payload,
+            window_context: WindowContext::None,
+        }
+    }
+
+    fn reply(plugin_ref_id: &str, reply_id: &str) -> InternalEvent {
+        InternalEvent {
+            reply_id: Some(reply_id.to_string()),
+            ..event(plugin_ref_id, InternalEventPayload::EmptyResponse)
+        }
+    }
+
+    fn importer(plugin_ref_id: &str, extensions: &[&str], mime_types: &[&str]) -> Importer {
+        Importer {
+            plugin_ref_id: plugin_ref_id.to_string(),
+            extensions: extensions.iter().map(|e| e.to_string()).collect(),
+            mime_types: mime_types.iter().map(|m| m.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn error(message: &str) -> InternalEventPayload {
+        InternalEventPayload::ErrorResponse(ErrorResponse {
+            error: message.to_string(),
+        })
+    }
+
+    #[test]
+    fn import_garbage() {
+        let events = vec![
+            event("plg_1", InternalEventPayload::EmptyResponse),
+            event("plg_2", error("Unexpected token } in JSON at position 12")),
+            event("plg_3", error("Invalid YAML")),
+        ];
+        match pick_import_response(events) {
+            Err(PluginImportErr { plugin, error }) => {
+                assert_eq!(plugin, "plg_2");
+                assert_eq!(error, "Unexpected token } in JSON at position 12");
+            }
+            r => panic!("Expected import error, got {r:?}"),
+        }
+
+        let events = vec![event("plg_1", InternalEventPayload::EmptyResponse)];
+        assert!(matches!(pick_import_response(events), Err(PluginErr(_))));
+    }
+
+    #[test]
+    fn import_success_wins_over_errors() {
+        let events = vec![
+            event("plg_1", error("Invalid YAML")),
+            event(
+                "plg_2",
+                InternalEventPayload::ImportResponse(ImportResponse::default()),
+            ),
+        ];
+        let (_, ref_id) = pick_import_response(events).unwrap();
+        assert_eq!(ref_id, "plg_2");
+    }
+
+    #[test]
+    fn importers_for_file_by_format() {
+        let importers = vec![
+            importer("plg_curl", &[], &[]),
+            importer("plg_postman", &["json"], &[]),
+            importer("plg_openapi", &[".yaml", "yml"], &["application/json"]),
+        ];
+        let ids = |file_path| {
+            importers_for_file(importers.clone(), file_path)
+                .into_iter()
+                .map(|i| i.plugin_ref_id)
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            ids("/tmp/api.json"),
+            vec!["plg_postman", "plg_openapi", "plg_curl"]
+        );
+        assert_eq!(ids("/tmp/API.YML"), vec!["plg_openapi", "plg_curl"]);
+        assert_eq!(ids("/tmp/curl.txt"), vec!["plg_curl"]);
+    }
+
+    #[test]
+    fn no_importer_for_format() {
+        let importers = vec![importer("plg_postman", &["json"], &[])];
+        assert!(importers_for_file(importers, "/tmp/capture.har").is_empty());
+    }
+
+    #[tokio::test]
+    async fn slow_plugin_times_out() {
+        let sent = vec![
+            InternalEvent {
+                id: "ev_fast".to_string(),
+                ..event("plg_fast", InternalEventPayload::EmptyResponse)
+            },
+            InternalEvent {
+                id: "ev_slow".to_string(),
+                ..event("plg_slow", InternalEventPayload::EmptyResponse)
+            },
+        ];
+        let (tx, mut rx) = mpsc::channel(8);
+        tokio::spawn(async move {
+            tx.send(reply("plg_fast", "ev_fast")).await.unwrap();
+            // A hook that takes far longer than the timeout
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            tx.send(reply("plg_slow", "ev_slow")).await.unwrap();
+        });
+
+        let replies = collect_replies(&mut rx, &sent, Some(Duration::from_millis(50))).await;
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].plugin_ref_id, "plg_fast");
+    }
+
+    #[tokio::test]
+    async fn no_timeout_waits_for_slow_plugin() {
+        let sent = vec![InternalEvent {
+            id: "ev_slow".to_string(),
+            ..event("plg_slow", InternalEventPayload::EmptyResponse)
+        }];
+        let (tx, mut rx) = mpsc::channel(8);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            tx.send(reply("plg_slow", "ev_slow")).await.unwrap();
+        });
+
+        let replies = collect_replies(&mut rx, &sent, None).await;
+        assert_eq!(replies.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn prompting_plugin_does_not_time_out() {
+        let sent = vec![InternalEvent {
+            id: "ev_prompt".to_string(),
+            ..event("plg_prompt", InternalEventPayload::EmptyResponse)
+        }];
+        let (tx, mut rx) = mpsc::channel(8);
+        tokio::spawn(async move {
+            let prompt = PromptTextRequest {
+                id: "enter-password".to_string(),
+                ..Default::default()
+            };
+            let prompt = event(
+                "plg_prompt",
+                InternalEventPayload::PromptTextRequest(prompt),
+            );
+            tx.send(prompt).await.unwrap();
+            // The user takes longer than the timeout to answer
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            tx.send(reply("plg_prompt", "ev_prompt")).await.unwrap();
+        });
+
+        let replies = collect_replies(&mut rx, &sent, Some(Duration::from_millis(50))).await;
+        assert_eq!(replies.len(), 1);
+    }
+}
@@ -6,6 +6,10 @@ use log::info;
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex};
 
+/// Reserved ref id of the runtime's script worker, which isn't a plugin but is sent events the
+/// same way
+pub const SCRIPT_RUNNER_REF_ID: &str = "__script_runner__";
+
 #[derive(Clone)]
 pub struct PluginHandle {
     pub ref_id: String,
@@ -26,6 +30,15 @@ impl PluginHandle {
         }
     }
 
+    /// A handle to the runtime's script worker. Each script runs in a fresh, isolated worker that
+    /// isn't any plugin's, so scripts get nothing the plugins have.
+    pub(crate) fn script_runner(tx: mpsc::Sender<tonic::Result<EventStreamEvent>>) -> Self {
+        PluginHandle {
+            ref_id: SCRIPT_RUNNER_REF_ID.to_string(),
+            ..PluginHandle::new("", tx)
+        }
+    }
+
     pub async fn info(&self) -> BootResponse {
         let resp = &*self.boot_resp.lock().await;
         resp.clone()
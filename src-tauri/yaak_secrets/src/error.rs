@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Keychain error: {0}")]
+    KeychainErr(#[from] keyring::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
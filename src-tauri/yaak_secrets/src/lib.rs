@@ -0,0 +1,39 @@
+use crate::error::Result;
+use keyring::Entry;
+
+pub mod error;
+
+/// Prefix of the value saved in place of a secret, followed by the keychain account holding it
+pub const KEYCHAIN_REF_PREFIX: &str = "keychain:";
+
+/// Whether a saved value is a reference to a secret in the keychain, rather than the value itself
+pub fn is_keychain_ref(value: &str) -> bool {
+    value.starts_with(KEYCHAIN_REF_PREFIX)
+}
+
+/// Save a secret to the OS keychain, returning the reference to save in its place
+pub fn store_secret(service: &str, account: &str, value: &str) -> Result<String> {
+    Entry::new(service, account)?.set_password(value)?;
+    Ok(format!("{KEYCHAIN_REF_PREFIX}{account}"))
+}
+
+/// Read the secret a saved value refers to. Values that aren't references are returned as is.
+pub fn read_secret(service: &str, value: &str) -> Result<String> {
+    match value.strip_prefix(KEYCHAIN_REF_PREFIX) {
+        Some(account) => Ok(Entry::new(service, account)?.get_password()?),
+        None => Ok(value.to_string()),
+    }
+}
+
+/// Remove the secret a saved value refers to from the keychain. Values that aren't references,
+/// and entries that are already gone, are ignored.
+pub fn delete_secret(service: &str, value: &str) -> Result<()> {
+    let account = match value.strip_prefix(KEYCHAIN_REF_PREFIX) {
+        Some(account) => account,
+        None => return Ok(()),
+    };
+    match Entry::new(service, account)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
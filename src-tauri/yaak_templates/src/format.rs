@@ -1,3 +1,6 @@
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
 enum FormatState {
     TemplateTag,
     String,
@@ -141,9 +144,883 @@ pub fn format_json(text: &str, tab: &str) -> String {
         .join("\n") // Join the lines back into a single string
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl FormatError {
+    fn at(text: &str, offset: usize, message: &str) -> Self {
+        let before = &text[..offset];
+        FormatError {
+            message: message.to_string(),
+            line: before.matches('\n').count() + 1,
+            column: before.chars().rev().take_while(|c| *c != '\n').count() + 1,
+        }
+    }
+}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at line {} column {}", self.message, self.line, self.column)
+    }
+}
+
+/// Checks that JSON is well-formed, treating template tags as plain values
+pub fn validate_json(text: &str) -> Result<(), FormatError> {
+    match serde_json::from_str::<serde_json::Value>(&mask_json_template_tags(text)) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            let message = e.to_string();
+            let message = message.split(" at line ").next().unwrap_or_default();
+            Err(FormatError {
+                message: message.to_string(),
+                line: e.line(),
+                column: e.column(),
+            })
+        }
+    }
+}
+
+/// Blanks out template tags without moving anything else, so parse errors still point at
+/// the right line and column. Tags outside strings become a number so they parse as values.
+fn mask_json_template_tags(text: &str) -> String {
+    let mut masked = String::with_capacity(text.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        if !escaped && rest.starts_with("${[") {
+            let len = rest.find("]}").map(|n| n + 2).unwrap_or(rest.len());
+            for (n, c) in rest[..len].char_indices() {
+                masked.push(match c {
+                    _ if n == 0 && !in_string => '0',
+                    '\n' if !in_string => '\n',
+                    _ => ' ',
+                });
+                for _ in 1..c.len_utf8() {
+                    masked.push(' ');
+                }
+            }
+            i += len;
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap_or_default();
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+        }
+        masked.push(c);
+        i += c.len_utf8();
+    }
+
+    masked
+}
+
+/// Strips the whitespace outside strings from JSON that might contain template tags (kept
+/// as-is)
+pub fn minify_json(text: &str) -> String {
+    let mut minified = String::with_capacity(text.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        if !in_string && rest.starts_with("${[") {
+            let len = rest.find("]}").map(|n| n + 2).unwrap_or(rest.len());
+            minified.push_str(&rest[..len]);
+            i += len;
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap_or_default();
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            minified.push(c);
+        } else if c == '"' {
+            in_string = true;
+            minified.push(c);
+        } else if !c.is_whitespace() {
+            minified.push(c);
+        }
+        i += c.len_utf8();
+    }
+
+    minified
+}
+
+enum XmlToken<'a> {
+    Open {
+        name: &'a str,
+        raw: &'a str,
+        offset: usize,
+    },
+    Close {
+        name: &'a str,
+        raw: &'a str,
+        offset: usize,
+    },
+    SelfClosing(&'a str),
+    /// Comments, declarations, and processing instructions
+    Other(&'a str),
+    /// Text content, CDATA sections, and template tags
+    Text(&'a str),
+}
+
+/// Validates and formats XML that might contain template tags (kept as-is). Elements
+/// containing only text are kept on a single line.
+pub fn format_xml(text: &str, tab: &str) -> Result<String, FormatError> {
+    Ok(markup_lines(text, tab, false)?.join("\n"))
+}
+
+/// Validates XML and strips the whitespace that doesn't matter. See [`minify_markup`].
+pub fn minify_xml(text: &str) -> Result<String, FormatError> {
+    minify_markup(text, false)
+}
+
+/// Formats HTML like [`format_xml`], except void elements like `<br>` need no closing tag,
+/// tag names are case-insensitive, and script and style contents are left alone
+pub fn format_html(text: &str, tab: &str) -> Result<String, FormatError> {
+    Ok(markup_lines(text, tab, true)?.join("\n"))
+}
+
+/// Validates HTML and strips the whitespace that doesn't matter. See [`minify_markup`].
+pub fn minify_html(text: &str) -> Result<String, FormatError> {
+    minify_markup(text, true)
+}
+
+const HTML_VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements that flow along with text, so whitespace beside them shows up as a space
+const HTML_INLINE_ELEMENTS: [&str; 29] = [
+    "a", "abbr", "b", "bdi", "bdo", "br", "button", "cite", "code", "data", "dfn", "em", "i",
+    "img", "input", "kbd", "label", "mark", "q", "s", "samp", "select", "small", "span", "strong",
+    "sub", "sup", "time", "u",
+];
+
+/// Elements whose whitespace is part of their contents
+const HTML_PRESERVE_ELEMENTS: [&str; 4] = ["pre", "textarea", "script", "style"];
+
+/// Removes whitespace-only text between tags when it sits in an element that only holds other
+/// elements, and trims text at the start and end of an element. Any other run of whitespace
+/// around text is collapsed to one space, since it separates words in mixed content like
+/// `<p>Hi <b>there</b></p>`, as is whitespace beside an HTML inline element. Contents of
+/// `<pre>`, `<textarea>`, `<script>`, `<style>`, CDATA sections, and elements under
+/// `xml:space="preserve"` are kept as they are.
+fn minify_markup(text: &str, html: bool) -> Result<String, FormatError> {
+    markup_lines(text, "", html)?;
+    let tokens = tokenize_xml(text, html)?;
+
+    // Each token's parent element, and whether each element holds text directly or keeps its
+    // whitespace, by the index of its opening tag
+    let mut parents = Vec::with_capacity(tokens.len());
+    let mut mixed = vec![false; tokens.len()];
+    let mut preserve = vec![false; tokens.len()];
+    let mut stack: Vec<usize> = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if let XmlToken::Close { .. } = token {
+            stack.pop();
+        }
+        let parent = stack.last().copied();
+        parents.push(parent);
+        match token {
+            XmlToken::Open { name, raw, .. } => {
+                let inherited = parent.is_some_and(|p| preserve[p]);
+                let lower_name = name.to_ascii_lowercase();
+                preserve[i] = match xml_space_preserve(raw) {
+                    Some(p) => p,
+                    None => inherited || (html && HTML_PRESERVE_ELEMENTS.contains(&&*lower_name)),
+                };
+                stack.push(i);
+            }
+            XmlToken::Text(t) if !t.trim().is_empty() => {
+                if let Some(p) = parent {
+                    mixed[p] = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let is_inline = |token: Option<&XmlToken>| {
+        if !html {
+            return false;
+        }
+        let raw = match token {
+            Some(XmlToken::Open { raw, .. })
+            | Some(XmlToken::Close { raw, .. })
+            | Some(XmlToken::SelfClosing(raw)) => raw,
+            _ => return false,
+        };
+        let name = raw
+            .trim_start_matches(['<', '/'])
+            .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        HTML_INLINE_ELEMENTS.contains(&name.as_str())
+    };
+
+    let mut minified = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let t = match token {
+            XmlToken::Text(t) => *t,
+            XmlToken::Open { raw, .. }
+            | XmlToken::Close { raw, .. }
+            | XmlToken::SelfClosing(raw)
+            | XmlToken::Other(raw) => {
+                minified.push_str(raw);
+                continue;
+            }
+        };
+        if parents[i].is_some_and(|p| preserve[p]) || t.starts_with("<![CDATA[") {
+            minified.push_str(t);
+            continue;
+        }
+
+        let prev = i.checked_sub(1).and_then(|j| tokens.get(j));
+        let next = tokens.get(i + 1);
+        let at_start = matches!(prev, None | Some(XmlToken::Open { .. }));
+        let at_end = matches!(next, None | Some(XmlToken::Close { .. }));
+        let trimmed = t.trim();
+        if trimmed.is_empty() {
+            let in_text = parents[i].is_some_and(|p| mixed[p]) && !at_start && !at_end;
+            if in_text || is_inline(prev) || is_inline(next) {
+                minified.push(' ');
+            }
+            continue;
+        }
+        if trimmed.len() < t.trim_end().len() && (!at_start || is_inline(prev)) {
+            minified.push(' ');
+        }
+        minified.push_str(trimmed);
+        if trimmed.len() < t.trim_start().len() && (!at_end || is_inline(next)) {
+            minified.push(' ');
+        }
+    }
+    Ok(minified)
+}
+
+/// Whether an opening tag's `xml:space` asks for its whitespace to be kept, if it has one
+fn xml_space_preserve(tag: &str) -> Option<bool> {
+    let value = tag.split("xml:space=").nth(1)?;
+    Some(value.trim_start_matches(['"', '\'']).starts_with("preserve"))
+}
+
+fn markup_lines(text: &str, tab: &str, html: bool) -> Result<Vec<String>, FormatError> {
+    let same_name = |a: &str, b: &str| if html { a.eq_ignore_ascii_case(b) } else { a == b };
+    let tokens = tokenize_xml(text, html)?;
+    let mut lines: Vec<String> = Vec::new();
+    let mut stack: Vec<(&str, usize)> = Vec::new();
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let indent = tab.repeat(stack.len());
+        match &tokens[i] {
+            XmlToken::Text(t) if t.trim().is_empty() => {}
+            XmlToken::Text(t) | XmlToken::Other(t) | XmlToken::SelfClosing(t) => {
+                lines.push(format!("{indent}{}", t.trim()));
+            }
+            XmlToken::Open { name, raw, offset } => {
+                let inner = match tokens.get(i + 1) {
+                    Some(XmlToken::Text(t)) => Some(t.trim()),
+                    _ => None,
+                };
+                let close_index = if inner.is_some() { i + 2 } else { i + 1 };
+                match tokens.get(close_index) {
+                    Some(XmlToken::Close {
+                        name: n,
+                        raw: close,
+                        ..
+                    }) if same_name(n, name) => {
+                        lines.push(format!("{indent}{raw}{}{close}", inner.unwrap_or_default()));
+                        i = close_index;
+                    }
+                    _ => {
+                        lines.push(format!("{indent}{raw}"));
+                        stack.push((name, *offset));
+                    }
+                }
+            }
+            XmlToken::Close { name, raw, offset } => match stack.pop() {
+                Some((open, _)) if same_name(open, name) => {
+                    lines.push(format!("{}{raw}", tab.repeat(stack.len())));
+                }
+                Some((open, _)) => {
+                    let message = format!("Expected </{open}> but found </{name}>");
+                    return Err(FormatError::at(text, *offset, &message));
+                }
+                None => {
+                    let message = format!("Unexpected closing tag </{name}>");
+                    return Err(FormatError::at(text, *offset, &message));
+                }
+            },
+        }
+        i += 1;
+    }
+
+    if let Some((name, offset)) = stack.pop() {
+        return Err(FormatError::at(text, offset, &format!("Unclosed tag <{name}>")));
+    }
+
+    Ok(lines)
+}
+
+fn tokenize_xml(text: &str, html: bool) -> Result<Vec<XmlToken<'_>>, FormatError> {
+    let find_end = |start: usize, end: &str, message: &str| {
+        text[start..]
+            .find(end)
+            .map(|n| n + end.len())
+            .ok_or_else(|| FormatError::at(text, start, message))
+    };
+
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        let (len, token) = if rest.starts_with("<!--") {
+            let len = find_end(i, "-->", "Unterminated comment")?;
+            (len, XmlToken::Other(&rest[..len]))
+        } else if rest.starts_with("<![CDATA[") {
+            let len = find_end(i, "]]>", "Unterminated CDATA section")?;
+            (len, XmlToken::Text(&rest[..len]))
+        } else if rest.starts_with("<?") {
+            let len = find_end(i, "?>", "Unterminated processing instruction")?;
+            (len, XmlToken::Other(&rest[..len]))
+        } else if rest.starts_with("<!") {
+            let len = find_end(i, ">", "Unterminated declaration")?;
+            (len, XmlToken::Other(&rest[..len]))
+        } else if rest.starts_with('<') {
+            let len =
+                xml_tag_len(rest).ok_or_else(|| FormatError::at(text, i, "Unterminated tag"))?;
+            let raw = &rest[..len];
+            if let Some(name) = raw.strip_prefix("</") {
+                let name = name.trim_end_matches('>').trim();
+                (
+                    len,
+                    XmlToken::Close {
+                        name,
+                        raw,
+                        offset: i,
+                    },
+                )
+            } else if raw.ends_with("/>") {
+                (len, XmlToken::SelfClosing(raw))
+            } else {
+                let name = raw[1..]
+                    .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                    .next()
+                    .unwrap_or_default();
+                if name.is_empty() {
+                    return Err(FormatError::at(text, i, "Expected a tag name"));
+                }
+                let lower_name = name.to_ascii_lowercase();
+                if html && HTML_VOID_ELEMENTS.contains(&lower_name.as_str()) {
+                    tokens.push(XmlToken::SelfClosing(raw));
+                    i += len;
+                    continue;
+                }
+                if html && (lower_name == "script" || lower_name == "style") {
+                    // Contents are code, so take everything up to the closing tag as text
+                    let contents = &text[i + len..];
+                    let end = contents
+                        .to_ascii_lowercase()
+                        .find(&format!("</{lower_name}"))
+                        .unwrap_or(contents.len());
+                    tokens.push(XmlToken::Open {
+                        name,
+                        raw,
+                        offset: i,
+                    });
+                    tokens.push(XmlToken::Text(&contents[..end]));
+                    i += len + end;
+                    continue;
+                }
+                (
+                    len,
+                    XmlToken::Open {
+                        name,
+                        raw,
+                        offset: i,
+                    },
+                )
+            }
+        } else {
+            let len = xml_text_len(rest);
+            (len, XmlToken::Text(&rest[..len]))
+        };
+        tokens.push(token);
+        i += len;
+    }
+
+    Ok(tokens)
+}
+
+/// Length of the tag at the start of `text`, skipping over quoted attribute values and
+/// template tags, or None if it's never closed
+fn xml_tag_len(text: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    let mut i = 1;
+    while i < text.len() {
+        let rest = &text[i..];
+        if rest.starts_with("${[") {
+            i += rest.find("]}")? + 2;
+            continue;
+        }
+        let c = rest.chars().next()?;
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '>' => return Some(i + 1),
+            None => {}
+        }
+        i += c.len_utf8();
+    }
+    None
+}
+
+/// Length of the text at the start of `text`, up to the next tag outside a template tag
+fn xml_text_len(text: &str) -> usize {
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        if rest.starts_with("${[") {
+            match rest.find("]}") {
+                Some(n) => i += n + 2,
+                None => return text.len(),
+            }
+            continue;
+        }
+        if rest.starts_with('<') {
+            return i;
+        }
+        i += rest.chars().next().map(|c| c.len_utf8()).unwrap_or(1);
+    }
+    text.len()
+}
+
+/// Keywords that start a clause, which goes on its own line with its contents indented below
+const SQL_CLAUSES: [&str; 29] = [
+    "WITH",
+    "SELECT",
+    "FROM",
+    "WHERE",
+    "GROUP BY",
+    "HAVING",
+    "ORDER BY",
+    "LIMIT",
+    "OFFSET",
+    "UNION ALL",
+    "UNION",
+    "INTERSECT",
+    "EXCEPT",
+    "INSERT INTO",
+    "VALUES",
+    "UPDATE",
+    "SET",
+    "DELETE FROM",
+    "RETURNING",
+    "JOIN",
+    "INNER JOIN",
+    "LEFT JOIN",
+    "LEFT OUTER JOIN",
+    "RIGHT JOIN",
+    "RIGHT OUTER JOIN",
+    "FULL JOIN",
+    "FULL OUTER JOIN",
+    "CROSS JOIN",
+    "ON CONFLICT",
+];
+
+enum SqlToken<'a> {
+    Whitespace,
+    LineComment(&'a str),
+    /// Keywords, identifiers, operators, quoted strings, block comments, and template tags
+    Word(&'a str),
+    Open(usize),
+    Close(usize),
+    Comma,
+    Semicolon,
+}
+
+/// Formats SQL that might contain template tags (kept as-is), putting each clause and each
+/// item of a top-level list on its own line. Keyword case is left alone.
+pub fn format_sql(text: &str, tab: &str) -> Result<String, FormatError> {
+    struct Paren {
+        block: bool,
+        offset: usize,
+        depth: usize,
+        level: usize,
+    }
+
+    let tokens = tokenize_sql(text)?;
+    let mut w = SqlWriter {
+        out: String::new(),
+        tab,
+        line_start: true,
+        pending_space: false,
+    };
+    let mut stack: Vec<Paren> = Vec::new();
+    // Indent of the current clause keyword, and of the clause's contents
+    let mut depth = 0;
+    let mut level = 0;
+    let mut in_between = false;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let inline = stack.last().map(|p| !p.block).unwrap_or(false);
+        match &tokens[i] {
+            SqlToken::Whitespace => w.pending_space = true,
+            SqlToken::LineComment(c) => {
+                w.push(c);
+                w.newline(level);
+            }
+            SqlToken::Word(word) => match sql_clause_at(&tokens, i) {
+                Some((clause, next)) if !inline => {
+                    w.newline(depth);
+                    w.push(&clause);
+                    level = depth + 1;
+                    w.newline(level);
+                    i = next;
+                    continue;
+                }
+                _ => {
+                    let is_and = word.eq_ignore_ascii_case("AND");
+                    if !inline && !in_between && (is_and || word.eq_ignore_ascii_case("OR")) {
+                        w.newline(level);
+                    }
+                    if is_and {
+                        in_between = false;
+                    } else if word.eq_ignore_ascii_case("BETWEEN") {
+                        in_between = true;
+                    }
+                    w.push(word);
+                }
+            },
+            SqlToken::Open(offset) => {
+                let block = tokens[i + 1..]
+                    .iter()
+                    .find(|t| !matches!(t, SqlToken::Whitespace))
+                    .map(|t| match t {
+                        SqlToken::Word(w) => {
+                            w.eq_ignore_ascii_case("SELECT") || w.eq_ignore_ascii_case("WITH")
+                        }
+                        _ => false,
+                    })
+                    .unwrap_or(false);
+                w.push("(");
+                stack.push(Paren {
+                    block,
+                    offset: *offset,
+                    depth,
+                    level,
+                });
+                if block {
+                    depth = level + 1;
+                    level = depth;
+                }
+            }
+            SqlToken::Close(offset) => match stack.pop() {
+                None => return Err(FormatError::at(text, *offset, "Unexpected )")),
+                Some(p) => {
+                    if p.block {
+                        depth = p.depth;
+                        level = p.level;
+                        w.newline(level);
+                    }
+                    w.pending_space = false;
+                    w.push(")");
+                }
+            },
+            SqlToken::Comma => {
+                w.pending_space = false;
+                w.push(",");
+                if inline {
+                    w.pending_space = true;
+                } else {
+                    w.newline(level);
+                }
+            }
+            SqlToken::Semicolon => {
+                w.pending_space = false;
+                w.push(";");
+                // Leave a blank line between statements
+                w.out.push('\n');
+                w.newline(0);
+                depth = 0;
+                level = 0;
+            }
+        }
+        i += 1;
+    }
+
+    if let Some(p) = stack.pop() {
+        return Err(FormatError::at(text, p.offset, "Unclosed ("));
+    }
+
+    Ok(w.out.lines().map(|l| l.trim_end()).collect::<Vec<&str>>().join("\n").trim().to_string())
+}
+
+/// Collapses the whitespace in SQL that might contain template tags (kept as-is)
+pub fn minify_sql(text: &str) -> Result<String, FormatError> {
+    let mut minified = String::with_capacity(text.len());
+    let mut pending_space = false;
+    for token in tokenize_sql(text)? {
+        let (s, spaced) = match token {
+            SqlToken::Whitespace => {
+                pending_space = true;
+                continue;
+            }
+            // Line comments run to the end of the line, so they need to keep it
+            SqlToken::LineComment(c) => {
+                if !minified.is_empty() && !minified.ends_with('\n') {
+                    minified.push(' ');
+                }
+                minified.push_str(c);
+                minified.push('\n');
+                pending_space = false;
+                continue;
+            }
+            SqlToken::Word(w) => (w, true),
+            SqlToken::Open(_) => ("(", true),
+            SqlToken::Close(_) => (")", false),
+            SqlToken::Comma => (",", false),
+            SqlToken::Semicolon => (";", false),
+        };
+        if spaced
+            && pending_space
+            && !minified.is_empty()
+            && !minified.ends_with(['\n', '(', ',', ';'])
+        {
+            minified.push(' ');
+        }
+        minified.push_str(s);
+        pending_space = false;
+    }
+    Ok(minified)
+}
+
+struct SqlWriter<'a> {
+    out: String,
+    tab: &'a str,
+    line_start: bool,
+    pending_space: bool,
+}
+
+impl SqlWriter<'_> {
+    fn push(&mut self, s: &str) {
+        if !self.line_start && self.pending_space && !self.out.ends_with('(') {
+            self.out.push(' ');
+        }
+        self.out.push_str(s);
+        self.line_start = false;
+        self.pending_space = false;
+    }
+
+    /// Start a new line at the given indent, replacing the current line if it's still empty
+    fn newline(&mut self, level: usize) {
+        if self.line_start {
+            let start = self.out.rfind('\n').map(|n| n + 1).unwrap_or(0);
+            self.out.truncate(start);
+        } else {
+            self.out.push('\n');
+        }
+        self.out.push_str(&self.tab.repeat(level));
+        self.line_start = true;
+        self.pending_space = false;
+    }
+}
+
+/// The clause starting with the word at `index`, written in upper case with single spaces,
+/// and the index of the token after it
+fn sql_clause_at(tokens: &[SqlToken], index: usize) -> Option<(String, usize)> {
+    let mut words: Vec<(&str, usize)> = Vec::new();
+    for (i, token) in tokens.iter().enumerate().skip(index) {
+        match token {
+            SqlToken::Word(w) if words.len() < 3 => words.push((w, i + 1)),
+            SqlToken::Whitespace => {}
+            _ => break,
+        }
+    }
+
+    SQL_CLAUSES
+        .iter()
+        .filter_map(|clause| {
+            let parts: Vec<&str> = clause.split(' ').collect();
+            let matched = parts.len() <= words.len()
+                && parts.iter().zip(&words).all(|(p, (w, _))| p.eq_ignore_ascii_case(w));
+            matched.then_some((parts.len(), clause))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(len, clause)| (clause.to_string(), words[len - 1].1))
+}
+
+fn tokenize_sql(text: &str) -> Result<Vec<SqlToken<'_>>, FormatError> {
+    let find_end = |start: usize, skip: usize, end: &str, message: &str| {
+        text[start + skip..]
+            .find(end)
+            .map(|n| n + skip + end.len())
+            .ok_or_else(|| FormatError::at(text, start, message))
+    };
+
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.chars().next().unwrap_or_default();
+        let (len, token) = if c.is_whitespace() {
+            let len = rest.find(|c: char| !c.is_whitespace()).unwrap_or(rest.len());
+            (len, SqlToken::Whitespace)
+        } else if rest.starts_with("--") {
+            let len = rest.find('\n').unwrap_or(rest.len());
+            (len, SqlToken::LineComment(rest[..len].trim_end()))
+        } else if rest.starts_with("/*") {
+            let len = find_end(i, 2, "*/", "Unterminated comment")?;
+            (len, SqlToken::Word(&rest[..len]))
+        } else if rest.starts_with("${[") {
+            let len = find_end(i, 3, "]}", "Unterminated template tag")?;
+            (len, SqlToken::Word(&rest[..len]))
+        } else if c == '\'' || c == '"' || c == '`' {
+            let len = sql_quoted_len(rest, c)
+                .ok_or_else(|| FormatError::at(text, i, "Unterminated string"))?;
+            (len, SqlToken::Word(&rest[..len]))
+        } else if c == '(' {
+            (1, SqlToken::Open(i))
+        } else if c == ')' {
+            (1, SqlToken::Close(i))
+        } else if c == ',' {
+            (1, SqlToken::Comma)
+        } else if c == ';' {
+            (1, SqlToken::Semicolon)
+        } else {
+            let len = sql_word_len(rest);
+            (len, SqlToken::Word(&rest[..len]))
+        };
+        tokens.push(token);
+        i += len;
+    }
+
+    Ok(tokens)
+}
+
+/// Length of the quoted string or identifier at the start of `text`, where a doubled quote or
+/// a backslash escapes the next character, or None if it's never closed
+fn sql_quoted_len(text: &str, quote: char) -> Option<usize> {
+    let mut chars = text.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c == quote {
+            match chars.peek() {
+                Some((_, next)) if *next == quote => {
+                    chars.next();
+                }
+                _ => return Some(i + c.len_utf8()),
+            }
+        }
+    }
+    None
+}
+
+/// Length of the word at the start of `text`, up to whitespace, punctuation, or the start of
+/// a string, comment, or template tag
+fn sql_word_len(text: &str) -> usize {
+    for (i, c) in text.char_indices() {
+        let rest = &text[i..];
+        if i > 0
+            && (c.is_whitespace()
+                || "(),;'\"`".contains(c)
+                || rest.starts_with("--")
+                || rest.starts_with("/*")
+                || rest.starts_with("${["))
+        {
+            return i;
+        }
+    }
+    text.len()
+}
+
+/// Languages a body can be formatted as
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BodyLanguage {
+    Json,
+    Xml,
+    Html,
+    Sql,
+}
+
+impl BodyLanguage {
+    /// The language of a content type like `application/json; charset=utf-8`, also accepting
+    /// bare language names like `json`
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let mime = content_type.split(';').next().unwrap_or_default().trim().to_lowercase();
+        if mime.contains("json") {
+            Some(BodyLanguage::Json)
+        } else if mime.contains("xml") {
+            Some(BodyLanguage::Xml)
+        } else if mime.contains("html") {
+            Some(BodyLanguage::Html)
+        } else if mime.contains("sql") {
+            Some(BodyLanguage::Sql)
+        } else {
+            None
+        }
+    }
+}
+
+/// Pretty-prints a body, or minifies it, based on its content type
+pub fn format_body(
+    body: &str,
+    content_type: &str,
+    minify: bool,
+    tab: &str,
+) -> Result<String, FormatError> {
+    let language = BodyLanguage::from_content_type(content_type).ok_or_else(|| {
+        FormatError::at(body, 0, &format!("Formatting {content_type} is not supported"))
+    })?;
+
+    match (language, minify) {
+        (BodyLanguage::Json, false) => validate_json(body).map(|_| format_json(body, tab)),
+        (BodyLanguage::Json, true) => validate_json(body).map(|_| minify_json(body)),
+        (BodyLanguage::Xml, false) => format_xml(body, tab),
+        (BodyLanguage::Xml, true) => minify_xml(body),
+        (BodyLanguage::Html, false) => format_html(body, tab),
+        (BodyLanguage::Html, true) => minify_html(body),
+        (BodyLanguage::Sql, false) => format_sql(body, tab),
+        (BodyLanguage::Sql, true) => minify_sql(body),
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::format::format_json;
+    use crate::format::{
+        format_body, format_html, format_json, format_sql, format_xml, minify_html, minify_json,
+        minify_sql, minify_xml, validate_json, FormatError,
+    };
 
     #[test]
     fn test_simple_object() {
@@ -301,4 +1178,244 @@ mod test {
             .trim()
         );
     }
+
+    #[test]
+    fn test_validate_json() {
+        assert_eq!(validate_json(r#"{"foo": [1, 2], "bar": null}"#), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_json_template_tags() {
+        assert_eq!(validate_json(r#"{"foo": ${[ fn("a") ]}, "bar": "${[ baz ]}"}"#), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_json_error_position() {
+        let err = validate_json("{\n  \"foo\": ${[ bar ]},\n  \"baz\" 1\n}").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 9));
+    }
+
+    #[test]
+    fn test_xml_simple() {
+        assert_eq!(
+            format_xml(r#"<?xml version="1.0"?><a><b>hello</b><c/><d></d></a>"#, "  "),
+            Ok(r#"
+<?xml version="1.0"?>
+<a>
+  <b>hello</b>
+  <c/>
+  <d></d>
+</a>
+"#
+            .trim()
+            .to_string())
+        );
+    }
+
+    #[test]
+    fn test_xml_reindents() {
+        assert_eq!(
+            format_xml("<a>\n<b attr=\"x > y\">\n<!-- hi --><c>${[ foo ]}</c></b>\n</a>", "  "),
+            Ok(r#"
+<a>
+  <b attr="x > y">
+    <!-- hi -->
+    <c>${[ foo ]}</c>
+  </b>
+</a>
+"#
+            .trim()
+            .to_string())
+        );
+    }
+
+    #[test]
+    fn test_xml_mismatched_tag() {
+        assert_eq!(
+            format_xml("<a>\n  <b></c>\n</a>", "  "),
+            Err(FormatError {
+                message: "Expected </b> but found </c>".to_string(),
+                line: 2,
+                column: 6,
+            })
+        );
+    }
+
+    #[test]
+    fn test_xml_unclosed_tag() {
+        assert_eq!(
+            format_xml("<a><b></b>", "  "),
+            Err(FormatError {
+                message: "Unclosed tag <a>".to_string(),
+                line: 1,
+                column: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_minify_json() {
+        assert_eq!(
+            minify_json("{\n  \"foo\": \"a b\",\n  \"bar\": ${[ fn(\"x y\") ]}\n}"),
+            r#"{"foo":"a b","bar":${[ fn("x y") ]}}"#
+        );
+    }
+
+    #[test]
+    fn test_minify_xml() {
+        assert_eq!(
+            minify_xml("<a>\n  <b>hello there</b>\n  <c/>\n</a>"),
+            Ok("<a><b>hello there</b><c/></a>".to_string())
+        );
+    }
+
+    #[test]
+    fn test_minify_xml_keeps_significant_whitespace() {
+        // Spaces between words in mixed content
+        assert_eq!(
+            minify_xml("<p>\n  Hello <b>big</b> <i>world</i>\n</p>"),
+            Ok("<p>Hello <b>big</b> <i>world</i></p>".to_string())
+        );
+        assert_eq!(
+            minify_xml(
+                "<a>\n  <code xml:space=\"preserve\">  x\n    y\n</code>\n  <b> z </b>\n</a>"
+            ),
+            Ok("<a><code xml:space=\"preserve\">  x\n    y\n</code><b>z</b></a>".to_string())
+        );
+        assert_eq!(
+            minify_xml("<a><![CDATA[ x ]]>\n</a>"),
+            Ok("<a><![CDATA[ x ]]></a>".to_string())
+        );
+    }
+
+    #[test]
+    fn test_minify_html_keeps_significant_whitespace() {
+        assert_eq!(
+            minify_html("<div>\n  <span>a</span>\n  <span>b</span>\n</div>"),
+            Ok("<div> <span>a</span> <span>b</span> </div>".to_string())
+        );
+        assert_eq!(
+            minify_html("<ul>\n  <li>One <em>two</em></li>\n  <li>Three</li>\n</ul>"),
+            Ok("<ul><li>One <em>two</em></li><li>Three</li></ul>".to_string())
+        );
+        assert_eq!(
+            minify_html("<body>\n  <pre>\n  a\n    b\n</pre>\n</body>"),
+            Ok("<body><pre>\n  a\n    b\n</pre></body>".to_string())
+        );
+    }
+
+    #[test]
+    fn test_html_void_and_script() {
+        assert_eq!(
+            format_html(
+                "<!DOCTYPE html><HTML><head><meta charset=\"utf-8\"><script>if (a < b) {}</script></head><body><br></body></html>",
+                "  "
+            ),
+            Ok(r#"
+<!DOCTYPE html>
+<HTML>
+  <head>
+    <meta charset="utf-8">
+    <script>if (a < b) {}</script>
+  </head>
+  <body>
+    <br>
+  </body>
+</html>
+"#
+            .trim()
+            .to_string())
+        );
+    }
+
+    #[test]
+    fn test_sql_clauses() {
+        assert_eq!(
+            format_sql(
+                "select u.id, count(*) from users u left join posts p on p.user_id = u.id where u.name = 'O''Brien' and u.age between 1 and 2 group by u.id; delete from t",
+                "  "
+            ),
+            Ok(r#"
+SELECT
+  u.id,
+  count(*)
+FROM
+  users u
+LEFT JOIN
+  posts p on p.user_id = u.id
+WHERE
+  u.name = 'O''Brien'
+  and u.age between 1 and 2
+GROUP BY
+  u.id;
+
+DELETE FROM
+  t
+"#
+            .trim()
+            .to_string())
+        );
+    }
+
+    #[test]
+    fn test_sql_subquery() {
+        assert_eq!(
+            format_sql("SELECT * FROM a WHERE id IN (SELECT id FROM b WHERE x = ${[ y ]})", "  "),
+            Ok(r#"
+SELECT
+  *
+FROM
+  a
+WHERE
+  id IN (
+    SELECT
+      id
+    FROM
+      b
+    WHERE
+      x = ${[ y ]}
+  )
+"#
+            .trim()
+            .to_string())
+        );
+        assert_eq!(
+            minify_sql("SELECT a,\n  b\nFROM t -- all\nWHERE f( 1 )"),
+            Ok("SELECT a,b FROM t -- all\nWHERE f(1)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sql_unbalanced() {
+        assert_eq!(
+            format_sql("SELECT (1", "  "),
+            Err(FormatError {
+                message: "Unclosed (".to_string(),
+                line: 1,
+                column: 8,
+            })
+        );
+        assert_eq!(format_sql("SELECT 'a", "  ").unwrap_err().message, "Unterminated string");
+    }
+
+    #[test]
+    fn test_format_body_by_content_type() {
+        assert_eq!(
+            format_body(r#"{"a":1}"#, "application/vnd.api+json; charset=utf-8", false, "  "),
+            Ok("{\n  \"a\": 1\n}".to_string())
+        );
+        assert_eq!(
+            format_body("{\n  \"a\": 1\n}", "json", true, "  "),
+            Ok(r#"{"a":1}"#.to_string())
+        );
+        assert_eq!(
+            format_body("<a> <b/> </a>", "text/xml", true, "  "),
+            Ok("<a><b/></a>".to_string())
+        );
+        assert_eq!(
+            format_body("{}", "image/png", false, "  ").unwrap_err().message,
+            "Formatting image/png is not supported"
+        );
+        assert!(format_body(r#"{"a":}"#, "application/json", false, "  ").is_err());
+    }
 }
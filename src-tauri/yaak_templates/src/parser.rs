@@ -20,6 +20,114 @@ impl Display for Tokens {
     }
 }
 
+impl Tokens {
+    /// Insert a variable tag at a character offset into the template (as the tokens print it),
+    /// splitting raw text if needed. Offsets inside an existing tag are rejected.
+    pub fn insert_variable(&mut self, position: usize, name: &str) -> Result<(), String> {
+        if !is_var_name(name) {
+            return Err(format!("Invalid variable name {name:?}"));
+        }
+        let tag = Token::Tag {
+            val: Val::Var {
+                name: name.to_string(),
+            },
+        };
+
+        let mut offset = 0;
+        for (i, token) in self.tokens.iter().enumerate() {
+            if offset == position {
+                self.tokens.insert(i, tag);
+                return Ok(());
+            }
+
+            let len = token.to_string().chars().count();
+            if position < offset + len {
+                let text = match token {
+                    Token::Raw { text } => text,
+                    _ => return Err(format!("Position {position} is inside a template tag")),
+                };
+                let (before, after): (String, String) = {
+                    let chars: Vec<char> = text.chars().collect();
+                    let (b, a) = chars.split_at(position - offset);
+                    (b.iter().collect(), a.iter().collect())
+                };
+                self.tokens.splice(
+                    i..=i,
+                    [Token::Raw { text: before }, tag, Token::Raw { text: after }],
+                );
+                return Ok(());
+            }
+            offset += len;
+        }
+
+        if position != offset {
+            return Err(format!(
+                "Position {position} is past the end of the template"
+            ));
+        }
+        self.tokens.push(tag);
+        Ok(())
+    }
+
+    /// Rename every reference to a variable, including those passed as function arguments,
+    /// returning how many were renamed
+    pub fn rename_variable(&mut self, old_name: &str, new_name: &str) -> Result<usize, String> {
+        if !is_var_name(new_name) {
+            return Err(format!("Invalid variable name {new_name:?}"));
+        }
+
+        fn rename(val: &mut Val, old_name: &str, new_name: &str) -> usize {
+            match val {
+                Val::Var { name } if name == old_name => {
+                    *name = new_name.to_string();
+                    1
+                }
+                Val::Fn { args, .. } => args
+                    .iter_mut()
+                    .map(|a| rename(&mut a.value, old_name, new_name))
+                    .sum(),
+                _ => 0,
+            }
+        }
+
+        Ok(self
+            .tokens
+            .iter_mut()
+            .map(|t| match t {
+                Token::Tag { val } => rename(val, old_name, new_name),
+                _ => 0,
+            })
+            .sum())
+    }
+
+    /// Names of every variable referenced, including those passed as function arguments, in the
+    /// order they first appear
+    pub fn variable_names(&self) -> Vec<String> {
+        fn collect(val: &Val, names: &mut Vec<String>) {
+            match val {
+                Val::Var { name } if !names.contains(name) => names.push(name.to_string()),
+                Val::Fn { args, .. } => args.iter().for_each(|a| collect(&a.value, names)),
+                _ => {}
+            }
+        }
+
+        let mut names = Vec::new();
+        for token in &self.tokens {
+            if let Token::Tag { val } = token {
+                collect(val, &mut names);
+            }
+        }
+        names
+    }
+}
+
+/// Whether a name parses back as a variable, rather than a keyword or something else entirely
+fn is_var_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && !["null", "true", "false"].contains(&name)
+}
+
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to="parser.ts")]
 pub struct FnArg {
@@ -773,4 +881,58 @@ mod tests {
             r#"${[ my_var ]} Some cool text ${[ 'Hello World' ]}"#
         );
     }
+
+    #[test]
+    fn rename_variable_everywhere() {
+        let mut tokens =
+            Parser::new("${[ foo ]}/${[ bar ]}?q=${[ foo ]}&h=${[ hash(value=foo, other=foo) ]}")
+                .parse();
+        assert_eq!(tokens.rename_variable("foo", "baz"), Ok(4));
+        assert_eq!(
+            tokens.to_string(),
+            "${[ baz ]}/${[ bar ]}?q=${[ baz ]}&h=${[ hash(value=baz, other=baz) ]}"
+        );
+
+        assert_eq!(tokens.rename_variable("missing", "other"), Ok(0));
+        assert!(tokens.rename_variable("bar", "not valid").is_err());
+        assert!(tokens.rename_variable("bar", "null").is_err());
+    }
+
+    #[test]
+    fn variable_names() {
+        let tokens = Parser::new(
+            "${[ host ]}/${[ hash(value=token, salt='x') ]}?q=${[ host ]}&n=${[ null ]}",
+        )
+        .parse();
+        assert_eq!(tokens.variable_names(), vec!["host", "token"]);
+        assert!(Parser::new("plain text")
+            .parse()
+            .variable_names()
+            .is_empty());
+    }
+
+    #[test]
+    fn insert_variable() {
+        let mut tokens = Parser::new("https://${[ host ]}/path").parse();
+        tokens.insert_variable(19, "version").unwrap();
+        assert_eq!(tokens.to_string(), "https://${[ host ]}${[ version ]}/path");
+
+        tokens.insert_variable(36, "id").unwrap();
+        assert_eq!(
+            tokens.to_string(),
+            "https://${[ host ]}${[ version ]}/pa${[ id ]}th"
+        );
+
+        tokens.insert_variable(47, "end").unwrap();
+        tokens.insert_variable(0, "start").unwrap();
+        assert_eq!(
+            tokens.to_string(),
+            "${[ start ]}https://${[ host ]}${[ version ]}/pa${[ id ]}th${[ end ]}"
+        );
+        assert_eq!(Parser::new(&tokens.to_string()).parse(), tokens);
+
+        assert!(tokens.insert_variable(25, "x").is_err());
+        assert!(tokens.insert_variable(1000, "x").is_err());
+        assert!(tokens.insert_variable(0, "").is_err());
+    }
 }
@@ -18,7 +18,7 @@ export function BasicAuth<T extends HttpRequest | GrpcRequest>({ request }: Prop
         useTemplating
         autocompleteVariables
         forceUpdateKey={request.id}
-        placeholder="username"
+        placeholder={request.authenticationType === 'ntlm' ? 'DOMAIN\\username' : 'username'}
         label="Username"
         name="username"
         size="sm"
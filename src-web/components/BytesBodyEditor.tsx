@@ -0,0 +1,45 @@
+import type { HttpRequest } from '@yaakapp-internal/models';
+import { Editor } from './core/Editor';
+import { Select } from './core/Select';
+import { VStack } from './core/Stacks';
+
+type Encoding = 'hex' | 'base64';
+
+type Props = {
+  forceUpdateKey: string;
+  heightMode: 'full' | 'auto';
+  body: HttpRequest['body'];
+  onChange: (body: HttpRequest['body']) => void;
+};
+
+export function BytesBodyEditor({ forceUpdateKey, heightMode, body, onChange }: Props) {
+  const encoding: Encoding = body.encoding === 'base64' ? 'base64' : 'hex';
+  const text = typeof body.text === 'string' ? body.text : '';
+
+  return (
+    <VStack space={2} className="h-full">
+      <Select
+        name="bytesEncoding"
+        label="Encoding"
+        labelPosition="left"
+        size="sm"
+        value={encoding}
+        onChange={(encoding) => onChange({ ...body, encoding })}
+        options={[
+          { label: 'Hex', value: 'hex' },
+          { label: 'Base64', value: 'base64' },
+        ]}
+      />
+      <Editor
+        forceUpdateKey={forceUpdateKey}
+        useTemplating
+        autocompleteVariables
+        placeholder={encoding === 'hex' ? '0xdeadbeef' : 'aGVsbG8='}
+        heightMode={heightMode}
+        defaultValue={text}
+        language="text"
+        onChange={(text) => onChange({ ...body, text })}
+      />
+    </VStack>
+  );
+}
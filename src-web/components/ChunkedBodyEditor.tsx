@@ -0,0 +1,66 @@
+import type { HttpRequest } from '@yaakapp-internal/models';
+import { Editor } from './core/Editor';
+import { PlainInput } from './core/PlainInput';
+import { Select } from './core/Select';
+import { VStack } from './core/Stacks';
+import { SelectFile } from './SelectFile';
+
+type Mode = 'repeat' | 'lines';
+
+type Props = {
+  forceUpdateKey: string;
+  heightMode: 'full' | 'auto';
+  body: HttpRequest['body'];
+  onChange: (body: HttpRequest['body']) => void;
+};
+
+export function ChunkedBodyEditor({ forceUpdateKey, heightMode, body, onChange }: Props) {
+  const mode: Mode = body.mode === 'lines' ? 'lines' : 'repeat';
+  const chunk = typeof body.chunk === 'string' ? body.chunk : '';
+  const filePath = typeof body.filePath === 'string' ? body.filePath : null;
+
+  return (
+    <VStack space={2} className="h-full">
+      <Select
+        name="chunkedMode"
+        label="Chunks"
+        labelPosition="left"
+        size="sm"
+        value={mode}
+        onChange={(mode) => onChange({ ...body, mode })}
+        options={[
+          { label: 'Repeat Template', value: 'repeat' },
+          { label: 'File Lines', value: 'lines' },
+        ]}
+      />
+      {mode === 'lines' ? (
+        <SelectFile
+          filePath={filePath}
+          onChange={({ filePath }) => onChange({ ...body, filePath: filePath ?? undefined })}
+        />
+      ) : (
+        <>
+          <PlainInput
+            size="sm"
+            name="chunkedCount"
+            label="Count"
+            labelPosition="left"
+            placeholder="1"
+            defaultValue={`${body.count ?? ''}`}
+            onChange={(count) => onChange({ ...body, count: count.trim() || undefined })}
+          />
+          <Editor
+            forceUpdateKey={forceUpdateKey}
+            useTemplating
+            autocompleteVariables
+            placeholder="Sent as each chunk"
+            heightMode={heightMode}
+            defaultValue={chunk}
+            language="text"
+            onChange={(chunk) => onChange({ ...body, chunk })}
+          />
+        </>
+      )}
+    </VStack>
+  );
+}
@@ -1,7 +1,7 @@
 import type { Cookie } from '@yaakapp-internal/models';
 import { useCookieJars } from '../hooks/useCookieJars';
-import { useUpdateCookieJar } from '../hooks/useUpdateCookieJar';
 import { cookieDomain } from '../lib/model_util';
+import { invokeCmd } from '../lib/tauri';
 import { Banner } from './core/Banner';
 import { IconButton } from './core/IconButton';
 import { InlineCode } from './core/InlineCode';
@@ -11,7 +11,6 @@ interface Props {
 }
 
 export const CookieDialog = function ({ cookieJarId }: Props) {
-  const updateCookieJar = useUpdateCookieJar(cookieJarId ?? null);
   const cookieJars = useCookieJars();
   const cookieJar = cookieJars?.find((c) => c.id === cookieJarId);
 
@@ -54,9 +53,11 @@ export const CookieDialog = function ({ cookieJarId }: Props) {
                   title="Delete"
                   className="ml-auto"
                   onClick={async () => {
-                    await updateCookieJar.mutateAsync({
-                      ...cookieJar,
-                      cookies: cookieJar.cookies.filter((c2: Cookie) => c2 !== c),
+                    await invokeCmd('cmd_delete_cookie', {
+                      cookieJarId: cookieJar.id,
+                      domain: typeof c.domain === 'string' ? '' : cookieDomain(c),
+                      path: c.path[0],
+                      name: c.raw_cookie.split('=')[0]?.trim() ?? '',
                     });
                   }}
                 />
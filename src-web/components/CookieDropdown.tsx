@@ -4,12 +4,14 @@ import { useCreateCookieJar } from '../hooks/useCreateCookieJar';
 import { useDeleteCookieJar } from '../hooks/useDeleteCookieJar';
 import { usePrompt } from '../hooks/usePrompt';
 import { useUpdateCookieJar } from '../hooks/useUpdateCookieJar';
+import { invokeCmd } from '../lib/tauri';
 import { CookieDialog } from './CookieDialog';
 import { Dropdown, type DropdownItem } from './core/Dropdown';
 import { Icon } from './core/Icon';
 import { IconButton } from './core/IconButton';
 import { InlineCode } from './core/InlineCode';
 import { useDialog } from './DialogContext';
+import { useToast } from './ToastContext';
 
 export function CookieDropdown() {
   const cookieJars = useCookieJars() ?? [];
@@ -19,6 +21,7 @@ export function CookieDropdown() {
   const createCookieJar = useCreateCookieJar();
   const dialog = useDialog();
   const prompt = usePrompt();
+  const toast = useToast();
 
   return (
     <Dropdown
@@ -68,6 +71,39 @@ export function CookieDropdown() {
                   updateCookieJar.mutate({ name });
                 },
               },
+              {
+                key: 'import-curl',
+                label: 'Import from cURL',
+                leftSlot: <Icon icon="paste" />,
+                onSelect: async () => {
+                  const command = await prompt({
+                    id: 'import-curl-cookies',
+                    title: 'Import Cookies from cURL',
+                    description: (
+                      <>
+                        Paste a cURL command to add its cookies to{' '}
+                        <InlineCode>{activeCookieJar?.name}</InlineCode>
+                      </>
+                    ),
+                    label: 'cURL Command',
+                    confirmText: 'Import',
+                    placeholder: "curl https://example.com -b 'session=abc'",
+                  });
+                  if (command == null) return;
+                  try {
+                    await invokeCmd('cmd_curl_cookies_to_jar', {
+                      command,
+                      cookieJarId: activeCookieJar.id,
+                    });
+                  } catch (err) {
+                    toast.show({
+                      id: 'import-curl-cookies-error',
+                      color: 'danger',
+                      message: String(err),
+                    });
+                  }
+                },
+              },
               ...((cookieJars.length > 1 // Never delete the last one
                 ? [
                     {
@@ -82,6 +82,12 @@ export const EnvironmentActionsDropdown = memo(function EnvironmentActionsDropdo
         onClick={environments.length === 0 ? showEnvironmentDialog : undefined}
         {...buttonProps}
       >
+        {activeEnvironment?.color && (
+          <span
+            className="inline-block w-2 h-2 mr-1.5 rounded-full shrink-0"
+            style={{ backgroundColor: activeEnvironment.color }}
+          />
+        )}
         {activeEnvironment?.name ?? (hasWorkspaceVars ? 'Environment' : 'No Environment')}
       </Button>
     </Dropdown>
@@ -4,11 +4,12 @@ import React, { useCallback, useMemo, useState } from 'react';
 import { useActiveWorkspace } from '../hooks/useActiveWorkspace';
 import { useCreateEnvironment } from '../hooks/useCreateEnvironment';
 import { useDeleteEnvironment } from '../hooks/useDeleteEnvironment';
+import { useDuplicateEnvironment } from '../hooks/useDuplicateEnvironment';
 import { useEnvironments } from '../hooks/useEnvironments';
 import { useKeyValue } from '../hooks/useKeyValue';
 import { usePrompt } from '../hooks/usePrompt';
 import { useUpdateEnvironment } from '../hooks/useUpdateEnvironment';
-import { useUpdateWorkspace } from '../hooks/useUpdateWorkspace';
+import { useUpdateWorkspaceVariables } from '../hooks/useUpdateWorkspaceVariables';
 import type { Environment, Workspace } from '@yaakapp-internal/models';
 import { Button } from './core/Button';
 import { ContextMenu } from './core/Dropdown';
@@ -124,17 +125,17 @@ const EnvironmentEditor = function ({
   });
   const environments = useEnvironments();
   const updateEnvironment = useUpdateEnvironment(environment?.id ?? null);
-  const updateWorkspace = useUpdateWorkspace(workspace.id);
+  const updateWorkspaceVariables = useUpdateWorkspaceVariables(workspace.id);
   const variables = environment == null ? workspace.variables : environment.variables;
   const handleChange = useCallback<PairEditorProps['onChange']>(
     (variables) => {
       if (environment != null) {
         updateEnvironment.mutate({ variables });
       } else {
-        updateWorkspace.mutate({ variables });
+        updateWorkspaceVariables.mutate(variables);
       }
     },
-    [updateWorkspace, updateEnvironment, environment],
+    [updateWorkspaceVariables, updateEnvironment, environment],
   );
 
   // Gather a list of env names from other environments, to help the user get them aligned
@@ -195,6 +196,7 @@ const EnvironmentEditor = function ({
           nameValidate={validateName}
           valueType={valueVisibility.value ? 'text' : 'password'}
           valueAutocompleteVariables={true}
+          allowSecretValues
           forceUpdateKey={environment?.id ?? workspace?.id ?? 'n/a'}
           pairs={variables}
           onChange={handleChange}
@@ -222,6 +224,7 @@ function SidebarButton({
   const prompt = usePrompt();
   const updateEnvironment = useUpdateEnvironment(environment?.id ?? null);
   const deleteEnvironment = useDeleteEnvironment(environment);
+  const duplicateEnvironment = useDuplicateEnvironment(environment);
   const [showContextMenu, setShowContextMenu] = useState<{
     x: number;
     y: number;
@@ -254,6 +257,12 @@ function SidebarButton({
           onContextMenu={handleContextMenu}
         >
           {children}
+          {environment?.color && (
+            <span
+              className="inline-block w-2 h-2 ml-1.5 rounded-full"
+              style={{ backgroundColor: environment.color }}
+            />
+          )}
         </Button>
         {rightSlot}
       </div>
@@ -284,6 +293,45 @@ function SidebarButton({
                 updateEnvironment.mutate({ name });
               },
             },
+            {
+              key: 'duplicate',
+              label: 'Duplicate',
+              leftSlot: <Icon icon="copy" size="sm" />,
+              onSelect: () => duplicateEnvironment.mutate(),
+            },
+            {
+              key: 'color',
+              label: 'Set Color',
+              leftSlot: <Icon icon="palette" size="sm" />,
+              onSelect: async () => {
+                const color = await prompt({
+                  id: 'environment-color',
+                  title: 'Environment Color',
+                  description: (
+                    <>
+                      Enter a hex color for <InlineCode>{environment.name}</InlineCode>, or leave
+                      it empty to clear it
+                    </>
+                  ),
+                  label: 'Color',
+                  confirmText: 'Save',
+                  placeholder: '#e53e3e',
+                  defaultValue: environment.color ?? '',
+                  require: false,
+                });
+                if (color == null) return;
+                if (color !== '' && !color.match(/^#([0-9a-f]{3}|[0-9a-f]{6}|[0-9a-f]{8})$/i)) {
+                  return;
+                }
+                updateEnvironment.mutate({ color: color || null });
+              },
+            },
+            {
+              key: 'production',
+              label: environment.isProduction ? 'Unmark as Production' : 'Mark as Production',
+              leftSlot: <Icon icon="alert_triangle" size="sm" />,
+              onSelect: () => updateEnvironment.mutate({ isProduction: !environment.isProduction }),
+            },
             {
               key: 'delete-environment',
               variant: 'danger',
@@ -6,6 +6,7 @@ import { count } from '../lib/pluralize';
 import { invokeCmd } from '../lib/tauri';
 import { Button } from './core/Button';
 import { Checkbox } from './core/Checkbox';
+import { PlainInput } from './core/PlainInput';
 import { HStack, VStack } from './core/Stacks';
 
 interface Props {
@@ -24,6 +25,7 @@ export function ExportDataDialog({
   const [selectedWorkspaces, setSelectedWorkspaces] = useState<Record<string, boolean>>({
     [activeWorkspace.id]: true,
   });
+  const [passphrase, setPassphrase] = useState<string>('');
 
   // Put active workspace first
   const workspaces = useMemo(
@@ -49,10 +51,14 @@ export function ExportDataDialog({
       return;
     }
 
-    await invokeCmd('cmd_export_data', { workspaceIds: ids, exportPath });
+    await invokeCmd('cmd_export_data', {
+      workspaceIds: ids,
+      exportPath,
+      passphrase: passphrase || null,
+    });
     onHide();
     onSuccess(exportPath);
-  }, [onHide, onSuccess, selectedWorkspaces, workspaces]);
+  }, [onHide, onSuccess, passphrase, selectedWorkspaces, workspaces]);
 
   const allSelected = workspaces.every((w) => selectedWorkspaces[w.id]);
   const numSelected = Object.values(selectedWorkspaces).filter(Boolean).length;
@@ -100,6 +106,15 @@ export function ExportDataDialog({
           ))}
         </tbody>
       </table>
+      <PlainInput
+        size="sm"
+        type="password"
+        name="passphrase"
+        label="Passphrase (optional)"
+        placeholder="Leave empty to export as plain JSON"
+        defaultValue={passphrase}
+        onChange={setPassphrase}
+      />
       <HStack space={2} justifyContent="end">
         <Button className="focus" variant="border" onClick={onHide}>
           Cancel
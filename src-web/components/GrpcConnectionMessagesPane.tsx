@@ -135,6 +135,16 @@ export function GrpcConnectionMessagesPane({ style, methodType, activeRequest }:
                   ) : (
                     <JsonAttributeTree attrValue={JSON.parse(activeEvent?.content ?? '{}')} />
                   )}
+                  {activeEvent.contentRaw != null && (
+                    <>
+                      <div className="mt-3 mb-1 select-text cursor-text font-semibold">
+                        Raw Bytes
+                      </div>
+                      <div className="select-text cursor-text font-mono text-xs break-all text-text-subtle">
+                        {base64ToHex(activeEvent.contentRaw)}
+                      </div>
+                    </>
+                  )}
                 </>
               ) : (
                 <div className="h-full grid grid-rows-[auto_minmax(0,1fr)]">
@@ -171,6 +181,10 @@ export function GrpcConnectionMessagesPane({ style, methodType, activeRequest }:
   );
 }
 
+function base64ToHex(b64: string) {
+  return Array.from(atob(b64), (c) => c.charCodeAt(0).toString(16).padStart(2, '0')).join(' ');
+}
+
 function EventRow({
   onClick,
   isActive,
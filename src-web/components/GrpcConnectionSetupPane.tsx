@@ -11,6 +11,7 @@ import { AUTH_TYPE_BASIC, AUTH_TYPE_BEARER, AUTH_TYPE_NONE } from '../lib/model_
 import { BasicAuth } from './BasicAuth';
 import { BearerAuth } from './BearerAuth';
 import { Button } from './core/Button';
+import { Checkbox } from './core/Checkbox';
 import { Icon } from './core/Icon';
 import { IconButton } from './core/IconButton';
 import { PairOrBulkEditor } from './core/PairOrBulkEditor';
@@ -20,6 +21,7 @@ import type { TabItem } from './core/Tabs/Tabs';
 import { TabContent, Tabs } from './core/Tabs/Tabs';
 import { EmptyStateText } from './EmptyStateText';
 import { GrpcEditor } from './GrpcEditor';
+import { GrpcRequestSettings } from './GrpcRequestSettings';
 import { UrlBar } from './UrlBar';
 
 interface Props {
@@ -161,6 +163,7 @@ export function GrpcConnectionSetupPane({
         },
       },
       { value: 'metadata', label: 'Metadata' },
+      { value: 'settings', label: 'Settings' },
     ],
     [
       activeRequest.authentication,
@@ -303,14 +306,26 @@ export function GrpcConnectionSetupPane({
           )}
         </TabContent>
         <TabContent value="metadata">
-          <PairOrBulkEditor
-            preferenceName="grpc_metadata"
-            valueAutocompleteVariables
-            nameAutocompleteVariables
-            pairs={activeRequest.metadata}
-            onChange={handleMetadataChange}
-            forceUpdateKey={forceUpdateKey}
-          />
+          <VStack space={2} className="h-full">
+            <Checkbox
+              checked={activeRequest.inheritMetadata}
+              title="Inherit workspace metadata"
+              onChange={(inheritMetadata) =>
+                updateRequest.mutate({ id: activeRequest.id, update: { inheritMetadata } })
+              }
+            />
+            <PairOrBulkEditor
+              preferenceName="grpc_metadata"
+              valueAutocompleteVariables
+              nameAutocompleteVariables
+              pairs={activeRequest.metadata}
+              onChange={handleMetadataChange}
+              forceUpdateKey={forceUpdateKey}
+            />
+          </VStack>
+        </TabContent>
+        <TabContent value="settings">
+          <GrpcRequestSettings key={forceUpdateKey} request={activeRequest} />
         </TabContent>
       </Tabs>
     </VStack>
@@ -1,6 +1,6 @@
 import { open } from '@tauri-apps/plugin-dialog';
 import { useGrpc } from '../hooks/useGrpc';
-import { useGrpcProtoFiles } from '../hooks/useGrpcProtoFiles';
+import { useGrpcProtoFiles, useGrpcProtoImportDirs } from '../hooks/useGrpcProtoFiles';
 import { useGrpcRequest } from '../hooks/useGrpcRequest';
 import { count } from '../lib/pluralize';
 import { Banner } from './core/Banner';
@@ -19,6 +19,8 @@ export function GrpcProtoSelection({ requestId }: Props) {
   const request = useGrpcRequest(requestId);
   const protoFilesKv = useGrpcProtoFiles(requestId);
   const protoFiles = protoFilesKv.value ?? [];
+  const importDirsKv = useGrpcProtoImportDirs(requestId);
+  const importDirs = importDirsKv.value ?? [];
   const grpc = useGrpc(request, null, protoFiles);
   const services = grpc.reflect.data;
   const serverReflection = protoFiles.length === 0 && services != null;
@@ -54,6 +56,24 @@ export function GrpcProtoSelection({ requestId }: Props) {
         >
           Add File
         </Button>
+        <Button
+          color="secondary"
+          title="Directories that imports in the proto files are resolved against, like protoc's -I"
+          onClick={async () => {
+            const selected = await open({
+              title: 'Select Import Directories',
+              multiple: true,
+              directory: true,
+            });
+            if (selected == null) return;
+
+            const newDirs = selected.filter((p) => !importDirs.includes(p));
+            await importDirsKv.set([...importDirs, ...newDirs]);
+            await grpc.reflect.refetch();
+          }}
+        >
+          Add Import Directory
+        </Button>
         <Button
           isLoading={grpc.reflect.isFetching}
           disabled={grpc.reflect.isFetching}
@@ -62,6 +82,11 @@ export function GrpcProtoSelection({ requestId }: Props) {
         >
           Refresh Schema
         </Button>
+        {grpc.reflect.isFetching && (
+          <Button color="secondary" variant="border" onClick={() => grpc.cancelReflect.mutate()}>
+            Cancel
+          </Button>
+        )}
       </HStack>
       <VStack space={5}>
         {!serverReflection && services != null && services.length > 0 && (
@@ -127,6 +152,33 @@ export function GrpcProtoSelection({ requestId }: Props) {
             </tbody>
           </table>
         )}
+        {importDirs.length > 0 && (
+          <table className="w-full divide-y divide-surface-highlight">
+            <thead>
+              <tr>
+                <th className="text-text-subtlest">Import Directories</th>
+                <th></th>
+              </tr>
+            </thead>
+            <tbody className="divide-y divide-surface-highlight">
+              {importDirs.map((d, i) => (
+                <tr key={d + i} className="group">
+                  <td className="pl-1 font-mono">{d}</td>
+                  <td className="w-0 py-0.5">
+                    <IconButton
+                      title="Remove directory"
+                      icon="trash"
+                      className="ml-auto opacity-50 transition-opacity group-hover:opacity-100"
+                      onClick={async () => {
+                        await importDirsKv.set(importDirs.filter((p) => p !== d));
+                      }}
+                    />
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
         {reflectError && (
           <Banner color="warning">
             <h1 className="font-bold">
@@ -0,0 +1,76 @@
+import type { GrpcRequest } from '@yaakapp-internal/models';
+import { useUpdateAnyGrpcRequest } from '../hooks/useUpdateAnyGrpcRequest';
+import { PlainInput } from './core/PlainInput';
+import { Select } from './core/Select';
+import { VStack } from './core/Stacks';
+import { SelectFile } from './SelectFile';
+
+interface Props {
+  request: GrpcRequest;
+}
+
+export function GrpcRequestSettings({ request }: Props) {
+  const updateRequest = useUpdateAnyGrpcRequest();
+  const update = (patch: Partial<GrpcRequest>) =>
+    updateRequest.mutate({ id: request.id, update: patch });
+
+  return (
+    <VStack className="my-2" space={2}>
+      <Select
+        name="settingValidateCertificates"
+        label="Validate TLS Certificates"
+        size="sm"
+        value={
+          request.settingValidateCertificates == null
+            ? 'inherit'
+            : request.settingValidateCertificates
+              ? 'validate'
+              : 'none'
+        }
+        onChange={(v) =>
+          update({ settingValidateCertificates: v === 'inherit' ? null : v === 'validate' })
+        }
+        options={[
+          { label: 'Use Workspace Setting', value: 'inherit' },
+          { label: 'Validate', value: 'validate' },
+          { label: "Don't Validate", value: 'none' },
+        ]}
+      />
+      <SelectFile
+        noun="CA Certificate"
+        filePath={request.settingCaCertificate}
+        onChange={({ filePath }) => update({ settingCaCertificate: filePath })}
+      />
+      <SelectFile
+        noun="Client Certificate"
+        filePath={request.settingClientCertificate}
+        onChange={({ filePath }) => update({ settingClientCertificate: filePath })}
+      />
+      <SelectFile
+        noun="Client Key"
+        filePath={request.settingClientKey}
+        onChange={({ filePath }) => update({ settingClientKey: filePath })}
+      />
+      <PlainInput
+        size="sm"
+        name="settingKeepaliveInterval"
+        label="Keepalive Interval (ms)"
+        placeholder="0"
+        defaultValue={`${request.settingKeepaliveInterval || ''}`}
+        validate={(value) => value === '' || parseInt(value) >= 0}
+        onChange={(v) => update({ settingKeepaliveInterval: parseInt(v) || 0 })}
+        type="number"
+      />
+      <PlainInput
+        size="sm"
+        name="settingTimeout"
+        label="Timeout (ms)"
+        placeholder="0"
+        defaultValue={`${request.settingTimeout || ''}`}
+        validate={(value) => value === '' || parseInt(value) >= 0}
+        onChange={(v) => update({ settingTimeout: parseInt(v) || 0 })}
+        type="number"
+      />
+    </VStack>
+  );
+}
@@ -0,0 +1,80 @@
+import type { HttpRequest } from '@yaakapp-internal/models';
+import classNames from 'classnames';
+import { useUpdateAnyHttpRequest } from '../hooks/useUpdateAnyHttpRequest';
+import { Banner } from './core/Banner';
+import { Button } from './core/Button';
+import { Editor } from './core/Editor';
+import { Separator } from './core/Separator';
+
+interface Props {
+  forceUpdateKey: string;
+  request: HttpRequest;
+}
+
+export function HttpRequestScripts({ forceUpdateKey, request }: Props) {
+  const updateRequest = useUpdateAnyHttpRequest();
+  const hasScripts = [request.preRequestScript, request.postResponseScript].some(
+    (s) => s.trim() !== '',
+  );
+  const untrusted = hasScripts && !request.scriptsTrusted;
+
+  return (
+    <div
+      className={classNames(
+        'h-full w-full grid grid-cols-1',
+        untrusted
+          ? 'grid-rows-[auto_auto_minmax(0,1fr)_auto_minmax(0,1fr)]'
+          : 'grid-rows-[auto_minmax(0,1fr)_auto_minmax(0,1fr)]',
+      )}
+    >
+      {untrusted && (
+        <Banner color="warning" className="flex items-center justify-between gap-3 mb-2">
+          <p>These scripts were imported, so they won&apos;t run until you trust them.</p>
+          <Button
+            size="xs"
+            color="warning"
+            variant="border"
+            onClick={() =>
+              updateRequest.mutate({ id: request.id, update: { scriptsTrusted: true } })
+            }
+          >
+            Trust Scripts
+          </Button>
+        </Banner>
+      )}
+      <Separator dashed className="pb-1">
+        Pre-request
+      </Separator>
+      <Editor
+        forceUpdateKey={forceUpdateKey}
+        language="javascript"
+        heightMode="auto"
+        placeholder="yaak.request.headers.push({ name: 'X-Request-Id', value: '...' });"
+        defaultValue={request.preRequestScript}
+        onChange={(preRequestScript) =>
+          // Scripts the user writes are trusted
+          updateRequest.mutate({
+            id: request.id,
+            update: { preRequestScript, scriptsTrusted: true },
+          })
+        }
+      />
+      <Separator dashed className="pb-1">
+        Post-response
+      </Separator>
+      <Editor
+        forceUpdateKey={forceUpdateKey}
+        language="javascript"
+        heightMode="auto"
+        placeholder="yaak.variables.set('token', yaak.response.json().token);"
+        defaultValue={request.postResponseScript}
+        onChange={(postResponseScript) =>
+          updateRequest.mutate({
+            id: request.id,
+            update: { postResponseScript, scriptsTrusted: true },
+          })
+        }
+      />
+    </div>
+  );
+}
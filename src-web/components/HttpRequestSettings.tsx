@@ -0,0 +1,167 @@
+import type { HttpRequest } from '@yaakapp-internal/models';
+import { useActiveWorkspace } from '../hooks/useActiveWorkspace';
+import { usePreviewCookies } from '../hooks/usePreviewCookies';
+import { useUpdateAnyHttpRequest } from '../hooks/useUpdateAnyHttpRequest';
+import { Button } from './core/Button';
+import { Checkbox } from './core/Checkbox';
+import { Input } from './core/Input';
+import { PlainInput } from './core/PlainInput';
+import { Select } from './core/Select';
+import { HStack, VStack } from './core/Stacks';
+import { ProxySettingEditor } from './ProxySettingEditor';
+
+interface Props {
+  request: HttpRequest;
+}
+
+/** An empty input clears the override, so the workspace setting applies */
+function parseOverride(value: string): number | null {
+  return value.trim() === '' ? null : Math.max(0, parseInt(value) || 0);
+}
+
+function isValidOverride(value: string): boolean {
+  return value.trim() === '' || parseInt(value) >= 0;
+}
+
+export function HttpRequestSettings({ request }: Props) {
+  const updateRequest = useUpdateAnyHttpRequest();
+  const previewCookies = usePreviewCookies(request);
+  const workspace = useActiveWorkspace();
+
+  return (
+    <VStack className="my-2" space={2}>
+      <Input
+        useTemplating
+        autocompleteVariables
+        placeholder="api.example.com"
+        label="Server Name (TLS SNI and Host)"
+        name="settingServerName"
+        size="sm"
+        defaultValue={request.settingServerName}
+        onChange={(settingServerName: string) =>
+          updateRequest.mutate({ id: request.id, update: { settingServerName } })
+        }
+      />
+      <ProxySettingEditor
+        name="settingProxy"
+        inheritLabel="Use Workspace Proxy Setting"
+        proxy={request.settingProxy}
+        onChange={(settingProxy) =>
+          updateRequest.mutate({ id: request.id, update: { settingProxy } })
+        }
+      />
+      <Select
+        name="settingHttpVersion"
+        label="HTTP Version"
+        size="sm"
+        value={request.settingHttpVersion ?? 'inherit'}
+        onChange={(v) =>
+          updateRequest.mutate({
+            id: request.id,
+            update: { settingHttpVersion: v === 'inherit' ? null : v },
+          })
+        }
+        options={[
+          { label: 'Use Workspace Setting', value: 'inherit' },
+          { label: 'Auto', value: 'auto' },
+          { label: 'HTTP/1.1', value: 'http1' },
+          { label: 'HTTP/2', value: 'http2' },
+          { label: 'HTTP/2 (Prior Knowledge)', value: 'http2-prior-knowledge' },
+        ]}
+      />
+      <Select
+        name="settingFollowRedirects"
+        label="Follow Redirects"
+        size="sm"
+        value={
+          request.settingFollowRedirects == null
+            ? 'inherit'
+            : request.settingFollowRedirects
+              ? 'follow'
+              : 'none'
+        }
+        onChange={(v) =>
+          updateRequest.mutate({
+            id: request.id,
+            update: { settingFollowRedirects: v === 'inherit' ? null : v === 'follow' },
+          })
+        }
+        options={[
+          { label: 'Use Workspace Setting', value: 'inherit' },
+          { label: 'Follow', value: 'follow' },
+          { label: "Don't Follow", value: 'none' },
+        ]}
+      />
+      <PlainInput
+        size="sm"
+        name="settingRequestTimeout"
+        label="Request Timeout (ms)"
+        placeholder={`${workspace?.settingRequestTimeout ?? 0} (workspace setting)`}
+        defaultValue={
+          request.settingRequestTimeout == null ? '' : `${request.settingRequestTimeout}`
+        }
+        validate={isValidOverride}
+        onChange={(v) =>
+          updateRequest.mutate({
+            id: request.id,
+            update: { settingRequestTimeout: parseOverride(v) },
+          })
+        }
+        type="number"
+      />
+      <PlainInput
+        size="sm"
+        name="settingRetryCount"
+        label="Retries"
+        placeholder={`${workspace?.settingRetryCount ?? 0} (workspace setting)`}
+        defaultValue={request.settingRetryCount == null ? '' : `${request.settingRetryCount}`}
+        validate={isValidOverride}
+        onChange={(v) =>
+          updateRequest.mutate({ id: request.id, update: { settingRetryCount: parseOverride(v) } })
+        }
+        type="number"
+      />
+      <PlainInput
+        size="sm"
+        name="settingRetryBackoff"
+        label="Retry Backoff (ms)"
+        placeholder={`${workspace?.settingRetryBackoff ?? 0} (workspace setting)`}
+        defaultValue={request.settingRetryBackoff == null ? '' : `${request.settingRetryBackoff}`}
+        validate={isValidOverride}
+        onChange={(v) =>
+          updateRequest.mutate({
+            id: request.id,
+            update: { settingRetryBackoff: parseOverride(v) },
+          })
+        }
+        type="number"
+      />
+      <Checkbox
+        checked={request.settingRetryNonIdempotent}
+        title="Retry POST and PATCH (may take effect more than once)"
+        onChange={(settingRetryNonIdempotent) =>
+          updateRequest.mutate({ id: request.id, update: { settingRetryNonIdempotent } })
+        }
+      />
+      <Checkbox
+        checked={request.settingIgnoreCookies}
+        title="Ignore Cookie Jar"
+        onChange={(settingIgnoreCookies) =>
+          updateRequest.mutate({ id: request.id, update: { settingIgnoreCookies } })
+        }
+      />
+      <HStack>
+        <Button
+          size="xs"
+          color="secondary"
+          variant="border"
+          disabled={request.settingIgnoreCookies}
+          isLoading={previewCookies.isPending}
+          onClick={() => previewCookies.mutate()}
+        >
+          Preview Cookies
+        </Button>
+      </HStack>
+    </VStack>
+  );
+}
@@ -1,16 +1,20 @@
 import React, { useState } from 'react';
 import { useLocalStorage } from 'react-use';
 import { Button } from './core/Button';
+import { Checkbox } from './core/Checkbox';
+import { PlainInput } from './core/PlainInput';
 import { VStack } from './core/Stacks';
 import { SelectFile } from './SelectFile';
 
 interface Props {
-  importData: (filePath: string) => Promise<void>;
+  importData: (filePath: string, merge: boolean, passphrase: string | null) => Promise<void>;
 }
 
 export function ImportDataDialog({ importData }: Props) {
   const [isLoading, setIsLoading] = useState<boolean>(false);
   const [filePath, setFilePath] = useLocalStorage<string | null>('importFilePath', null);
+  const [merge, setMerge] = useLocalStorage<boolean>('importMerge', false);
+  const [passphrase, setPassphrase] = useState<string>('');
   return (
     <VStack space={5} className="pb-4">
       <VStack space={1}>
@@ -29,6 +33,20 @@ export function ImportDataDialog({ importData }: Props) {
           filePath={filePath ?? null}
           onChange={({ filePath }) => setFilePath(filePath)}
         />
+        <Checkbox
+          checked={merge ?? false}
+          title="Update existing items instead of creating duplicates"
+          onChange={setMerge}
+        />
+        <PlainInput
+          size="sm"
+          type="password"
+          name="passphrase"
+          label="Passphrase"
+          placeholder="Only needed for encrypted exports"
+          defaultValue={passphrase}
+          onChange={setPassphrase}
+        />
         {filePath && (
           <Button
             color="primary"
@@ -38,7 +56,7 @@ export function ImportDataDialog({ importData }: Props) {
             onClick={async () => {
               setIsLoading(true);
               try {
-                await importData(filePath);
+                await importData(filePath, merge ?? false, passphrase || null);
               } finally {
                 setIsLoading(false);
               }
@@ -0,0 +1,105 @@
+import type { LoadTest } from '@yaakapp-internal/models';
+import React, { useState } from 'react';
+import {
+  useCancelLoadTest,
+  useLoadTestProgress,
+  useLoadTests,
+  useRunLoadTest,
+} from '../hooks/useLoadTest';
+import { Button } from './core/Button';
+import { KeyValueRow, KeyValueRows } from './core/KeyValueRow';
+import { PlainInput } from './core/PlainInput';
+import { HStack, VStack } from './core/Stacks';
+
+interface Props {
+  requestId: string;
+}
+
+export function LoadTestDialog({ requestId }: Props) {
+  const [duration, setDuration] = useState<number>(10);
+  const [concurrency, setConcurrency] = useState<number>(10);
+  const runLoadTest = useRunLoadTest(requestId);
+  const cancelLoadTest = useCancelLoadTest();
+  const progress = useLoadTestProgress(requestId);
+  const [latest] = useLoadTests(requestId);
+  const isRunning = runLoadTest.isPending && progress?.state === 'running';
+  const test = runLoadTest.isPending ? progress : runLoadTest.data ?? latest ?? null;
+
+  return (
+    <VStack space={4} className="mb-4">
+      <HStack space={2} alignItems="end">
+        <PlainInput
+          size="sm"
+          name="duration"
+          label="Duration (seconds)"
+          defaultValue={`${duration}`}
+          validate={(value) => parseInt(value) > 0}
+          onChange={(v) => setDuration(parseInt(v) || 0)}
+          type="number"
+        />
+        <PlainInput
+          size="sm"
+          name="concurrency"
+          label="Concurrency"
+          defaultValue={`${concurrency}`}
+          validate={(value) => parseInt(value) > 0 && parseInt(value) <= 256}
+          onChange={(v) => setConcurrency(parseInt(v) || 0)}
+          type="number"
+        />
+        {isRunning ? (
+          <Button
+            size="sm"
+            color="danger"
+            onClick={() => progress && cancelLoadTest.mutate(progress.id)}
+          >
+            Cancel
+          </Button>
+        ) : (
+          <Button
+            size="sm"
+            color="primary"
+            isLoading={runLoadTest.isPending}
+            disabled={duration <= 0 || concurrency <= 0}
+            onClick={() => runLoadTest.mutate({ duration: duration * 1000, concurrency })}
+          >
+            Run
+          </Button>
+        )}
+      </HStack>
+      {runLoadTest.error != null && <div className="text-danger">{runLoadTest.error}</div>}
+      {test != null && <LoadTestMetrics test={test} />}
+    </VStack>
+  );
+}
+
+function LoadTestMetrics({ test }: { test: LoadTest }) {
+  const seconds = test.elapsed / 1000;
+  const perSecond = seconds > 0 ? (test.requests / seconds).toFixed(1) : '0';
+  const statuses = Object.entries(test.statusCounts)
+    .map(([status, count]) => `${status} × ${count}`)
+    .join(', ');
+
+  return (
+    <KeyValueRows>
+      <KeyValueRow labelColor="info" label="State" value={test.state} />
+      <KeyValueRow
+        labelColor="info"
+        label="Elapsed"
+        value={`${seconds.toFixed(1)}s of ${test.duration / 1000}s`}
+      />
+      <KeyValueRow labelColor="info" label="Requests" value={`${test.requests} (${perSecond}/s)`} />
+      <KeyValueRow labelColor="info" label="Errors" value={test.errors} />
+      <KeyValueRow labelColor="info" label="Statuses" value={statuses || '--'} />
+      <KeyValueRow
+        labelColor="info"
+        label="Latency"
+        value={`min ${test.latencyMin}ms, mean ${test.latencyMean}ms, max ${test.latencyMax}ms`}
+      />
+      <KeyValueRow
+        labelColor="info"
+        label="Percentiles"
+        value={`p50 ${test.latencyP50}ms, p90 ${test.latencyP90}ms, p95 ${test.latencyP95}ms, p99 ${test.latencyP99}ms`}
+      />
+    </KeyValueRows>
+  );
+}
@@ -0,0 +1,105 @@
+import type { ProxySetting } from '@yaakapp-internal/models';
+import { Checkbox } from './core/Checkbox';
+import { PlainInput } from './core/PlainInput';
+import { Select } from './core/Select';
+import { HStack, VStack } from './core/Stacks';
+
+type EnabledProxySetting = Extract<ProxySetting, { type: 'enabled' }>;
+
+interface Props {
+  name: string;
+  proxy: ProxySetting | null;
+  onChange: (proxy: ProxySetting | null) => void;
+  /** Offer to inherit the proxy from a broader setting, with this label */
+  inheritLabel?: string;
+}
+
+export function ProxySettingEditor({ name, proxy, onChange, inheritLabel }: Props) {
+  const emptyValue = inheritLabel ? 'inherit' : 'system';
+  const enabled: EnabledProxySetting | null = proxy?.type === 'enabled' ? proxy : null;
+  const update = (patch: Partial<EnabledProxySetting>) => {
+    if (enabled == null) return;
+    onChange({ ...enabled, ...patch });
+  };
+
+  return (
+    <VStack space={1.5}>
+      <Select
+        name={name}
+        label="Proxy"
+        size="sm"
+        value={proxy?.type ?? emptyValue}
+        onChange={(v) => {
+          if (v === 'enabled') {
+            onChange({ type: 'enabled', http: '', https: '', socks: '', auth: null });
+          } else if (v === 'disabled' || (v === 'system' && inheritLabel)) {
+            onChange({ type: v });
+          } else {
+            onChange(null);
+          }
+        }}
+        options={[
+          ...(inheritLabel ? [{ label: inheritLabel, value: 'inherit' }] : []),
+          { label: 'Automatic Proxy Detection', value: 'system' },
+          { label: 'Custom Proxy Configuration', value: 'enabled' },
+          { label: 'No Proxy', value: 'disabled' },
+        ]}
+      />
+      {enabled != null && (
+        <VStack space={1.5}>
+          <HStack space={1.5}>
+            <PlainInput
+              size="sm"
+              label="HTTP"
+              placeholder="localhost:9090"
+              defaultValue={enabled.http}
+              onChange={(http) => update({ http })}
+            />
+            <PlainInput
+              size="sm"
+              label="HTTPS"
+              placeholder="localhost:9090"
+              defaultValue={enabled.https}
+              onChange={(https) => update({ https })}
+            />
+          </HStack>
+          <PlainInput
+            size="sm"
+            label="SOCKS5 (used when HTTP or HTTPS is empty)"
+            placeholder="localhost:1080"
+            defaultValue={enabled.socks ?? ''}
+            onChange={(socks) => update({ socks })}
+          />
+          <Checkbox
+            checked={enabled.auth != null}
+            title="Enable authentication"
+            onChange={(checked) => update({ auth: checked ? { user: '', password: '' } : null })}
+          />
+          {enabled.auth != null && (
+            <HStack space={1.5}>
+              <PlainInput
+                size="sm"
+                label="User"
+                placeholder="myUser"
+                defaultValue={enabled.auth.user}
+                onChange={(user) =>
+                  update({ auth: { user, password: enabled.auth?.password ?? '' } })
+                }
+              />
+              <PlainInput
+                size="sm"
+                label="Password"
+                type="password"
+                placeholder="s3cretPassw0rd"
+                defaultValue={enabled.auth.password}
+                onChange={(password) =>
+                  update({ auth: { user: enabled.auth?.user ?? '', password } })
+                }
+              />
+            </HStack>
+          )}
+        </VStack>
+      )}
+    </VStack>
+  );
+}
@@ -3,6 +3,7 @@ import { useCopyHttpResponse } from '../hooks/useCopyHttpResponse';
 import { useDeleteHttpResponse } from '../hooks/useDeleteHttpResponse';
 import { useDeleteHttpResponses } from '../hooks/useDeleteHttpResponses';
 import { useSaveResponse } from '../hooks/useSaveResponse';
+import { useSaveResponseExample } from '../hooks/useSaveResponseExample';
 import { pluralize } from '../lib/pluralize';
 import { Dropdown } from './core/Dropdown';
 import { Icon } from './core/Icon';
@@ -27,6 +28,7 @@ export const RecentResponsesDropdown = function ResponsePane({
   const latestResponseId = responses[0]?.id ?? 'n/a';
   const saveResponse = useSaveResponse(activeResponse);
   const copyResponse = useCopyHttpResponse(activeResponse);
+  const saveExample = useSaveResponseExample(activeResponse);
 
   return (
     <Dropdown
@@ -39,6 +41,14 @@ export const RecentResponsesDropdown = function ResponsePane({
           hidden: responses.length === 0,
           disabled: activeResponse.state !== 'closed' && activeResponse.status >= 100,
         },
+        {
+          key: 'save-example',
+          label: 'Save as Example',
+          onSelect: saveExample.mutate,
+          leftSlot: <Icon icon="archive" />,
+          hidden: responses.length === 0,
+          disabled: activeResponse.state !== 'closed',
+        },
         {
           key: 'copy',
           label: 'Copy Body',
@@ -5,6 +5,7 @@ import { getRecentCookieJars } from '../hooks/useRecentCookieJars';
 import { getRecentEnvironments } from '../hooks/useRecentEnvironments';
 import { getRecentRequests } from '../hooks/useRecentRequests';
 import { useRecentWorkspaces } from '../hooks/useRecentWorkspaces';
+import { useSettings } from '../hooks/useSettings';
 import { useWorkspaces } from '../hooks/useWorkspaces';
 
 export function RedirectToLatestWorkspace() {
@@ -12,6 +13,7 @@ export function RedirectToLatestWorkspace() {
   const routes = useAppRoutes();
   const workspaces = useWorkspaces();
   const recentWorkspaces = useRecentWorkspaces();
+  const settings = useSettings();
 
   useEffect(() => {
     if (workspaces.length === 0) {
@@ -23,7 +25,9 @@ export function RedirectToLatestWorkspace() {
       const workspaceId = recentWorkspaces[0] ?? workspaces[0]?.id ?? 'n/a';
       const environmentId = (await getRecentEnvironments(workspaceId))[0] ?? null;
       const cookieJarId = (await getRecentCookieJars(workspaceId))[0] ?? null;
-      const requestId = (await getRecentRequests(workspaceId))[0] ?? null;
+      const requestId = settings.restoreLastLocation
+        ? ((await getRecentRequests(workspaceId))[0] ?? null)
+        : null;
 
       if (workspaceId != null && requestId != null) {
         navigate(routes.paths.request({ workspaceId, environmentId, requestId, cookieJarId }));
@@ -31,7 +35,14 @@ export function RedirectToLatestWorkspace() {
         navigate(routes.paths.workspace({ workspaceId, environmentId, cookieJarId }));
       }
     })();
-  }, [navigate, recentWorkspaces, routes.paths, workspaces, workspaces.length]);
+  }, [
+    navigate,
+    recentWorkspaces,
+    routes.paths,
+    settings.restoreLastLocation,
+    workspaces,
+    workspaces.length,
+  ]);
 
   return <></>;
 }
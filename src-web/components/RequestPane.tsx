@@ -19,8 +19,12 @@ import { tryFormatJson } from '../lib/formatters';
 import {
   AUTH_TYPE_BASIC,
   AUTH_TYPE_BEARER,
+  AUTH_TYPE_DIGEST,
   AUTH_TYPE_NONE,
+  AUTH_TYPE_NTLM,
   BODY_TYPE_BINARY,
+  BODY_TYPE_BYTES,
+  BODY_TYPE_CHUNKED,
   BODY_TYPE_FORM_MULTIPART,
   BODY_TYPE_FORM_URLENCODED,
   BODY_TYPE_GRAPHQL,
@@ -32,8 +36,9 @@ import {
 import { BasicAuth } from './BasicAuth';
 import { BearerAuth } from './BearerAuth';
 import { BinaryFileEditor } from './BinaryFileEditor';
+import { BytesBodyEditor } from './BytesBodyEditor';
+import { ChunkedBodyEditor } from './ChunkedBodyEditor';
 import { CountBadge } from './core/CountBadge';
-import { Editor } from './core/Editor';
 import type { GenericCompletionOption } from './core/Editor/genericCompletion';
 import { InlineCode } from './core/InlineCode';
 import type { Pair } from './core/PairEditor';
@@ -44,6 +49,9 @@ import { FormMultipartEditor } from './FormMultipartEditor';
 import { FormUrlencodedEditor } from './FormUrlencodedEditor';
 import { GraphQLEditor } from './GraphQLEditor';
 import { HeadersEditor } from './HeadersEditor';
+import { HttpRequestScripts } from './HttpRequestScripts';
+import { HttpRequestSettings } from './HttpRequestSettings';
+import { TextBodyEditor } from './TextBodyEditor';
 import { useToast } from './ToastContext';
 import { UrlBar } from './UrlBar';
 import { UrlParametersEditor } from './UrlParameterEditor';
@@ -59,6 +67,8 @@ const TAB_BODY = 'body';
 const TAB_PARAMS = 'params';
 const TAB_HEADERS = 'headers';
 const TAB_AUTH = 'auth';
+const TAB_SCRIPTS = 'scripts';
+const TAB_SETTINGS = 'settings';
 
 const DEFAULT_TAB = TAB_BODY;
 
@@ -135,6 +145,8 @@ export const RequestPane = memo(function RequestPane({
             { label: 'Other', value: BODY_TYPE_OTHER },
             { type: 'separator', label: 'Other' },
             { label: 'Binary File', value: BODY_TYPE_BINARY },
+            { label: 'Raw Bytes', shortLabel: 'Bytes', value: BODY_TYPE_BYTES },
+            { label: 'Chunked Stream', shortLabel: 'Chunked', value: BODY_TYPE_CHUNKED },
             { label: 'No Body', shortLabel: 'Body', value: BODY_TYPE_NONE },
           ],
           onChange: async (bodyType) => {
@@ -172,6 +184,8 @@ export const RequestPane = memo(function RequestPane({
                 showMethodToast(patch.method);
               }
               newContentType = bodyType === BODY_TYPE_OTHER ? 'text/plain' : bodyType;
+            } else if (bodyType === BODY_TYPE_BYTES) {
+              newContentType = 'application/octet-stream';
             } else if (bodyType == BODY_TYPE_GRAPHQL) {
               patch.method = 'POST';
               newContentType = 'application/json';
@@ -212,12 +226,18 @@ export const RequestPane = memo(function RequestPane({
           items: [
             { label: 'Basic Auth', shortLabel: 'Basic', value: AUTH_TYPE_BASIC },
             { label: 'Bearer Token', shortLabel: 'Bearer', value: AUTH_TYPE_BEARER },
+            { label: 'Digest Auth', shortLabel: 'Digest', value: AUTH_TYPE_DIGEST },
+            { label: 'NTLM Auth', shortLabel: 'NTLM', value: AUTH_TYPE_NTLM },
             { type: 'separator' },
             { label: 'No Authentication', shortLabel: 'Auth', value: AUTH_TYPE_NONE },
           ],
           onChange: async (authenticationType) => {
             let authentication: HttpRequest['authentication'] = activeRequest.authentication;
-            if (authenticationType === AUTH_TYPE_BASIC) {
+            if (
+              authenticationType === AUTH_TYPE_BASIC ||
+              authenticationType === AUTH_TYPE_DIGEST ||
+              authenticationType === AUTH_TYPE_NTLM
+            ) {
               authentication = {
                 username: authentication.username ?? '',
                 password: authentication.password ?? '',
@@ -234,6 +254,25 @@ export const RequestPane = memo(function RequestPane({
           },
         },
       },
+      {
+        value: TAB_SCRIPTS,
+        label: (
+          <div className="flex items-center">
+            Scripts
+            <CountBadge
+              count={
+                [activeRequest.preRequestScript, activeRequest.postResponseScript].filter((s) =>
+                  s.trim(),
+                ).length
+              }
+            />
+          </div>
+        ),
+      },
+      {
+        value: TAB_SETTINGS,
+        label: 'Settings',
+      },
     ],
     [
       activeRequest.authentication,
@@ -241,6 +280,8 @@ export const RequestPane = memo(function RequestPane({
       activeRequest.bodyType,
       activeRequest.headers,
       activeRequest.method,
+      activeRequest.postResponseScript,
+      activeRequest.preRequestScript,
       activeRequestId,
       handleContentTypeChange,
       toast,
@@ -260,10 +301,6 @@ export const RequestPane = memo(function RequestPane({
     },
     [activeRequestId, updateRequest],
   );
-  const handleBodyTextChange = useCallback(
-    (text: string) => updateRequest.mutate({ id: activeRequestId, update: { body: { text } } }),
-    [activeRequestId, updateRequest],
-  );
   const handleHeadersChange = useCallback(
     (headers: HttpRequestHeader[]) =>
       updateRequest.mutate({ id: activeRequestId, update: { headers } }),
@@ -368,7 +405,9 @@ export const RequestPane = memo(function RequestPane({
             tabListClassName="mt-2 !mb-1.5"
           >
             <TabContent value={TAB_AUTH}>
-              {activeRequest.authenticationType === AUTH_TYPE_BASIC ? (
+              {activeRequest.authenticationType === AUTH_TYPE_BASIC ||
+              activeRequest.authenticationType === AUTH_TYPE_DIGEST ||
+              activeRequest.authenticationType === AUTH_TYPE_NTLM ? (
                 <BasicAuth key={forceUpdateKey} request={activeRequest} />
               ) : activeRequest.authenticationType === AUTH_TYPE_BEARER ? (
                 <BearerAuth key={forceUpdateKey} request={activeRequest} />
@@ -378,6 +417,16 @@ export const RequestPane = memo(function RequestPane({
                 </EmptyStateText>
               )}
             </TabContent>
+            <TabContent value={TAB_SCRIPTS}>
+              <HttpRequestScripts
+                key={forceUpdateKey}
+                forceUpdateKey={forceUpdateKey}
+                request={activeRequest}
+              />
+            </TabContent>
+            <TabContent value={TAB_SETTINGS}>
+              <HttpRequestSettings key={forceUpdateKey} request={activeRequest} />
+            </TabContent>
             <TabContent value={TAB_HEADERS}>
               <HeadersEditor
                 forceUpdateKey={`${forceUpdateHeaderEditorKey}::${forceUpdateKey}`}
@@ -394,27 +443,21 @@ export const RequestPane = memo(function RequestPane({
             </TabContent>
             <TabContent value={TAB_BODY}>
               {activeRequest.bodyType === BODY_TYPE_JSON ? (
-                <Editor
+                <TextBodyEditor
                   forceUpdateKey={forceUpdateKey}
-                  useTemplating
-                  autocompleteVariables
-                  placeholder="..."
                   heightMode={fullHeight ? 'full' : 'auto'}
-                  defaultValue={`${activeRequest.body?.text ?? ''}`}
+                  body={activeRequest.body}
                   language="json"
-                  onChange={handleBodyTextChange}
+                  onChange={handleBodyChange}
                   format={tryFormatJson}
                 />
               ) : activeRequest.bodyType === BODY_TYPE_XML ? (
-                <Editor
+                <TextBodyEditor
                   forceUpdateKey={forceUpdateKey}
-                  useTemplating
-                  autocompleteVariables
-                  placeholder="..."
                   heightMode={fullHeight ? 'full' : 'auto'}
-                  defaultValue={`${activeRequest.body?.text ?? ''}`}
+                  body={activeRequest.body}
                   language="xml"
-                  onChange={handleBodyTextChange}
+                  onChange={handleBodyChange}
                 />
               ) : activeRequest.bodyType === BODY_TYPE_GRAPHQL ? (
                 <GraphQLEditor
@@ -435,6 +478,20 @@ export const RequestPane = memo(function RequestPane({
                   body={activeRequest.body}
                   onChange={handleBodyChange}
                 />
+              ) : activeRequest.bodyType === BODY_TYPE_BYTES ? (
+                <BytesBodyEditor
+                  forceUpdateKey={forceUpdateKey}
+                  heightMode={fullHeight ? 'full' : 'auto'}
+                  body={activeRequest.body}
+                  onChange={handleBodyChange}
+                />
+              ) : activeRequest.bodyType === BODY_TYPE_CHUNKED ? (
+                <ChunkedBodyEditor
+                  forceUpdateKey={forceUpdateKey}
+                  heightMode={fullHeight ? 'full' : 'auto'}
+                  body={activeRequest.body}
+                  onChange={handleBodyChange}
+                />
               ) : activeRequest.bodyType === BODY_TYPE_BINARY ? (
                 <BinaryFileEditor
                   requestId={activeRequest.id}
@@ -444,15 +501,12 @@ export const RequestPane = memo(function RequestPane({
                   onChangeContentType={handleContentTypeChange}
                 />
               ) : typeof activeRequest.bodyType === 'string' ? (
-                <Editor
+                <TextBodyEditor
                   forceUpdateKey={forceUpdateKey}
-                  useTemplating
-                  autocompleteVariables
-                  language={languageFromContentType(contentType)}
-                  placeholder="..."
                   heightMode={fullHeight ? 'full' : 'auto'}
-                  defaultValue={`${activeRequest.body?.text ?? ''}`}
-                  onChange={handleBodyTextChange}
+                  body={activeRequest.body}
+                  language={languageFromContentType(contentType)}
+                  onChange={handleBodyChange}
                 />
               ) : (
                 <EmptyStateText>Empty Body</EmptyStateText>
@@ -1,5 +1,7 @@
 import { open } from '@tauri-apps/plugin-shell';
 import type { HttpResponse } from '@yaakapp-internal/models';
+import { useCopy } from '../hooks/useCopy';
+import { getRawRequestText } from '../lib/responseBody';
 import { IconButton } from './core/IconButton';
 import { KeyValueRow, KeyValueRows } from './core/KeyValueRow';
 
@@ -8,11 +10,13 @@ interface Props {
 }
 
 export function ResponseInfo({ response }: Props) {
+  const copy = useCopy();
   return (
     <div className="overflow-auto h-full pb-4">
       <KeyValueRows>
         <KeyValueRow labelColor="info" label="Version" value={response.version} />
         <KeyValueRow labelColor="info" label="Remote Address" value={response.remoteAddr} />
+        <KeyValueRow labelColor="info" label="Attempts" value={response.attempts} />
         <KeyValueRow
           labelColor="info"
           label={
@@ -33,6 +37,48 @@ export function ResponseInfo({ response }: Props) {
             </div>
           }
         />
+        {response.rawRequestPath != null && (
+          <KeyValueRow
+            labelColor="info"
+            label={
+              <div className="flex items-center">
+                Raw Request
+                <IconButton
+                  iconSize="sm"
+                  className="inline-block w-auto ml-1 !h-auto opacity-50 hover:opacity-100"
+                  icon="copy"
+                  onClick={async () => copy(await getRawRequestText(response))}
+                  title="Copy raw request"
+                />
+              </div>
+            }
+            value={<span className="select-text cursor-text">{response.rawRequestPath}</span>}
+          />
+        )}
+        {response.tlsCertificate != null && (
+          <>
+            <KeyValueRow
+              labelColor="info"
+              label="Certificate Subject"
+              value={response.tlsCertificate.subject}
+            />
+            <KeyValueRow
+              labelColor="info"
+              label="Certificate Issuer"
+              value={response.tlsCertificate.issuer}
+            />
+            <KeyValueRow
+              labelColor="info"
+              label="Certificate Valid"
+              value={`${response.tlsCertificate.notBefore} – ${response.tlsCertificate.notAfter}`}
+            />
+            <KeyValueRow
+              labelColor="info"
+              label="Certificate Names"
+              value={response.tlsCertificate.subjectAltNames.join(', ')}
+            />
+          </>
+        )}
       </KeyValueRows>
     </div>
   );
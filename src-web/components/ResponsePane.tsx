@@ -4,8 +4,10 @@ import type { CSSProperties, ReactNode } from 'react';
 import React, { memo, useCallback, useMemo } from 'react';
 import { useLocalStorage } from 'react-use';
 import { useContentTypeFromHeaders } from '../hooks/useContentTypeFromHeaders';
+import { useDownloadProgress } from '../hooks/useDownloadProgress';
 import { usePinnedHttpResponse } from '../hooks/usePinnedHttpResponse';
 import { useResponseViewMode } from '../hooks/useResponseViewMode';
+import { useUploadProgress } from '../hooks/useUploadProgress';
 import { isResponseLoading } from '../lib/model_util';
 import { Banner } from './core/Banner';
 import { CountBadge } from './core/CountBadge';
@@ -21,6 +23,7 @@ import { EmptyStateText } from './EmptyStateText';
 import { RecentResponsesDropdown } from './RecentResponsesDropdown';
 import { ResponseHeaders } from './ResponseHeaders';
 import { ResponseInfo } from './ResponseInfo';
+import { ResponseTimeline } from './ResponseTimeline';
 import { AudioViewer } from './responseViewers/AudioViewer';
 import { CsvViewer } from './responseViewers/CsvViewer';
 import { EventStreamViewer } from './responseViewers/EventStreamViewer';
@@ -38,6 +41,7 @@ interface Props {
 const TAB_BODY = 'body';
 const TAB_HEADERS = 'headers';
 const TAB_INFO = 'info';
+const TAB_TIMELINE = 'timeline';
 const DEFAULT_TAB = TAB_BODY;
 
 export const ResponsePane = memo(function ResponsePane({ style, className, activeRequest }: Props) {
@@ -85,11 +89,21 @@ export const ResponsePane = memo(function ResponsePane({ style, className, activ
         value: TAB_INFO,
         label: 'Info',
       },
+      {
+        value: TAB_TIMELINE,
+        label: 'Timeline',
+      },
     ],
     [activeResponse?.headers, contentType, setViewMode, viewMode],
   );
 
   const isLoading = isResponseLoading(activeResponse);
+  const uploadProgress = useUploadProgress(activeRequest.id, activeResponse?.id ?? null);
+  const isUploading =
+    isLoading && uploadProgress != null && uploadProgress.bytesSent < uploadProgress.totalBytes;
+  const downloadProgress = useDownloadProgress(activeRequest.id, activeResponse?.id ?? null);
+  const isDownloading =
+    isLoading && !isUploading && downloadProgress != null && !!downloadProgress.totalBytes;
 
   return (
     <div
@@ -125,14 +139,67 @@ export const ResponsePane = memo(function ResponsePane({ style, className, activ
                 )}
               >
                 {isLoading && <Icon size="sm" icon="refresh" spin />}
+                {isUploading && (
+                  <>
+                    <span>
+                      Uploading{' '}
+                      {Math.floor((100 * uploadProgress.bytesSent) / uploadProgress.totalBytes)}%
+                    </span>
+                    <span>&bull;</span>
+                  </>
+                )}
+                {isDownloading && (
+                  <>
+                    <span>
+                      Downloading{' '}
+                      {Math.floor(
+                        (100 * downloadProgress.bytesReceived) / (downloadProgress.totalBytes ?? 1),
+                      )}
+                      %
+                    </span>
+                    <span>&bull;</span>
+                  </>
+                )}
                 <StatusTag showReason response={activeResponse} />
                 <span>&bull;</span>
                 <DurationTag
                   headers={activeResponse.elapsedHeaders}
                   total={activeResponse.elapsed}
+                  slow={activeResponse.slow}
                 />
                 <span>&bull;</span>
                 <SizeTag contentLength={activeResponse.contentLength ?? 0} />
+                {!!activeResponse.requestContentLength && (
+                  <>
+                    <span>&bull;</span>
+                    <span title="Size of the request body that was sent">
+                      Sent <SizeTag contentLength={activeResponse.requestContentLength} />
+                    </span>
+                  </>
+                )}
+                {activeResponse.attempts > 1 && (
+                  <>
+                    <span>&bull;</span>
+                    <span className="text-warning" title="Earlier attempts failed and were retried">
+                      {activeResponse.attempts} attempts
+                    </span>
+                  </>
+                )}
+                {activeResponse.source !== 'network' && (
+                  <>
+                    <span>&bull;</span>
+                    <span
+                      className="text-text-subtle"
+                      title={
+                        activeResponse.source === 'not_modified'
+                          ? 'Body reused from an earlier response after 304 Not Modified'
+                          : 'Served from local cache'
+                      }
+                    >
+                      cached
+                    </span>
+                  </>
+                )}
 
                 <div className="ml-auto">
                   <RecentResponsesDropdown
@@ -192,6 +259,9 @@ export const ResponsePane = memo(function ResponsePane({ style, className, activ
               <TabContent value={TAB_INFO}>
                 <ResponseInfo response={activeResponse} />
               </TabContent>
+              <TabContent value={TAB_TIMELINE}>
+                <ResponseTimeline response={activeResponse} />
+              </TabContent>
             </Tabs>
           )}
         </div>
@@ -0,0 +1,70 @@
+import type { HttpResponse, HttpTimelineEvent, HttpTimelineEventType } from '@yaakapp-internal/models';
+import classNames from 'classnames';
+import { useHttpTimelineEvents } from '../hooks/useHttpTimelineEvents';
+import { KeyValueRow, KeyValueRows } from './core/KeyValueRow';
+import { VStack } from './core/Stacks';
+import { EmptyStateText } from './EmptyStateText';
+
+interface Props {
+  response: HttpResponse;
+}
+
+const LABELS: Record<HttpTimelineEventType, string> = {
+  request_headers: 'Request Sent',
+  dns_lookup: 'DNS Lookup',
+  connection: 'Connect, TLS, and Wait',
+  response_headers: 'Response Headers',
+  download: 'Download',
+};
+
+export function ResponseTimeline({ response }: Props) {
+  const events = useHttpTimelineEvents(response.id);
+  if (events.length === 0) {
+    return <EmptyStateText>No timeline recorded for this response</EmptyStateText>;
+  }
+
+  const total = Math.max(1, response.elapsed, ...events.map((e) => e.elapsed + (e.duration ?? 0)));
+  return (
+    <div className="overflow-auto h-full pb-4">
+      <VStack space={3}>
+        {events.map((e) => (
+          <TimelineEvent key={e.id} event={e} total={total} />
+        ))}
+      </VStack>
+    </div>
+  );
+}
+
+function TimelineEvent({ event, total }: { event: HttpTimelineEvent; total: number }) {
+  const left = (100 * event.elapsed) / total;
+  const width = (100 * (event.duration ?? 0)) / total;
+  return (
+    <div className="text-xs font-mono">
+      <div className="grid grid-cols-[10rem_minmax(0,1fr)_4rem] gap-2 items-center">
+        <span className="text-info truncate">{LABELS[event.eventType]}</span>
+        <div className="relative h-2 rounded bg-surface-highlight">
+          <div
+            className={classNames(
+              'absolute inset-y-0 rounded bg-primary',
+              event.duration == null && 'w-0.5',
+            )}
+            style={{ left: `${left}%`, width: event.duration == null ? undefined : `${width}%` }}
+          />
+        </div>
+        <span className="text-text-subtle text-right">
+          {event.duration == null ? `@${event.elapsed} ms` : `${event.duration} ms`}
+        </span>
+      </div>
+      <div className="whitespace-pre-wrap break-all select-text cursor-text text-text-subtle">
+        {event.content}
+      </div>
+      {event.headers.length > 0 && (
+        <KeyValueRows>
+          {event.headers.map((h, i) => (
+            <KeyValueRow labelColor="primary" key={i} label={h.name} value={h.value} />
+          ))}
+        </KeyValueRows>
+      )}
+    </div>
+  );
+}
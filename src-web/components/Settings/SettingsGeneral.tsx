@@ -5,10 +5,19 @@ import { useCheckForUpdates } from '../../hooks/useCheckForUpdates';
 import { useSettings } from '../../hooks/useSettings';
 import { useUpdateSettings } from '../../hooks/useUpdateSettings';
 import { useUpdateWorkspace } from '../../hooks/useUpdateWorkspace';
+import {
+  BODY_TYPE_FORM_MULTIPART,
+  BODY_TYPE_FORM_URLENCODED,
+  BODY_TYPE_GRAPHQL,
+  BODY_TYPE_JSON,
+  BODY_TYPE_OTHER,
+  BODY_TYPE_XML,
+} from '../../lib/model_util';
 import { Checkbox } from '../core/Checkbox';
 import { Heading } from '../core/Heading';
 import { IconButton } from '../core/IconButton';
 import { KeyValueRow, KeyValueRows } from '../core/KeyValueRow';
+import { PairOrBulkEditor } from '../core/PairOrBulkEditor';
 import { PlainInput } from '../core/PlainInput';
 import { Select } from '../core/Select';
 import { Separator } from '../core/Separator';
@@ -76,6 +85,13 @@ export function SettingsGeneral() {
         ]}
       />
 
+      <Checkbox
+        className="mt-3"
+        checked={settings.restoreLastLocation}
+        title="Reopen Last Request on Launch"
+        onChange={(restoreLastLocation) => updateSettings.mutate({ restoreLastLocation })}
+      />
+
       <Checkbox
         className="mt-3"
         checked={settings.telemetry}
@@ -83,6 +99,29 @@ export function SettingsGeneral() {
         onChange={(telemetry) => updateSettings.mutate({ telemetry })}
       />
 
+      <PlainInput
+        size="sm"
+        name="responseBodyDir"
+        label="Response Storage Directory"
+        placeholder="App data directory"
+        labelPosition="left"
+        labelClassName="w-[12rem]"
+        defaultValue={settings.responseBodyDir ?? ''}
+        onChange={(v) => updateSettings.mutate({ responseBodyDir: v.trim() || null })}
+      />
+
+      <PlainInput
+        size="sm"
+        name="pluginTimeout"
+        label="Plugin Timeout (ms)"
+        placeholder="0"
+        labelPosition="left"
+        labelClassName="w-[12rem]"
+        defaultValue={`${settings.pluginTimeout}`}
+        validate={(value) => parseInt(value) >= 0}
+        onChange={(v) => updateSettings.mutate({ pluginTimeout: parseInt(v) || 0 })}
+      />
+
       <Separator className="my-4" />
 
       <Heading size={2}>
@@ -104,6 +143,159 @@ export function SettingsGeneral() {
           type="number"
         />
 
+        <PlainInput
+          size="sm"
+          name="retryCount"
+          label="Retries"
+          placeholder="0"
+          labelPosition="left"
+          defaultValue={`${workspace.settingRetryCount}`}
+          validate={(value) => parseInt(value) >= 0}
+          onChange={(v) => updateWorkspace.mutate({ settingRetryCount: parseInt(v) || 0 })}
+          type="number"
+        />
+
+        <PlainInput
+          size="sm"
+          name="retryBackoff"
+          label="Retry Backoff (ms)"
+          placeholder="0"
+          labelPosition="left"
+          defaultValue={`${workspace.settingRetryBackoff}`}
+          validate={(value) => parseInt(value) >= 0}
+          onChange={(v) => updateWorkspace.mutate({ settingRetryBackoff: parseInt(v) || 0 })}
+          type="number"
+        />
+
+        <PlainInput
+          size="sm"
+          name="slowResponseThreshold"
+          label="Slow Response Warning (ms)"
+          placeholder="0"
+          labelPosition="left"
+          defaultValue={`${workspace.settingSlowResponseThreshold}`}
+          validate={(value) => parseInt(value) >= 0}
+          onChange={(v) =>
+            updateWorkspace.mutate({ settingSlowResponseThreshold: parseInt(v) || 0 })
+          }
+          type="number"
+        />
+
+        <PlainInput
+          size="sm"
+          name="userAgent"
+          label="User-Agent"
+          placeholder="yaak"
+          labelPosition="left"
+          defaultValue={workspace.settingUserAgent}
+          onChange={(settingUserAgent) => updateWorkspace.mutate({ settingUserAgent })}
+        />
+
+        <PlainInput
+          size="sm"
+          name="grpcMaxSendMessageSize"
+          label="gRPC Max Send Size (bytes)"
+          placeholder="33554432"
+          labelPosition="left"
+          defaultValue={`${workspace.settingGrpcMaxSendMessageSize || ''}`}
+          validate={(value) => value === '' || parseInt(value) >= 0}
+          onChange={(v) =>
+            updateWorkspace.mutate({ settingGrpcMaxSendMessageSize: parseInt(v) || 0 })
+          }
+          type="number"
+        />
+
+        <PlainInput
+          size="sm"
+          name="grpcMaxReceiveMessageSize"
+          label="gRPC Max Receive Size (bytes)"
+          placeholder="33554432"
+          labelPosition="left"
+          defaultValue={`${workspace.settingGrpcMaxReceiveMessageSize || ''}`}
+          validate={(value) => value === '' || parseInt(value) >= 0}
+          onChange={(v) =>
+            updateWorkspace.mutate({ settingGrpcMaxReceiveMessageSize: parseInt(v) || 0 })
+          }
+          type="number"
+        />
+
+        <Select
+          size="sm"
+          name="httpVersion"
+          label="HTTP Version"
+          labelPosition="left"
+          value={workspace.settingHttpVersion}
+          onChange={(settingHttpVersion) => updateWorkspace.mutate({ settingHttpVersion })}
+          options={[
+            { label: 'Auto', value: 'auto' },
+            { label: 'HTTP/1.1', value: 'http1' },
+            { label: 'HTTP/2', value: 'http2' },
+            { label: 'HTTP/2 (Prior Knowledge)', value: 'http2-prior-knowledge' },
+          ]}
+        />
+
+        <Select
+          size="sm"
+          name="tlsMinVersion"
+          label="Minimum TLS Version"
+          labelPosition="left"
+          value={workspace.settingTlsMinVersion ?? 'default'}
+          onChange={(v) =>
+            updateWorkspace.mutate({ settingTlsMinVersion: v === 'default' ? null : v })
+          }
+          options={[
+            { label: 'Default', value: 'default' },
+            { label: 'TLS 1.2', value: '1.2' },
+            { label: 'TLS 1.3', value: '1.3' },
+          ]}
+        />
+
+        <Select
+          size="sm"
+          name="tlsMaxVersion"
+          label="Maximum TLS Version"
+          labelPosition="left"
+          value={workspace.settingTlsMaxVersion ?? 'default'}
+          onChange={(v) =>
+            updateWorkspace.mutate({ settingTlsMaxVersion: v === 'default' ? null : v })
+          }
+          options={[
+            { label: 'Default', value: 'default' },
+            { label: 'TLS 1.2', value: '1.2' },
+            { label: 'TLS 1.3', value: '1.3' },
+          ]}
+        />
+
+        <Select
+          size="sm"
+          name="defaultBodyType"
+          label="Default Body Type"
+          labelPosition="left"
+          value={workspace.settingDefaultBodyType ?? 'none'}
+          onChange={(v) =>
+            updateWorkspace.mutate({ settingDefaultBodyType: v === 'none' ? null : v })
+          }
+          options={[
+            { label: 'No Body', value: 'none' },
+            { label: 'JSON', value: BODY_TYPE_JSON },
+            { label: 'GraphQL', value: BODY_TYPE_GRAPHQL },
+            { label: 'XML', value: BODY_TYPE_XML },
+            { label: 'Url Encoded', value: BODY_TYPE_FORM_URLENCODED },
+            { label: 'Multi-Part', value: BODY_TYPE_FORM_MULTIPART },
+            { label: 'Other', value: BODY_TYPE_OTHER },
+          ]}
+        />
+
+        <PlainInput
+          size="sm"
+          name="defaultMethod"
+          label="Default Method"
+          placeholder="GET"
+          labelPosition="left"
+          defaultValue={workspace.settingDefaultMethod}
+          onChange={(settingDefaultMethod) => updateWorkspace.mutate({ settingDefaultMethod })}
+        />
+
         <Checkbox
           checked={workspace.settingValidateCertificates}
           title="Validate TLS Certificates"
@@ -112,11 +304,45 @@ export function SettingsGeneral() {
           }
         />
 
+        <Checkbox
+          checked={workspace.settingSendDefaultAccept}
+          title="Send Default Accept Header"
+          onChange={(settingSendDefaultAccept) =>
+            updateWorkspace.mutate({ settingSendDefaultAccept })
+          }
+        />
+
         <Checkbox
           checked={workspace.settingFollowRedirects}
           title="Follow Redirects"
           onChange={(settingFollowRedirects) => updateWorkspace.mutate({ settingFollowRedirects })}
         />
+
+        <Checkbox
+          checked={workspace.settingHttpCache}
+          title="Cache GET Responses"
+          onChange={(settingHttpCache) => updateWorkspace.mutate({ settingHttpCache })}
+        />
+
+        <Checkbox
+          checked={workspace.settingGrpcCaptureRawMessages}
+          title="Capture Raw gRPC Message Bytes"
+          onChange={(settingGrpcCaptureRawMessages) =>
+            updateWorkspace.mutate({ settingGrpcCaptureRawMessages })
+          }
+        />
+
+        <Heading size={3}>Default gRPC Metadata</Heading>
+        <div className="h-[10rem]">
+          <PairOrBulkEditor
+            preferenceName="workspace_grpc_metadata"
+            valueAutocompleteVariables
+            nameAutocompleteVariables
+            pairs={workspace.grpcMetadata}
+            onChange={(grpcMetadata) => updateWorkspace.mutate({ grpcMetadata })}
+            forceUpdateKey={workspace.id}
+          />
+        </div>
       </VStack>
 
       <Separator className="my-4" />
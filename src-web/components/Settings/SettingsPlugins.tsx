@@ -1,10 +1,14 @@
 import type { Plugin } from '@yaakapp-internal/models';
+import type { PluginStatus } from '@yaakapp-internal/plugin';
 import { open } from '@tauri-apps/plugin-shell';
 import React from 'react';
 import { useInstallPlugin } from '../../hooks/useInstallPlugin';
 import { useUninstallPlugin } from '../../hooks/useUninstallPlugin';
 import { usePluginInfo } from '../../hooks/usePluginInfo';
+import { usePluginStatuses } from '../../hooks/usePluginStatuses';
 import { usePlugins, useRefreshPlugins } from '../../hooks/usePlugins';
+import { useReloadPlugin } from '../../hooks/useReloadPlugin';
+import { Banner } from '../core/Banner';
 import { Button } from '../core/Button';
 import { IconButton } from '../core/IconButton';
 import { InlineCode } from '../core/InlineCode';
@@ -17,32 +21,49 @@ export function SettingsPlugins() {
   const plugins = usePlugins();
   const createPlugin = useInstallPlugin();
   const refreshPlugins = useRefreshPlugins();
+  const statuses = usePluginStatuses();
+  const installedDirs = plugins.map((p) => p.directory);
+  // Bundled plugins have no row of their own, so show their errors separately
+  const otherFailures = (statuses.data ?? []).filter(
+    (s) => s.error != null && !installedDirs.includes(s.dir),
+  );
   return (
     <div className="grid grid-rows-[minmax(0,1fr)_auto] h-full">
-      {plugins.length === 0 ? (
-        <div className="pb-4">
-          <EmptyStateText className="text-center">
-            Plugins extend the functionality of Yaak.
-            <br />
-            Add your first plugin to get started.
-          </EmptyStateText>
-        </div>
-      ) : (
-        <table className="w-full text-sm mb-auto min-w-full max-w-full divide-y divide-surface-highlight">
-          <thead>
-            <tr>
-              <th className="py-2 text-left">Plugin</th>
-              <th className="py-2 text-right">Version</th>
-              <th></th>
-            </tr>
-          </thead>
-          <tbody className="divide-y divide-surface-highlight">
-            {plugins.map((p) => (
-              <PluginInfo key={p.id} plugin={p} />
-            ))}
-          </tbody>
-        </table>
-      )}
+      <div>
+        {plugins.length === 0 ? (
+          <div className="pb-4">
+            <EmptyStateText className="text-center">
+              Plugins extend the functionality of Yaak.
+              <br />
+              Add your first plugin to get started.
+            </EmptyStateText>
+          </div>
+        ) : (
+          <table className="w-full text-sm mb-auto min-w-full max-w-full divide-y divide-surface-highlight">
+            <thead>
+              <tr>
+                <th className="py-2 text-left">Plugin</th>
+                <th className="py-2 text-right">Version</th>
+                <th></th>
+              </tr>
+            </thead>
+            <tbody className="divide-y divide-surface-highlight">
+              {plugins.map((p) => (
+                <PluginInfo
+                  key={p.id}
+                  plugin={p}
+                  status={statuses.data?.find((s) => s.dir === p.directory) ?? null}
+                />
+              ))}
+            </tbody>
+          </table>
+        )}
+        {otherFailures.map((s) => (
+          <Banner key={s.dir} color="danger" className="mb-2">
+            Failed to load plugin <InlineCode>{s.dir}</InlineCode>: {s.error}
+          </Banner>
+        ))}
+      </div>
       <form
         onSubmit={(e) => {
           e.preventDefault();
@@ -85,23 +106,37 @@ export function SettingsPlugins() {
   );
 }
 
-function PluginInfo({ plugin }: { plugin: Plugin }) {
+function PluginInfo({ plugin, status }: { plugin: Plugin; status: PluginStatus | null }) {
   const pluginInfo = usePluginInfo(plugin.id);
   const deletePlugin = useUninstallPlugin(plugin.id);
+  const reloadPlugin = useReloadPlugin(plugin.id);
   return (
     <tr className="group">
-      <td className="py-2 select-text cursor-text w-full">{pluginInfo.data?.name}</td>
+      <td className="py-2 select-text cursor-text w-full">
+        {pluginInfo.data?.name || plugin.directory}
+        {status?.error != null && <div className="text-danger text-xs">{status.error}</div>}
+      </td>
       <td className="py-2 select-text cursor-text text-right">
         <InlineCode>{pluginInfo.data?.version}</InlineCode>
       </td>
       <td className="py-2 select-text cursor-text pl-2">
-        <IconButton
-          size="sm"
-          icon="trash"
-          title="Uninstall plugin"
-          className="text-text-subtlest"
-          onClick={() => deletePlugin.mutate()}
-        />
+        <HStack>
+          <IconButton
+            size="sm"
+            icon="refresh"
+            title="Reload plugin"
+            className="text-text-subtlest"
+            spin={reloadPlugin.isPending}
+            onClick={() => reloadPlugin.mutate()}
+          />
+          <IconButton
+            size="sm"
+            icon="trash"
+            title="Uninstall plugin"
+            className="text-text-subtlest"
+            onClick={() => deletePlugin.mutate()}
+          />
+        </HStack>
       </td>
     </tr>
   );
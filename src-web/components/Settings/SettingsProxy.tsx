@@ -1,118 +1,42 @@
 import React from 'react';
+import { useActiveWorkspace } from '../../hooks/useActiveWorkspace';
 import { useSettings } from '../../hooks/useSettings';
 import { useUpdateSettings } from '../../hooks/useUpdateSettings';
-import { Checkbox } from '../core/Checkbox';
-import { PlainInput } from '../core/PlainInput';
-import { Select } from '../core/Select';
+import { useUpdateWorkspace } from '../../hooks/useUpdateWorkspace';
+import { Heading } from '../core/Heading';
 import { Separator } from '../core/Separator';
-import { HStack, VStack } from '../core/Stacks';
+import { VStack } from '../core/Stacks';
+import { ProxySettingEditor } from '../ProxySettingEditor';
 
 export function SettingsProxy() {
   const settings = useSettings();
   const updateSettings = useUpdateSettings();
+  const workspace = useActiveWorkspace();
+  const updateWorkspace = useUpdateWorkspace(workspace?.id ?? null);
 
   return (
     <VStack space={1.5} className="mb-4">
-      <Select
+      <ProxySettingEditor
         name="proxy"
-        label="Proxy"
-        hideLabel
-        size="sm"
-        value={settings.proxy?.type ?? 'automatic'}
-        onChange={(v) => {
-          if (v === 'automatic') {
-            updateSettings.mutate({ proxy: undefined });
-          } else if (v === 'enabled') {
-            updateSettings.mutate({
-              proxy: {
-                type: 'enabled',
-                http: '',
-                https: '',
-                auth: { user: '', password: '' },
-              },
-            });
-          } else {
-            updateSettings.mutate({ proxy: { type: 'disabled' } });
-          }
-        }}
-        options={[
-          { label: 'Automatic Proxy Detection', value: 'automatic' },
-          { label: 'Custom Proxy Configuration', value: 'enabled' },
-          { label: 'No Proxy', value: 'disabled' },
-        ]}
+        proxy={settings.proxy}
+        onChange={(proxy) => updateSettings.mutate({ proxy })}
       />
-      {settings.proxy?.type === 'enabled' && (
-        <VStack space={1.5}>
-          <HStack space={1.5} className="mt-3">
-            <PlainInput
-              size="sm"
-              label="HTTP"
-              placeholder="localhost:9090"
-              defaultValue={settings.proxy?.http}
-              onChange={(http) => {
-                const https = settings.proxy?.type === 'enabled' ? settings.proxy.https : '';
-                const auth = settings.proxy?.type === 'enabled' ? settings.proxy.auth : null;
-                updateSettings.mutate({ proxy: { type: 'enabled', http, https, auth } });
-              }}
-            />
-            <PlainInput
-              size="sm"
-              label="HTTPS"
-              placeholder="localhost:9090"
-              defaultValue={settings.proxy?.https}
-              onChange={(https) => {
-                const http = settings.proxy?.type === 'enabled' ? settings.proxy.http : '';
-                const auth = settings.proxy?.type === 'enabled' ? settings.proxy.auth : null;
-                updateSettings.mutate({ proxy: { type: 'enabled', http, https, auth } });
-              }}
-            />
-          </HStack>
-          <Separator className="my-6"/>
-          <Checkbox
-            checked={settings.proxy.auth != null}
-            title="Enable authentication"
-            onChange={(enabled) => {
-              const http = settings.proxy?.type === 'enabled' ? settings.proxy.http : '';
-              const https = settings.proxy?.type === 'enabled' ? settings.proxy.https : '';
-              const auth = enabled ? { user: '', password: '' } : null;
-              updateSettings.mutate({ proxy: { type: 'enabled', http, https, auth } });
-            }}
+      {workspace != null && (
+        <>
+          <Separator className="my-6" />
+          <Heading size={2}>
+            Workspace{' '}
+            <div className="inline-block ml-1 bg-surface-highlight px-2 py-0.5 rounded text text-shrink">
+              {workspace.name}
+            </div>
+          </Heading>
+          <ProxySettingEditor
+            name="workspaceProxy"
+            inheritLabel="Use Global Proxy Setting"
+            proxy={workspace.settingProxy}
+            onChange={(settingProxy) => updateWorkspace.mutate({ settingProxy })}
           />
-
-          {settings.proxy.auth != null && (
-            <HStack space={1.5}>
-              <PlainInput
-                size="sm"
-                label="User"
-                placeholder="myUser"
-                defaultValue={settings.proxy.auth.user}
-                onChange={(user) => {
-                  const https = settings.proxy?.type === 'enabled' ? settings.proxy.https : '';
-                  const http = settings.proxy?.type === 'enabled' ? settings.proxy.http : '';
-                  const password =
-                    settings.proxy?.type === 'enabled' ? (settings.proxy.auth?.password ?? '') : '';
-                  const auth = { user, password };
-                  updateSettings.mutate({ proxy: { type: 'enabled', http, https, auth } });
-                }}
-              />
-              <PlainInput
-                size="sm"
-                label="Password"
-                type="password"
-                placeholder="s3cretPassw0rd"
-                defaultValue={settings.proxy.auth.password}
-                onChange={(password) => {
-                  const https = settings.proxy?.type === 'enabled' ? settings.proxy.https : '';
-                  const http = settings.proxy?.type === 'enabled' ? settings.proxy.http : '';
-                  const user =
-                    settings.proxy?.type === 'enabled' ? (settings.proxy.auth?.user ?? '') : '';
-                  const auth = { user, password };
-                  updateSettings.mutate({ proxy: { type: 'enabled', http, https, auth } });
-                }}
-              />
-            </HStack>
-          )}
-        </VStack>
+        </>
       )}
     </VStack>
   );
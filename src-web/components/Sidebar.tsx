@@ -31,12 +31,14 @@ import type { CallableHttpRequestAction } from '../hooks/useHttpRequestActions';
 import { useHttpRequestActions } from '../hooks/useHttpRequestActions';
 import { useHttpResponses } from '../hooks/useHttpResponses';
 import { useKeyValue } from '../hooks/useKeyValue';
+import { useLoadTestDialog } from '../hooks/useLoadTestDialog';
 import { useMoveToWorkspace } from '../hooks/useMoveToWorkspace';
 import { usePrompt } from '../hooks/usePrompt';
 import { useRenameRequest } from '../hooks/useRenameRequest';
 import { useRequests } from '../hooks/useRequests';
 import { useScrollIntoView } from '../hooks/useScrollIntoView';
 import { useSendAnyHttpRequest } from '../hooks/useSendAnyHttpRequest';
+import { useRunFolder } from '../hooks/useRunFolder';
 import { useSendManyRequests } from '../hooks/useSendManyRequests';
 import { useSidebarHidden } from '../hooks/useSidebarHidden';
 import { useUpdateAnyFolder } from '../hooks/useUpdateAnyFolder';
@@ -700,7 +702,9 @@ function SidebarItem({
   const duplicateGrpcRequest = useDuplicateGrpcRequest({ id: itemId, navigateAfter: true });
   const sendRequest = useSendAnyHttpRequest();
   const moveToWorkspace = useMoveToWorkspace(itemId);
+  const loadTestDialog = useLoadTestDialog(itemId);
   const sendManyRequests = useSendManyRequests();
+  const runFolder = useRunFolder(itemId);
   const updateHttpRequest = useUpdateAnyHttpRequest();
   const workspaces = useWorkspaces();
   const updateGrpcRequest = useUpdateAnyGrpcRequest();
@@ -787,7 +791,22 @@ function SidebarItem({
           key: 'sendAll',
           label: 'Send All',
           leftSlot: <Icon icon="send_horizontal" />,
-          onSelect: () => sendManyRequests.mutate(child.children.map((c) => c.item.id)),
+          onSelect: () =>
+            sendManyRequests.mutate(
+              child.children.filter((c) => c.item.model === 'http_request').map((c) => c.item.id),
+            ),
+        },
+        {
+          key: 'runFolder',
+          label: 'Run in Order',
+          leftSlot: <Icon icon="play" />,
+          onSelect: () => runFolder.mutate({ stopOnFailure: false }),
+        },
+        {
+          key: 'runFolderUntilFailure',
+          label: 'Run Until Failure',
+          leftSlot: <Icon icon="play" />,
+          onSelect: () => runFolder.mutate({ stopOnFailure: true }),
         },
         {
           key: 'rename',
@@ -833,6 +852,12 @@ function SidebarItem({
                 leftSlot: <Icon icon="send_horizontal" />,
                 onSelect: () => sendRequest.mutate(itemId),
               },
+              {
+                key: 'loadTest',
+                label: 'Load Test',
+                leftSlot: <Icon icon="flask" />,
+                onSelect: loadTestDialog,
+              },
               ...httpRequestActions.map((a) => ({
                 key: a.key,
                 label: a.label,
@@ -865,6 +890,13 @@ function SidebarItem({
               ? duplicateHttpRequest.mutate()
               : duplicateGrpcRequest.mutate(),
         },
+        {
+          key: 'duplicateRequestWithResponses',
+          label: 'Duplicate with Responses',
+          hidden: itemModel !== 'http_request',
+          leftSlot: <Icon icon="copy" />,
+          onSelect: () => duplicateHttpRequest.mutate({ responseCount: 5 }),
+        },
         {
           key: 'moveWorkspace',
           label: 'Move',
@@ -892,9 +924,11 @@ function SidebarItem({
     itemId,
     itemModel,
     itemName,
+    loadTestDialog,
     moveToWorkspace.mutate,
     prompt,
     renameRequest.mutate,
+    runFolder,
     sendManyRequests,
     sendRequest,
     updateAnyFolder,
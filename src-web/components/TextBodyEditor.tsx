@@ -0,0 +1,65 @@
+import type { HttpRequest } from '@yaakapp-internal/models';
+import type { EditorProps } from './core/Editor';
+import { Editor } from './core/Editor';
+import { Select } from './core/Select';
+import { VStack } from './core/Stacks';
+import { SelectFile } from './SelectFile';
+
+type Source = 'inline' | 'file';
+
+type Props = Pick<EditorProps, 'language' | 'format'> & {
+  forceUpdateKey: string;
+  heightMode: 'full' | 'auto';
+  body: HttpRequest['body'];
+  onChange: (body: HttpRequest['body']) => void;
+};
+
+export function TextBodyEditor({
+  forceUpdateKey,
+  heightMode,
+  body,
+  language,
+  format,
+  onChange,
+}: Props) {
+  // Kept apart from the binary body's filePath, so switching body types doesn't mix them up
+  const filePath = typeof body.textFilePath === 'string' ? body.textFilePath : null;
+  const source: Source = filePath == null ? 'inline' : 'file';
+
+  return (
+    <VStack space={2} className="h-full">
+      <Select
+        name="textBodySource"
+        label="Source"
+        labelPosition="left"
+        size="sm"
+        value={source}
+        onChange={(source) =>
+          onChange({ ...body, textFilePath: source === 'file' ? (filePath ?? '') : undefined })
+        }
+        options={[
+          { label: 'Inline', value: 'inline' },
+          { label: 'File (rendered when sent)', value: 'file' },
+        ]}
+      />
+      {source === 'file' ? (
+        <SelectFile
+          filePath={filePath || null}
+          onChange={({ filePath }) => onChange({ ...body, textFilePath: filePath ?? '' })}
+        />
+      ) : (
+        <Editor
+          forceUpdateKey={forceUpdateKey}
+          useTemplating
+          autocompleteVariables
+          placeholder="..."
+          heightMode={heightMode}
+          defaultValue={`${body.text ?? ''}`}
+          language={language}
+          format={format}
+          onChange={(text) => onChange({ ...body, text })}
+        />
+      )}
+    </VStack>
+  );
+}
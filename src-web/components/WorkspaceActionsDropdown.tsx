@@ -4,6 +4,11 @@ import { useActiveWorkspace } from '../hooks/useActiveWorkspace';
 import { useCreateWorkspace } from '../hooks/useCreateWorkspace';
 import { useDeleteSendHistory } from '../hooks/useDeleteSendHistory';
 import { useDeleteWorkspace } from '../hooks/useDeleteWorkspace';
+import {
+  useMockServerPort,
+  useStartMockServer,
+  useStopMockServer,
+} from '../hooks/useMockServer';
 import { useOpenWorkspace } from '../hooks/useOpenWorkspace';
 import { usePrompt } from '../hooks/usePrompt';
 import { useSettings } from '../hooks/useSettings';
@@ -38,6 +43,9 @@ export const WorkspaceActionsDropdown = memo(function WorkspaceActionsDropdown({
   const openWorkspace = useOpenWorkspace();
   const openWorkspaceNewWindow = settings?.openWorkspaceNewWindow ?? null;
   const deleteSendHistory = useDeleteSendHistory();
+  const mockServerPort = useMockServerPort();
+  const startMockServer = useStartMockServer();
+  const stopMockServer = useStopMockServer();
 
   const { workspaceItems, extraItems } = useMemo<{
     workspaceItems: RadioDropdownItem[];
@@ -78,6 +86,19 @@ export const WorkspaceActionsDropdown = memo(function WorkspaceActionsDropdown({
         leftSlot: <Icon icon="history" />,
         onSelect: deleteSendHistory.mutate,
       },
+      mockServerPort == null
+        ? {
+            key: 'start-mock-server',
+            label: 'Start Mock Server',
+            leftSlot: <Icon icon="flask" />,
+            onSelect: startMockServer.mutate,
+          }
+        : {
+            key: 'stop-mock-server',
+            label: `Stop Mock Server (:${mockServerPort})`,
+            leftSlot: <Icon icon="flask" />,
+            onSelect: stopMockServer.mutate,
+          },
       {
         key: 'delete',
         label: 'Delete Workspace',
@@ -101,7 +122,10 @@ export const WorkspaceActionsDropdown = memo(function WorkspaceActionsDropdown({
     createWorkspace.mutate,
     deleteSendHistory.mutate,
     deleteWorkspace.mutate,
+    mockServerPort,
     prompt,
+    startMockServer.mutate,
+    stopMockServer.mutate,
     updateWorkspace,
     workspaces,
   ]);
@@ -20,13 +20,15 @@ export function BulkPairEditor({
 
   const handleChange = useCallback(
     (text: string) => {
-      const pairs = text
+      const newPairs = text
         .split('\n')
         .filter((l: string) => l.trim())
-        .map(lineToPair);
-      onChange(pairs);
+        .map(lineToPair)
+        // Keep secret flags, which the text can't hold, by matching on name
+        .map((p) => ({ ...p, secret: pairs.find((old) => old.name === p.name)?.secret }));
+      onChange(newPairs);
     },
-    [onChange],
+    [onChange, pairs],
   );
 
   return (
@@ -1,13 +1,19 @@
+import classNames from 'classnames';
+
 interface Props {
   total: number;
   headers: number;
+  slow?: boolean;
 }
 
-export function DurationTag({ total, headers }: Props) {
+export function DurationTag({ total, headers, slow }: Props) {
   return (
     <span
-      className="font-mono"
-      title={`HEADER: ${formatMillis(headers)}\nTOTAL: ${formatMillis(total)}`}
+      className={classNames('font-mono', slow && 'text-warning')}
+      title={
+        `HEADER: ${formatMillis(headers)}\nTOTAL: ${formatMillis(total)}` +
+        (slow ? '\nSlower than the workspace threshold' : '')
+      }
     >
       {formatMillis(total)}
     </span>
@@ -54,13 +54,16 @@ const icons = {
   keyboard: lucide.KeyboardIcon,
   left_panel_hidden: lucide.PanelLeftOpenIcon,
   left_panel_visible: lucide.PanelLeftCloseIcon,
+  lock: lucide.LockIcon,
   magic_wand: lucide.Wand2Icon,
   minus: lucide.MinusIcon,
   moon: lucide.MoonIcon,
   more_vertical: lucide.MoreVerticalIcon,
+  palette: lucide.PaletteIcon,
   paste: lucide.ClipboardPasteIcon,
   pencil: lucide.PencilIcon,
   pin: lucide.PinIcon,
+  play: lucide.PlayIcon,
   plug: lucide.Plug,
   plus: lucide.PlusIcon,
   plus_circle: lucide.PlusCircleIcon,
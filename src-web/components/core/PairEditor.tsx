@@ -43,6 +43,8 @@ export type PairEditorProps = {
   nameAutocompleteVariables?: boolean;
   valueAutocompleteVariables?: boolean;
   allowFileValues?: boolean;
+  /** Let values be marked secret, which always hides them */
+  allowSecretValues?: boolean;
   nameValidate?: InputProps['validate'];
   valueValidate?: InputProps['validate'];
   noScroll?: boolean;
@@ -55,6 +57,7 @@ export type Pair = {
   value: string;
   contentType?: string;
   isFile?: boolean;
+  secret?: boolean;
   readOnlyName?: boolean;
 };
 
@@ -80,6 +83,7 @@ export const PairEditor = forwardRef<PairEditorRef, PairEditorProps>(function Pa
     valuePlaceholder,
     valueValidate,
     allowFileValues,
+    allowSecretValues,
   }: PairEditorProps,
   ref,
 ) {
@@ -215,6 +219,7 @@ export const PairEditor = forwardRef<PairEditorRef, PairEditorProps>(function Pa
               className="py-1"
               isLast={isLast}
               allowFileValues={allowFileValues}
+              allowSecretValues={allowSecretValues}
               nameAutocompleteVariables={nameAutocompleteVariables}
               valueAutocompleteVariables={valueAutocompleteVariables}
               valueType={valueType}
@@ -271,10 +276,12 @@ type PairEditorRowProps = {
   | 'valueValidate'
   | 'forceUpdateKey'
   | 'allowFileValues'
+  | 'allowSecretValues'
 >;
 
 function PairEditorRow({
   allowFileValues,
+  allowSecretValues,
   className,
   forceFocusNamePairId,
   forceFocusValuePairId,
@@ -341,6 +348,11 @@ function PairEditorRow({
     [onChange, id, pairContainer.pair],
   );
 
+  const handleChangeSecret = useMemo(
+    () => (secret: boolean) => onChange({ id, pair: { ...pairContainer.pair, secret } }),
+    [onChange, id, pairContainer.pair],
+  );
+
   const handleChangeValueContentType = useMemo(
     () => (contentType: string) => onChange({ id, pair: { ...pairContainer.pair, contentType } }),
     [onChange, id, pairContainer.pair],
@@ -486,7 +498,7 @@ function PairEditorRow({
               name={`value[${index}]`}
               onChange={handleChangeValueText}
               onFocus={handleFocus}
-              type={isLast ? 'text' : valueType}
+              type={isLast ? 'text' : pairContainer.pair.secret ? 'password' : valueType}
               placeholder={valuePlaceholder ?? 'value'}
               autocomplete={valueAutocomplete?.(pairContainer.pair.name)}
               autocompleteVariables={valueAutocompleteVariables}
@@ -554,6 +566,13 @@ function PairEditorRow({
       ) : (
         <Dropdown
           items={[
+            {
+              key: 'secret',
+              label: pairContainer.pair.secret ? 'Unmark as Secret' : 'Mark as Secret',
+              leftSlot: <Icon icon="lock" />,
+              hidden: !allowSecretValues,
+              onSelect: () => handleChangeSecret(!pairContainer.pair.secret),
+            },
             {
               key: 'delete',
               label: 'Delete',
@@ -1,11 +1,10 @@
 import { useVirtualizer } from '@tanstack/react-virtual';
-import type { HttpResponse } from '@yaakapp-internal/models';
-import type { ServerSentEvent } from '@yaakapp-internal/sse';
+import type { HttpResponse, HttpResponseEvent } from '@yaakapp-internal/models';
 import classNames from 'classnames';
 import { motion } from 'framer-motion';
 import React, { Fragment, useMemo, useRef, useState } from 'react';
 import { useFormatText } from '../../hooks/useFormatText';
-import { useResponseBodyEventSource } from '../../hooks/useResponseBodyEventSource';
+import { useHttpResponseEvents } from '../../hooks/useHttpResponseEvents';
 import { isJSON } from '../../lib/contentType';
 import { Button } from '../core/Button';
 import type { EditorProps } from '../core/Editor';
@@ -34,9 +33,9 @@ function ActualEventStreamViewer({ response }: Props) {
   const [showLarge, setShowLarge] = useState<boolean>(false);
   const [showingLarge, setShowingLarge] = useState<boolean>(false);
   const [activeEventIndex, setActiveEventIndex] = useState<number | null>(null);
-  const events = useResponseBodyEventSource(response);
+  const events = useHttpResponseEvents(response.id);
   const activeEvent = useMemo(
-    () => (activeEventIndex == null ? null : events.data?.[activeEventIndex]),
+    () => (activeEventIndex == null ? null : events[activeEventIndex]),
     [activeEventIndex, events],
   );
 
@@ -53,7 +52,7 @@ function ActualEventStreamViewer({ response }: Props) {
       minHeightPx={20}
       firstSlot={() => (
         <EventStreamEventsVirtual
-          events={events.data ?? []}
+          events={events}
           activeEventIndex={activeEventIndex}
           setActiveEventIndex={setActiveEventIndex}
         />
@@ -118,7 +117,7 @@ function EventStreamEventsVirtual({
   activeEventIndex,
   setActiveEventIndex,
 }: {
-  events: ServerSentEvent[];
+  events: HttpResponseEvent[];
   activeEventIndex: number | null;
   setActiveEventIndex: (eventId: number | null) => void;
 }) {
@@ -181,7 +180,7 @@ function EventStreamEvent({
 }: {
   onClick: () => void;
   isActive: boolean;
-  event: ServerSentEvent;
+  event: HttpResponseEvent;
   className?: string;
   index: number;
 }) {
@@ -211,7 +210,7 @@ function EventLabels({
   index,
   isActive,
 }: {
-  event: ServerSentEvent;
+  event: HttpResponseEvent;
   index: number;
   className: string;
   isActive?: boolean;
@@ -219,7 +218,7 @@ function EventLabels({
   return (
     <HStack space={1.5} alignItems="center" className={className}>
       <InlineCode className={classNames('py-0', isActive && 'bg-text-subtlest text-text')}>
-        {event.id ?? index}
+        {event.eventId ?? index}
       </InlineCode>
       {event.eventType && (
         <InlineCode className={classNames('py-0', isActive && 'bg-text-subtlest text-text')}>
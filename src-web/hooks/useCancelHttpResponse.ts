@@ -1,11 +1,11 @@
 import { useMutation } from '@tanstack/react-query';
-import { event } from '@tauri-apps/api';
 import { trackEvent } from '../lib/analytics';
+import { invokeCmd } from '../lib/tauri';
 
 export function useCancelHttpResponse(id: string | null) {
-  return useMutation<void>({
+  return useMutation<boolean>({
     mutationKey: ['cancel_http_response', id],
-    mutationFn: () => event.emit(`cancel_http_response_${id}`),
+    mutationFn: () => invokeCmd('cmd_cancel_request', { responseId: id }),
     onSettled: () => trackEvent('http_response', 'cancel'),
   });
 }
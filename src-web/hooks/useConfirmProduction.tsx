@@ -0,0 +1,27 @@
+import type { Environment } from '@yaakapp-internal/models';
+import { useCallback } from 'react';
+import { InlineCode } from '../components/core/InlineCode';
+import { useConfirm } from './useConfirm';
+
+/** Ask before sending to a production environment. Resolves true when it's fine to send. */
+export function useConfirmProduction() {
+  const confirm = useConfirm();
+  return useCallback(
+    async (environment: Environment | null) => {
+      if (!environment?.isProduction) return true;
+      return confirm({
+        id: 'confirm-production',
+        title: 'Send to Production',
+        variant: 'delete',
+        confirmText: 'Send',
+        description: (
+          <>
+            <InlineCode>{environment.name}</InlineCode> is a production environment. Send anyway?
+          </>
+        ),
+      });
+    },
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+    [],
+  );
+}
@@ -0,0 +1,34 @@
+import { useMutation } from '@tanstack/react-query';
+import { useToast } from '../components/ToastContext';
+import { invokeCmd } from '../lib/tauri';
+import { useActiveEnvironment } from './useActiveEnvironment';
+import { useConfirmProduction } from './useConfirmProduction';
+import { useCopy } from './useCopy';
+
+export function useCopyAsCurl() {
+  const copy = useCopy();
+  const toast = useToast();
+  const [environment] = useActiveEnvironment();
+  const confirmProduction = useConfirmProduction();
+
+  return useMutation<string | null, string, string>({
+    mutationKey: ['export_curl'],
+    mutationFn: async (requestId) => {
+      if (!(await confirmProduction(environment))) {
+        return null;
+      }
+
+      return invokeCmd('cmd_export_curl', {
+        requestId,
+        environmentId: environment?.id,
+        confirmProduction: environment?.isProduction ?? false,
+      });
+    },
+    onSuccess: (command) => {
+      if (command != null) copy(command);
+    },
+    onError: (err) => {
+      toast.show({ message: `Failed to copy as curl: ${err}`, color: 'danger' });
+    },
+  });
+}
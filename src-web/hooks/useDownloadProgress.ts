@@ -0,0 +1,20 @@
+import { useState } from 'react';
+import { useListenToTauriEvent } from './useListenToTauriEvent';
+
+export interface DownloadProgress {
+  responseId: string;
+  bytesReceived: number;
+  totalBytes: number | null;
+}
+
+export function useDownloadProgress(requestId: string, responseId: string | null) {
+  const [progress, setProgress] = useState<DownloadProgress | null>(null);
+
+  useListenToTauriEvent<DownloadProgress>(
+    `http_request_download_progress_${requestId}`,
+    (event) => setProgress(event.payload),
+  );
+
+  // Ignore progress left over from an earlier send
+  return progress?.responseId === responseId ? progress : null;
+}
@@ -0,0 +1,24 @@
+import { useMutation } from '@tanstack/react-query';
+import type { Environment } from '@yaakapp-internal/models';
+import { useSetAtom } from 'jotai';
+import { trackEvent } from '../lib/analytics';
+import { invokeCmd } from '../lib/tauri';
+import { environmentsAtom } from './useEnvironments';
+import { updateModelList } from './useSyncModelStores';
+
+export function useDuplicateEnvironment(environment: Environment | null) {
+  const setEnvironments = useSetAtom(environmentsAtom);
+
+  return useMutation<Environment, string>({
+    mutationKey: ['duplicate_environment', environment?.id],
+    mutationFn: async () => {
+      if (environment == null) throw new Error("Can't duplicate a null environment");
+      return invokeCmd('cmd_duplicate_environment', { environmentId: environment.id });
+    },
+    onSettled: () => trackEvent('environment', 'duplicate'),
+    onSuccess: (environment) => {
+      // Optimistic update
+      setEnvironments(updateModelList(environment));
+    },
+  });
+}
@@ -6,7 +6,12 @@ import {useActiveCookieJar} from "./useActiveCookieJar";
 import { useActiveEnvironment } from './useActiveEnvironment';
 import { useActiveWorkspace } from './useActiveWorkspace';
 import { useAppRoutes } from './useAppRoutes';
-import { getGrpcProtoFiles, setGrpcProtoFiles } from './useGrpcProtoFiles';
+import {
+  getGrpcProtoFiles,
+  getGrpcProtoImportDirs,
+  setGrpcProtoFiles,
+  setGrpcProtoImportDirs,
+} from './useGrpcProtoFiles';
 
 export function useDuplicateGrpcRequest({
   id,
@@ -30,9 +35,11 @@ export function useDuplicateGrpcRequest({
     onSuccess: async (request) => {
       if (id == null) return;
 
-      // Also copy proto files to new request
+      // Also copy proto files and their import directories to new request
       const protoFiles = await getGrpcProtoFiles(id);
       await setGrpcProtoFiles(request.id, protoFiles);
+      const importDirs = await getGrpcProtoImportDirs(id);
+      await setGrpcProtoImportDirs(request.id, importDirs);
 
       if (navigateAfter && activeWorkspace !== null) {
         routes.navigate('request', {
@@ -18,11 +18,11 @@ export function useDuplicateHttpRequest({
   const [activeEnvironment] = useActiveEnvironment();
   const [activeCookieJar] = useActiveCookieJar();
   const routes = useAppRoutes();
-  return useMutation<HttpRequest, string>({
+  return useMutation<HttpRequest, string, { responseCount?: number } | void>({
     mutationKey: ['duplicate_http_request', id],
-    mutationFn: async () => {
+    mutationFn: async (args) => {
       if (id === null) throw new Error("Can't duplicate a null request");
-      return invokeCmd('cmd_duplicate_http_request', { id });
+      return invokeCmd('cmd_duplicate_http_request', { id, responseCount: args?.responseCount });
     },
     onSettled: () => trackEvent('http_request', 'duplicate'),
     onSuccess: async (request) => {
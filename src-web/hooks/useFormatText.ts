@@ -18,8 +18,10 @@ export function useFormatText({
         return text;
       } else if (language === 'json') {
         return tryFormatJson(text);
-      } else if (language === 'xml' || language === 'html') {
+      } else if (language === 'xml') {
         return tryFormatXml(text);
+      } else if (language === 'html') {
+        return tryFormatXml(text, 'text/html');
       } else {
         return text;
       }
@@ -6,7 +6,9 @@ import { minPromiseMillis } from '../lib/minPromiseMillis';
 import { isResponseLoading } from '../lib/model_util';
 import { invokeCmd } from '../lib/tauri';
 import { useActiveEnvironment } from './useActiveEnvironment';
+import { useConfirmProduction } from './useConfirmProduction';
 import { useDebouncedValue } from './useDebouncedValue';
+import { useGrpcProtoImportDirs } from './useGrpcProtoFiles';
 
 export interface ReflectResponseService {
   name: string;
@@ -20,11 +22,21 @@ export function useGrpc(
 ) {
   const requestId = req?.id ?? 'n/a';
   const [environment] = useActiveEnvironment();
+  const confirmProduction = useConfirmProduction();
+  const importDirs = useGrpcProtoImportDirs(req?.id ?? null).value ?? [];
 
   const go = useMutation<void, string>({
     mutationKey: ['grpc_go', conn?.id],
-    mutationFn: () =>
-      invokeCmd<void>('cmd_grpc_go', { requestId, environmentId: environment?.id, protoFiles }),
+    mutationFn: async () => {
+      if (!(await confirmProduction(environment))) return;
+      await invokeCmd<void>('cmd_grpc_go', {
+        requestId,
+        environmentId: environment?.id,
+        confirmProduction: environment?.isProduction ?? false,
+        protoFiles,
+        importDirs,
+      });
+    },
     onSettled: () => trackEvent('grpc_request', 'send'),
   });
 
@@ -51,17 +63,23 @@ export function useGrpc(
 
   const reflect = useQuery<ReflectResponseService[], string>({
     enabled: req != null,
-    queryKey: ['grpc_reflect', req?.id ?? 'n/a', debouncedUrl, protoFiles],
+    queryKey: ['grpc_reflect', req?.id ?? 'n/a', debouncedUrl, protoFiles, importDirs],
     queryFn: () =>
       minPromiseMillis<ReflectResponseService[]>(
-        invokeCmd('cmd_grpc_reflect', { requestId, protoFiles }),
+        invokeCmd('cmd_grpc_reflect', { requestId, protoFiles, importDirs }),
         300,
       ),
   });
 
+  const cancelReflect = useMutation({
+    mutationKey: ['grpc_cancel_reflect', requestId],
+    mutationFn: () => emit(`cancel_grpc_reflect_${requestId}`),
+  });
+
   return {
     go,
     reflect,
+    cancelReflect,
     cancel,
     commit,
     isStreaming: isResponseLoading(conn),
@@ -19,3 +19,22 @@ export async function getGrpcProtoFiles(requestId: string) {
 export async function setGrpcProtoFiles(requestId: string, protoFiles: string[]) {
   return setKeyValue<string[]>({ ...protoFilesArgs(requestId), value: protoFiles });
 }
+
+export function protoImportDirsArgs(requestId: string | null) {
+  return {
+    namespace: 'global' as const,
+    key: ['proto_import_dirs', requestId ?? 'n/a'],
+  };
+}
+
+export function useGrpcProtoImportDirs(activeRequestId: string | null) {
+  return useKeyValue<string[]>({ ...protoImportDirsArgs(activeRequestId), fallback: [] });
+}
+
+export async function getGrpcProtoImportDirs(requestId: string) {
+  return getKeyValue<string[]>({ ...protoImportDirsArgs(requestId), fallback: [] });
+}
+
+export async function setGrpcProtoImportDirs(requestId: string, importDirs: string[]) {
+  return setKeyValue<string[]>({ ...protoImportDirsArgs(requestId), value: importDirs });
+}
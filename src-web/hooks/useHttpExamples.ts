@@ -0,0 +1,20 @@
+import { useQuery } from '@tanstack/react-query';
+import type { HttpExample } from '@yaakapp-internal/models';
+import { invokeCmd } from '../lib/tauri';
+
+export function httpExamplesQueryKey({ requestId }: { requestId: string }) {
+  return ['http_examples', { requestId }];
+}
+
+export function useHttpExamples(requestId: string | null) {
+  return (
+    useQuery<HttpExample[]>({
+      enabled: requestId !== null,
+      initialData: [],
+      queryKey: httpExamplesQueryKey({ requestId: requestId ?? 'n/a' }),
+      queryFn: async () => {
+        return (await invokeCmd('cmd_list_request_examples', { requestId })) as HttpExample[];
+      },
+    }).data ?? []
+  );
+}
@@ -6,14 +6,19 @@ import type {
   HttpRequestAction,
 } from '@yaakapp-internal/plugin';
 import { invokeCmd } from '../lib/tauri';
+import { useCopyAsCurl } from './useCopyAsCurl';
 import { usePluginsKey } from './usePlugins';
 
+/** The bundled exporter-curl plugin's action, which Yaak now does natively in its place */
+const CURL_EXPORT_ACTION_KEY = 'export-curl';
+
 export type CallableHttpRequestAction = Pick<HttpRequestAction, 'key' | 'label' | 'icon'> & {
   call: (httpRequest: HttpRequest) => Promise<void>;
 };
 
 export function useHttpRequestActions() {
   const pluginsKey = usePluginsKey();
+  const copyAsCurl = useCopyAsCurl();
 
   const httpRequestActions = useQuery({
     queryKey: ['http_request_actions', pluginsKey],
@@ -32,6 +37,10 @@ export function useHttpRequestActions() {
         label: a.label,
         icon: a.icon,
         call: async (httpRequest: HttpRequest) => {
+          if (a.key === CURL_EXPORT_ACTION_KEY) {
+            copyAsCurl.mutate(httpRequest.id);
+            return;
+          }
           const payload: CallHttpRequestActionRequest = {
             key: a.key,
             pluginRefId: r.pluginRefId,
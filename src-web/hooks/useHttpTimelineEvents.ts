@@ -0,0 +1,21 @@
+import { useQuery } from '@tanstack/react-query';
+import type { HttpTimelineEvent } from '@yaakapp-internal/models';
+import { invokeCmd } from '../lib/tauri';
+
+export function httpTimelineEventsQueryKey({ responseId }: { responseId: string }) {
+  return ['http_timeline_events', { responseId }];
+}
+
+export function useHttpTimelineEvents(responseId: string) {
+  return (
+    useQuery<HttpTimelineEvent[]>({
+      initialData: [],
+      queryKey: httpTimelineEventsQueryKey({ responseId }),
+      queryFn: async () => {
+        return (await invokeCmd('cmd_list_http_timeline_events', {
+          responseId,
+        })) as HttpTimelineEvent[];
+      },
+    }).data ?? []
+  );
+}
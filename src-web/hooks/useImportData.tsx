@@ -23,15 +23,23 @@ export function useImportData() {
   const alert = useAlert();
   const activeWorkspace = useActiveWorkspace();
 
-  const importData = async (filePath: string): Promise<boolean> => {
+  const importData = async (
+    filePath: string,
+    merge: boolean,
+    passphrase: string | null,
+  ): Promise<boolean> => {
     const imported: {
       workspaces: Workspace[];
       environments: Environment[];
       folders: Folder[];
       httpRequests: HttpRequest[];
       grpcRequests: GrpcRequest[];
+      created: number;
+      updated: number;
     } = await invokeCmd('cmd_import_data', {
       filePath,
+      merge,
+      passphrase,
       workspaceId: activeWorkspace?.id,
     });
 
@@ -43,7 +51,8 @@ export function useImportData() {
       size: 'sm',
       hideX: true,
       render: ({ hide }) => {
-        const { workspaces, environments, folders, httpRequests, grpcRequests } = imported;
+        const { workspaces, environments, folders, httpRequests, grpcRequests, created, updated } =
+          imported;
         return (
           <VStack space={3} className="pb-4">
             <ul className="list-disc pl-6">
@@ -53,6 +62,11 @@ export function useImportData() {
               <li>{count('HTTP Request', httpRequests.length)}</li>
               <li>{count('GRPC Request', grpcRequests.length)}</li>
             </ul>
+            {updated > 0 && (
+              <p className="text-text-subtle">
+                {created} created, {updated} updated
+              </p>
+            )}
             <div>
               <Button className="ml-auto" onClick={hide} color="primary">
                 Done
@@ -91,9 +105,13 @@ export function useImportData() {
           title: 'Import Data',
           size: 'sm',
           render: ({ hide }) => {
-            const importAndHide = async (filePath: string) => {
+            const importAndHide = async (
+              filePath: string,
+              merge: boolean,
+              passphrase: string | null,
+            ) => {
               try {
-                const didImport = await importData(filePath);
+                const didImport = await importData(filePath, merge, passphrase);
                 if (!didImport) {
                   return;
                 }
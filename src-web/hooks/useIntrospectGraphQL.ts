@@ -1,29 +1,29 @@
 import type { HttpRequest } from '@yaakapp-internal/models';
 import type { IntrospectionQuery } from 'graphql';
-import { useCallback, useEffect, useMemo, useState } from 'react';
-import { buildClientSchema, getIntrospectionQuery } from '../components/core/Editor';
+import { useCallback, useEffect, useMemo, useRef, useState } from 'react';
+import { buildClientSchema } from '../components/core/Editor';
 import { minPromiseMillis } from '../lib/minPromiseMillis';
-import { getResponseBodyText } from '../lib/responseBody';
-import { sendEphemeralRequest } from '../lib/sendEphemeralRequest';
+import { invokeCmd } from '../lib/tauri';
+import { useActiveCookieJar } from './useActiveCookieJar';
 import { useActiveEnvironment } from './useActiveEnvironment';
+import { useConfirmProduction } from './useConfirmProduction';
 import { useDebouncedValue } from './useDebouncedValue';
 import { useKeyValue } from './useKeyValue';
 
-const introspectionRequestBody = JSON.stringify({
-  query: getIntrospectionQuery(),
-  operationName: 'IntrospectionQuery',
-});
-
 export function useIntrospectGraphQL(baseRequest: HttpRequest) {
   // Debounce the request because it can change rapidly and we don't
   // want to send so too many requests.
   const request = useDebouncedValue(baseRequest);
   const [activeEnvironment] = useActiveEnvironment();
+  const [activeCookieJar] = useActiveCookieJar();
   const [refetchKey, setRefetchKey] = useState<number>(0);
   const [isLoading, setIsLoading] = useState<boolean>(false);
   const [error, setError] = useState<string>();
+  const confirmProduction = useConfirmProduction();
+  const confirmedRefetch = useRef<boolean>(false);
 
-  const { value: introspection, set: setIntrospection } = useKeyValue<IntrospectionQuery | null>({
+  // Kept up to date by cmd_graphql_introspect, which caches the schema here
+  const { value: introspection } = useKeyValue<IntrospectionQuery | null>({
     key: ['graphql_introspection', baseRequest.id],
     fallback: null,
     namespace: 'global',
@@ -31,46 +31,35 @@ export function useIntrospectGraphQL(baseRequest: HttpRequest) {
 
   useEffect(() => {
     const fetchIntrospection = async () => {
+      // Only a refetch the user confirmed may go to production, never an automatic one
+      const confirmed = confirmedRefetch.current;
+      confirmedRefetch.current = false;
       setIsLoading(true);
       setError(undefined);
-      const args = {
-        ...baseRequest,
-        bodyType: 'application/json',
-        body: { text: introspectionRequestBody },
-      };
-      const response = await minPromiseMillis(
-        sendEphemeralRequest(args, activeEnvironment?.id ?? null),
+      await minPromiseMillis(
+        invokeCmd('cmd_graphql_introspect', {
+          requestId: baseRequest.id,
+          environmentId: activeEnvironment?.id ?? null,
+          cookieJarId: activeCookieJar?.id ?? null,
+          confirmProduction: confirmed,
+        }),
         700,
       );
-
-      if (response.error) {
-        throw new Error(response.error);
-      }
-
-      const bodyText = await getResponseBodyText(response);
-      if (response.status < 200 || response.status >= 300) {
-        throw new Error(`Request failed with status ${response.status}.\n\n${bodyText}`);
-      }
-
-      if (bodyText === null) {
-        throw new Error('Empty body returned in response');
-      }
-
-      const { data } = JSON.parse(bodyText);
-      console.log(`Got introspection response for ${baseRequest.url}`, data);
-      await setIntrospection(data);
+      console.log(`Got introspection response for ${baseRequest.url}`);
     };
 
     fetchIntrospection()
-      .catch((e) => setError(e.message))
+      .catch((e) => setError(String(e)))
       .finally(() => setIsLoading(false));
 
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [request.id, request.url, request.method, refetchKey, activeEnvironment?.id]);
 
-  const refetch = useCallback(() => {
+  const refetch = useCallback(async () => {
+    if (!(await confirmProduction(activeEnvironment))) return;
+    confirmedRefetch.current = activeEnvironment?.isProduction ?? false;
     setRefetchKey((k) => k + 1);
-  }, []);
+  }, [activeEnvironment, confirmProduction]);
 
   const schema = useMemo(() => {
     try {
@@ -0,0 +1,70 @@
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import type { LoadTest } from '@yaakapp-internal/models';
+import { useState } from 'react';
+import { trackEvent } from '../lib/analytics';
+import { invokeCmd } from '../lib/tauri';
+import { useActiveCookieJar } from './useActiveCookieJar';
+import { useActiveEnvironment } from './useActiveEnvironment';
+import { useConfirmProduction } from './useConfirmProduction';
+import { useListenToTauriEvent } from './useListenToTauriEvent';
+
+export function loadTestsQueryKey({ requestId }: { requestId: string }) {
+  return ['load_tests', { requestId }];
+}
+
+/** Load tests of the request, newest first */
+export function useLoadTests(requestId: string) {
+  return (
+    useQuery<LoadTest[]>({
+      initialData: [],
+      queryKey: loadTestsQueryKey({ requestId }),
+      queryFn: async () => {
+        return (await invokeCmd('cmd_list_load_tests', { requestId })) as LoadTest[];
+      },
+    }).data ?? []
+  );
+}
+
+export function useLoadTestProgress(requestId: string) {
+  const [progress, setProgress] = useState<LoadTest | null>(null);
+  useListenToTauriEvent<LoadTest>(`load_test_progress_${requestId}`, (event) =>
+    setProgress(event.payload),
+  );
+  return progress;
+}
+
+export function useRunLoadTest(requestId: string) {
+  const queryClient = useQueryClient();
+  const [environment] = useActiveEnvironment();
+  const [activeCookieJar] = useActiveCookieJar();
+  const confirmProduction = useConfirmProduction();
+
+  return useMutation<LoadTest | null, string, { duration: number; concurrency: number }>({
+    mutationKey: ['run_load_test', requestId],
+    mutationFn: async ({ duration, concurrency }) => {
+      if (!(await confirmProduction(environment))) {
+        return null;
+      }
+
+      return invokeCmd('cmd_run_load_test', {
+        requestId,
+        duration,
+        concurrency,
+        environmentId: environment?.id,
+        cookieJarId: activeCookieJar?.id,
+        confirmProduction: environment?.isProduction ?? false,
+      });
+    },
+    onSettled: async () => {
+      trackEvent('http_request', 'send');
+      await queryClient.invalidateQueries({ queryKey: loadTestsQueryKey({ requestId }) });
+    },
+  });
+}
+
+export function useCancelLoadTest() {
+  return useMutation<boolean, string, string>({
+    mutationKey: ['cancel_load_test'],
+    mutationFn: (id) => invokeCmd('cmd_cancel_request', { responseId: id }),
+  });
+}
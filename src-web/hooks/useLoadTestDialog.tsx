@@ -0,0 +1,15 @@
+import { useCallback } from 'react';
+import { useDialog } from '../components/DialogContext';
+import { LoadTestDialog } from '../components/LoadTestDialog';
+
+export function useLoadTestDialog(requestId: string) {
+  const dialog = useDialog();
+  return useCallback(() => {
+    dialog.show({
+      id: 'load-test',
+      title: 'Load Test',
+      size: 'md',
+      render: () => <LoadTestDialog requestId={requestId} />,
+    });
+  }, [dialog, requestId]);
+}
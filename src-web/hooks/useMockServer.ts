@@ -0,0 +1,75 @@
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { useToast } from '../components/ToastContext';
+import { invokeCmd } from '../lib/tauri';
+import { useActiveWorkspace } from './useActiveWorkspace';
+import { usePrompt } from './usePrompt';
+
+function mockServerQueryKey(workspaceId: string | null) {
+  return ['mock_server', { workspaceId }];
+}
+
+/** Port of the active workspace's mock server, or null when it isn't running */
+export function useMockServerPort() {
+  const workspace = useActiveWorkspace();
+  return (
+    useQuery<number | null>({
+      queryKey: mockServerQueryKey(workspace?.id ?? null),
+      queryFn: () => null,
+      initialData: null,
+      staleTime: Infinity,
+    }).data ?? null
+  );
+}
+
+export function useStartMockServer() {
+  const workspace = useActiveWorkspace();
+  const queryClient = useQueryClient();
+  const prompt = usePrompt();
+  const toast = useToast();
+
+  return useMutation<number | null, string>({
+    mutationKey: ['start_mock_server', workspace?.id],
+    mutationFn: async () => {
+      if (workspace == null) return null;
+      const port = await prompt({
+        id: 'start-mock-server',
+        title: 'Start Mock Server',
+        description: 'Serve the saved examples of this workspace. Use 0 for any free port.',
+        label: 'Port',
+        placeholder: '8080',
+        defaultValue: '8080',
+        confirmText: 'Start',
+      });
+      if (port == null) return null;
+
+      return invokeCmd('cmd_start_mock_server', {
+        workspaceId: workspace.id,
+        port: parseInt(port, 10) || 0,
+      });
+    },
+    onSuccess: (port) => {
+      if (port == null) return;
+      queryClient.setQueryData(mockServerQueryKey(workspace?.id ?? null), port);
+      toast.show({ message: `Mock server listening on http://localhost:${port}`, color: 'success' });
+    },
+    onError: (err) => {
+      toast.show({ message: `Failed to start mock server: ${err}`, color: 'danger' });
+    },
+  });
+}
+
+export function useStopMockServer() {
+  const workspace = useActiveWorkspace();
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationKey: ['stop_mock_server', workspace?.id],
+    mutationFn: async () => {
+      if (workspace == null) return;
+      await invokeCmd('cmd_stop_mock_server', { workspaceId: workspace.id });
+    },
+    onSettled: () => {
+      queryClient.setQueryData(mockServerQueryKey(workspace?.id ?? null), null);
+    },
+  });
+}
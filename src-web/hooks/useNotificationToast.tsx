@@ -11,6 +11,11 @@ export function useNotificationToast() {
     invokeCmd('cmd_dismiss_notification', { notificationId: id }).catch(console.error);
   };
 
+  // Notification was dismissed in another window
+  useListenToTauriEvent<string>('notification_dismissed', ({ payload }) => {
+    toast.hide(payload);
+  });
+
   useListenToTauriEvent<{
     id: string;
     timestamp: string;
@@ -0,0 +1,16 @@
+import { useQuery } from '@tanstack/react-query';
+import type { PluginStatus } from '@yaakapp-internal/plugin';
+import { invokeCmd } from '../lib/tauri';
+import { usePluginsKey } from './usePlugins';
+
+/** Whether each plugin loaded in the runtime, and the error for any that didn't */
+export function usePluginStatuses() {
+  const pluginsKey = usePluginsKey();
+  return useQuery({
+    queryKey: ['plugin_statuses', pluginsKey],
+    queryFn: async () => {
+      const statuses = (await invokeCmd('cmd_plugin_statuses')) as PluginStatus[];
+      return statuses;
+    },
+  });
+}
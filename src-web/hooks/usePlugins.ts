@@ -1,4 +1,4 @@
-import { useMutation } from '@tanstack/react-query';
+import { useMutation, useQueryClient } from '@tanstack/react-query';
 import type { Plugin } from '@yaakapp-internal/models';
 import { atom, useAtomValue, useSetAtom } from 'jotai';
 import { minPromiseMillis } from '../lib/minPromiseMillis';
@@ -23,6 +23,7 @@ export function usePluginsKey() {
  */
 export function useRefreshPlugins() {
   const setPlugins = useSetAtom(pluginsAtom);
+  const queryClient = useQueryClient();
   return useMutation({
     mutationKey: ['refresh_plugins'],
     mutationFn: async () => {
@@ -33,6 +34,7 @@ export function useRefreshPlugins() {
         })(),
       );
       setPlugins(plugins);
+      await queryClient.invalidateQueries({ queryKey: ['plugin_statuses'] });
     },
   });
 }
@@ -0,0 +1,41 @@
+import { useMutation } from '@tanstack/react-query';
+import type { HttpRequest } from '@yaakapp-internal/models';
+import { InlineCode } from '../components/core/InlineCode';
+import { useToast } from '../components/ToastContext';
+import { invokeCmd } from '../lib/tauri';
+import { useActiveCookieJar } from './useActiveCookieJar';
+import { useActiveEnvironment } from './useActiveEnvironment';
+
+export function usePreviewCookies(request: HttpRequest) {
+  const [activeCookieJar] = useActiveCookieJar();
+  const [activeEnvironment] = useActiveEnvironment();
+  const toast = useToast();
+
+  return useMutation<string, string>({
+    mutationKey: ['preview_cookies', request.id],
+    mutationFn: async () => {
+      if (activeCookieJar == null) throw new Error('No cookie jar is active');
+      return invokeCmd('cmd_preview_cookies', {
+        requestId: request.id,
+        cookieJarId: activeCookieJar.id,
+        environmentId: activeEnvironment?.id,
+      });
+    },
+    onSuccess: (header) => {
+      toast.show({
+        timeout: 8000,
+        message:
+          header === '' ? (
+            'No cookies from the jar would be sent'
+          ) : (
+            <>
+              Cookie: <InlineCode>{header}</InlineCode>
+            </>
+          ),
+      });
+    },
+    onError: (err) => {
+      toast.show({ message: `Failed to preview cookies: ${err}`, color: 'danger' });
+    },
+  });
+}
@@ -0,0 +1,23 @@
+import { useMutation, useQueryClient } from '@tanstack/react-query';
+import type { BootResponse } from '@yaakapp-internal/plugin';
+import { useToast } from '../components/ToastContext';
+import { invokeCmd } from '../lib/tauri';
+
+/** Reload a single plugin's code. If it fails, the previous version keeps running. */
+export function useReloadPlugin(pluginId: string) {
+  const queryClient = useQueryClient();
+  const toast = useToast();
+  return useMutation<BootResponse, unknown>({
+    mutationKey: ['reload_plugin', pluginId],
+    mutationFn: async () => {
+      return (await invokeCmd('cmd_reload_plugin', { pluginId })) as BootResponse;
+    },
+    onSuccess: (info) => {
+      queryClient.setQueryData(['plugin_info', pluginId], info);
+      toast.show({ message: `Reloaded plugin ${info.name}`, color: 'success' });
+    },
+    onError: (err) => {
+      toast.show({ message: `Failed to reload plugin: ${err}`, color: 'danger' });
+    },
+  });
+}
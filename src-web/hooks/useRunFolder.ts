@@ -0,0 +1,79 @@
+import { useMutation, useQuery } from '@tanstack/react-query';
+import type { RunResult } from '@yaakapp-internal/models';
+import { useState } from 'react';
+import { useToast } from '../components/ToastContext';
+import { trackEvent } from '../lib/analytics';
+import { invokeCmd } from '../lib/tauri';
+import { useActiveCookieJar } from './useActiveCookieJar';
+import { useActiveEnvironment } from './useActiveEnvironment';
+import { useConfirmProduction } from './useConfirmProduction';
+import { useListenToTauriEvent } from './useListenToTauriEvent';
+
+export interface RunProgress {
+  runId: string;
+  completed: number;
+  total: number;
+  result: RunResult;
+}
+
+export function runResultsQueryKey({ folderId }: { folderId: string }) {
+  return ['run_results', { folderId }];
+}
+
+/** Results of every run of the folder, oldest run first */
+export function useRunResults(folderId: string) {
+  return (
+    useQuery<RunResult[]>({
+      initialData: [],
+      queryKey: runResultsQueryKey({ folderId }),
+      queryFn: async () => {
+        return (await invokeCmd('cmd_list_run_results', { folderId })) as RunResult[];
+      },
+    }).data ?? []
+  );
+}
+
+export function useRunProgress(folderId: string) {
+  const [progress, setProgress] = useState<RunProgress | null>(null);
+  useListenToTauriEvent<RunProgress>(`run_folder_progress_${folderId}`, (event) =>
+    setProgress(event.payload),
+  );
+  return progress;
+}
+
+export function useRunFolder(folderId: string) {
+  const toast = useToast();
+  const [environment] = useActiveEnvironment();
+  const [activeCookieJar] = useActiveCookieJar();
+  const confirmProduction = useConfirmProduction();
+
+  return useMutation<RunResult[] | null, string, { stopOnFailure: boolean }>({
+    mutationKey: ['run_folder', folderId],
+    mutationFn: async ({ stopOnFailure }) => {
+      if (!(await confirmProduction(environment))) {
+        return null;
+      }
+
+      return invokeCmd('cmd_run_folder', {
+        folderId,
+        environmentId: environment?.id,
+        cookieJarId: activeCookieJar?.id,
+        confirmProduction: environment?.isProduction ?? false,
+        stopOnFailure,
+      });
+    },
+    onSettled: () => trackEvent('folder', 'send'),
+    onSuccess: (results) => {
+      if (results == null) return;
+      const passed = results.filter((r) => r.status === 'passed').length;
+      toast.show({
+        id: `run-folder-${folderId}`,
+        color: passed === results.length ? 'success' : 'danger',
+        message: `${passed} of ${results.length} requests passed`,
+      });
+    },
+    onError: (err) => {
+      toast.show({ message: `Failed to run folder: ${err}`, color: 'danger' });
+    },
+  });
+}
@@ -0,0 +1,37 @@
+import { useMutation } from '@tanstack/react-query';
+import type { HttpExample, HttpResponse } from '@yaakapp-internal/models';
+import { useToast } from '../components/ToastContext';
+import { trackEvent } from '../lib/analytics';
+import { invokeCmd } from '../lib/tauri';
+import { usePrompt } from './usePrompt';
+
+export function useSaveResponseExample(response: HttpResponse) {
+  const prompt = usePrompt();
+  const toast = useToast();
+
+  return useMutation<HttpExample | null, string>({
+    mutationKey: ['save_response_example', response.id],
+    mutationFn: async () => {
+      const name = await prompt({
+        id: 'save-response-example',
+        title: 'Save as Example',
+        description: 'Examples keep a copy of the response after it is deleted from history',
+        placeholder: `${response.status} Response`,
+        confirmText: 'Save',
+        label: 'Name',
+        defaultValue: `${response.status} Response`,
+      });
+      if (name == null) return null;
+
+      return invokeCmd('cmd_save_response_example', { responseId: response.id, name });
+    },
+    onSuccess: (example) => {
+      if (example == null) return;
+      toast.show({ message: `Saved example ${example.name}`, color: 'success' });
+    },
+    onError: (err) => {
+      toast.show({ message: `Failed to save example: ${err}`, color: 'danger' });
+    },
+    onSettled: () => trackEvent('http_response', 'create'),
+  });
+}
@@ -6,11 +6,13 @@ import { invokeCmd } from '../lib/tauri';
 import { useActiveCookieJar } from './useActiveCookieJar';
 import { useActiveEnvironment } from './useActiveEnvironment';
 import { useAlert } from './useAlert';
+import { useConfirmProduction } from './useConfirmProduction';
 
 export function useSendAnyHttpRequest() {
   const alert = useAlert();
   const [environment] = useActiveEnvironment();
   const [activeCookieJar] = useActiveCookieJar();
+  const confirmProduction = useConfirmProduction();
   return useMutation<HttpResponse | null, string, string | null>({
     mutationKey: ['send_any_request'],
     mutationFn: async (id) => {
@@ -19,10 +21,15 @@ export function useSendAnyHttpRequest() {
         return null;
       }
 
+      if (!(await confirmProduction(environment))) {
+        return null;
+      }
+
       return invokeCmd('cmd_send_http_request', {
         request,
         environmentId: environment?.id,
         cookieJarId: activeCookieJar?.id,
+        confirmProduction: environment?.isProduction ?? false,
       });
     },
     onSettled: () => trackEvent('http_request', 'send'),
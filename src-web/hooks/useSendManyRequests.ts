@@ -1,14 +1,45 @@
 import { useMutation } from '@tanstack/react-query';
-import { useSendAnyHttpRequest } from './useSendAnyHttpRequest';
+import type { HttpResponse } from '@yaakapp-internal/models';
+import { useToast } from '../components/ToastContext';
+import { trackEvent } from '../lib/analytics';
+import { invokeCmd } from '../lib/tauri';
+import { useActiveCookieJar } from './useActiveCookieJar';
+import { useActiveEnvironment } from './useActiveEnvironment';
+import { useConfirmProduction } from './useConfirmProduction';
 
+/** Send the HTTP requests concurrently, with each response arriving as it finishes */
 export function useSendManyRequests() {
-  const sendAnyRequest = useSendAnyHttpRequest();
-  return useMutation<void, string, string[]>({
+  const toast = useToast();
+  const [environment] = useActiveEnvironment();
+  const [activeCookieJar] = useActiveCookieJar();
+  const confirmProduction = useConfirmProduction();
+
+  return useMutation<HttpResponse[] | null, string, string[]>({
     mutationKey: ['send_many_requests'],
     mutationFn: async (requestIds: string[]) => {
-      for (const id of requestIds) {
-        sendAnyRequest.mutate(id);
+      if (requestIds.length === 0 || !(await confirmProduction(environment))) {
+        return null;
       }
+
+      return invokeCmd('cmd_send_many_http_requests', {
+        requestIds,
+        environmentId: environment?.id,
+        cookieJarId: activeCookieJar?.id,
+        confirmProduction: environment?.isProduction ?? false,
+      });
+    },
+    onSettled: () => trackEvent('http_request', 'send'),
+    onSuccess: (responses) => {
+      if (responses == null) return;
+      const ok = responses.filter((r) => r.error == null && r.status > 0 && r.status < 400);
+      toast.show({
+        id: 'send-many-requests',
+        color: ok.length === responses.length ? 'success' : 'danger',
+        message: `${ok.length} of ${responses.length} requests succeeded`,
+      });
+    },
+    onError: (err) => {
+      toast.show({ message: `Failed to send requests: ${err}`, color: 'danger' });
     },
   });
 }
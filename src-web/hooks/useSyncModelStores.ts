@@ -11,12 +11,16 @@ import { foldersAtom } from './useFolders';
 import { grpcConnectionsAtom } from './useGrpcConnections';
 import { grpcEventsQueryKey } from './useGrpcEvents';
 import { grpcRequestsAtom } from './useGrpcRequests';
+import { httpExamplesQueryKey } from './useHttpExamples';
 import { httpRequestsAtom } from './useHttpRequests';
+import { httpResponseEventsQueryKey } from './useHttpResponseEvents';
+import { httpTimelineEventsQueryKey } from './useHttpTimelineEvents';
 import { httpResponsesAtom } from './useHttpResponses';
 import { keyValueQueryKey } from './useKeyValue';
 import { useListenToTauriEvent } from './useListenToTauriEvent';
 import { pluginsAtom } from './usePlugins';
 import { useRequestUpdateKey } from './useRequestUpdateKey';
+import { runResultsQueryKey } from './useRunFolder';
 import { settingsAtom } from './useSettings';
 import { workspacesAtom } from './useWorkspaces';
 
@@ -48,7 +52,15 @@ export function useSyncModelStores() {
         ? grpcEventsQueryKey(model)
         : model.model === 'key_value'
           ? keyValueQueryKey(model)
-          : null;
+          : model.model === 'http_example'
+            ? httpExamplesQueryKey(model)
+            : model.model === 'http_response_event'
+              ? httpResponseEventsQueryKey(model)
+              : model.model === 'http_timeline_event'
+                ? httpTimelineEventsQueryKey(model)
+                : model.model === 'run_result'
+                  ? runResultsQueryKey(model)
+                  : null;
 
     // TODO: Move this logic to useRequestEditor() hook
     if (model.model === 'http_request' && windowLabel !== getCurrentWebviewWindow().label) {
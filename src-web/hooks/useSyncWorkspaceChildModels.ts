@@ -1,3 +1,10 @@
+import type {
+  CookieJar,
+  Environment,
+  Folder,
+  GrpcRequest,
+  HttpRequest,
+} from '@yaakapp-internal/models';
 import { useSetAtom } from 'jotai/index';
 import { useEffect } from 'react';
 import { invokeCmd } from '../lib/tauri';
@@ -10,6 +17,14 @@ import { grpcRequestsAtom } from './useGrpcRequests';
 import { httpRequestsAtom } from './useHttpRequests';
 import { httpResponsesAtom } from './useHttpResponses';
 
+interface WorkspaceModels {
+  cookieJars: CookieJar[];
+  environments: Environment[];
+  folders: Folder[];
+  grpcRequests: GrpcRequest[];
+  httpRequests: HttpRequest[];
+}
+
 export function useSyncWorkspaceChildModels() {
   const setCookieJars = useSetAtom(cookieJarsAtom);
   const setFolders = useSetAtom(foldersAtom);
@@ -25,15 +40,16 @@ export function useSyncWorkspaceChildModels() {
     (async function () {
       console.log('Syncing model stores', { workspaceId });
       // Set the things we need first, first
-      setHttpRequests(await invokeCmd('cmd_list_http_requests', { workspaceId }));
-      setGrpcRequests(await invokeCmd('cmd_list_grpc_requests', { workspaceId }));
-      setFolders(await invokeCmd('cmd_list_folders', { workspaceId }));
+      const models: WorkspaceModels = await invokeCmd('cmd_load_workspace', { workspaceId });
+      setHttpRequests(models.httpRequests);
+      setGrpcRequests(models.grpcRequests);
+      setFolders(models.folders);
+      setCookieJars(models.cookieJars);
+      setEnvironments(models.environments);
 
       // Then, set the rest
-      setCookieJars(await invokeCmd('cmd_list_cookie_jars', { workspaceId }));
       setHttpResponses(await invokeCmd('cmd_list_http_responses', { workspaceId }));
       setGrpcConnections(await invokeCmd('cmd_list_grpc_connections', { workspaceId }));
-      setEnvironments(await invokeCmd('cmd_list_environments', { workspaceId }));
     })().catch(console.error);
 
     // eslint-disable-next-line react-hooks/exhaustive-deps
@@ -0,0 +1,18 @@
+import { useMutation } from '@tanstack/react-query';
+import type { EnvironmentVariable, Workspace } from '@yaakapp-internal/models';
+import { useSetAtom } from 'jotai/index';
+import { invokeCmd } from '../lib/tauri';
+import { updateModelList } from './useSyncModelStores';
+import { workspacesAtom } from './useWorkspaces';
+
+export function useUpdateWorkspaceVariables(workspaceId: string) {
+  const setWorkspaces = useSetAtom(workspacesAtom);
+  return useMutation<Workspace, unknown, EnvironmentVariable[]>({
+    mutationKey: ['update_workspace_variables', workspaceId],
+    mutationFn: (variables) =>
+      invokeCmd('cmd_update_workspace_variables', { workspaceId, variables }),
+    onSuccess: async (workspace) => {
+      setWorkspaces(updateModelList(workspace));
+    },
+  });
+}
@@ -0,0 +1,19 @@
+import { useState } from 'react';
+import { useListenToTauriEvent } from './useListenToTauriEvent';
+
+export interface UploadProgress {
+  responseId: string;
+  bytesSent: number;
+  totalBytes: number;
+}
+
+export function useUploadProgress(requestId: string, responseId: string | null) {
+  const [progress, setProgress] = useState<UploadProgress | null>(null);
+
+  useListenToTauriEvent<UploadProgress>(`http_request_upload_progress_${requestId}`, (event) =>
+    setProgress(event.payload),
+  );
+
+  // Ignore progress left over from an earlier send
+  return progress?.responseId === responseId ? progress : null;
+}
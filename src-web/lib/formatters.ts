@@ -3,6 +3,27 @@ import { invokeCmd } from './tauri';
 
 const INDENT = '  ';
 
+/** Pretty-print, or minify, a body based on its content type. Throws if it fails to parse. */
+export function formatBody(text: string, contentType: string, minify = false): Promise<string> {
+  return invokeCmd<string>('cmd_format_body', { body: text, contentType, minify });
+}
+
+export interface FormatError {
+  message: string;
+  line: number;
+  column: number;
+}
+
+/** Check that JSON parses, ignoring template tags. Returns where it fails, if it does. */
+export async function validateJson(text: string): Promise<FormatError | null> {
+  try {
+    await invokeCmd<void>('cmd_validate_json', { text });
+    return null;
+  } catch (err) {
+    return err as FormatError;
+  }
+}
+
 export async function tryFormatJson(text: string): Promise<string> {
   if (text === '') return text;
 
@@ -25,9 +46,21 @@ export async function tryFormatJson(text: string): Promise<string> {
   return text;
 }
 
-export async function tryFormatXml(text: string): Promise<string> {
+export async function tryFormatXml(
+  text: string,
+  contentType: 'application/xml' | 'text/html' = 'application/xml',
+): Promise<string> {
   if (text === '') return text;
 
+  try {
+    const result = await formatBody(text, contentType);
+    return result;
+    // eslint-disable-next-line @typescript-eslint/no-unused-vars
+  } catch (err) {
+    console.warn('Failed to format XML', err);
+    // Nothing
+  }
+
   try {
     return xmlFormat(text, { throwOnFailure: true, strictMode: false, indentation: INDENT });
     // eslint-disable-next-line @typescript-eslint/no-unused-vars
@@ -44,6 +44,33 @@ export async function getKeyValue<T>({
   return extractKeyValueOrFallback(kv, fallback);
 }
 
+/** Store a sensitive value, like a token, in the OS keychain instead of the database */
+export async function setSecureKeyValue({
+  namespace = 'global',
+  key,
+  value,
+}: {
+  namespace?: string;
+  key: string | string[];
+  value: string;
+}): Promise<void> {
+  await invokeCmd('cmd_set_secure_key_value', {
+    namespace,
+    key: buildKeyValueKey(key),
+    value,
+  });
+}
+
+export async function getSecureKeyValue({
+  namespace = 'global',
+  key,
+}: {
+  namespace?: string;
+  key: string | string[];
+}): Promise<string | null> {
+  return invokeCmd('cmd_get_secure_key_value', { namespace, key: buildKeyValueKey(key) });
+}
+
 export function extractKeyValue<T>(kv: KeyValue | null): T | undefined {
   if (kv === null) return undefined;
   try {
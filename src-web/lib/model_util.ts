@@ -11,6 +11,8 @@ export const BODY_TYPE_NONE = null;
 export const BODY_TYPE_GRAPHQL = 'graphql';
 export const BODY_TYPE_JSON = 'application/json';
 export const BODY_TYPE_BINARY = 'binary';
+export const BODY_TYPE_BYTES = 'bytes';
+export const BODY_TYPE_CHUNKED = 'chunked';
 export const BODY_TYPE_OTHER = 'other';
 export const BODY_TYPE_FORM_URLENCODED = 'application/x-www-form-urlencoded';
 export const BODY_TYPE_FORM_MULTIPART = 'multipart/form-data';
@@ -19,6 +21,8 @@ export const BODY_TYPE_XML = 'text/xml';
 export const AUTH_TYPE_NONE = null;
 export const AUTH_TYPE_BASIC = 'basic';
 export const AUTH_TYPE_BEARER = 'bearer';
+export const AUTH_TYPE_DIGEST = 'digest';
+export const AUTH_TYPE_NTLM = 'ntlm';
 
 export function cookieDomain(cookie: Cookie): string {
   if (cookie.domain === 'NotPresent' || cookie.domain === 'Empty') {
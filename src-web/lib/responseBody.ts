@@ -1,8 +1,6 @@
 import { readFile } from '@tauri-apps/plugin-fs';
 import type { HttpResponse } from '@yaakapp-internal/models';
-import type { ServerSentEvent } from '@yaakapp-internal/sse';
 import { getCharsetFromContentType } from './model_util';
-import { invokeCmd } from './tauri';
 
 export async function getResponseBodyText(response: HttpResponse): Promise<string | null> {
   if (!response.bodyPath) return null;
@@ -24,11 +22,9 @@ export async function getResponseBodyBlob(response: HttpResponse): Promise<Uint8
   return readFile(response.bodyPath);
 }
 
-export async function getResponseBodyEventSource(
-  response: HttpResponse,
-): Promise<ServerSentEvent[]> {
-  if (!response.bodyPath) return [];
-  return invokeCmd<ServerSentEvent[]>('cmd_get_sse_events', {
-    filePath: response.bodyPath,
-  });
+/** The request as it was written to the connection, with any binary body decoded lossily */
+export async function getRawRequestText(response: HttpResponse): Promise<string | null> {
+  if (!response.rawRequestPath) return null;
+  const bytes = await readFile(response.rawRequestPath);
+  return new TextDecoder('utf-8').decode(bytes);
 }
@@ -3,7 +3,9 @@ import { invoke } from '@tauri-apps/api/core';
 
 type TauriCmd =
   | 'cmd_call_http_request_action'
+  | 'cmd_cancel_request'
   | 'cmd_check_for_updates'
+  | 'cmd_clear_cookie_jar'
   | 'cmd_create_cookie_jar'
   | 'cmd_create_environment'
   | 'cmd_template_tokens_to_string'
@@ -11,10 +13,12 @@ type TauriCmd =
   | 'cmd_create_grpc_request'
   | 'cmd_create_http_request'
   | 'cmd_create_workspace'
+  | 'cmd_curl_cookies_to_jar'
   | 'cmd_curl_to_request'
   | 'cmd_delete_all_grpc_connections'
   | 'cmd_delete_all_http_responses'
   | 'cmd_delete_send_history'
+  | 'cmd_delete_cookie'
   | 'cmd_delete_cookie_jar'
   | 'cmd_delete_environment'
   | 'cmd_delete_folder'
@@ -24,11 +28,15 @@ type TauriCmd =
   | 'cmd_delete_http_response'
   | 'cmd_delete_workspace'
   | 'cmd_dismiss_notification'
+  | 'cmd_duplicate_environment'
   | 'cmd_duplicate_grpc_request'
   | 'cmd_duplicate_http_request'
+  | 'cmd_export_curl'
   | 'cmd_export_data'
   | 'cmd_filter_response'
+  | 'cmd_format_body'
   | 'cmd_format_json'
+  | 'cmd_format_xml'
   | 'cmd_get_cookie_jar'
   | 'cmd_get_environment'
   | 'cmd_get_folder'
@@ -36,35 +44,56 @@ type TauriCmd =
   | 'cmd_get_http_request'
   | 'cmd_get_sse_events'
   | 'cmd_get_key_value'
+  | 'cmd_get_secure_key_value'
   | 'cmd_get_settings'
   | 'cmd_get_workspace'
+  | 'cmd_graphql_introspect'
   | 'cmd_grpc_go'
   | 'cmd_grpc_reflect'
   | 'cmd_http_request_actions'
   | 'cmd_import_data'
   | 'cmd_install_plugin'
   | 'cmd_list_cookie_jars'
+  | 'cmd_list_cookies'
   | 'cmd_list_environments'
   | 'cmd_list_folders'
   | 'cmd_list_grpc_connections'
   | 'cmd_list_grpc_events'
   | 'cmd_list_grpc_requests'
   | 'cmd_list_http_requests'
+  | 'cmd_list_http_response_events'
   | 'cmd_list_http_responses'
+  | 'cmd_list_http_timeline_events'
+  | 'cmd_list_load_tests'
   | 'cmd_list_plugins'
+  | 'cmd_list_request_examples'
+  | 'cmd_list_request_history'
+  | 'cmd_list_run_results'
   | 'cmd_list_workspaces'
+  | 'cmd_load_workspace'
   | 'cmd_metadata'
   | 'cmd_new_main_window'
   | 'cmd_new_child_window'
   | 'cmd_parse_template'
   | 'cmd_plugin_info'
+  | 'cmd_plugin_statuses'
+  | 'cmd_preview_cookies'
   | 'cmd_render_template'
+  | 'cmd_reload_plugin'
   | 'cmd_reload_plugins'
+  | 'cmd_run_folder'
+  | 'cmd_run_load_test'
   | 'cmd_save_response'
+  | 'cmd_save_response_example'
+  | 'cmd_search_workspace'
   | 'cmd_send_ephemeral_request'
   | 'cmd_send_http_request'
+  | 'cmd_send_many_http_requests'
   | 'cmd_set_key_value'
+  | 'cmd_set_secure_key_value'
   | 'cmd_set_update_mode'
+  | 'cmd_start_mock_server'
+  | 'cmd_stop_mock_server'
   | 'cmd_template_functions'
   | 'cmd_track_event'
   | 'cmd_uninstall_plugin'
@@ -75,9 +104,54 @@ type TauriCmd =
   | 'cmd_update_http_request'
   | 'cmd_update_settings'
   | 'cmd_update_workspace'
+  | 'cmd_update_workspace_variables'
+  | 'cmd_upsert_cookie'
+  | 'cmd_validate_json'
   | 'cmd_write_file_dev';
 
+export type InvokeErrorKind =
+  | 'not_found'
+  | 'validation'
+  | 'network'
+  | 'database'
+  | 'io'
+  | 'plugin'
+  | 'unknown';
+
+/** Structured error thrown by commands that return one. Stringifies to just the message. */
+export class InvokeError extends Error {
+  kind: InvokeErrorKind;
+  details: string | null;
+
+  constructor(kind: InvokeErrorKind, message: string, details: string | null) {
+    super(message);
+    this.name = 'InvokeError';
+    this.kind = kind;
+    this.details = details;
+  }
+
+  toString() {
+    return this.message;
+  }
+}
+
+export function isInvokeError(err: unknown, kind?: InvokeErrorKind): err is InvokeError {
+  return err instanceof InvokeError && (kind == null || err.kind === kind);
+}
+
 export async function invokeCmd<T>(cmd: TauriCmd, args?: InvokeArgs): Promise<T> {
   // console.log('RUN COMMAND', cmd, args);
-  return invoke(cmd, args);
+  try {
+    return await invoke(cmd, args);
+  } catch (err) {
+    if (err != null && typeof err === 'object' && 'kind' in err && 'message' in err) {
+      const { kind, message, details } = err as {
+        kind: InvokeErrorKind;
+        message: string;
+        details?: string | null;
+      };
+      throw new InvokeError(kind, message, details ?? null);
+    }
+    throw err;
+  }
 }